@@ -0,0 +1,138 @@
+//! Pluggable time source
+//!
+//! Expiry and uptime logic reads time through [`Clock`] so tests can swap in
+//! a [`MockClock`] and move time forward instantly instead of sleeping.
+
+use parking_lot::Mutex;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+
+/// Source of wall-clock and monotonic time
+pub trait Clock: Send + Sync + fmt::Debug {
+    /// Current wall-clock time
+    fn now(&self) -> SystemTime;
+
+    /// Current monotonic time, for measuring elapsed durations
+    fn instant(&self) -> Instant;
+}
+
+/// Clock shared between components
+pub type SharedClock = Arc<dyn Clock>;
+
+/// Clock backed by the operating system
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl SystemClock {
+    /// Shared handle to the system clock
+    pub fn shared() -> SharedClock {
+        Arc::new(SystemClock)
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+
+    fn instant(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Manually driven clock for tests
+///
+/// Time stands still until [`MockClock::advance`] is called. Clones share the
+/// same offset, so advancing one handle advances every component using it.
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    base_time: SystemTime,
+    base_instant: Instant,
+    offset: Arc<Mutex<Duration>>,
+}
+
+impl MockClock {
+    /// Create a clock frozen at the current system time
+    pub fn new() -> Self {
+        Self::starting_at(SystemTime::now())
+    }
+
+    /// Create a clock frozen at the given wall-clock time
+    pub fn starting_at(time: SystemTime) -> Self {
+        Self {
+            base_time: time,
+            base_instant: Instant::now(),
+            offset: Arc::new(Mutex::new(Duration::ZERO)),
+        }
+    }
+
+    /// Move time forward
+    pub fn advance(&self, duration: Duration) {
+        *self.offset.lock() += duration;
+    }
+
+    /// Total time advanced since creation
+    pub fn elapsed(&self) -> Duration {
+        *self.offset.lock()
+    }
+
+    /// Shared handle to this clock
+    pub fn shared(&self) -> SharedClock {
+        Arc::new(self.clone())
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> SystemTime {
+        self.base_time + self.elapsed()
+    }
+
+    fn instant(&self) -> Instant {
+        self.base_instant + self.elapsed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_system_clock_moves_forward() {
+        let clock = SystemClock;
+        let start = clock.instant();
+        assert!(clock.instant() >= start);
+        assert!(clock.now() > SystemTime::UNIX_EPOCH);
+    }
+
+    #[test]
+    fn test_mock_clock_is_frozen_until_advanced() {
+        let clock = MockClock::starting_at(SystemTime::UNIX_EPOCH);
+        let start = clock.instant();
+        assert_eq!(clock.now(), SystemTime::UNIX_EPOCH);
+        assert_eq!(clock.instant(), start);
+
+        clock.advance(Duration::from_secs(90));
+        assert_eq!(
+            clock.now(),
+            SystemTime::UNIX_EPOCH + Duration::from_secs(90)
+        );
+        assert_eq!(clock.instant() - start, Duration::from_secs(90));
+    }
+
+    #[test]
+    fn test_mock_clock_clones_share_time() {
+        let clock = MockClock::new();
+        let shared = clock.shared();
+        let start = shared.instant();
+
+        clock.advance(Duration::from_millis(250));
+        assert_eq!(shared.instant() - start, Duration::from_millis(250));
+    }
+}
@@ -3,7 +3,9 @@
 //! This crate provides shared types, error handling, and utility functions
 //! used across the Aegis-Flow project.
 
+pub mod clock;
 pub mod error;
 pub mod types;
 
+pub use clock::{Clock, MockClock, SharedClock, SystemClock};
 pub use error::{AegisError, Result};
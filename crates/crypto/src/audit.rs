@@ -0,0 +1,382 @@
+//! Connection Audit Events
+//!
+//! Structured record of the parameters negotiated by a successful handshake,
+//! and of each step of mTLS authentication ([`AuthEvent`]). Events are emitted
+//! on the [`AUDIT_TARGET`] tracing target and can additionally be appended as
+//! JSON lines to an [`AuditLog`] file or passed to any [`AuditSink`].
+//!
+//! Events carry identifiers only: connection and channel ids, peer address,
+//! certificate subject and fingerprint. Keys, shared secrets and handshake
+//! messages are never recorded.
+
+use crate::cipher::CipherAlgorithm;
+use crate::tls::{PqcAlgorithm, SecureChannel};
+use aegis_common::Result;
+use parking_lot::Mutex;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{info, warn};
+
+/// Tracing target carrying audit events
+pub const AUDIT_TARGET: &str = "aegis_audit";
+
+/// Parameters of an established secure connection
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectionEstablished {
+    /// Remote address, when known
+    pub peer_addr: Option<SocketAddr>,
+    /// Key exchange algorithm
+    pub algorithm: PqcAlgorithm,
+    /// Symmetric cipher protecting the channel
+    pub cipher: CipherAlgorithm,
+    /// Channel identifier
+    pub channel_id: u64,
+    /// Whether the client authenticated with a certificate
+    pub client_authenticated: bool,
+}
+
+impl ConnectionEstablished {
+    /// Describe a connection using the given channel parameters
+    pub fn new(channel_id: u64, algorithm: PqcAlgorithm, cipher: CipherAlgorithm) -> Self {
+        Self {
+            peer_addr: None,
+            algorithm,
+            cipher,
+            channel_id,
+            client_authenticated: false,
+        }
+    }
+
+    /// Describe the connection protected by `channel`
+    pub fn from_channel(channel: &SecureChannel) -> Self {
+        Self::new(channel.channel_id(), channel.algorithm(), channel.cipher())
+    }
+
+    /// Set the remote address
+    pub fn with_peer_addr(mut self, peer_addr: SocketAddr) -> Self {
+        self.peer_addr = Some(peer_addr);
+        self
+    }
+
+    /// Record whether the client authenticated with a certificate
+    pub fn with_client_auth(mut self, client_authenticated: bool) -> Self {
+        self.client_authenticated = client_authenticated;
+        self
+    }
+
+    fn peer(&self) -> String {
+        self.peer_addr
+            .map_or_else(|| "unknown".to_string(), |addr| addr.to_string())
+    }
+
+    /// Render the event as a single JSON object
+    pub fn to_json(&self) -> String {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        format!(
+            "{{\"event\":\"connection_established\",\"timestamp\":{},\"peer_addr\":\"{}\",\"algorithm\":\"{:?}\",\"cipher\":\"{:?}\",\"channel_id\":{},\"client_auth\":{}}}",
+            timestamp,
+            self.peer(),
+            self.algorithm,
+            self.cipher,
+            self.channel_id,
+            self.client_authenticated
+        )
+    }
+
+    /// Emit the event, also appending it to `audit_log` when configured
+    pub fn emit(&self, audit_log: Option<&AuditLog>) {
+        info!(
+            target: AUDIT_TARGET,
+            event = "connection_established",
+            peer_addr = %self.peer(),
+            algorithm = ?self.algorithm,
+            cipher = ?self.cipher,
+            channel_id = self.channel_id,
+            client_auth = self.client_authenticated,
+            "Secure connection established"
+        );
+
+        if let Some(log) = audit_log
+            && let Err(e) = log.record(self)
+        {
+            warn!("Failed to write audit log entry: {}", e);
+        }
+    }
+}
+
+/// Step of an mTLS authentication
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuthEventKind {
+    /// Connection accepted and handshake started
+    Accepted,
+    /// Key exchange finished and the secure channel is up
+    HandshakeComplete { channel_id: u64 },
+    /// Client authenticated; certificate details when one was presented
+    AuthSuccess {
+        subject_cn: Option<String>,
+        fingerprint: Option<String>,
+    },
+    /// Authentication rejected
+    AuthFailure { reason: String },
+    /// Connection removed
+    Disconnected,
+}
+
+/// Audit record of one step of an mTLS authentication
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthEvent {
+    pub connection_id: u64,
+    /// Remote address, when known
+    pub peer_addr: Option<SocketAddr>,
+    pub kind: AuthEventKind,
+}
+
+impl AuthEvent {
+    pub fn new(connection_id: u64, peer_addr: Option<SocketAddr>, kind: AuthEventKind) -> Self {
+        Self {
+            connection_id,
+            peer_addr,
+            kind,
+        }
+    }
+
+    /// Event name used in logs
+    pub fn name(&self) -> &'static str {
+        match self.kind {
+            AuthEventKind::Accepted => "accept",
+            AuthEventKind::HandshakeComplete { .. } => "handshake_complete",
+            AuthEventKind::AuthSuccess { .. } => "auth_success",
+            AuthEventKind::AuthFailure { .. } => "auth_failure",
+            AuthEventKind::Disconnected => "disconnect",
+        }
+    }
+
+    fn peer(&self) -> String {
+        self.peer_addr
+            .map_or_else(|| "unknown".to_string(), |addr| addr.to_string())
+    }
+
+    /// Render the event as a single JSON object
+    pub fn to_json(&self) -> String {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let details = match &self.kind {
+            AuthEventKind::Accepted | AuthEventKind::Disconnected => String::new(),
+            AuthEventKind::HandshakeComplete { channel_id } => {
+                format!(",\"channel_id\":{}", channel_id)
+            }
+            AuthEventKind::AuthSuccess {
+                subject_cn,
+                fingerprint,
+            } => format!(
+                ",\"subject_cn\":{},\"fingerprint\":{}",
+                json_string_or_null(subject_cn.as_deref()),
+                json_string_or_null(fingerprint.as_deref())
+            ),
+            AuthEventKind::AuthFailure { reason } => {
+                format!(",\"reason\":{}", json_string(reason))
+            }
+        };
+        format!(
+            "{{\"event\":\"{}\",\"timestamp\":{},\"connection_id\":{},\"peer_addr\":\"{}\"{}}}",
+            self.name(),
+            timestamp,
+            self.connection_id,
+            self.peer(),
+            details
+        )
+    }
+
+    /// Emit the event, also passing it to `sink` when configured
+    pub fn emit(&self, sink: Option<&dyn AuditSink>) {
+        match &self.kind {
+            AuthEventKind::AuthSuccess {
+                subject_cn,
+                fingerprint,
+            } => info!(
+                target: AUDIT_TARGET,
+                event = self.name(),
+                connection_id = self.connection_id,
+                peer_addr = %self.peer(),
+                subject_cn = subject_cn.as_deref().unwrap_or(""),
+                fingerprint = fingerprint.as_deref().unwrap_or(""),
+                "Client authenticated"
+            ),
+            AuthEventKind::AuthFailure { reason } => warn!(
+                target: AUDIT_TARGET,
+                event = self.name(),
+                connection_id = self.connection_id,
+                peer_addr = %self.peer(),
+                reason = %reason,
+                "Client authentication failed"
+            ),
+            _ => info!(
+                target: AUDIT_TARGET,
+                event = self.name(),
+                connection_id = self.connection_id,
+                peer_addr = %self.peer(),
+                "mTLS connection event"
+            ),
+        }
+
+        if let Some(sink) = sink {
+            sink.record_auth(self);
+        }
+    }
+}
+
+/// Quote and escape `value` as a JSON string
+fn json_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn json_string_or_null(value: Option<&str>) -> String {
+    value.map_or_else(|| "null".to_string(), json_string)
+}
+
+/// Destination for [`AuthEvent`]s
+///
+/// Called inline on the authentication path, so implementations should not
+/// block for long.
+pub trait AuditSink: Send + Sync {
+    fn record_auth(&self, event: &AuthEvent);
+}
+
+impl<F> AuditSink for F
+where
+    F: Fn(&AuthEvent) + Send + Sync,
+{
+    fn record_auth(&self, event: &AuthEvent) {
+        self(event)
+    }
+}
+
+/// Append-only JSON lines audit file
+#[derive(Debug)]
+pub struct AuditLog {
+    file: Mutex<File>,
+}
+
+impl AuditLog {
+    /// Open (or create) the audit file at `path` for appending
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+
+    /// Append one event
+    pub fn record(&self, event: &ConnectionEstablished) -> Result<()> {
+        self.write_line(event.to_json())
+    }
+
+    fn write_line(&self, mut line: String) -> Result<()> {
+        line.push('\n');
+        self.file.lock().write_all(line.as_bytes())?;
+        Ok(())
+    }
+}
+
+impl AuditSink for AuditLog {
+    fn record_auth(&self, event: &AuthEvent) {
+        if let Err(e) = self.write_line(event.to_json()) {
+            warn!("Failed to write audit log entry: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event() -> ConnectionEstablished {
+        ConnectionEstablished::new(7, PqcAlgorithm::HybridMlKem768, CipherAlgorithm::Aes256Gcm)
+            .with_peer_addr("192.0.2.10:4433".parse().unwrap())
+            .with_client_auth(true)
+    }
+
+    #[test]
+    fn test_to_json_contains_fields() {
+        let json = event().to_json();
+        assert!(json.starts_with("{\"event\":\"connection_established\""));
+        assert!(json.contains("\"peer_addr\":\"192.0.2.10:4433\""));
+        assert!(json.contains("\"algorithm\":\"HybridMlKem768\""));
+        assert!(json.contains("\"cipher\":\"Aes256Gcm\""));
+        assert!(json.contains("\"channel_id\":7"));
+        assert!(json.contains("\"client_auth\":true"));
+    }
+
+    #[test]
+    fn test_unknown_peer() {
+        let event =
+            ConnectionEstablished::new(1, PqcAlgorithm::HybridMlKem768, CipherAlgorithm::Aes256Gcm);
+        assert!(event.to_json().contains("\"peer_addr\":\"unknown\""));
+        assert!(event.to_json().contains("\"client_auth\":false"));
+    }
+
+    #[test]
+    fn test_audit_log_appends_lines() {
+        let path = std::env::temp_dir().join(format!("aegis-audit-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let log = AuditLog::open(&path).unwrap();
+        event().emit(Some(&log));
+        event().emit(Some(&log));
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<_> = contents.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines.iter().all(|l| l.contains("\"channel_id\":7")));
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_auth_event_json_escapes_fields() {
+        let failure = AuthEvent::new(
+            3,
+            None,
+            AuthEventKind::AuthFailure {
+                reason: "bad \"cert\"\n".to_string(),
+            },
+        );
+        assert!(failure.to_json().starts_with("{\"event\":\"auth_failure\""));
+        assert!(
+            failure
+                .to_json()
+                .contains(r#""reason":"bad \"cert\"\u000a""#)
+        );
+
+        let success = AuthEvent::new(
+            4,
+            Some("192.0.2.1:443".parse().unwrap()),
+            AuthEventKind::AuthSuccess {
+                subject_cn: Some("client.example".to_string()),
+                fingerprint: None,
+            },
+        );
+        let json = success.to_json();
+        assert!(json.contains("\"connection_id\":4"));
+        assert!(json.contains("\"peer_addr\":\"192.0.2.1:443\""));
+        assert!(json.contains("\"subject_cn\":\"client.example\",\"fingerprint\":null"));
+    }
+}
@@ -0,0 +1,147 @@
+//! Handshake Cost Comparison
+//!
+//! Measures key generation, encapsulation and decapsulation for every
+//! supported [`PqcAlgorithm`] on the running host, together with the sizes
+//! of the messages each one puts on the wire, so operators can weigh
+//! ML-KEM-1024's extra margin against its cost. The criterion benches in
+//! `benches/` remain the tool for tracking regressions.
+
+use crate::hybrid_kex::{HybridKeyExchange, PqcAlgorithm};
+use aegis_common::{AegisError, Result};
+use std::time::{Duration, Instant};
+
+/// Mean handshake timings and message sizes for one algorithm
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AlgorithmBenchmark {
+    pub algorithm: PqcAlgorithm,
+    pub nist_category: u8,
+    pub keygen: Duration,
+    pub encapsulate: Duration,
+    pub decapsulate: Duration,
+    /// Serialized hybrid public key sent by the server
+    pub public_key_bytes: usize,
+    /// Serialized hybrid ciphertext sent by the client
+    pub ciphertext_bytes: usize,
+}
+
+impl AlgorithmBenchmark {
+    /// Combined cost of one key exchange across both peers
+    pub fn handshake(&self) -> Duration {
+        self.keygen + self.encapsulate + self.decapsulate
+    }
+
+    /// Bytes exchanged by one key exchange
+    pub fn wire_bytes(&self) -> usize {
+        self.public_key_bytes + self.ciphertext_bytes
+    }
+}
+
+/// Benchmark every supported algorithm, strongest first
+///
+/// Each timing is the mean over `iterations` complete key exchanges; at
+/// least one is always run.
+pub fn benchmark_algorithms(iterations: u32) -> Result<Vec<AlgorithmBenchmark>> {
+    PqcAlgorithm::strength_order()
+        .iter()
+        .map(|&algorithm| benchmark_algorithm(algorithm, iterations))
+        .collect()
+}
+
+/// Benchmark a single algorithm, see [`benchmark_algorithms`]
+pub fn benchmark_algorithm(algorithm: PqcAlgorithm, iterations: u32) -> Result<AlgorithmBenchmark> {
+    let iterations = iterations.max(1);
+    let kex = HybridKeyExchange::new_with_level(algorithm.security_level());
+    let (mut keygen, mut encapsulate, mut decapsulate) =
+        (Duration::ZERO, Duration::ZERO, Duration::ZERO);
+    let (mut public_key_bytes, mut ciphertext_bytes) = (0, 0);
+
+    for _ in 0..iterations {
+        let start = Instant::now();
+        let (public_key, secret_key) = kex.generate_keypair()?;
+        keygen += start.elapsed();
+
+        let start = Instant::now();
+        let (ciphertext, client_secret) = kex.encapsulate(&public_key)?;
+        encapsulate += start.elapsed();
+
+        let start = Instant::now();
+        let server_secret = kex.decapsulate(&ciphertext, &secret_key)?;
+        decapsulate += start.elapsed();
+
+        if client_secret.as_bytes() != server_secret.as_bytes() {
+            return Err(AegisError::Crypto(format!(
+                "{} benchmark derived mismatched shared secrets",
+                algorithm.name()
+            )));
+        }
+        public_key_bytes = public_key.to_bytes().len();
+        ciphertext_bytes = ciphertext.to_bytes().len();
+    }
+
+    Ok(AlgorithmBenchmark {
+        algorithm,
+        nist_category: algorithm.nist_category(),
+        keygen: keygen / iterations,
+        encapsulate: encapsulate / iterations,
+        decapsulate: decapsulate / iterations,
+        public_key_bytes,
+        ciphertext_bytes,
+    })
+}
+
+/// Cheapest algorithm meeting a NIST security category (1-5)
+///
+/// Categories up to 3 are met by ML-KEM-768; 4 and 5 need ML-KEM-1024.
+/// Returns `None` for targets no supported algorithm reaches.
+pub fn recommend_algorithm(security_target: u8) -> Option<PqcAlgorithm> {
+    PqcAlgorithm::strength_order()
+        .iter()
+        .rev()
+        .copied()
+        .find(|algorithm| algorithm.nist_category() >= security_target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_benchmark_covers_every_algorithm() {
+        let results = benchmark_algorithms(3).unwrap();
+        let algorithms: Vec<PqcAlgorithm> = results.iter().map(|r| r.algorithm).collect();
+        assert_eq!(algorithms, PqcAlgorithm::strength_order());
+
+        for result in &results {
+            assert!(result.keygen > Duration::ZERO, "{:?}", result);
+            assert!(result.encapsulate > Duration::ZERO, "{:?}", result);
+            assert!(result.decapsulate > Duration::ZERO, "{:?}", result);
+            assert!(result.handshake() >= result.keygen);
+        }
+
+        // ML-KEM-1024 is the larger exchange on the wire
+        let [strong, standard] = results.as_slice() else {
+            panic!("expected two algorithms");
+        };
+        assert_eq!(standard.public_key_bytes, 32 + 1184);
+        assert_eq!(standard.ciphertext_bytes, 32 + 1088);
+        assert_eq!(strong.public_key_bytes, 32 + 1568);
+        assert_eq!(strong.ciphertext_bytes, 32 + 1568);
+        assert!(strong.wire_bytes() > standard.wire_bytes());
+    }
+
+    #[test]
+    fn test_benchmark_runs_at_least_once() {
+        let result = benchmark_algorithm(PqcAlgorithm::HybridMlKem768, 0).unwrap();
+        assert_eq!(result.nist_category, 3);
+        assert!(result.handshake() > Duration::ZERO);
+    }
+
+    #[test]
+    fn test_recommend_algorithm() {
+        assert_eq!(recommend_algorithm(5), Some(PqcAlgorithm::HybridMlKem1024));
+        assert_eq!(recommend_algorithm(4), Some(PqcAlgorithm::HybridMlKem1024));
+        assert_eq!(recommend_algorithm(3), Some(PqcAlgorithm::HybridMlKem768));
+        assert_eq!(recommend_algorithm(1), Some(PqcAlgorithm::HybridMlKem768));
+        assert_eq!(recommend_algorithm(6), None);
+    }
+}
@@ -5,11 +5,14 @@
 //! - Certificate chain validation
 //! - Expiry monitoring
 //! - PEM/DER parsing
+//! - Hot reload of the server certificate when it is rotated on disk
 
 use aegis_common::{AegisError, Result};
+use parking_lot::RwLock;
 use rcgen::{CertificateParams, DnType, KeyPair, SanType};
-use std::path::Path;
-use std::time::SystemTime;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 use tracing::{debug, info, warn};
 use x509_parser::prelude::*;
 
@@ -17,6 +20,15 @@ use x509_parser::prelude::*;
 // Re-import time crate with explicit path to avoid conflict with x509_parser::time
 use ::time as time_crate;
 
+/// Default allowance for clock drift between peers when checking validity
+pub const DEFAULT_CLOCK_SKEW_TOLERANCE: Duration = Duration::from_secs(300);
+
+/// How often a reload task repeats warnings about expiring certificates
+pub const EXPIRY_WARNING_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Most intermediates followed from a certificate toward a trusted CA
+pub const MAX_CHAIN_DEPTH: usize = 8;
+
 /// Certificate type classification
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CertType {
@@ -54,11 +66,23 @@ pub struct ParsedCert {
 impl ParsedCert {
     /// Check if certificate is currently valid
     pub fn is_valid_now(&self) -> bool {
+        self.is_valid_with_tolerance(Duration::ZERO)
+    }
+
+    /// Check if certificate is currently valid, allowing for clock skew
+    pub fn is_valid_with_tolerance(&self, tolerance: Duration) -> bool {
         let now = SystemTime::now()
             .duration_since(SystemTime::UNIX_EPOCH)
             .map(|d| d.as_secs() as i64)
             .unwrap_or(0);
-        now >= self.not_before && now <= self.not_after
+        self.is_valid_at(now, tolerance)
+    }
+
+    /// Check validity at a UTC timestamp, widening both bounds by `tolerance`
+    pub fn is_valid_at(&self, now: i64, tolerance: Duration) -> bool {
+        let tolerance = i64::try_from(tolerance.as_secs()).unwrap_or(i64::MAX);
+        now >= self.not_before.saturating_sub(tolerance)
+            && now <= self.not_after.saturating_add(tolerance)
     }
 
     /// Get days until expiry
@@ -76,15 +100,159 @@ impl ParsedCert {
     }
 }
 
-/// Certificate Manager for handling X.509 certificates
+/// Files the server certificate and private key are reloaded from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CertPaths {
+    /// Certificate file (PEM or DER)
+    pub cert: PathBuf,
+    /// Private key file (PEM)
+    pub key: PathBuf,
+}
+
+impl CertPaths {
+    pub fn new(cert: impl Into<PathBuf>, key: impl Into<PathBuf>) -> Self {
+        Self {
+            cert: cert.into(),
+            key: key.into(),
+        }
+    }
+
+    /// Modification times of both files, if they can be read
+    fn modified(&self) -> Option<(SystemTime, SystemTime)> {
+        let cert = std::fs::metadata(&self.cert)
+            .and_then(|m| m.modified())
+            .ok()?;
+        let key = std::fs::metadata(&self.key)
+            .and_then(|m| m.modified())
+            .ok()?;
+        Some((cert, key))
+    }
+}
+
+/// Called with the new server certificate after every reload
+pub type ReloadHook = Arc<dyn Fn(&ParsedCert) + Send + Sync>;
+
+/// Server certificate and key, always replaced together
+struct ServerIdentity {
+    cert: ParsedCert,
+    private_key_pem: String,
+}
+
+/// Server identity and reload settings, shared with the reload task
 #[derive(Default)]
+struct ReloadState {
+    server: RwLock<Option<Arc<ServerIdentity>>>,
+    paths: RwLock<Option<CertPaths>>,
+    hooks: RwLock<Vec<ReloadHook>>,
+}
+
+impl ReloadState {
+    fn set_server(&self, cert: ParsedCert, private_key_pem: String) {
+        *self.server.write() = Some(Arc::new(ServerIdentity {
+            cert,
+            private_key_pem,
+        }));
+    }
+
+    fn server_cert(&self) -> Option<ParsedCert> {
+        self.server
+            .read()
+            .as_ref()
+            .map(|identity| identity.cert.clone())
+    }
+
+    /// Load the certificate and key from the configured paths and swap them in
+    ///
+    /// The current identity is kept when the certificate is outside its
+    /// validity period or the key does not belong to it, so a rotation
+    /// caught halfway through is retried on the next check.
+    fn reload(&self, tolerance: Duration) -> Result<ParsedCert> {
+        let paths = self.paths.read().clone().ok_or_else(|| {
+            AegisError::Config("No certificate paths configured for reload".to_string())
+        })?;
+        let cert = CertManager::load_from_file(&paths.cert)?;
+        let private_key_pem = std::fs::read_to_string(&paths.key).map_err(|e| {
+            AegisError::Config(format!("Failed to read {}: {}", paths.key.display(), e))
+        })?;
+        if !cert.is_valid_with_tolerance(tolerance) {
+            return Err(AegisError::Crypto(format!(
+                "Certificate {} is not yet valid or has expired",
+                cert.subject_cn
+            )));
+        }
+        check_key_matches(&cert, &private_key_pem)?;
+
+        self.set_server(cert.clone(), private_key_pem);
+        info!(
+            subject = %cert.subject_cn,
+            serial = %cert.serial,
+            days_until_expiry = cert.days_until_expiry(),
+            "Reloaded server certificate from {}",
+            paths.cert.display()
+        );
+        // Clone the hooks so one may register another without deadlocking
+        let hooks = self.hooks.read().clone();
+        for hook in hooks {
+            hook(&cert);
+        }
+        Ok(cert)
+    }
+}
+
+/// Fail unless `private_key_pem` holds the key for `cert`'s public key
+fn check_key_matches(cert: &ParsedCert, private_key_pem: &str) -> Result<()> {
+    let key = KeyPair::from_pem(private_key_pem)
+        .map_err(|e| AegisError::Crypto(format!("Failed to parse private key: {}", e)))?;
+    let x509 = CertManager::x509(cert)?;
+    if x509.public_key().raw != key.public_key_der().as_slice() {
+        return Err(AegisError::Crypto(format!(
+            "Private key does not match certificate {}",
+            cert.subject_cn
+        )));
+    }
+    Ok(())
+}
+
+/// Certificates within 30 days of expiry
+fn expiring_certs<'a>(
+    trusted_cas: &'a [ParsedCert],
+    server_cert: Option<&'a ParsedCert>,
+) -> Vec<&'a ParsedCert> {
+    trusted_cas
+        .iter()
+        .chain(server_cert)
+        .filter(|cert| cert.is_expiring_soon())
+        .collect()
+}
+
+fn warn_expiring(certs: &[&ParsedCert]) {
+    for cert in certs {
+        warn!(
+            subject = %cert.subject_cn,
+            days_until_expiry = cert.days_until_expiry(),
+            "Certificate expires within 30 days"
+        );
+    }
+}
+
+/// Certificate Manager for handling X.509 certificates
 pub struct CertManager {
     /// Trusted CA certificates
     trusted_cas: Vec<ParsedCert>,
-    /// Server certificate
-    server_cert: Option<ParsedCert>,
-    /// Private key (PEM format)
-    private_key_pem: Option<String>,
+    /// Server certificate and key, swapped on reload
+    reload: Arc<ReloadState>,
+    /// Allowed clock skew when checking CA validity
+    clock_skew_tolerance: Duration,
+}
+
+impl Default for CertManager {
+    fn default() -> Self {
+        Self {
+            trusted_cas: Vec::new(),
+            reload: Arc::new(ReloadState::default()),
+            clock_skew_tolerance: DEFAULT_CLOCK_SKEW_TOLERANCE,
+        }
+    }
 }
 
 impl CertManager {
@@ -93,6 +261,17 @@ impl CertManager {
         Self::default()
     }
 
+    /// Set the allowed clock skew for validity checks
+    pub fn with_clock_skew_tolerance(mut self, tolerance: Duration) -> Self {
+        self.clock_skew_tolerance = tolerance;
+        self
+    }
+
+    /// Allowed clock skew for validity checks
+    pub fn clock_skew_tolerance(&self) -> Duration {
+        self.clock_skew_tolerance
+    }
+
     /// Parse a PEM-encoded certificate
     pub fn parse_pem(pem_data: &[u8]) -> Result<ParsedCert> {
         let pem_parsed = ::pem::parse(pem_data)
@@ -213,39 +392,226 @@ impl CertManager {
             warn!("Server certificate is a CA certificate, this may not be intended");
         }
         info!("Set server certificate: {}", cert.subject_cn);
-        self.server_cert = Some(cert);
-        self.private_key_pem = Some(private_key_pem);
+        self.reload.set_server(cert, private_key_pem);
         Ok(())
     }
 
-    /// Verify a certificate chain
-    pub fn verify_chain(&self, cert: &ParsedCert) -> Result<bool> {
-        // Check if the issuer is in trusted CAs
-        for ca in &self.trusted_cas {
-            if cert.issuer_cn == ca.subject_cn {
-                if !ca.is_valid_now() {
-                    return Err(AegisError::Crypto("CA certificate has expired".to_string()));
+    /// Keep the server certificate in sync with `paths`
+    ///
+    /// Spawns a task on the current Tokio runtime that checks the files'
+    /// modification times every `interval` and swaps in the new certificate
+    /// and key when either changes, starting with the first check. A pair
+    /// that does not match or is outside its validity period is skipped and
+    /// tried again on the next check. It also
+    /// warns about certificates within 30 days of expiry, repeating at most
+    /// every [`EXPIRY_WARNING_INTERVAL`]; CAs trusted after the task starts
+    /// are not included. Abort the returned handle to stop watching.
+    pub fn watch_and_reload(
+        &self,
+        paths: CertPaths,
+        interval: Duration,
+    ) -> tokio::task::JoinHandle<()> {
+        *self.reload.paths.write() = Some(paths.clone());
+        let state = self.reload.clone();
+        let trusted_cas = self.trusted_cas.clone();
+        let tolerance = self.clock_skew_tolerance;
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            let mut last_modified = None;
+            let mut last_warning: Option<tokio::time::Instant> = None;
+
+            loop {
+                ticker.tick().await;
+
+                let modified = paths.modified();
+                if modified.is_some() && modified != last_modified {
+                    match state.reload(tolerance) {
+                        Ok(_) => last_modified = modified,
+                        Err(e) => warn!("Failed to reload server certificate: {}", e),
+                    }
                 }
-                debug!(
-                    "Certificate {} issued by trusted CA {}",
-                    cert.subject_cn, ca.subject_cn
-                );
+
+                if last_warning.is_none_or(|at| at.elapsed() >= EXPIRY_WARNING_INTERVAL) {
+                    let server_cert = state.server_cert();
+                    warn_expiring(&expiring_certs(&trusted_cas, server_cert.as_ref()));
+                    last_warning = Some(tokio::time::Instant::now());
+                }
+            }
+        })
+    }
+
+    /// Reload the server certificate and key from the paths given to
+    /// [`watch_and_reload`](Self::watch_and_reload) right away
+    pub fn reload_now(&self) -> Result<ParsedCert> {
+        self.reload.reload(self.clock_skew_tolerance)
+    }
+
+    /// Run `hook` with the new certificate after every reload
+    pub fn on_reload(&self, hook: impl Fn(&ParsedCert) + Send + Sync + 'static) {
+        self.reload.hooks.write().push(Arc::new(hook));
+    }
+
+    /// Verify a certificate chain against the trusted CAs
+    pub fn verify_chain(&self, cert: &ParsedCert) -> Result<bool> {
+        self.verify_chain_with_intermediates(cert, &[])
+    }
+
+    /// Verify a certificate chain, following `intermediates` supplied by the
+    /// peer toward a trusted CA
+    ///
+    /// Every link must carry a signature made by its issuer's key, and every
+    /// issuer must be a CA whose path length constraint allows the
+    /// intermediates below it. Issuers are matched on their full
+    /// distinguished name, so copying a CA's common name is not enough. A
+    /// certificate that is itself in the trusted store is accepted as is.
+    pub fn verify_chain_with_intermediates(
+        &self,
+        cert: &ParsedCert,
+        intermediates: &[ParsedCert],
+    ) -> Result<bool> {
+        let mut current = cert;
+        let mut current_x509 = Self::x509(cert)?;
+
+        for depth in 0..=MAX_CHAIN_DEPTH {
+            if let Some(ca) = self
+                .trusted_cas
+                .iter()
+                .find(|ca| ca.der_bytes == current.der_bytes)
+            {
+                self.check_ca_validity(ca)?;
+                debug!("Certificate {} is a trusted CA", ca.subject_cn);
                 return Ok(true);
             }
-        }
 
-        // Self-signed check
-        if cert.subject_cn == cert.issuer_cn && cert.cert_type == CertType::RootCa {
-            debug!("Certificate {} is self-signed root CA", cert.subject_cn);
-            return Ok(true);
+            let mut rejected = None;
+            for ca in &self.trusted_cas {
+                match Self::check_issuer(&current_x509, ca, depth) {
+                    Ok(true) => {
+                        self.check_ca_validity(ca)?;
+                        debug!(
+                            "Certificate {} issued by trusted CA {}",
+                            current.subject_cn, ca.subject_cn
+                        );
+                        return Ok(true);
+                    }
+                    Ok(false) => {}
+                    Err(e) => rejected = Some(e),
+                }
+            }
+
+            let mut next = None;
+            for intermediate in intermediates {
+                match Self::check_issuer(&current_x509, intermediate, depth) {
+                    Ok(true) => {
+                        next = Some(intermediate);
+                        break;
+                    }
+                    Ok(false) => {}
+                    Err(e) => rejected = Some(e),
+                }
+            }
+            let Some(issuer) = next else {
+                return Err(rejected.unwrap_or_else(|| {
+                    AegisError::Crypto(format!(
+                        "Issuer {} not found in trusted CAs",
+                        current.issuer_cn
+                    ))
+                }));
+            };
+            if !issuer.is_valid_with_tolerance(self.clock_skew_tolerance) {
+                return Err(AegisError::Crypto(format!(
+                    "Intermediate CA certificate {} has expired",
+                    issuer.subject_cn
+                )));
+            }
+            debug!(
+                "Certificate {} issued by intermediate CA {}",
+                current.subject_cn, issuer.subject_cn
+            );
+            current = issuer;
+            current_x509 = Self::x509(issuer)?;
         }
 
         Err(AegisError::Crypto(format!(
-            "Issuer {} not found in trusted CAs",
-            cert.issuer_cn
+            "Certificate chain for {} is longer than {} intermediates",
+            cert.subject_cn, MAX_CHAIN_DEPTH
         )))
     }
 
+    fn x509(cert: &ParsedCert) -> Result<X509Certificate<'_>> {
+        X509Certificate::from_der(&cert.der_bytes)
+            .map(|(_, x509)| x509)
+            .map_err(|e| {
+                AegisError::Crypto(format!(
+                    "Cannot verify certificate {}: {:?}",
+                    cert.subject_cn, e
+                ))
+            })
+    }
+
+    fn check_ca_validity(&self, ca: &ParsedCert) -> Result<()> {
+        if !ca.is_valid_with_tolerance(self.clock_skew_tolerance) {
+            return Err(AegisError::Crypto("CA certificate has expired".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Whether `issuer` issued `cert`, with `intermediates_below` CA
+    /// certificates between it and the end entity
+    ///
+    /// Returns `Ok(false)` when the names do not match and an error when they
+    /// do but the issuer may not sign certificates or the signature is bad.
+    fn check_issuer(
+        cert: &X509Certificate<'_>,
+        issuer: &ParsedCert,
+        intermediates_below: usize,
+    ) -> Result<bool> {
+        let Ok((_, issuer_x509)) = X509Certificate::from_der(&issuer.der_bytes) else {
+            return Ok(false);
+        };
+        if issuer_x509.subject().as_raw() != cert.issuer().as_raw() {
+            return Ok(false);
+        }
+
+        match issuer_x509.basic_constraints() {
+            Ok(Some(constraints)) if constraints.value.ca => {
+                if let Some(max) = constraints.value.path_len_constraint
+                    && intermediates_below > max as usize
+                {
+                    return Err(AegisError::Crypto(format!(
+                        "Path length constraint of {} exceeded",
+                        issuer.subject_cn
+                    )));
+                }
+            }
+            _ => {
+                return Err(AegisError::Crypto(format!(
+                    "Issuer {} is not a CA certificate",
+                    issuer.subject_cn
+                )));
+            }
+        }
+        if let Ok(Some(usage)) = issuer_x509.key_usage()
+            && !usage.value.key_cert_sign()
+        {
+            return Err(AegisError::Crypto(format!(
+                "Issuer {} may not sign certificates",
+                issuer.subject_cn
+            )));
+        }
+
+        cert.verify_signature(Some(issuer_x509.public_key()))
+            .map_err(|e| {
+                AegisError::Crypto(format!(
+                    "Signature does not verify against {}: {:?}",
+                    issuer.subject_cn, e
+                ))
+            })?;
+        Ok(true)
+    }
+
     /// Generate a self-signed certificate for testing
     pub fn generate_self_signed(
         cn: &str,
@@ -298,33 +664,26 @@ impl CertManager {
     }
 
     /// Get all certificates that are expiring soon
-    pub fn get_expiring_certs(&self) -> Vec<&ParsedCert> {
-        // Estimate: usually 0-2 certs expiring
-        let mut expiring = Vec::with_capacity(self.trusted_cas.len() + 1);
-
-        for ca in &self.trusted_cas {
-            if ca.is_expiring_soon() {
-                expiring.push(ca);
-            }
-        }
-
-        if let Some(ref cert) = self.server_cert
-            && cert.is_expiring_soon()
-        {
-            expiring.push(cert);
-        }
-
-        expiring
+    pub fn get_expiring_certs(&self) -> Vec<ParsedCert> {
+        let server_cert = self.reload.server_cert();
+        expiring_certs(&self.trusted_cas, server_cert.as_ref())
+            .into_iter()
+            .cloned()
+            .collect()
     }
 
     /// Get the server certificate
-    pub fn server_cert(&self) -> Option<&ParsedCert> {
-        self.server_cert.as_ref()
+    pub fn server_cert(&self) -> Option<ParsedCert> {
+        self.reload.server_cert()
     }
 
     /// Get the private key PEM
-    pub fn private_key_pem(&self) -> Option<&str> {
-        self.private_key_pem.as_deref()
+    pub fn private_key_pem(&self) -> Option<String> {
+        self.reload
+            .server
+            .read()
+            .as_ref()
+            .map(|identity| identity.private_key_pem.clone())
     }
 
     /// Get trusted CA count
@@ -337,6 +696,40 @@ impl CertManager {
 mod tests {
     use super::*;
 
+    /// CA with the given path length constraint, if any
+    fn test_ca(cn: &str, path_len: Option<u8>) -> (rcgen::Certificate, KeyPair) {
+        let mut params = CertificateParams::default();
+        params.distinguished_name.push(DnType::CommonName, cn);
+        params.is_ca = rcgen::IsCa::Ca(match path_len {
+            Some(len) => rcgen::BasicConstraints::Constrained(len),
+            None => rcgen::BasicConstraints::Unconstrained,
+        });
+        let key = KeyPair::generate().unwrap();
+        let cert = params.self_signed(&key).unwrap();
+        (cert, key)
+    }
+
+    /// Certificate for `cn` signed by `issuer`
+    fn issue(
+        cn: &str,
+        is_ca: bool,
+        issuer: &rcgen::Certificate,
+        issuer_key: &KeyPair,
+    ) -> (rcgen::Certificate, KeyPair) {
+        let mut params = CertificateParams::default();
+        params.distinguished_name.push(DnType::CommonName, cn);
+        if is_ca {
+            params.is_ca = rcgen::IsCa::Ca(rcgen::BasicConstraints::Unconstrained);
+        }
+        let key = KeyPair::generate().unwrap();
+        let cert = params.signed_by(&key, issuer, issuer_key).unwrap();
+        (cert, key)
+    }
+
+    fn parsed(cert: &rcgen::Certificate) -> ParsedCert {
+        CertManager::parse_der(cert.der()).unwrap()
+    }
+
     #[test]
     fn test_generate_self_signed() {
         let (cert_pem, key_pem) = CertManager::generate_self_signed(
@@ -483,34 +876,23 @@ mod tests {
 
     #[test]
     fn test_verify_chain_expired_ca() {
-        let (ca_pem, _) = CertManager::generate_self_signed("Expired CA", &[], 365).unwrap();
-        let mut ca_cert = CertManager::parse_pem(ca_pem.as_bytes()).unwrap();
+        let (ca, ca_key) = test_ca("Expired CA", None);
+        let mut ca_cert = parsed(&ca);
 
         // Force expiry
         let now = SystemTime::now()
             .duration_since(SystemTime::UNIX_EPOCH)
             .unwrap()
             .as_secs() as i64;
-        ca_cert.not_after = now - 100; // Expired
-        ca_cert.cert_type = CertType::RootCa;
+        ca_cert.not_after = now - 3600; // Expired beyond the skew tolerance
 
         let mut manager = CertManager::new();
         manager.add_trusted_ca(ca_cert).unwrap();
 
-        let leaf = ParsedCert {
-            subject_cn: "leaf".to_string(),
-            issuer_cn: "Expired CA".to_string(), // Matches expired CA
-            serial: "1".to_string(),
-            not_before: now,
-            not_after: now + 1000,
-            cert_type: CertType::EndEntity,
-            fingerprint: "abc".to_string(),
-            san: vec![],
-            der_bytes: vec![],
-        };
+        let (leaf, _) = issue("leaf", false, &ca, &ca_key);
 
         // Should fail due to expired CA
-        let result = manager.verify_chain(&leaf);
+        let result = manager.verify_chain(&parsed(&leaf));
         assert!(result.is_err());
         match result {
             Err(AegisError::Crypto(msg)) => assert!(msg.contains("expired")),
@@ -667,26 +1049,15 @@ mod tests {
 
     #[test]
     fn test_verify_chain_self_signed_root() {
-        let manager = CertManager::new();
-        let now = SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .unwrap()
-            .as_secs() as i64;
+        let (root, _) = test_ca("self-signed-root", None);
+        let root_ca = parsed(&root);
 
-        let root_ca = ParsedCert {
-            subject_cn: "self-signed-root".to_string(),
-            issuer_cn: "self-signed-root".to_string(),
-            serial: "001".to_string(),
-            not_before: now - 86400,
-            not_after: now + 86400 * 365,
-            cert_type: CertType::RootCa,
-            fingerprint: "root-fp".to_string(),
-            san: vec![],
-            der_bytes: vec![],
-        };
+        // Being self-signed is not enough to be trusted
+        let mut manager = CertManager::new();
+        assert!(manager.verify_chain(&root_ca).is_err());
 
-        let result = manager.verify_chain(&root_ca);
-        assert!(result.unwrap());
+        manager.add_trusted_ca(root_ca.clone()).unwrap();
+        assert!(manager.verify_chain(&root_ca).unwrap());
     }
 
     #[test]
@@ -723,7 +1094,7 @@ mod tests {
     fn test_cert_manager_empty_initialization() {
         let manager = CertManager::new();
         assert!(manager.trusted_cas.is_empty());
-        assert!(manager.server_cert.is_none());
+        assert!(manager.server_cert().is_none());
     }
 
     #[test]
@@ -889,43 +1260,74 @@ mod tests {
     }
 
     #[test]
-    fn test_intermediate_chain_verification_mock() {
-        // Creating a full valid chain with rcgen is complex because we need to sign the intermediate cert with the root key.
-        // Instead, we verify the logic flow of `verify_chain` which iterates `trusted_cas` looking for `issuer_cn`.
-        // We will create 3 certs: Root, Intermediate, Leaf.
-        // We TRUST the Intermediate.
-        // Leaf is issued by Intermediate.
-        // Root is issued by itself.
-        // Intermediate is issued by Root.
+    fn test_intermediate_chain_verification() {
+        let (root, root_key) = test_ca("Root CA", None);
+        let (intermediate, int_key) = issue("Intermediate CA", true, &root, &root_key);
+        let (leaf, _) = issue("Leaf", false, &intermediate, &int_key);
+        let int_cert = parsed(&intermediate);
+        let leaf = parsed(&leaf);
+        assert_eq!(int_cert.cert_type, CertType::IntermediateCa);
+
+        // Trusting the root needs the intermediate to complete the chain
+        let mut manager = CertManager::new();
+        manager.add_trusted_ca(parsed(&root)).unwrap();
+        assert!(manager.verify_chain(&leaf).is_err());
+        assert!(
+            manager
+                .verify_chain_with_intermediates(&leaf, std::slice::from_ref(&int_cert))
+                .unwrap()
+        );
 
-        // 1. Generate Intermediate CA
-        let (int_pem, _) = CertManager::generate_self_signed("Intermediate CA", &[], 365).unwrap();
-        let mut int_cert = CertManager::parse_pem(int_pem.as_bytes()).unwrap();
-        int_cert.cert_type = CertType::IntermediateCa;
+        // A trusted intermediate anchors the chain on its own
+        let mut manager = CertManager::new();
+        manager.add_trusted_ca(int_cert).unwrap();
+        assert!(manager.verify_chain(&leaf).unwrap());
+    }
 
-        // 2. Add Intermediate to Trusted CAs
+    #[test]
+    fn test_verify_chain_rejects_forged_issuer_cn() {
+        let (ca, ca_key) = test_ca("Aegis Root CA", None);
         let mut manager = CertManager::new();
-        manager.add_trusted_ca(int_cert.clone()).unwrap();
+        manager.add_trusted_ca(parsed(&ca)).unwrap();
 
-        // 3. Mock a Leaf cert issued by Intermediate
-        let now = SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .unwrap()
-            .as_secs() as i64;
-        let leaf = ParsedCert {
-            subject_cn: "Leaf".to_string(),
-            issuer_cn: "Intermediate CA".to_string(), // Matches trusted intermediate
-            serial: "100".to_string(),
-            not_before: now - 100,
-            not_after: now + 1000,
-            cert_type: CertType::EndEntity,
-            fingerprint: "leaf-fp".to_string(),
-            san: vec![],
-            der_bytes: vec![],
-        };
+        // An attacker's own "CA" with the same name signs a leaf
+        let (forged_ca, forged_key) = test_ca("Aegis Root CA", None);
+        let (forged_leaf, _) = issue("client", false, &forged_ca, &forged_key);
+        let forged_leaf = parsed(&forged_leaf);
+        assert_eq!(forged_leaf.issuer_cn, "Aegis Root CA");
+        let err = manager.verify_chain(&forged_leaf).unwrap_err();
+        assert!(err.to_string().contains("Signature"));
 
-        // 4. Verify
-        assert!(manager.verify_chain(&leaf).is_ok());
+        // ...or the forged CA certificate is presented directly
+        assert!(manager.verify_chain(&parsed(&forged_ca)).is_err());
+
+        let (leaf, _) = issue("client", false, &ca, &ca_key);
+        assert!(manager.verify_chain(&parsed(&leaf)).unwrap());
+    }
+
+    #[test]
+    fn test_verify_chain_enforces_basic_constraints() {
+        let (root, root_key) = test_ca("Constrained Root", Some(0));
+        let (intermediate, int_key) = issue("Intermediate CA", true, &root, &root_key);
+        let (leaf, _) = issue("Leaf", false, &intermediate, &int_key);
+
+        let mut manager = CertManager::new();
+        manager.add_trusted_ca(parsed(&root)).unwrap();
+        let err = manager
+            .verify_chain_with_intermediates(&parsed(&leaf), &[parsed(&intermediate)])
+            .unwrap_err();
+        assert!(err.to_string().contains("Path length"));
+
+        // An end-entity certificate cannot act as an intermediate
+        let (root, root_key) = test_ca("Root CA", None);
+        let (not_ca, not_ca_key) = issue("Not A CA", false, &root, &root_key);
+        let (leaf, _) = issue("Leaf", false, &not_ca, &not_ca_key);
+        let mut manager = CertManager::new();
+        manager.add_trusted_ca(parsed(&root)).unwrap();
+        let err = manager
+            .verify_chain_with_intermediates(&parsed(&leaf), &[parsed(&not_ca)])
+            .unwrap_err();
+        assert!(err.to_string().contains("not a CA"));
     }
 
     #[test]
@@ -1096,6 +1498,52 @@ mod tests {
         assert!(!cert.is_valid_now());
     }
 
+    #[test]
+    fn test_parsed_cert_clock_skew_tolerance() {
+        let now = 1_700_000_000;
+        let cert = ParsedCert {
+            subject_cn: "skewed".to_string(),
+            issuer_cn: "test".to_string(),
+            serial: "003".to_string(),
+            not_before: now - 3600,
+            not_after: now - 100, // expired 100s ago
+            cert_type: CertType::EndEntity,
+            fingerprint: "fp".to_string(),
+            san: vec![],
+            der_bytes: vec![],
+        };
+
+        assert!(!cert.is_valid_at(now, Duration::ZERO));
+        assert!(cert.is_valid_at(now, DEFAULT_CLOCK_SKEW_TOLERANCE));
+        assert!(!cert.is_valid_at(now + 201, DEFAULT_CLOCK_SKEW_TOLERANCE));
+
+        // Not yet valid by less than the tolerance
+        assert!(cert.is_valid_at(now - 3600 - 300, DEFAULT_CLOCK_SKEW_TOLERANCE));
+        assert!(!cert.is_valid_at(now - 3600 - 301, DEFAULT_CLOCK_SKEW_TOLERANCE));
+    }
+
+    #[test]
+    fn test_verify_chain_tolerates_ca_skew() {
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        let (ca_cert, ca_key) = test_ca("Skewed CA", None);
+        let mut ca = parsed(&ca_cert);
+        ca.not_after = now - 100;
+        let (leaf, _) = issue("leaf", false, &ca_cert, &ca_key);
+        let leaf = parsed(&leaf);
+
+        let mut manager = CertManager::new();
+        assert_eq!(manager.clock_skew_tolerance(), DEFAULT_CLOCK_SKEW_TOLERANCE);
+        manager.add_trusted_ca(ca.clone()).unwrap();
+        assert!(manager.verify_chain(&leaf).is_ok());
+
+        let mut strict = CertManager::new().with_clock_skew_tolerance(Duration::ZERO);
+        strict.add_trusted_ca(ca).unwrap();
+        assert!(strict.verify_chain(&leaf).is_err());
+    }
+
     #[test]
     fn test_parsed_cert_days_until_expiry() {
         let now = std::time::SystemTime::now()
@@ -1345,7 +1793,10 @@ mod tests {
             .set_server_cert(cert, "my-private-key-pem".to_string())
             .unwrap();
 
-        assert_eq!(manager.private_key_pem(), Some("my-private-key-pem"));
+        assert_eq!(
+            manager.private_key_pem().as_deref(),
+            Some("my-private-key-pem")
+        );
     }
 
     #[test]
@@ -1358,33 +1809,16 @@ mod tests {
             .as_secs() as i64;
 
         // Add expired CA
-        let expired_ca = ParsedCert {
-            subject_cn: "Expired CA".to_string(),
-            issuer_cn: "Expired CA".to_string(),
-            serial: "1".to_string(),
-            not_before: now - 86400 * 400,
-            not_after: now - 86400, // Expired yesterday
-            cert_type: CertType::RootCa,
-            fingerprint: "fp".to_string(),
-            san: vec![],
-            der_bytes: vec![],
-        };
+        let (ca, ca_key) = test_ca("Expired CA", None);
+        let mut expired_ca = parsed(&ca);
+        expired_ca.not_before = now - 86400 * 400;
+        expired_ca.not_after = now - 86400; // Expired yesterday
         manager.add_trusted_ca(expired_ca).unwrap();
 
         // Create cert issued by expired CA
-        let cert = ParsedCert {
-            subject_cn: "server".to_string(),
-            issuer_cn: "Expired CA".to_string(),
-            serial: "2".to_string(),
-            not_before: now,
-            not_after: now + 86400,
-            cert_type: CertType::EndEntity,
-            fingerprint: "fp2".to_string(),
-            san: vec![],
-            der_bytes: vec![],
-        };
+        let (cert, _) = issue("server", false, &ca, &ca_key);
 
-        let result = manager.verify_chain(&cert);
+        let result = manager.verify_chain(&parsed(&cert));
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("expired"));
     }
@@ -1444,27 +1878,12 @@ mod tests {
 
     #[test]
     fn test_verify_chain_issuer_not_found() {
-        // Line 243-246: verify_chain should error when issuer not in trusted CAs
         let manager = CertManager::new(); // Empty trusted CAs
 
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::SystemTime::UNIX_EPOCH)
-            .unwrap()
-            .as_secs() as i64;
+        let (ca, ca_key) = test_ca("UnknownCA", None);
+        let (cert, _) = issue("server", false, &ca, &ca_key);
 
-        let cert = ParsedCert {
-            subject_cn: "server".to_string(),
-            issuer_cn: "UnknownCA".to_string(), // Not in trusted CAs
-            serial: "456".to_string(),
-            not_before: now - 86400,
-            not_after: now + 86400 * 365,
-            cert_type: CertType::EndEntity,
-            fingerprint: "fp".to_string(),
-            san: vec![],
-            der_bytes: vec![],
-        };
-
-        let result = manager.verify_chain(&cert);
+        let result = manager.verify_chain(&parsed(&cert));
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("not found"));
     }
@@ -1533,32 +1952,13 @@ mod tests {
             .with_test_writer()
             .try_init();
 
-        let (ca_pem, _ca_key_pem) =
-            CertManager::generate_self_signed("Logging CA", &[], 365).unwrap();
-        let mut ca_cert = CertManager::parse_pem(ca_pem.as_bytes()).unwrap();
-        ca_cert.cert_type = CertType::RootCa; // MARK AS CA explicitly
-
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::SystemTime::UNIX_EPOCH)
-            .unwrap()
-            .as_secs() as i64;
-
-        let child_cert = ParsedCert {
-            subject_cn: "Child".to_string(),
-            issuer_cn: "Logging CA".to_string(), // Matches CA
-            serial: "100".to_string(),
-            not_before: now - 100,
-            not_after: now + 1000,
-            cert_type: CertType::EndEntity,
-            fingerprint: "fp".to_string(),
-            san: vec![],
-            der_bytes: vec![],
-        };
+        let (ca, ca_key) = test_ca("Logging CA", None);
+        let (child, _) = issue("Child", false, &ca, &ca_key);
 
         let mut manager = CertManager::new();
-        manager.add_trusted_ca(ca_cert).unwrap();
+        manager.add_trusted_ca(parsed(&ca)).unwrap();
 
-        assert!(manager.verify_chain(&child_cert).is_ok());
+        assert!(manager.verify_chain(&parsed(&child)).is_ok());
     }
 
     #[test]
@@ -1608,4 +2008,136 @@ mod tests {
         let result = CertManager::generate_self_signed("test", &sans, 1);
         assert!(result.is_ok());
     }
+
+    /// Write a self-signed certificate for `cn` and its key, dated `modified`
+    fn write_identity(paths: &CertPaths, cn: &str, modified: SystemTime) {
+        let (cert_pem, key_pem) = CertManager::generate_self_signed(cn, &[], 365).unwrap();
+        for (path, contents) in [(&paths.cert, cert_pem), (&paths.key, key_pem)] {
+            std::fs::write(path, contents).unwrap();
+            let file = std::fs::File::options().write(true).open(path).unwrap();
+            file.set_modified(modified).unwrap();
+        }
+    }
+
+    async fn wait_for_subject(manager: &CertManager, cn: &str) {
+        for _ in 0..100 {
+            if manager
+                .server_cert()
+                .is_some_and(|cert| cert.subject_cn == cn)
+            {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("server certificate never became {}", cn);
+    }
+
+    #[tokio::test]
+    async fn test_watch_and_reload_picks_up_rotated_cert() {
+        let dir = std::env::temp_dir().join(format!("aegis-cert-reload-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let paths = CertPaths::new(dir.join("server.crt"), dir.join("server.key"));
+        let start = SystemTime::now();
+        write_identity(&paths, "first.aegis.local", start);
+
+        let manager = CertManager::new();
+        let reloaded = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let seen = reloaded.clone();
+        manager.on_reload(move |cert| seen.lock().push(cert.subject_cn.clone()));
+
+        let watcher = manager.watch_and_reload(paths.clone(), Duration::from_millis(20));
+        wait_for_subject(&manager, "first.aegis.local").await;
+        let first_key = manager.private_key_pem().unwrap();
+
+        // Rotate both files on disk
+        write_identity(
+            &paths,
+            "second.aegis.local",
+            start + Duration::from_secs(60),
+        );
+        wait_for_subject(&manager, "second.aegis.local").await;
+        assert_ne!(manager.private_key_pem().unwrap(), first_key);
+        assert_eq!(
+            *reloaded.lock(),
+            vec!["first.aegis.local", "second.aegis.local"]
+        );
+
+        watcher.abort();
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_reload_now() {
+        let manager = CertManager::new();
+        assert!(matches!(manager.reload_now(), Err(AegisError::Config(_))));
+
+        let dir =
+            std::env::temp_dir().join(format!("aegis-cert-reload-now-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let paths = CertPaths::new(dir.join("server.crt"), dir.join("server.key"));
+        write_identity(&paths, "manual.aegis.local", SystemTime::now());
+        *manager.reload.paths.write() = Some(paths);
+
+        let cert = manager.reload_now().unwrap();
+        assert_eq!(cert.subject_cn, "manual.aegis.local");
+        assert_eq!(manager.server_cert().unwrap().fingerprint, cert.fingerprint);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_reload_keeps_identity_until_key_matches() {
+        let manager = CertManager::new();
+        let dir =
+            std::env::temp_dir().join(format!("aegis-cert-reload-half-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let paths = CertPaths::new(dir.join("server.crt"), dir.join("server.key"));
+        write_identity(&paths, "old.aegis.local", SystemTime::now());
+        *manager.reload.paths.write() = Some(paths.clone());
+        manager.reload_now().unwrap();
+        let old_key = manager.private_key_pem().unwrap();
+
+        // Only the certificate has been rotated so far
+        let (cert_pem, key_pem) =
+            CertManager::generate_self_signed("new.aegis.local", &[], 365).unwrap();
+        std::fs::write(&paths.cert, cert_pem).unwrap();
+        assert!(matches!(manager.reload_now(), Err(AegisError::Crypto(_))));
+        assert_eq!(manager.server_cert().unwrap().subject_cn, "old.aegis.local");
+        assert_eq!(manager.private_key_pem().unwrap(), old_key);
+
+        // The next attempt succeeds once the key is in place
+        std::fs::write(&paths.key, key_pem).unwrap();
+        assert_eq!(manager.reload_now().unwrap().subject_cn, "new.aegis.local");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_reload_rejects_expired_cert() {
+        let manager = CertManager::new();
+        let dir =
+            std::env::temp_dir().join(format!("aegis-cert-reload-expired-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let paths = CertPaths::new(dir.join("server.crt"), dir.join("server.key"));
+        write_identity(&paths, "current.aegis.local", SystemTime::now());
+        *manager.reload.paths.write() = Some(paths.clone());
+        manager.reload_now().unwrap();
+
+        let mut params = CertificateParams::default();
+        params
+            .distinguished_name
+            .push(DnType::CommonName, "expired.aegis.local");
+        let now = time_crate::OffsetDateTime::now_utc();
+        params.not_before = now - time_crate::Duration::days(30);
+        params.not_after = now - time_crate::Duration::days(1);
+        let key = KeyPair::generate().unwrap();
+        let cert = params.self_signed(&key).unwrap();
+        std::fs::write(&paths.cert, cert.pem()).unwrap();
+        std::fs::write(&paths.key, key.serialize_pem()).unwrap();
+
+        assert!(matches!(manager.reload_now(), Err(AegisError::Crypto(_))));
+        assert_eq!(
+            manager.server_cert().unwrap().subject_cn,
+            "current.aegis.local"
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -1,24 +1,30 @@
 //! Symmetric Encryption Module
 //!
-//! Provides AES-256-GCM and ChaCha20-Poly1305 encryption for secure data transfer.
+//! Provides AES-256-GCM, AES-128-GCM and ChaCha20-Poly1305 encryption for
+//! secure data transfer.
 //!
 //! Security properties:
 //! - Keys are zeroized on drop via `ZeroizeOnDrop`
-//! - Nonce counter is monotonically increasing; exhaustion returns `Err`
+//! - Nonce counter is monotonically increasing and never wraps; past a soft
+//!   limit (2^48 by default) `encrypt` returns `Err` until the key is rotated
+//! - Nonces are a 32-bit per-instance prefix followed by the 64-bit counter,
+//!   so ciphers sharing a key only collide if their prefixes match
 
 use aegis_common::{AegisError, Result};
 use aes_gcm::{
-    Aes256Gcm, Nonce,
-    aead::{Aead, KeyInit},
+    Aes128Gcm, Aes256Gcm, Nonce,
+    aead::{Aead, KeyInit, Payload},
 };
 use chacha20poly1305::ChaCha20Poly1305;
 use hkdf::Hkdf;
+use rand::RngCore;
+use rand::rngs::OsRng;
 use sha2::Sha256;
 use std::sync::atomic::{AtomicU64, Ordering};
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
-/// Maximum safe nonce value. One below u64::MAX to leave a sentinel.
-const NONCE_EXHAUSTION_THRESHOLD: u64 = u64::MAX - 1;
+/// Default number of nonces a key may use before `encrypt` refuses to continue
+pub const DEFAULT_NONCE_LIMIT: u64 = 1 << 48;
 
 /// Cipher algorithm selection
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -28,6 +34,18 @@ pub enum CipherAlgorithm {
     Aes256Gcm,
     /// ChaCha20-Poly1305 (recommended for software-only)
     ChaCha20Poly1305,
+    /// AES-128-GCM (for constrained peers that prefer 128-bit keys)
+    Aes128Gcm,
+}
+
+impl CipherAlgorithm {
+    /// Key length in bytes
+    pub fn key_len(self) -> usize {
+        match self {
+            Self::Aes256Gcm | Self::ChaCha20Poly1305 => 32,
+            Self::Aes128Gcm => 16,
+        }
+    }
 }
 
 /// Encryption key derived from shared secret.
@@ -35,6 +53,7 @@ pub enum CipherAlgorithm {
 /// The key material is zeroized on drop via [`ZeroizeOnDrop`].
 #[derive(Clone, Zeroize, ZeroizeOnDrop)]
 pub struct EncryptionKey {
+    /// Only the first `algorithm.key_len()` bytes are used; the rest stay zero
     key: [u8; 32],
     #[zeroize(skip)]
     algorithm: CipherAlgorithm,
@@ -42,17 +61,23 @@ pub struct EncryptionKey {
 
 impl EncryptionKey {
     /// Derive encryption key from shared secret using HKDF-SHA256
+    ///
+    /// The output length matches the algorithm's key size.
     pub fn derive(shared_secret: &[u8], info: &[u8], algorithm: CipherAlgorithm) -> Result<Self> {
         let hk = Hkdf::<Sha256>::new(None, shared_secret);
         let mut key = [0u8; 32];
-        hk.expand(info, &mut key)
+        hk.expand(info, &mut key[..algorithm.key_len()])
             .map_err(|_| AegisError::Crypto("HKDF expansion failed".to_string()))?;
 
         Ok(Self { key, algorithm })
     }
 
     /// Create from raw key bytes (for testing)
-    pub fn from_raw(key: [u8; 32], algorithm: CipherAlgorithm) -> Self {
+    ///
+    /// For 128-bit algorithms only the first 16 bytes are kept, which is the
+    /// same key HKDF yields when asked for 16 bytes instead of 32.
+    pub fn from_raw(mut key: [u8; 32], algorithm: CipherAlgorithm) -> Self {
+        key[algorithm.key_len()..].fill(0);
         Self { key, algorithm }
     }
 
@@ -61,9 +86,9 @@ impl EncryptionKey {
         self.algorithm
     }
 
-    /// Get raw key bytes
-    pub fn as_bytes(&self) -> &[u8; 32] {
-        &self.key
+    /// Get raw key bytes (`algorithm().key_len()` of them)
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.key[..self.algorithm.key_len()]
     }
 }
 
@@ -79,59 +104,119 @@ impl std::fmt::Debug for EncryptionKey {
 /// Internal engine holding initialized cipher states
 enum CipherEngine {
     Aes(Box<Aes256Gcm>),
+    Aes128(Box<Aes128Gcm>),
     ChaCha(ChaCha20Poly1305),
 }
 
+impl CipherEngine {
+    fn new(key: &EncryptionKey) -> Self {
+        let key_bytes = key.as_bytes();
+        match key.algorithm() {
+            CipherAlgorithm::Aes256Gcm => Self::Aes(Box::new(
+                Aes256Gcm::new_from_slice(key_bytes).expect("Invalid AES-256 key length"),
+            )),
+            CipherAlgorithm::Aes128Gcm => Self::Aes128(Box::new(
+                Aes128Gcm::new_from_slice(key_bytes).expect("Invalid AES-128 key length"),
+            )),
+            CipherAlgorithm::ChaCha20Poly1305 => Self::ChaCha(
+                ChaCha20Poly1305::new_from_slice(key_bytes).expect("Invalid ChaCha key length"),
+            ),
+        }
+    }
+}
+
 /// Cipher for encrypting/decrypting data
 pub struct Cipher {
     key: EncryptionKey,
     engine: CipherEngine,
     nonce_counter: AtomicU64,
+    nonce_limit: u64,
+    nonce_prefix: u32,
 }
 
 impl Cipher {
-    /// Create a new cipher with the given key
+    /// Create a new cipher with the given key and a random nonce prefix
     pub fn new(key: EncryptionKey) -> Self {
-        let engine = match key.algorithm() {
-            CipherAlgorithm::Aes256Gcm => CipherEngine::Aes(Box::new(
-                Aes256Gcm::new_from_slice(&key.key)
-                    .map_err(|_| {
-                        AegisError::Crypto("Invalid key length for AES-256-GCM".to_string())
-                    })
-                    .expect("Invalid AES key length (should be caught by map_err)"),
-            )),
-            CipherAlgorithm::ChaCha20Poly1305 => CipherEngine::ChaCha(
-                ChaCha20Poly1305::new_from_slice(&key.key).expect("Invalid ChaCha key length"),
-            ),
-        };
+        let engine = CipherEngine::new(&key);
 
         Self {
             key,
             engine,
             nonce_counter: AtomicU64::new(1),
+            nonce_limit: DEFAULT_NONCE_LIMIT,
+            nonce_prefix: OsRng.next_u32(),
         }
     }
 
+    /// Use `prefix` as the first four bytes of every nonce
+    ///
+    /// Every counter starts at 1, so any key that might be used by more than
+    /// one `Cipher` (several proxy replicas sharing a resumed session key, for
+    /// example) needs a distinct prefix per instance or nonces will repeat.
+    /// [`new`](Self::new) picks a random one; set it explicitly when replicas
+    /// can be assigned unique prefixes instead.
+    pub fn with_prefix(mut self, prefix: u32) -> Self {
+        self.nonce_prefix = prefix;
+        self
+    }
+
+    /// The per-instance nonce prefix
+    pub fn nonce_prefix(&self) -> u32 {
+        self.nonce_prefix
+    }
+
+    /// Refuse to encrypt once the nonce counter reaches `limit`
+    ///
+    /// [`needs_rekey`](Self::needs_rekey) starts returning `true` when the
+    /// last quarter of the limit is reached.
+    pub fn with_nonce_limit(mut self, limit: u64) -> Self {
+        self.nonce_limit = limit;
+        self
+    }
+
     /// Encrypt plaintext data.
     ///
-    /// Returns `Err(AegisError::Crypto("Nonce space exhausted"))` when the nonce
-    /// counter approaches `u64::MAX` to prevent nonce reuse.
+    /// Returns `Err(AegisError::Crypto("Nonce space exhausted"))` once the nonce
+    /// counter reaches the soft limit, so a nonce is never reused.
     pub fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
-        // Guard against nonce exhaustion *before* incrementing
-        let nonce_value = self.nonce_counter.fetch_add(1, Ordering::SeqCst);
-        if nonce_value >= NONCE_EXHAUSTION_THRESHOLD {
-            return Err(AegisError::Crypto(
-                "Nonce space exhausted — rotate encryption key immediately".to_string(),
-            ));
-        }
+        self.encrypt_with_aad(plaintext, &[])
+    }
+
+    /// Encrypt plaintext data, authenticating `aad` alongside it.
+    ///
+    /// The associated data is not included in the output; the same bytes must
+    /// be passed to [`decrypt_with_aad`](Self::decrypt_with_aad).
+    pub fn encrypt_with_aad(&self, plaintext: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+        // Only advance the counter while below the limit, so it never wraps
+        let nonce_value = self
+            .nonce_counter
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| {
+                if n < self.nonce_limit {
+                    Some(n + 1)
+                } else {
+                    None
+                }
+            })
+            .map_err(|_| {
+                AegisError::Crypto(
+                    "Nonce space exhausted — rotate encryption key immediately".to_string(),
+                )
+            })?;
         let nonce = self.create_nonce(nonce_value);
 
+        let payload = Payload {
+            msg: plaintext,
+            aad,
+        };
         let ciphertext = match &self.engine {
             CipherEngine::Aes(cipher) => cipher
-                .encrypt(Nonce::from_slice(&nonce), plaintext)
+                .encrypt(Nonce::from_slice(&nonce), payload)
+                .map_err(|e| AegisError::Crypto(format!("AES encryption failed: {}", e)))?,
+            CipherEngine::Aes128(cipher) => cipher
+                .encrypt(Nonce::from_slice(&nonce), payload)
                 .map_err(|e| AegisError::Crypto(format!("AES encryption failed: {}", e)))?,
             CipherEngine::ChaCha(cipher) => cipher
-                .encrypt(chacha20poly1305::Nonce::from_slice(&nonce), plaintext)
+                .encrypt(chacha20poly1305::Nonce::from_slice(&nonce), payload)
                 .map_err(|e| AegisError::Crypto(format!("ChaCha encryption failed: {}", e)))?,
         };
 
@@ -145,27 +230,39 @@ impl Cipher {
 
     /// Decrypt ciphertext data
     pub fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>> {
+        self.decrypt_with_aad(ciphertext, &[])
+    }
+
+    /// Decrypt ciphertext data produced by
+    /// [`encrypt_with_aad`](Self::encrypt_with_aad); fails unless `aad`
+    /// matches the associated data it was encrypted with
+    pub fn decrypt_with_aad(&self, ciphertext: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
         if ciphertext.len() < 12 {
             return Err(AegisError::Crypto("Ciphertext too short".to_string()));
         }
 
         let (nonce, data) = ciphertext.split_at(12);
+        let payload = Payload { msg: data, aad };
 
         let plaintext = match &self.engine {
             CipherEngine::Aes(cipher) => cipher
-                .decrypt(Nonce::from_slice(nonce), data)
+                .decrypt(Nonce::from_slice(nonce), payload)
+                .map_err(|e| AegisError::Crypto(format!("AES decryption failed: {}", e)))?,
+            CipherEngine::Aes128(cipher) => cipher
+                .decrypt(Nonce::from_slice(nonce), payload)
                 .map_err(|e| AegisError::Crypto(format!("AES decryption failed: {}", e)))?,
             CipherEngine::ChaCha(cipher) => cipher
-                .decrypt(chacha20poly1305::Nonce::from_slice(nonce), data)
+                .decrypt(chacha20poly1305::Nonce::from_slice(nonce), payload)
                 .map_err(|e| AegisError::Crypto(format!("ChaCha decryption failed: {}", e)))?,
         };
 
         Ok(plaintext)
     }
 
-    /// Create a 12-byte nonce from counter value
+    /// Create a 12-byte nonce from the prefix and counter value
     fn create_nonce(&self, counter: u64) -> [u8; 12] {
         let mut nonce = [0u8; 12];
+        nonce[..4].copy_from_slice(&self.nonce_prefix.to_be_bytes());
         nonce[4..12].copy_from_slice(&counter.to_be_bytes());
         nonce
     }
@@ -178,7 +275,18 @@ impl Cipher {
     /// Number of encryptions remaining before nonce exhaustion.
     pub fn nonce_remaining(&self) -> u64 {
         let current = self.nonce_counter.load(Ordering::SeqCst);
-        NONCE_EXHAUSTION_THRESHOLD.saturating_sub(current)
+        self.nonce_limit.saturating_sub(current)
+    }
+
+    /// Whether the key is into the last quarter of its nonce limit and
+    /// should be rotated before `encrypt` starts failing
+    pub fn needs_rekey(&self) -> bool {
+        self.nonce_remaining() <= self.nonce_limit / 4
+    }
+
+    #[cfg(test)]
+    fn set_nonce_counter(&self, value: u64) {
+        self.nonce_counter.store(value, Ordering::SeqCst);
     }
 
     /// Rotate the encryption key without changing the counter position.
@@ -187,14 +295,7 @@ impl Cipher {
     /// # Policy
     /// Callers should rotate when `nonce_remaining()` is low or on a schedule.
     pub fn rotate_key(&mut self, new_key: EncryptionKey) {
-        self.engine = match new_key.algorithm() {
-            CipherAlgorithm::Aes256Gcm => CipherEngine::Aes(Box::new(
-                Aes256Gcm::new_from_slice(&new_key.key).expect("Invalid AES key length"),
-            )),
-            CipherAlgorithm::ChaCha20Poly1305 => CipherEngine::ChaCha(
-                ChaCha20Poly1305::new_from_slice(&new_key.key).expect("Invalid ChaCha key length"),
-            ),
-        };
+        self.engine = CipherEngine::new(&new_key);
         self.key = new_key;
         self.nonce_counter.store(1, Ordering::SeqCst);
     }
@@ -210,6 +311,7 @@ impl std::fmt::Debug for Cipher {
         f.debug_struct("Cipher")
             .field("algorithm", &self.key.algorithm)
             .field("nonce_counter", &self.nonce_counter.load(Ordering::SeqCst))
+            .field("nonce_prefix", &self.nonce_prefix)
             .finish()
     }
 }
@@ -245,6 +347,39 @@ mod tests {
         assert_eq!(&decrypted, plaintext);
     }
 
+    #[test]
+    fn test_aad_roundtrip_both_algorithms() {
+        for algorithm in [
+            CipherAlgorithm::Aes256Gcm,
+            CipherAlgorithm::ChaCha20Poly1305,
+        ] {
+            let cipher = Cipher::new(EncryptionKey::from_raw([0x42; 32], algorithm));
+            let ciphertext = cipher.encrypt_with_aad(b"payload", b"channel-7").unwrap();
+            let decrypted = cipher.decrypt_with_aad(&ciphertext, b"channel-7").unwrap();
+            assert_eq!(&decrypted, b"payload", "{algorithm:?}");
+
+            // Plain encrypt/decrypt are the empty-AAD case
+            let plain = cipher.encrypt(b"payload").unwrap();
+            assert_eq!(cipher.decrypt_with_aad(&plain, b"").unwrap(), b"payload");
+        }
+    }
+
+    #[test]
+    fn test_aad_mismatch_fails_both_algorithms() {
+        for algorithm in [
+            CipherAlgorithm::Aes256Gcm,
+            CipherAlgorithm::ChaCha20Poly1305,
+        ] {
+            let cipher = Cipher::new(EncryptionKey::from_raw([0x42; 32], algorithm));
+            let ciphertext = cipher.encrypt_with_aad(b"payload", b"channel-7").unwrap();
+            assert!(
+                cipher.decrypt_with_aad(&ciphertext, b"channel-8").is_err(),
+                "{algorithm:?}"
+            );
+            assert!(cipher.decrypt(&ciphertext).is_err(), "{algorithm:?}");
+        }
+    }
+
     #[test]
     fn test_key_derivation() {
         let shared_secret = [0xAB; 64];
@@ -274,6 +409,35 @@ mod tests {
         assert_eq!(cipher.nonce_counter(), 3);
     }
 
+    #[test]
+    fn test_prefixed_ciphers_never_share_nonces() {
+        use std::collections::HashSet;
+
+        let key = EncryptionKey::from_raw([0x42; 32], CipherAlgorithm::Aes256Gcm);
+        let replica_a = Cipher::new(key.clone()).with_prefix(0xA);
+        let replica_b = Cipher::new(key.clone()).with_prefix(0xB);
+        assert_eq!(replica_a.nonce_prefix(), 0xA);
+
+        // Same counter range on both replicas, and a window further along
+        let mut nonces = HashSet::new();
+        for start in [1, 1 << 32] {
+            replica_a.set_nonce_counter(start);
+            replica_b.set_nonce_counter(start);
+            for _ in 0..500 {
+                for cipher in [&replica_a, &replica_b] {
+                    let ciphertext = cipher.encrypt(b"payload").unwrap();
+                    assert_eq!(&ciphertext[..4], &cipher.nonce_prefix().to_be_bytes());
+                    assert!(nonces.insert(ciphertext[..12].to_vec()));
+                }
+            }
+        }
+        assert_eq!(nonces.len(), 2000);
+
+        // The nonce travels with the ciphertext, so any replica can decrypt
+        let ciphertext = replica_a.encrypt(b"shared").unwrap();
+        assert_eq!(replica_b.decrypt(&ciphertext).unwrap(), b"shared");
+    }
+
     #[test]
     fn test_different_ciphertexts_for_same_plaintext() {
         let key = EncryptionKey::from_raw([0x42; 32], CipherAlgorithm::Aes256Gcm);
@@ -363,6 +527,55 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_aes128_gcm_roundtrip() {
+        let cipher = Cipher::new(EncryptionKey::from_raw(
+            [0x42; 32],
+            CipherAlgorithm::Aes128Gcm,
+        ));
+        assert_eq!(cipher.key().as_bytes(), &[0x42; 16]);
+
+        let ciphertext = cipher.encrypt_with_aad(b"payload", b"channel-7").unwrap();
+        assert_eq!(
+            cipher.decrypt_with_aad(&ciphertext, b"channel-7").unwrap(),
+            b"payload"
+        );
+        assert!(cipher.decrypt(&ciphertext).is_err());
+
+        // Not interchangeable with AES-256 under the same key bytes
+        let aes256 = Cipher::new(EncryptionKey::from_raw(
+            [0x42; 32],
+            CipherAlgorithm::Aes256Gcm,
+        ));
+        assert!(
+            aes256
+                .decrypt(&cipher.encrypt(b"payload").unwrap())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_key_derivation_length_follows_algorithm() {
+        let shared_secret = [0xAB; 64];
+        let key128 =
+            EncryptionKey::derive(&shared_secret, b"aegis-flow-v1", CipherAlgorithm::Aes128Gcm)
+                .unwrap();
+        let key256 =
+            EncryptionKey::derive(&shared_secret, b"aegis-flow-v1", CipherAlgorithm::Aes256Gcm)
+                .unwrap();
+        assert_eq!(key128.as_bytes().len(), 16);
+        assert_eq!(key256.as_bytes().len(), 32);
+        // HKDF output is a prefix of the longer expansion
+        assert_eq!(key128.as_bytes(), &key256.as_bytes()[..16]);
+
+        let cipher = Cipher::new(key128);
+        let ciphertext = cipher.encrypt(b"HKDF derived 128-bit key").unwrap();
+        assert_eq!(
+            cipher.decrypt(&ciphertext).unwrap(),
+            b"HKDF derived 128-bit key"
+        );
+    }
+
     #[test]
     fn test_cipher_algorithm_variants() {
         assert_ne!(
@@ -461,10 +674,8 @@ mod tests {
         let key = EncryptionKey::from_raw([0x42; 32], CipherAlgorithm::Aes256Gcm);
         let cipher = Cipher::new(key);
 
-        // Manually advance counter to just before the threshold
-        cipher
-            .nonce_counter
-            .store(NONCE_EXHAUSTION_THRESHOLD, Ordering::SeqCst);
+        // Manually advance counter to the limit
+        cipher.set_nonce_counter(DEFAULT_NONCE_LIMIT);
 
         // Now encrypt should return an error
         let result = cipher.encrypt(b"test");
@@ -479,6 +690,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_nonce_limit_boundaries() {
+        let key = EncryptionKey::from_raw([0x42; 32], CipherAlgorithm::ChaCha20Poly1305);
+        let cipher = Cipher::new(key).with_nonce_limit(1000);
+        assert!(!cipher.needs_rekey());
+
+        // The warning starts with the last quarter of the nonce space
+        cipher.set_nonce_counter(749);
+        assert!(!cipher.needs_rekey());
+        cipher.set_nonce_counter(750);
+        assert!(cipher.needs_rekey());
+
+        // The last nonce below the limit is still usable, then encrypt fails
+        // without moving the counter
+        cipher.set_nonce_counter(999);
+        let ciphertext = cipher.encrypt(b"last").unwrap();
+        assert_eq!(cipher.decrypt(&ciphertext).unwrap(), b"last");
+        assert_eq!(cipher.nonce_remaining(), 0);
+        let err = cipher.encrypt(b"one too many").unwrap_err();
+        assert!(err.to_string().contains("Nonce space exhausted"));
+        assert_eq!(cipher.nonce_counter(), 1000);
+
+        // The default limit is far below the point where the counter wraps
+        let cipher = Cipher::new(EncryptionKey::from_raw(
+            [0x42; 32],
+            CipherAlgorithm::Aes256Gcm,
+        ));
+        cipher.set_nonce_counter(u64::MAX);
+        assert!(cipher.encrypt(b"x").is_err());
+        assert_eq!(cipher.nonce_counter(), u64::MAX);
+    }
+
     #[test]
     fn test_nonce_remaining_accessor() {
         let key = EncryptionKey::from_raw([0x42; 32], CipherAlgorithm::Aes256Gcm);
@@ -0,0 +1,308 @@
+//! PQC Handshake Protocol
+//!
+//! Typed client and server ends of the handshake spoken by the PQC proxy.
+//! Every handshake message is a big-endian `u32` length followed by that many
+//! bytes:
+//!
+//! 1. Server → client: hybrid public key, ML-DSA-65 signature over it, and the
+//!    server's identity public key ([`ServerHello`])
+//! 2. Client → server: hybrid ciphertext
+//!
+//! Both ends then switch to an [`EncryptedStream`] keyed with the directional
+//! keys of the resulting [`SecureChannel`].
+
+use crate::cipher::CipherAlgorithm;
+use crate::hybrid_kex::{HybridCiphertext, HybridPublicKey};
+use crate::signing::{MlDsaAlgorithm, MlDsaSignature, SigningKeyPair};
+use crate::stream::EncryptedStream;
+use crate::tls::{PqcAlgorithm, PqcHandshake, PqcTlsConfig, SecureChannel};
+use aegis_common::{AegisError, Result};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tracing::debug;
+
+/// Largest handshake message accepted from a peer
+pub const MAX_HANDSHAKE_FRAME: usize = 10_000;
+
+/// Write one length-prefixed handshake message
+async fn write_frame<W: AsyncWrite + Unpin>(writer: &mut W, bytes: &[u8]) -> Result<()> {
+    writer
+        .write_all(&(bytes.len() as u32).to_be_bytes())
+        .await?;
+    writer.write_all(bytes).await?;
+    Ok(())
+}
+
+/// Read one length-prefixed handshake message
+async fn read_frame<R: AsyncRead + Unpin>(reader: &mut R) -> Result<Vec<u8>> {
+    let mut len_bytes = [0u8; 4];
+    reader.read_exact(&mut len_bytes).await?;
+    let len = u32::from_be_bytes(len_bytes) as usize;
+    if len > MAX_HANDSHAKE_FRAME {
+        return Err(AegisError::Network(format!(
+            "Handshake message too large: {} bytes",
+            len
+        )));
+    }
+
+    let mut bytes = vec![0u8; len];
+    reader.read_exact(&mut bytes).await?;
+    Ok(bytes)
+}
+
+fn encrypted_stream<S>(stream: S, channel: &SecureChannel) -> EncryptedStream<S> {
+    EncryptedStream::new_bidirectional(
+        stream,
+        channel.send_key().as_bytes(),
+        channel.recv_key().as_bytes(),
+    )
+}
+
+/// First handshake message, sent by the server
+#[derive(Debug, Clone)]
+pub struct ServerHello {
+    /// Ephemeral hybrid public key
+    pub public_key: HybridPublicKey,
+    /// Signature over `public_key` by the server identity
+    pub signature: MlDsaSignature,
+    /// Server identity (ML-DSA-65) public key
+    pub identity_key: Vec<u8>,
+}
+
+impl ServerHello {
+    /// Read a server hello from the wire
+    pub async fn read_from<R: AsyncRead + Unpin>(reader: &mut R) -> Result<Self> {
+        let public_key = HybridPublicKey::from_bytes(&read_frame(reader).await?)?;
+        let signature = MlDsaSignature::new(read_frame(reader).await?, MlDsaAlgorithm::MlDsa65);
+        let identity_key = read_frame(reader).await?;
+        Ok(Self {
+            public_key,
+            signature,
+            identity_key,
+        })
+    }
+
+    /// Write this server hello to the wire
+    pub async fn write_to<W: AsyncWrite + Unpin>(&self, writer: &mut W) -> Result<()> {
+        write_frame(writer, &self.public_key.to_bytes()).await?;
+        write_frame(writer, self.signature.as_bytes()).await?;
+        write_frame(writer, &self.identity_key).await?;
+        writer.flush().await?;
+        Ok(())
+    }
+}
+
+/// Client end of the PQC handshake
+pub struct PqcClient {
+    handshake: PqcHandshake,
+    trusted_identity: Option<Vec<u8>>,
+}
+
+impl PqcClient {
+    /// Create a client accepting any correctly signed server identity
+    pub fn new(config: PqcTlsConfig) -> Self {
+        Self {
+            handshake: PqcHandshake::new(config),
+            trusted_identity: None,
+        }
+    }
+
+    /// Only accept servers presenting this identity public key
+    pub fn with_trusted_identity(mut self, identity_key: impl Into<Vec<u8>>) -> Self {
+        self.trusted_identity = Some(identity_key.into());
+        self
+    }
+
+    /// Run the handshake over `stream` and return the encrypted stream
+    pub async fn connect<S>(&self, mut stream: S) -> Result<EncryptedStream<S>>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        let hello = ServerHello::read_from(&mut stream).await?;
+        if let Some(trusted) = &self.trusted_identity
+            && *trusted != hello.identity_key
+        {
+            return Err(AegisError::Crypto(
+                "Server identity does not match the trusted key".to_string(),
+            ));
+        }
+
+        let (ciphertext, channel) = self.handshake.client_complete(
+            &hello.public_key,
+            &hello.identity_key,
+            &hello.signature,
+        )?;
+        write_frame(&mut stream, &ciphertext.to_bytes()).await?;
+        stream.flush().await?;
+
+        debug!("PQC client connected, channel_id={}", channel.channel_id());
+        Ok(encrypted_stream(stream, &channel))
+    }
+}
+
+impl Default for PqcClient {
+    fn default() -> Self {
+        Self::new(PqcTlsConfig::default())
+    }
+}
+
+/// Server end of a completed PQC handshake
+pub struct PqcServerConnection<S> {
+    stream: EncryptedStream<S>,
+    channel_id: u64,
+    algorithm: PqcAlgorithm,
+    cipher: CipherAlgorithm,
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> PqcServerConnection<S> {
+    /// Run the server side of the handshake over an accepted `stream`
+    pub async fn accept(
+        handshake: &PqcHandshake,
+        identity_key: &impl SigningKeyPair,
+        mut stream: S,
+    ) -> Result<Self> {
+        let (public_key, signature, state) = handshake.server_init(identity_key)?;
+        ServerHello {
+            public_key,
+            signature,
+            identity_key: identity_key.public_key().to_vec(),
+        }
+        .write_to(&mut stream)
+        .await?;
+
+        let ciphertext = HybridCiphertext::from_bytes(&read_frame(&mut stream).await?)?;
+        let channel = handshake.server_complete(&ciphertext, state)?;
+
+        Ok(Self {
+            stream: encrypted_stream(stream, &channel),
+            channel_id: channel.channel_id(),
+            algorithm: channel.algorithm(),
+            cipher: channel.cipher(),
+        })
+    }
+}
+
+impl<S> PqcServerConnection<S> {
+    /// Identifier of the negotiated channel
+    pub fn channel_id(&self) -> u64 {
+        self.channel_id
+    }
+
+    /// Algorithm used for the key exchange
+    pub fn algorithm(&self) -> PqcAlgorithm {
+        self.algorithm
+    }
+
+    /// Symmetric cipher protecting the stream
+    pub fn cipher(&self) -> CipherAlgorithm {
+        self.cipher
+    }
+
+    /// Encrypted stream for the application data
+    pub fn into_stream(self) -> EncryptedStream<S> {
+        self.stream
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signing::MlDsa65Signer;
+    use std::sync::Arc;
+    use tokio::net::{TcpListener, TcpStream};
+
+    async fn spawn_echo_server(identity: Arc<MlDsa65Signer>) -> std::net::SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let handshake = Arc::new(PqcHandshake::new(PqcTlsConfig::default()));
+
+        tokio::spawn(async move {
+            while let Ok((socket, _)) = listener.accept().await {
+                let handshake = handshake.clone();
+                let identity = identity.clone();
+                tokio::spawn(async move {
+                    let Ok(connection) =
+                        PqcServerConnection::accept(&handshake, identity.as_ref(), socket).await
+                    else {
+                        return;
+                    };
+                    assert_eq!(connection.algorithm(), PqcAlgorithm::HybridMlKem768);
+                    let mut stream = connection.into_stream();
+                    let mut buf = [0u8; 1024];
+                    while let Ok(n) = stream.read(&mut buf).await {
+                        if n == 0 {
+                            break;
+                        }
+                        let mut reply = b"echo: ".to_vec();
+                        reply.extend_from_slice(&buf[..n]);
+                        stream.write_all(&reply).await.unwrap();
+                        stream.flush().await.unwrap();
+                    }
+                });
+            }
+        });
+        addr
+    }
+
+    #[tokio::test]
+    async fn test_client_server_exchange_over_tcp() {
+        let identity = Arc::new(MlDsa65Signer::generate().unwrap());
+        let addr = spawn_echo_server(identity.clone()).await;
+
+        let client = PqcClient::default().with_trusted_identity(identity.public_key());
+        let socket = TcpStream::connect(addr).await.unwrap();
+        let mut stream = client.connect(socket).await.unwrap();
+
+        for message in [&b"hello"[..], &b"quantum-safe world"[..]] {
+            stream.write_all(message).await.unwrap();
+            stream.flush().await.unwrap();
+
+            let mut reply = vec![0u8; 6 + message.len()];
+            stream.read_exact(&mut reply).await.unwrap();
+            assert_eq!(&reply[..6], b"echo: ");
+            assert_eq!(&reply[6..], message);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_client_rejects_untrusted_identity() {
+        let identity = Arc::new(MlDsa65Signer::generate().unwrap());
+        let addr = spawn_echo_server(identity).await;
+
+        let other = MlDsa65Signer::generate().unwrap();
+        let client = PqcClient::default().with_trusted_identity(other.public_key());
+        let socket = TcpStream::connect(addr).await.unwrap();
+        let result = client.connect(socket).await;
+        assert!(matches!(result, Err(AegisError::Crypto(_))));
+    }
+
+    #[tokio::test]
+    async fn test_oversized_frame_rejected() {
+        let (mut client, mut server) = tokio::io::duplex(64);
+        client
+            .write_all(&((MAX_HANDSHAKE_FRAME + 1) as u32).to_be_bytes())
+            .await
+            .unwrap();
+
+        let result = read_frame(&mut server).await;
+        assert!(matches!(result, Err(AegisError::Network(_))));
+    }
+
+    #[tokio::test]
+    async fn test_server_hello_roundtrip() {
+        let identity = MlDsa65Signer::generate().unwrap();
+        let handshake = PqcHandshake::new(PqcTlsConfig::default());
+        let (public_key, signature, _state) = handshake.server_init(&identity).unwrap();
+        let hello = ServerHello {
+            public_key,
+            signature,
+            identity_key: identity.public_key().to_vec(),
+        };
+
+        let mut wire = Vec::new();
+        hello.write_to(&mut wire).await.unwrap();
+        let parsed = ServerHello::read_from(&mut wire.as_slice()).await.unwrap();
+        assert_eq!(parsed.public_key.to_bytes(), hello.public_key.to_bytes());
+        assert_eq!(parsed.signature.as_bytes(), hello.signature.as_bytes());
+        assert_eq!(parsed.identity_key, hello.identity_key);
+    }
+}
@@ -13,13 +13,13 @@
 
 use aegis_common::{AegisError, Result};
 use hkdf::Hkdf;
-use pqcrypto_mlkem::mlkem768;
+use pqcrypto_mlkem::{mlkem768, mlkem1024};
 use pqcrypto_traits::kem::{Ciphertext, PublicKey, SecretKey, SharedSecret as MlkemSharedSecret};
 use rand::rngs::OsRng;
 use sha2::Sha256;
 use tracing::{debug, instrument};
 use x25519_dalek::{PublicKey as X25519PublicKey, StaticSecret as X25519StaticSecret};
-use zeroize::{Zeroize, ZeroizeOnDrop};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
 
 // IETF draft-ietf-tls-hybrid-design-10 labels
 const KDF_EXTRACT_LABEL: &[u8] = b"aegis-flow-hybrid-kex-v1";
@@ -59,6 +59,22 @@ impl PqcAlgorithm {
             PqcAlgorithm::HybridMlKem768 => "X25519-MLKEM768-Hybrid",
         }
     }
+
+    /// ML-KEM parameter set behind this algorithm
+    pub fn security_level(self) -> SecurityLevel {
+        match self {
+            PqcAlgorithm::HybridMlKem1024 => SecurityLevel::High,
+            PqcAlgorithm::HybridMlKem768 => SecurityLevel::Standard,
+        }
+    }
+
+    /// NIST post-quantum security category (1-5) of the ML-KEM component
+    pub fn nist_category(self) -> u8 {
+        match self {
+            PqcAlgorithm::HybridMlKem1024 => 5,
+            PqcAlgorithm::HybridMlKem768 => 3,
+        }
+    }
 }
 
 /// Result of algorithm negotiation between two peers
@@ -184,20 +200,36 @@ impl HybridSharedSecret {
     ///
     /// Ensures client and server use distinct keys even from the same shared secret.
     pub fn derive_client_key(&self) -> [u8; 32] {
-        let hk = Hkdf::<Sha256>::new(None, &self.inner);
-        let mut key = [0u8; 32];
-        hk.expand(KDF_CLIENT_LABEL, &mut key)
-            .expect("32-byte output is within HKDF-SHA256 limits");
-        key
+        self.expand_with_context(KDF_CLIENT_LABEL, &[])
     }
 
     /// Derive a directional 32-byte key for the server→client direction.
     ///
     /// Ensures client and server use distinct keys even from the same shared secret.
     pub fn derive_server_key(&self) -> [u8; 32] {
+        self.expand_with_context(KDF_SERVER_LABEL, &[])
+    }
+
+    /// Derive the client→server key bound to a handshake transcript hash.
+    ///
+    /// info = `"aegis-flow-client-key-v1" || transcript_hash`, so peers that
+    /// saw different handshake bytes end up with different keys.
+    pub fn derive_client_key_with_transcript(&self, transcript_hash: &[u8]) -> [u8; 32] {
+        self.expand_with_context(KDF_CLIENT_LABEL, transcript_hash)
+    }
+
+    /// Derive the server→client key bound to a handshake transcript hash.
+    ///
+    /// info = `"aegis-flow-server-key-v1" || transcript_hash`
+    pub fn derive_server_key_with_transcript(&self, transcript_hash: &[u8]) -> [u8; 32] {
+        self.expand_with_context(KDF_SERVER_LABEL, transcript_hash)
+    }
+
+    /// HKDF-SHA256 expand with info = `label || context`
+    fn expand_with_context(&self, label: &[u8], context: &[u8]) -> [u8; 32] {
         let hk = Hkdf::<Sha256>::new(None, &self.inner);
         let mut key = [0u8; 32];
-        hk.expand(KDF_SERVER_LABEL, &mut key)
+        hk.expand_multi_info(&[label, context], &mut key)
             .expect("32-byte output is within HKDF-SHA256 limits");
         key
     }
@@ -238,27 +270,64 @@ impl HybridKeyExchange {
         }
     }
 
+    /// Security level this exchange was created with
+    pub fn security_level(&self) -> SecurityLevel {
+        self.security_level
+    }
+
+    /// Sizes of the ML-KEM public key and ciphertext at this security level
+    fn mlkem_sizes(&self) -> (usize, usize) {
+        match self.security_level {
+            SecurityLevel::Standard => (mlkem768::public_key_bytes(), mlkem768::ciphertext_bytes()),
+            SecurityLevel::High => (mlkem1024::public_key_bytes(), mlkem1024::ciphertext_bytes()),
+        }
+    }
+
+    /// Fail with a descriptive error if an ML-KEM component received from
+    /// the peer does not have the expected size, which means the two sides
+    /// were configured with different algorithms
+    fn check_mlkem_len(&self, component: &str, len: usize, expected: usize) -> Result<()> {
+        if len != expected {
+            return Err(AegisError::Crypto(format!(
+                "ML-KEM {} is {} bytes but {} expects {}; peer negotiated a different algorithm",
+                component,
+                len,
+                self.algorithm_name(),
+                expected
+            )));
+        }
+        Ok(())
+    }
+
     /// Generate a new hybrid key pair
     #[instrument(skip(self))]
     pub fn generate_keypair(&self) -> Result<(HybridPublicKey, HybridSecretKey)> {
-        debug!("Generating hybrid key pair (X25519 + ML-KEM-768)");
+        debug!("Generating hybrid key pair ({})", self.algorithm_name());
 
         // Generate X25519 key pair using StaticSecret (reusable)
         let x25519_secret = X25519StaticSecret::random_from_rng(OsRng);
         let x25519_public = X25519PublicKey::from(&x25519_secret);
 
-        // Generate ML-KEM-768 key pair (ML-KEM-1024 requires separate crate)
-        let (mlkem_pk, mlkem_sk) = mlkem768::keypair();
+        let (mlkem_pk, mlkem_sk) = match self.security_level {
+            SecurityLevel::Standard => {
+                let (pk, sk) = mlkem768::keypair();
+                (pk.as_bytes().to_vec(), sk.as_bytes().to_vec())
+            }
+            SecurityLevel::High => {
+                let (pk, sk) = mlkem1024::keypair();
+                (pk.as_bytes().to_vec(), sk.as_bytes().to_vec())
+            }
+        };
 
-        let mut bytes = Vec::with_capacity(32 + mlkem_pk.as_bytes().len());
+        let mut bytes = Vec::with_capacity(32 + mlkem_pk.len());
         bytes.extend_from_slice(x25519_public.as_bytes());
-        bytes.extend_from_slice(mlkem_pk.as_bytes());
+        bytes.extend_from_slice(&mlkem_pk);
 
         let public_key = HybridPublicKey { bytes };
 
         let secret_key = HybridSecretKey {
             x25519: x25519_secret,
-            mlkem: mlkem_sk.as_bytes().to_vec(),
+            mlkem: mlkem_sk,
         };
 
         debug!("Hybrid key pair generated successfully");
@@ -280,18 +349,36 @@ impl HybridKeyExchange {
         let peer_x25519_pk = X25519PublicKey::from(*peer_public_key.x25519_bytes());
         let x25519_shared = ephemeral_secret.diffie_hellman(&peer_x25519_pk);
 
-        // ML-KEM-768 encapsulation
-        let mlkem_pk = mlkem768::PublicKey::from_bytes(peer_public_key.mlkem_bytes())
-            .map_err(|e| AegisError::Crypto(format!("Invalid ML-KEM public key: {:?}", e)))?;
-        let (mlkem_ss, mlkem_ct) = mlkem768::encapsulate(&mlkem_pk);
+        let (pk_len, _) = self.mlkem_sizes();
+        self.check_mlkem_len("public key", peer_public_key.mlkem_bytes().len(), pk_len)?;
+        let invalid_pk = |e| AegisError::Crypto(format!("Invalid ML-KEM public key: {:?}", e));
+        let (mlkem_ss, mlkem_ct) = match self.security_level {
+            SecurityLevel::Standard => {
+                let pk = mlkem768::PublicKey::from_bytes(peer_public_key.mlkem_bytes())
+                    .map_err(invalid_pk)?;
+                let (ss, ct) = mlkem768::encapsulate(&pk);
+                (
+                    Zeroizing::new(ss.as_bytes().to_vec()),
+                    ct.as_bytes().to_vec(),
+                )
+            }
+            SecurityLevel::High => {
+                let pk = mlkem1024::PublicKey::from_bytes(peer_public_key.mlkem_bytes())
+                    .map_err(invalid_pk)?;
+                let (ss, ct) = mlkem1024::encapsulate(&pk);
+                (
+                    Zeroizing::new(ss.as_bytes().to_vec()),
+                    ct.as_bytes().to_vec(),
+                )
+            }
+        };
 
         let ciphertext = HybridCiphertext {
             x25519_ephemeral: ephemeral_public.to_bytes(),
-            mlkem_ciphertext: mlkem_ct.as_bytes().to_vec(),
+            mlkem_ciphertext: mlkem_ct,
         };
 
-        let shared_secret =
-            HybridSharedSecret::combine(x25519_shared.as_bytes(), mlkem_ss.as_bytes());
+        let shared_secret = HybridSharedSecret::combine(x25519_shared.as_bytes(), &mlkem_ss);
 
         debug!("Hybrid encapsulation completed");
         Ok((ciphertext, shared_secret))
@@ -310,16 +397,26 @@ impl HybridKeyExchange {
         let peer_ephemeral = X25519PublicKey::from(ciphertext.x25519_ephemeral);
         let x25519_shared = secret_key.x25519.diffie_hellman(&peer_ephemeral);
 
-        // ML-KEM-768 decapsulation
-        let mlkem_sk = mlkem768::SecretKey::from_bytes(&secret_key.mlkem)
-            .map_err(|e| AegisError::Crypto(format!("Invalid ML-KEM secret key: {:?}", e)))?;
-        let mlkem_ct = mlkem768::Ciphertext::from_bytes(&ciphertext.mlkem_ciphertext)
-            .map_err(|e| AegisError::Crypto(format!("Invalid ML-KEM ciphertext: {:?}", e)))?;
-
-        let mlkem_ss = mlkem768::decapsulate(&mlkem_ct, &mlkem_sk);
+        let (_, ct_len) = self.mlkem_sizes();
+        self.check_mlkem_len("ciphertext", ciphertext.mlkem_ciphertext.len(), ct_len)?;
+        let invalid_sk = |e| AegisError::Crypto(format!("Invalid ML-KEM secret key: {:?}", e));
+        let invalid_ct = |e| AegisError::Crypto(format!("Invalid ML-KEM ciphertext: {:?}", e));
+        let mlkem_ss = match self.security_level {
+            SecurityLevel::Standard => {
+                let sk = mlkem768::SecretKey::from_bytes(&secret_key.mlkem).map_err(invalid_sk)?;
+                let ct = mlkem768::Ciphertext::from_bytes(&ciphertext.mlkem_ciphertext)
+                    .map_err(invalid_ct)?;
+                Zeroizing::new(mlkem768::decapsulate(&ct, &sk).as_bytes().to_vec())
+            }
+            SecurityLevel::High => {
+                let sk = mlkem1024::SecretKey::from_bytes(&secret_key.mlkem).map_err(invalid_sk)?;
+                let ct = mlkem1024::Ciphertext::from_bytes(&ciphertext.mlkem_ciphertext)
+                    .map_err(invalid_ct)?;
+                Zeroizing::new(mlkem1024::decapsulate(&ct, &sk).as_bytes().to_vec())
+            }
+        };
 
-        let shared_secret =
-            HybridSharedSecret::combine(x25519_shared.as_bytes(), mlkem_ss.as_bytes());
+        let shared_secret = HybridSharedSecret::combine(x25519_shared.as_bytes(), &mlkem_ss);
 
         debug!("Hybrid decapsulation completed");
         Ok(shared_secret)
@@ -346,11 +443,7 @@ impl crate::traits::KeyExchange for HybridKeyExchange {
 
     fn generate_keypair(&self) -> Result<(Self::PublicKey, Vec<u8>)> {
         let (pk, sk) = HybridKeyExchange::generate_keypair(self)?;
-        // Serialize secret key: x25519 (32 bytes raw) || mlkem (remaining)
-        let mut sk_bytes = Vec::with_capacity(32 + sk.mlkem.len());
-        sk_bytes.extend_from_slice(sk.x25519.as_bytes());
-        sk_bytes.extend_from_slice(&sk.mlkem);
-        Ok((pk, sk_bytes))
+        Ok((pk, sk.to_bytes().to_vec()))
     }
 
     fn encapsulate(&self, peer_public_key: &[u8]) -> Result<(Vec<u8>, Self::SharedSecret)> {
@@ -360,19 +453,7 @@ impl crate::traits::KeyExchange for HybridKeyExchange {
     }
 
     fn decapsulate(&self, ciphertext: &[u8], secret_key: &[u8]) -> Result<Self::SharedSecret> {
-        if secret_key.len() < 32 {
-            return Err(AegisError::Crypto("Secret key too short".to_string()));
-        }
-        let mut x25519_bytes = [0u8; 32];
-        x25519_bytes.copy_from_slice(&secret_key[..32]);
-        let mlkem_bytes = secret_key[32..].to_vec();
-
-        // Reconstruct X25519StaticSecret from raw bytes
-        let x25519 = X25519StaticSecret::from(x25519_bytes);
-        let sk = HybridSecretKey {
-            x25519,
-            mlkem: mlkem_bytes,
-        };
+        let sk = HybridSecretKey::from_bytes(secret_key)?;
         let ct = HybridCiphertext::from_bytes(ciphertext)?;
         HybridKeyExchange::decapsulate(self, &ct, &sk)
     }
@@ -385,13 +466,53 @@ impl crate::traits::KeyExchange for HybridKeyExchange {
 /// Secret key for hybrid key exchange.
 ///
 /// The `mlkem` field is zeroized on drop via `ZeroizeOnDrop`.
-/// The `x25519` field (`X25519StaticSecret`) implements `ZeroizeOnDrop` natively.
-#[derive(ZeroizeOnDrop)]
+/// The `x25519` field (`X25519StaticSecret`) wipes itself on drop through
+/// x25519-dalek's default `zeroize` feature, and is zeroized again here.
+#[derive(Zeroize, ZeroizeOnDrop)]
 pub struct HybridSecretKey {
     x25519: X25519StaticSecret,
     mlkem: Vec<u8>,
 }
 
+impl HybridSecretKey {
+    /// Serialize to bytes (X25519 static secret || ML-KEM secret key)
+    ///
+    /// Both components have a fixed size for each security level, so the
+    /// layout needs no length prefix.
+    pub fn to_bytes(&self) -> Zeroizing<Vec<u8>> {
+        let mut bytes = Zeroizing::new(Vec::with_capacity(32 + self.mlkem.len()));
+        bytes.extend_from_slice(self.x25519.as_bytes());
+        bytes.extend_from_slice(&self.mlkem);
+        bytes
+    }
+
+    /// Deserialize from bytes produced by [`to_bytes`](Self::to_bytes)
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < 32 {
+            return Err(AegisError::Crypto("Secret key too short".to_string()));
+        }
+        let mlkem_len = bytes.len() - 32;
+        if mlkem_len != mlkem768::secret_key_bytes() && mlkem_len != mlkem1024::secret_key_bytes() {
+            return Err(AegisError::Crypto(format!(
+                "ML-KEM secret key is {} bytes; expected {} or {}",
+                mlkem_len,
+                mlkem768::secret_key_bytes(),
+                mlkem1024::secret_key_bytes()
+            )));
+        }
+
+        let mut x25519_bytes = [0u8; 32];
+        x25519_bytes.copy_from_slice(&bytes[..32]);
+        let x25519 = X25519StaticSecret::from(x25519_bytes);
+        x25519_bytes.zeroize();
+
+        Ok(Self {
+            x25519,
+            mlkem: bytes[32..].to_vec(),
+        })
+    }
+}
+
 impl std::fmt::Debug for HybridSecretKey {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("HybridSecretKey")
@@ -406,7 +527,7 @@ impl std::fmt::Debug for HybridSecretKey {
 pub struct HybridCiphertext {
     /// X25519 ephemeral public key
     pub x25519_ephemeral: [u8; 32],
-    /// ML-KEM-768 ciphertext
+    /// ML-KEM-768 or ML-KEM-1024 ciphertext
     pub mlkem_ciphertext: Vec<u8>,
 }
 
@@ -540,6 +661,28 @@ mod tests {
         assert_ne!(client_key, server_key, "Client and server keys must differ");
     }
 
+    #[test]
+    fn test_derive_key_deterministic_and_input_sensitive() {
+        let x25519 = [11u8; 32];
+        let mlkem = [12u8; 32];
+
+        // Independently combined secrets from the same inputs agree
+        let key1 = HybridSharedSecret::combine(&x25519, &mlkem).derive_key();
+        let key2 = HybridSharedSecret::combine(&x25519, &mlkem).derive_key();
+        assert_eq!(key1, key2, "derive_key() must be deterministic");
+
+        // Changing either half of the input changes the key
+        let mut other_mlkem = mlkem;
+        other_mlkem[31] ^= 1;
+        let key3 = HybridSharedSecret::combine(&x25519, &other_mlkem).derive_key();
+        assert_ne!(key1, key3, "ML-KEM secret must influence the key");
+
+        let mut other_x25519 = x25519;
+        other_x25519[0] ^= 1;
+        let key4 = HybridSharedSecret::combine(&other_x25519, &mlkem).derive_key();
+        assert_ne!(key1, key4, "X25519 secret must influence the key");
+    }
+
     // =========================================================================
     // Phase 2 Tests: Zeroization (compile-time via ZeroizeOnDrop)
     // =========================================================================
@@ -549,6 +692,43 @@ mod tests {
         // Verify that HybridSecretKey implements ZeroizeOnDrop (compile-time check)
         fn assert_zeroize_on_drop<T: ZeroizeOnDrop>() {}
         assert_zeroize_on_drop::<HybridSecretKey>();
+        // Needed for the derived Drop impl to wipe the X25519 half too
+        fn assert_zeroize<T: Zeroize>() {}
+        assert_zeroize::<X25519StaticSecret>();
+    }
+
+    #[test]
+    fn test_secret_key_wipe_clears_both_halves() {
+        let kex = HybridKeyExchange::new();
+        let (_, mut sk) = kex.generate_keypair().unwrap();
+        assert!(sk.mlkem.iter().any(|&b| b != 0));
+
+        // The same wipe the derived Drop impl performs
+        sk.zeroize();
+        assert!(sk.mlkem.is_empty());
+        assert_eq!(sk.x25519.to_bytes(), [0u8; 32]);
+        drop(sk);
+    }
+
+    #[test]
+    fn test_secret_key_bytes_roundtrip() {
+        for kex in [
+            HybridKeyExchange::new(),
+            HybridKeyExchange::new_with_level(SecurityLevel::High),
+        ] {
+            let (pk, sk) = kex.generate_keypair().unwrap();
+            let restored = HybridSecretKey::from_bytes(&sk.to_bytes()).unwrap();
+            assert_eq!(*restored.to_bytes(), *sk.to_bytes());
+
+            let (ct, client_ss) = kex.encapsulate(&pk).unwrap();
+            let original = kex.decapsulate(&ct, &sk).unwrap();
+            let resumed = kex.decapsulate(&ct, &restored).unwrap();
+            assert_eq!(original.as_bytes(), resumed.as_bytes());
+            assert_eq!(resumed.as_bytes(), client_ss.as_bytes());
+        }
+
+        assert!(HybridSecretKey::from_bytes(&[0u8; 16]).is_err());
+        assert!(HybridSecretKey::from_bytes(&[0u8; 32 + 100]).is_err());
     }
 
     #[test]
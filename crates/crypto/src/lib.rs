@@ -6,6 +6,7 @@
 //! # Features
 //!
 //! - **Hybrid Key Exchange**: X25519 + ML-KEM-768 for quantum resistance
+//! - **Digital Signatures**: ML-DSA-65 (FIPS 204), alone or hybrid with Ed25519
 //! - **TLS Integration**: Custom crypto provider for rustls (coming soon)
 //! - **Formal Verification**: Designed for Kani/Verus verification
 //!
@@ -30,10 +31,17 @@
 //! ```
 
 pub mod attestation;
+pub mod audit;
+pub mod bench;
 pub mod certmanager;
 pub mod cipher;
+pub mod connection;
 pub mod hybrid_kex;
 pub mod mtls;
+#[cfg(feature = "ocsp")]
+pub mod ocsp;
+pub mod replay;
+pub mod revocation;
 pub mod signing;
 pub mod stream;
 pub mod tls;
@@ -42,14 +50,20 @@ pub mod traits;
 pub use attestation::{
     AttestationProvider, AttestationQuote, EnclaveIdentity, TeeCapabilities, TeePlatform,
 };
-pub use certmanager::{CertManager, CertType, ParsedCert};
+pub use audit::{AuditLog, AuditSink, AuthEvent, AuthEventKind, ConnectionEstablished};
+pub use bench::{AlgorithmBenchmark, benchmark_algorithms, recommend_algorithm};
+pub use certmanager::{CertManager, CertPaths, CertType, ParsedCert};
 pub use cipher::{Cipher, CipherAlgorithm, EncryptionKey};
+pub use connection::{PqcClient, PqcServerConnection, ServerHello};
 pub use hybrid_kex::{HybridCiphertext, HybridKeyExchange, HybridPublicKey, HybridSharedSecret};
 pub use mtls::{
     AuthState, AuthenticatedClient, CertInfo, MtlsAuthenticator, MtlsConfig, MtlsHandler,
 };
+pub use replay::ReplayCache;
+pub use revocation::{CrlRevocationChecker, RevocationChecker, RevocationMode};
 pub use signing::{
     HybridSignature, HybridSigner, HybridSigningPublicKey, HybridVerifier, MlDsa44Signer,
-    MlDsa65Signer, MlDsa87Signer, MlDsaAlgorithm, MlDsaSignature, MlDsaVerifier, SigningKeyPair,
+    MlDsa65PublicKey, MlDsa65SecretKey, MlDsa65Signer, MlDsa87Signer, MlDsaAlgorithm,
+    MlDsaSignature, MlDsaVerifier, SigningKeyPair,
 };
-pub use traits::KeyExchange;
+pub use traits::{KeyExchange, Signer, Verifier};
@@ -2,14 +2,18 @@
 //!
 //! Provides certificate-based authentication with Post-Quantum cryptography.
 
-use crate::certmanager::{CertManager, ParsedCert};
+use crate::audit::{AuditLog, AuditSink, AuthEvent, AuthEventKind, ConnectionEstablished};
+use crate::certmanager::{CertManager, DEFAULT_CLOCK_SKEW_TOLERANCE, ParsedCert};
+use crate::revocation::{RevocationChecker, RevocationMode};
 use crate::tls::{PqcHandshake, PqcTlsConfig, SecureChannel};
 use aegis_common::{AegisError, Result};
 use parking_lot::RwLock;
 use std::collections::HashMap;
+use std::net::SocketAddr;
 use std::path::Path;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
 use tracing::{debug, error, info};
 
 /// mTLS Configuration
@@ -25,6 +29,10 @@ pub struct MtlsConfig {
     pub require_client_cert: bool,
     /// Enable PQC key exchange
     pub pqc_enabled: bool,
+    /// Allowed clock skew when checking certificate validity
+    pub clock_skew_tolerance: Duration,
+    /// Reject revoked client certificates
+    pub revocation: Option<RevocationMode>,
 }
 
 impl Default for MtlsConfig {
@@ -35,6 +43,8 @@ impl Default for MtlsConfig {
             ca_path: Some("/etc/aegis/certs/ca.crt".to_string()),
             require_client_cert: false,
             pqc_enabled: true,
+            clock_skew_tolerance: DEFAULT_CLOCK_SKEW_TOLERANCE,
+            revocation: None,
         }
     }
 }
@@ -80,6 +90,8 @@ pub struct AuthenticatedClient {
     pub channel: Option<SecureChannel>,
     /// Authentication timestamp
     pub authenticated_at: Option<u64>,
+    /// Remote address, when known
+    pub peer_addr: Option<SocketAddr>,
     /// PQC server-side handshake state — holds the ephemeral secret key
     /// generated during `accept_connection`. Consumed by `complete_handshake`.
     pub(crate) handshake_state: Option<crate::tls::ServerHandshakeState>,
@@ -94,6 +106,7 @@ impl AuthenticatedClient {
             state: AuthState::Unauthenticated,
             channel: None,
             authenticated_at: None,
+            peer_addr: None,
             handshake_state: None,
         }
     }
@@ -118,6 +131,12 @@ pub struct MtlsAuthenticator {
     connection_counter: AtomicU64,
     /// Server Identity Key for PQC Handshake Signatures
     pub server_identity_key: Option<crate::signing::MlDsa65Signer>,
+    /// Optional JSON audit log for established connections
+    audit_log: Option<Arc<AuditLog>>,
+    /// Optional destination for authentication audit events
+    audit_sink: Option<Arc<dyn AuditSink>>,
+    /// Revocation checker built from `config.revocation`
+    revocation: Option<Arc<dyn RevocationChecker>>,
 }
 
 impl MtlsAuthenticator {
@@ -129,16 +148,41 @@ impl MtlsAuthenticator {
             ..Default::default()
         };
 
+        let revocation = config
+            .revocation
+            .as_ref()
+            .map(RevocationMode::checker)
+            .transpose()?;
+
         Ok(Self {
+            cert_manager: CertManager::new().with_clock_skew_tolerance(config.clock_skew_tolerance),
             config,
-            cert_manager: CertManager::new(),
             pqc_handshake: PqcHandshake::new(pqc_config),
             clients: Arc::new(RwLock::new(HashMap::new())),
             connection_counter: AtomicU64::new(1),
             server_identity_key: None,
+            audit_log: None,
+            audit_sink: None,
+            revocation,
         })
     }
 
+    /// Append `connection_established` events to an audit log
+    pub fn with_audit_log(mut self, audit_log: Arc<AuditLog>) -> Self {
+        self.audit_log = Some(audit_log);
+        self
+    }
+
+    /// Send accept, handshake, authentication and disconnect events to `sink`
+    pub fn with_audit_sink(mut self, sink: Arc<dyn AuditSink>) -> Self {
+        self.audit_sink = Some(sink);
+        self
+    }
+
+    fn audit(&self, connection_id: u64, peer_addr: Option<SocketAddr>, kind: AuthEventKind) {
+        AuthEvent::new(connection_id, peer_addr, kind).emit(self.audit_sink.as_deref());
+    }
+
     /// Initialize with certificates from files
     pub fn init_from_files(&mut self) -> Result<()> {
         // Load server certificate
@@ -180,6 +224,29 @@ impl MtlsAuthenticator {
         u64,
         crate::hybrid_kex::HybridPublicKey,
         crate::signing::MlDsaSignature,
+    )> {
+        self.start_handshake(None)
+    }
+
+    /// Accept a new connection from `peer_addr` and start authentication
+    pub fn accept_connection_from(
+        &self,
+        peer_addr: SocketAddr,
+    ) -> Result<(
+        u64,
+        crate::hybrid_kex::HybridPublicKey,
+        crate::signing::MlDsaSignature,
+    )> {
+        self.start_handshake(Some(peer_addr))
+    }
+
+    fn start_handshake(
+        &self,
+        peer_addr: Option<SocketAddr>,
+    ) -> Result<(
+        u64,
+        crate::hybrid_kex::HybridPublicKey,
+        crate::signing::MlDsaSignature,
     )> {
         let conn_id = self.connection_counter.fetch_add(1, Ordering::SeqCst);
 
@@ -193,9 +260,11 @@ impl MtlsAuthenticator {
         // `complete_handshake` can decapsulate with the correct secret key.
         let mut client = AuthenticatedClient::new(conn_id);
         client.state = AuthState::HandshakeInProgress;
+        client.peer_addr = peer_addr;
         client.handshake_state = Some(server_state);
 
         self.clients.write().insert(conn_id, client);
+        self.audit(conn_id, peer_addr, AuthEventKind::Accepted);
 
         debug!("Accepted connection {}, starting PQC handshake", conn_id);
         Ok((conn_id, server_pk, signature))
@@ -212,9 +281,42 @@ impl MtlsAuthenticator {
         // We need to keep the lock while modifying
         let mut clients = self.clients.write();
 
-        let client = clients
-            .get_mut(&connection_id)
-            .ok_or_else(|| AegisError::Crypto("Connection not found".to_string()))?;
+        let Some(client) = clients.get_mut(&connection_id) else {
+            self.audit(
+                connection_id,
+                None,
+                AuthEventKind::AuthFailure {
+                    reason: "Connection not found".to_string(),
+                },
+            );
+            return Err(AegisError::Crypto("Connection not found".to_string()));
+        };
+
+        let result = self.authenticate(client, ciphertext, client_cert_der);
+        let kind = match &result {
+            Ok(()) => AuthEventKind::AuthSuccess {
+                subject_cn: client.cert.as_ref().map(|cert| cert.subject_cn.clone()),
+                fingerprint: client.cert.as_ref().map(|cert| cert.fingerprint.clone()),
+            },
+            Err(e) => AuthEventKind::AuthFailure {
+                reason: match &client.state {
+                    AuthState::Failed(reason) => reason.clone(),
+                    _ => e.to_string(),
+                },
+            },
+        };
+        self.audit(connection_id, client.peer_addr, kind);
+        result
+    }
+
+    /// Verify the client and finish the key exchange for `client`
+    fn authenticate(
+        &self,
+        client: &mut AuthenticatedClient,
+        ciphertext: &crate::hybrid_kex::HybridCiphertext,
+        client_cert_der: Option<&[u8]>,
+    ) -> Result<()> {
+        let connection_id = client.connection_id;
 
         // Parse client certificate if provided
         let client_cert = if let Some(der) = client_cert_der {
@@ -245,10 +347,27 @@ impl MtlsAuthenticator {
                 }
 
                 // If we get here, verify_chain returned Ok(true) (it never returns Ok(false))
-                if !cert.is_valid_now() {
+                if !cert.is_valid_with_tolerance(self.config.clock_skew_tolerance) {
                     client.state = AuthState::Failed("Client certificate expired".to_string());
                     return Err(AegisError::Crypto("Client certificate expired".to_string()));
                 }
+                if let Some(checker) = &self.revocation {
+                    match checker.is_revoked(cert) {
+                        Ok(false) => {}
+                        Ok(true) => {
+                            client.state =
+                                AuthState::Failed("Client certificate revoked".to_string());
+                            return Err(AegisError::Crypto(
+                                "Client certificate revoked".to_string(),
+                            ));
+                        }
+                        Err(e) => {
+                            client.state =
+                                AuthState::Failed(format!("Revocation check failed: {}", e));
+                            return Err(e);
+                        }
+                    }
+                }
                 debug!("Client certificate verified: {}", cert.subject_cn);
                 // Continue to PQC
             } else {
@@ -267,9 +386,27 @@ impl MtlsAuthenticator {
                 "Handshake state missing — accept_connection must be called first".to_string(),
             )
         })?;
-        let channel = self
-            .pqc_handshake
-            .server_complete(ciphertext, server_state)?;
+        let channel = match self.pqc_handshake.server_complete(ciphertext, server_state) {
+            Ok(channel) => channel,
+            Err(e) => {
+                client.state = AuthState::Failed(e.to_string());
+                return Err(e);
+            }
+        };
+
+        ConnectionEstablished {
+            peer_addr: client.peer_addr,
+            client_authenticated: client_cert.is_some(),
+            ..ConnectionEstablished::from_channel(&channel)
+        }
+        .emit(self.audit_log.as_deref());
+        self.audit(
+            connection_id,
+            client.peer_addr,
+            AuthEventKind::HandshakeComplete {
+                channel_id: channel.channel_id(),
+            },
+        );
 
         // Update client state
         client.cert = client_cert;
@@ -300,7 +437,8 @@ impl MtlsAuthenticator {
     pub fn disconnect(&self, connection_id: u64) -> Result<()> {
         let mut clients = self.clients.write();
 
-        if clients.remove(&connection_id).is_some() {
+        if let Some(client) = clients.remove(&connection_id) {
+            self.audit(connection_id, client.peer_addr, AuthEventKind::Disconnected);
             debug!("Disconnected client {}", connection_id);
             Ok(())
         } else {
@@ -411,7 +549,14 @@ pub struct CertInfo {
 impl CertInfo {
     /// Check if certificate is currently valid
     pub fn is_valid(&self, current_time: u64) -> bool {
-        current_time >= self.not_before && current_time <= self.not_after
+        self.is_valid_with_tolerance(current_time, Duration::ZERO)
+    }
+
+    /// Check validity, widening both bounds by `tolerance` to absorb clock skew
+    pub fn is_valid_with_tolerance(&self, current_time: u64, tolerance: Duration) -> bool {
+        let tolerance = tolerance.as_secs();
+        current_time >= self.not_before.saturating_sub(tolerance)
+            && current_time <= self.not_after.saturating_add(tolerance)
     }
 }
 
@@ -451,6 +596,27 @@ mod tests {
         assert!(!cert.is_valid(2500));
     }
 
+    #[test]
+    fn test_cert_validity_clock_skew_tolerance() {
+        let cert = CertInfo {
+            subject: "CN=test".to_string(),
+            issuer: "CN=ca".to_string(),
+            serial: "0001".to_string(),
+            not_before: 1000,
+            not_after: 2000,
+            is_ca: false,
+        };
+        let tolerance = MtlsConfig::default().clock_skew_tolerance;
+        assert_eq!(tolerance, Duration::from_secs(300));
+
+        // Expired 100s ago: accepted within tolerance, rejected without
+        assert!(!cert.is_valid(2100));
+        assert!(cert.is_valid_with_tolerance(2100, tolerance));
+        assert!(!cert.is_valid_with_tolerance(2301, tolerance));
+        assert!(cert.is_valid_with_tolerance(700, tolerance));
+        assert!(!cert.is_valid_with_tolerance(699, tolerance));
+    }
+
     #[test]
     fn test_path_validation_error() {
         let config = MtlsConfig {
@@ -556,6 +722,122 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_complete_handshake_rejects_replayed_ciphertext() {
+        use crate::signing::{MlDsa65Signer, SigningKeyPair};
+        use crate::tls::{PqcHandshake, PqcTlsConfig};
+
+        let mut auth = MtlsAuthenticator::new(MtlsConfig::default()).unwrap();
+        let identity = MlDsa65Signer::generate().unwrap();
+        let identity_pk = identity.public_key().to_vec();
+        auth.server_identity_key = Some(identity);
+        let client = PqcHandshake::new(PqcTlsConfig::default());
+
+        let (first, pk, sig) = auth.accept_connection().unwrap();
+        let (ciphertext, _) = client.client_complete(&pk, &identity_pk, &sig).unwrap();
+        auth.complete_handshake(first, &ciphertext, None).unwrap();
+
+        // The captured ciphertext is rejected on a new connection
+        let (second, _, _) = auth.accept_connection().unwrap();
+        let result = auth.complete_handshake(second, &ciphertext, None);
+        assert!(matches!(result, Err(AegisError::Crypto(msg)) if msg.contains("Replayed")));
+        assert!(matches!(
+            auth.get_client_state(second).unwrap(),
+            AuthState::Failed(_)
+        ));
+
+        // A fresh ciphertext still succeeds
+        let (third, pk, sig) = auth.accept_connection().unwrap();
+        let (ciphertext, _) = client.client_complete(&pk, &identity_pk, &sig).unwrap();
+        auth.complete_handshake(third, &ciphertext, None).unwrap();
+        assert_eq!(
+            auth.get_client_state(third).unwrap(),
+            AuthState::Authenticated
+        );
+    }
+
+    #[test]
+    fn test_complete_handshake_channel_matches_client() {
+        use crate::signing::{MlDsa65Signer, SigningKeyPair};
+        use crate::tls::{PqcHandshake, PqcTlsConfig};
+
+        let mut auth = MtlsAuthenticator::new(MtlsConfig::default()).unwrap();
+        let identity = MlDsa65Signer::generate().unwrap();
+        let identity_pk = identity.public_key().to_vec();
+        auth.server_identity_key = Some(identity);
+
+        // The client encapsulates against the key sent by accept_connection,
+        // so the server must decapsulate with that same keypair
+        let (conn_id, pk, sig) = auth.accept_connection().unwrap();
+        let client = PqcHandshake::new(PqcTlsConfig::default());
+        let (ciphertext, client_channel) = client.client_complete(&pk, &identity_pk, &sig).unwrap();
+        auth.complete_handshake(conn_id, &ciphertext, None).unwrap();
+
+        let clients = auth.clients.read();
+        let server_channel = clients[&conn_id].channel.as_ref().unwrap();
+        let sealed = client_channel.encrypt(b"hello over pqc").unwrap();
+        assert_eq!(server_channel.decrypt(&sealed).unwrap(), b"hello over pqc");
+        assert!(clients[&conn_id].handshake_state.is_none());
+    }
+
+    #[derive(Clone, Default)]
+    struct CaptureWriter(Arc<parking_lot::Mutex<Vec<u8>>>);
+
+    impl std::io::Write for CaptureWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> tracing_subscriber::fmt::MakeWriter<'a> for CaptureWriter {
+        type Writer = Self;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    #[test]
+    fn test_complete_handshake_emits_connection_established() {
+        use crate::signing::{MlDsa65Signer, SigningKeyPair};
+        use crate::tls::{PqcHandshake, PqcTlsConfig};
+
+        let capture = CaptureWriter::default();
+        let subscriber = tracing_subscriber::fmt()
+            .json()
+            .with_writer(capture.clone())
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let mut auth = MtlsAuthenticator::new(MtlsConfig::default()).unwrap();
+        let identity = MlDsa65Signer::generate().unwrap();
+        let identity_pk = identity.public_key().to_vec();
+        auth.server_identity_key = Some(identity);
+
+        let peer: SocketAddr = "10.0.0.5:5000".parse().unwrap();
+        let (conn_id, pk, sig) = auth.accept_connection_from(peer).unwrap();
+        let client = PqcHandshake::new(PqcTlsConfig::default());
+        let (ciphertext, channel) = client.client_complete(&pk, &identity_pk, &sig).unwrap();
+        auth.complete_handshake(conn_id, &ciphertext, None).unwrap();
+
+        let output = String::from_utf8(capture.0.lock().clone()).unwrap();
+        let line = output
+            .lines()
+            .find(|l| l.contains("\"event\":\"connection_established\""))
+            .expect("connection_established event");
+        assert!(line.contains("\"target\":\"aegis_audit\""));
+        assert!(line.contains("\"peer_addr\":\"10.0.0.5:5000\""));
+        assert!(line.contains("\"algorithm\":\"HybridMlKem768\""));
+        assert!(line.contains("\"cipher\":\"Aes256Gcm\""));
+        assert!(line.contains(&format!("\"channel_id\":{}", channel.channel_id())));
+        assert!(line.contains("\"client_auth\":false"));
+    }
+
     #[test]
     fn test_disconnect() {
         let config = MtlsConfig::default();
@@ -921,6 +1203,8 @@ mod tests {
             ca_path: Some("/custom/ca.crt".to_string()),
             require_client_cert: true,
             pqc_enabled: false,
+            clock_skew_tolerance: Duration::from_secs(60),
+            revocation: None,
         };
 
         assert!(config.cert_path.contains("custom"));
@@ -1541,4 +1825,176 @@ mod tests {
             "Client must decrypt server message — channel keys must match"
         );
     }
+
+    #[test]
+    fn test_complete_handshake_rejects_revoked_cert() {
+        use crate::signing::{MlDsa65Signer, SigningKeyPair};
+        use crate::tls::{PqcHandshake, PqcTlsConfig};
+
+        let mut ca_params = rcgen::CertificateParams::default();
+        ca_params
+            .distinguished_name
+            .push(rcgen::DnType::CommonName, "Revoking CA");
+        ca_params.is_ca = rcgen::IsCa::Ca(rcgen::BasicConstraints::Unconstrained);
+        let ca_key = rcgen::KeyPair::generate().unwrap();
+        let ca_cert = ca_params.self_signed(&ca_key).unwrap();
+        let issue = |serial: u64| {
+            let mut params = rcgen::CertificateParams::default();
+            params
+                .distinguished_name
+                .push(rcgen::DnType::CommonName, "client");
+            params.serial_number = Some(rcgen::SerialNumber::from(serial));
+            let key = rcgen::KeyPair::generate().unwrap();
+            params
+                .signed_by(&key, &ca_cert, &ca_key)
+                .unwrap()
+                .der()
+                .to_vec()
+        };
+
+        // CRL revoking serial 1001
+        let now = ::time::OffsetDateTime::now_utc();
+        let crl = rcgen::CertificateRevocationListParams {
+            this_update: now - ::time::Duration::hours(1),
+            next_update: now + ::time::Duration::days(7),
+            crl_number: rcgen::SerialNumber::from(1u64),
+            issuing_distribution_point: None,
+            revoked_certs: vec![rcgen::RevokedCertParams {
+                serial_number: rcgen::SerialNumber::from(1001u64),
+                revocation_time: now - ::time::Duration::minutes(5),
+                reason_code: Some(rcgen::RevocationReason::KeyCompromise),
+                invalidity_date: None,
+            }],
+            key_identifier_method: rcgen::KeyIdMethod::Sha256,
+        }
+        .signed_by(&ca_cert, &ca_key)
+        .unwrap();
+        let crl_path =
+            std::env::temp_dir().join(format!("aegis-mtls-crl-{}.pem", std::process::id()));
+        std::fs::write(&crl_path, crl.pem().unwrap()).unwrap();
+
+        let config = MtlsConfig {
+            require_client_cert: true,
+            revocation: Some(RevocationMode::Crl {
+                path: crl_path.display().to_string(),
+            }),
+            ..Default::default()
+        };
+        let mut auth = MtlsAuthenticator::new(config).unwrap();
+        std::fs::remove_file(&crl_path).unwrap();
+        auth.cert_manager
+            .add_trusted_ca(CertManager::parse_der(ca_cert.der()).unwrap())
+            .unwrap();
+        auth.server_identity_key = Some(MlDsa65Signer::generate().unwrap());
+
+        let handshake = |client_der: &[u8]| {
+            let (conn_id, server_pk, sig) = auth.accept_connection().unwrap();
+            let (ciphertext, _) = PqcHandshake::new(PqcTlsConfig::default())
+                .client_complete(
+                    &server_pk,
+                    auth.server_identity_key.as_ref().unwrap().public_key(),
+                    &sig,
+                )
+                .unwrap();
+            auth.complete_handshake(conn_id, &ciphertext, Some(client_der))
+        };
+
+        match handshake(&issue(1001)) {
+            Err(AegisError::Crypto(msg)) => assert_eq!(msg, "Client certificate revoked"),
+            other => panic!("Expected revocation error, got {:?}", other),
+        }
+        assert!(handshake(&issue(1002)).is_ok());
+    }
+
+    #[test]
+    fn test_audit_sink_records_authentication_flow() {
+        use crate::audit::{AuthEvent, AuthEventKind};
+        use crate::signing::{MlDsa65Signer, SigningKeyPair};
+        use crate::tls::{PqcHandshake, PqcTlsConfig};
+
+        let mut ca_params = rcgen::CertificateParams::default();
+        ca_params
+            .distinguished_name
+            .push(rcgen::DnType::CommonName, "Audit CA");
+        ca_params.is_ca = rcgen::IsCa::Ca(rcgen::BasicConstraints::Unconstrained);
+        let ca_key = rcgen::KeyPair::generate().unwrap();
+        let ca_cert = ca_params.self_signed(&ca_key).unwrap();
+        let mut client_params = rcgen::CertificateParams::default();
+        client_params
+            .distinguished_name
+            .push(rcgen::DnType::CommonName, "audited-client");
+        let client_key = rcgen::KeyPair::generate().unwrap();
+        let client_der = client_params
+            .signed_by(&client_key, &ca_cert, &ca_key)
+            .unwrap()
+            .der()
+            .to_vec();
+        let fingerprint = CertManager::parse_der(&client_der).unwrap().fingerprint;
+
+        let events = Arc::new(parking_lot::Mutex::new(Vec::<AuthEvent>::new()));
+        let sink = events.clone();
+        let config = MtlsConfig {
+            require_client_cert: true,
+            ..Default::default()
+        };
+        let mut auth = MtlsAuthenticator::new(config)
+            .unwrap()
+            .with_audit_sink(Arc::new(move |event: &AuthEvent| {
+                sink.lock().push(event.clone())
+            }));
+        auth.cert_manager
+            .add_trusted_ca(CertManager::parse_der(ca_cert.der()).unwrap())
+            .unwrap();
+        auth.server_identity_key = Some(MlDsa65Signer::generate().unwrap());
+
+        let peer: SocketAddr = "198.51.100.7:40000".parse().unwrap();
+        let handshake = |client_der: Option<&[u8]>| {
+            let (conn_id, server_pk, sig) = auth.accept_connection_from(peer).unwrap();
+            let (ciphertext, _) = PqcHandshake::new(PqcTlsConfig::default())
+                .client_complete(
+                    &server_pk,
+                    auth.server_identity_key.as_ref().unwrap().public_key(),
+                    &sig,
+                )
+                .unwrap();
+            (
+                conn_id,
+                auth.complete_handshake(conn_id, &ciphertext, client_der),
+            )
+        };
+
+        let (ok_id, result) = handshake(Some(&client_der));
+        result.unwrap();
+        let (failed_id, result) = handshake(None);
+        assert!(result.is_err());
+        auth.disconnect(ok_id).unwrap();
+
+        let events = events.lock();
+        let kinds: Vec<_> = events.iter().map(|e| (e.connection_id, e.name())).collect();
+        assert_eq!(
+            kinds,
+            [
+                (ok_id, "accept"),
+                (ok_id, "handshake_complete"),
+                (ok_id, "auth_success"),
+                (failed_id, "accept"),
+                (failed_id, "auth_failure"),
+                (ok_id, "disconnect"),
+            ]
+        );
+        assert!(events.iter().all(|e| e.peer_addr == Some(peer)));
+        assert_eq!(
+            events[2].kind,
+            AuthEventKind::AuthSuccess {
+                subject_cn: Some("audited-client".to_string()),
+                fingerprint: Some(fingerprint),
+            }
+        );
+        assert_eq!(
+            events[4].kind,
+            AuthEventKind::AuthFailure {
+                reason: "Client certificate required".to_string(),
+            }
+        );
+    }
 }
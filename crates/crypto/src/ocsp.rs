@@ -0,0 +1,461 @@
+//! OCSP Revocation Checking
+//!
+//! Enabled by the `ocsp` feature. [`OcspRevocationChecker`] sends an
+//! RFC 6960 request for each certificate to its CA's responder over plain
+//! HTTP and accepts an answer signed by the CA itself or by a responder
+//! certificate the CA issued for OCSP signing. The query blocks the calling
+//! thread for up to the configured timeout.
+
+use crate::certmanager::ParsedCert;
+use crate::revocation::RevocationChecker;
+use aegis_common::{AegisError, Result};
+use sha1::{Digest, Sha1};
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::{Duration, SystemTime};
+use tracing::debug;
+use x509_parser::asn1_rs::{Any, BitString, Class, FromDer, GeneralizedTime, Tag};
+use x509_parser::prelude::*;
+
+/// `id-pkix-ocsp-basic`
+const OCSP_BASIC_OID: &[u8] = &[0x2b, 0x06, 0x01, 0x05, 0x05, 0x07, 0x30, 0x01, 0x01];
+/// `id-sha1` with NULL parameters, the hash used for `CertID`s
+const SHA1_ALGORITHM: &[u8] = &[
+    0x30, 0x09, 0x06, 0x05, 0x2b, 0x0e, 0x03, 0x02, 0x1a, 0x05, 0x00,
+];
+
+/// Revocation checker that queries an OCSP responder
+pub struct OcspRevocationChecker {
+    /// `host:port` of the responder
+    authority: String,
+    /// Request path on the responder
+    path: String,
+    /// CA whose certificates are checked
+    issuer: ParsedCert,
+    timeout: Duration,
+}
+
+impl OcspRevocationChecker {
+    /// Default time allowed for the responder to answer
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+    /// Check certificates issued by `issuer` with the responder at
+    /// `responder_url`, which must be an `http://` URL
+    pub fn new(responder_url: &str, issuer: ParsedCert) -> Result<Self> {
+        let rest = responder_url.strip_prefix("http://").ok_or_else(|| {
+            AegisError::Config(format!(
+                "OCSP responder URL must use http://: {}",
+                responder_url
+            ))
+        })?;
+        let (authority, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], &rest[i..]),
+            None => (rest, "/"),
+        };
+        let has_port = authority
+            .rsplit_once(':')
+            .is_some_and(|(_, port)| port.parse::<u16>().is_ok());
+        let authority = if has_port {
+            authority.to_string()
+        } else {
+            format!("{}:80", authority)
+        };
+
+        Ok(Self {
+            authority,
+            path: path.to_string(),
+            issuer,
+            timeout: Self::DEFAULT_TIMEOUT,
+        })
+    }
+
+    /// Set the time allowed for the responder to answer
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// DER `CertID` identifying `cert` to the responder
+    fn cert_id(&self, cert: &X509Certificate<'_>, issuer: &X509Certificate<'_>) -> Vec<u8> {
+        let name_hash = Sha1::digest(cert.issuer().as_raw());
+        let key_hash = Sha1::digest(&issuer.public_key().subject_public_key.data);
+        let mut content = SHA1_ALGORITHM.to_vec();
+        content.extend(tlv(0x04, &name_hash));
+        content.extend(tlv(0x04, &key_hash));
+        content.extend(tlv(0x02, cert.raw_serial()));
+        tlv(0x30, &content)
+    }
+
+    /// Send a DER `OCSPRequest` and return the response body
+    fn post(&self, request: &[u8]) -> Result<Vec<u8>> {
+        let addr = self
+            .authority
+            .to_socket_addrs()
+            .map_err(|e| AegisError::Network(format!("Failed to resolve OCSP responder: {}", e)))?
+            .next()
+            .ok_or_else(|| AegisError::Network("OCSP responder has no address".to_string()))?;
+        let mut stream = TcpStream::connect_timeout(&addr, self.timeout)
+            .map_err(|e| AegisError::Network(format!("OCSP responder unreachable: {}", e)))?;
+        stream.set_read_timeout(Some(self.timeout))?;
+        stream.set_write_timeout(Some(self.timeout))?;
+
+        let host = self.authority.trim_end_matches(":80");
+        let head = format!(
+            "POST {} HTTP/1.0\r\nHost: {}\r\nContent-Type: application/ocsp-request\r\n\
+             Content-Length: {}\r\n\r\n",
+            self.path,
+            host,
+            request.len()
+        );
+        stream.write_all(head.as_bytes())?;
+        stream.write_all(request)?;
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response)?;
+
+        let split = response
+            .windows(4)
+            .position(|w| w == b"\r\n\r\n")
+            .ok_or_else(|| AegisError::Network("Malformed OCSP HTTP response".to_string()))?;
+        let status_line = String::from_utf8_lossy(&response[..split]);
+        if status_line.split_whitespace().nth(1) != Some("200") {
+            return Err(AegisError::Network(format!(
+                "OCSP responder returned {}",
+                status_line.lines().next().unwrap_or_default()
+            )));
+        }
+        Ok(response[split + 4..].to_vec())
+    }
+}
+
+impl RevocationChecker for OcspRevocationChecker {
+    fn is_revoked(&self, cert: &ParsedCert) -> Result<bool> {
+        let (_, cert_x509) = X509Certificate::from_der(&cert.der_bytes)
+            .map_err(|e| AegisError::Crypto(format!("Failed to parse X.509: {:?}", e)))?;
+        let (_, issuer_x509) = X509Certificate::from_der(&self.issuer.der_bytes)
+            .map_err(|e| AegisError::Crypto(format!("Failed to parse X.509: {:?}", e)))?;
+        if cert_x509.issuer().as_raw() != issuer_x509.subject().as_raw() {
+            return Ok(false);
+        }
+
+        let cert_id = self.cert_id(&cert_x509, &issuer_x509);
+        let request = tlv(0x30, &tlv(0x30, &tlv(0x30, &tlv(0x30, &cert_id))));
+        let response = self.post(&request)?;
+        let revoked = parse_response(&response, &cert_id, &issuer_x509)?;
+        debug!(
+            "OCSP status of {} ({}): {}",
+            cert.subject_cn,
+            cert.serial,
+            if revoked { "revoked" } else { "good" }
+        );
+        Ok(revoked)
+    }
+}
+
+/// DER tag-length-value
+fn tlv(tag: u8, content: &[u8]) -> Vec<u8> {
+    let len = content.len();
+    let mut out = vec![tag];
+    if len < 0x80 {
+        out.push(len as u8);
+    } else {
+        let bytes = len.to_be_bytes();
+        let skip = bytes.iter().take_while(|b| **b == 0).count();
+        out.push(0x80 | (bytes.len() - skip) as u8);
+        out.extend(&bytes[skip..]);
+    }
+    out.extend(content);
+    out
+}
+
+fn malformed(what: &str) -> AegisError {
+    AegisError::Crypto(format!("Malformed OCSP response: {}", what))
+}
+
+/// Each DER element in `data` with its encoded bytes
+fn elements(mut data: &[u8]) -> Result<Vec<(Any<'_>, &[u8])>> {
+    let mut out = Vec::new();
+    while !data.is_empty() {
+        let (rest, any) = Any::from_der(data).map_err(|_| malformed("bad DER"))?;
+        out.push((any, &data[..data.len() - rest.len()]));
+        data = rest;
+    }
+    Ok(out)
+}
+
+fn is_universal(any: &Any<'_>, tag: Tag) -> bool {
+    any.header.class() == Class::Universal && any.header.tag() == tag
+}
+
+fn is_context(any: &Any<'_>, tag: u32) -> bool {
+    any.header.class() == Class::ContextSpecific && any.header.tag() == Tag(tag)
+}
+
+/// Whether a DER `OCSPResponse` says the certificate with `cert_id` is
+/// revoked, after checking it was signed on behalf of `issuer`
+fn parse_response(der: &[u8], cert_id: &[u8], issuer: &X509Certificate<'_>) -> Result<bool> {
+    let outer = elements(der)?;
+    let (response, _) = outer.first().ok_or_else(|| malformed("empty"))?;
+    let fields = elements(response.data)?;
+    match fields.first() {
+        Some((status, _)) if is_universal(status, Tag::Enumerated) => {
+            if status.data != [0] {
+                return Err(AegisError::Crypto(format!(
+                    "OCSP responder refused the request (status {:?})",
+                    status.data
+                )));
+            }
+        }
+        _ => return Err(malformed("missing status")),
+    }
+    let bytes = fields
+        .get(1)
+        .filter(|(any, _)| is_context(any, 0))
+        .ok_or_else(|| malformed("missing response bytes"))?;
+    let bytes = elements(bytes.0.data)?;
+    let bytes = elements(
+        bytes
+            .first()
+            .ok_or_else(|| malformed("empty response bytes"))?
+            .0
+            .data,
+    )?;
+    match (bytes.first(), bytes.get(1)) {
+        (Some((oid, _)), Some((octets, _)))
+            if oid.data == OCSP_BASIC_OID && is_universal(octets, Tag::OctetString) =>
+        {
+            parse_basic_response(octets.data, cert_id, issuer)
+        }
+        _ => Err(malformed("not a basic OCSP response")),
+    }
+}
+
+fn parse_basic_response(der: &[u8], cert_id: &[u8], issuer: &X509Certificate<'_>) -> Result<bool> {
+    let outer = elements(der)?;
+    let basic = elements(outer.first().ok_or_else(|| malformed("empty"))?.0.data)?;
+    let [
+        (tbs, tbs_raw),
+        (_, algorithm_raw),
+        (_, signature_raw),
+        rest @ ..,
+    ] = basic.as_slice()
+    else {
+        return Err(malformed("truncated basic response"));
+    };
+    let (_, algorithm) =
+        AlgorithmIdentifier::from_der(algorithm_raw).map_err(|_| malformed("algorithm"))?;
+    let (_, signature) = BitString::from_der(signature_raw).map_err(|_| malformed("signature"))?;
+
+    // Signed by the CA itself or by a delegated responder certificate
+    let mut delegated = Vec::new();
+    if let Some((certs, _)) = rest.iter().find(|(any, _)| is_context(any, 0)) {
+        for seq in elements(certs.data)? {
+            for (_, raw) in elements(seq.0.data)? {
+                if let Ok((_, responder)) = X509Certificate::from_der(raw) {
+                    let authorized = responder.issuer().as_raw() == issuer.subject().as_raw()
+                        && responder
+                            .verify_signature(Some(issuer.public_key()))
+                            .is_ok()
+                        && matches!(responder.extended_key_usage(), Ok(Some(eku)) if eku.value.ocsp_signing);
+                    if authorized {
+                        delegated.push(responder);
+                    }
+                }
+            }
+        }
+    }
+    let signed = std::iter::once(issuer.public_key())
+        .chain(delegated.iter().map(|cert| cert.public_key()))
+        .any(|key| {
+            x509_parser::verify::verify_signature(key, &algorithm, &signature, tbs_raw).is_ok()
+        });
+    if !signed {
+        return Err(AegisError::Crypto(
+            "OCSP response is not signed by the issuer or its responder".to_string(),
+        ));
+    }
+
+    let responses = elements(tbs.data)?
+        .into_iter()
+        .find(|(any, _)| is_universal(any, Tag::Sequence))
+        .ok_or_else(|| malformed("missing responses"))?;
+    let wanted = elements(cert_id)?;
+    let wanted = elements(wanted[0].0.data)?;
+    for (single, _) in elements(responses.0.data)? {
+        let single = elements(single.data)?;
+        let [(id, _), (status, _), (_this_update, _), rest @ ..] = single.as_slice() else {
+            return Err(malformed("truncated single response"));
+        };
+        // Compare hashes and serial; responders may encode the algorithm
+        // parameters differently
+        let id = elements(id.data)?;
+        if id.len() != 4
+            || id[1..]
+                .iter()
+                .map(|(any, _)| any.data)
+                .ne(wanted[1..].iter().map(|(any, _)| any.data))
+        {
+            continue;
+        }
+
+        if let Some((next_update, _)) = rest.iter().find(|(any, _)| is_context(any, 0)) {
+            let (_, time) =
+                GeneralizedTime::from_der(next_update.data).map_err(|_| malformed("nextUpdate"))?;
+            let next_update = time
+                .utc_datetime()
+                .map_err(|_| malformed("nextUpdate"))?
+                .unix_timestamp();
+            let now = SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or(0);
+            if now > next_update {
+                return Err(AegisError::Crypto(
+                    "OCSP response is out of date".to_string(),
+                ));
+            }
+        }
+
+        return match status.header.tag() {
+            Tag(0) if status.header.class() == Class::ContextSpecific => Ok(false),
+            Tag(1) if status.header.class() == Class::ContextSpecific => Ok(true),
+            _ => Err(AegisError::Crypto(
+                "OCSP responder does not know the certificate".to_string(),
+            )),
+        };
+    }
+    Err(malformed("no status for the requested certificate"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::certmanager::CertManager;
+    use rcgen::{CertificateParams, DnType, KeyPair, SerialNumber};
+    use ring::signature::{ECDSA_P256_SHA256_ASN1_SIGNING, EcdsaKeyPair};
+    use std::net::TcpListener;
+    use x509_parser::num_bigint::BigUint;
+
+    /// `ecdsa-with-SHA256`
+    const ECDSA_SHA256: &[u8] = &[
+        0x30, 0x0a, 0x06, 0x08, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x02,
+    ];
+
+    /// Serve one OCSP request, answering "revoked" for `revoked_serial` and
+    /// "good" for anything else, signed with `key`
+    fn respond_once(listener: TcpListener, key: &KeyPair, revoked_serial: u64) {
+        let signer = EcdsaKeyPair::from_pkcs8(
+            &ECDSA_P256_SHA256_ASN1_SIGNING,
+            &key.serialize_der(),
+            &ring::rand::SystemRandom::new(),
+        )
+        .unwrap();
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = Vec::new();
+            let mut chunk = [0u8; 1024];
+            let request = loop {
+                let n = stream.read(&mut chunk).unwrap();
+                buf.extend(&chunk[..n]);
+                if let Some(at) = buf.windows(4).position(|w| w == b"\r\n\r\n")
+                    && let Ok(request) = elements(&buf[at + 4..])
+                    && !request.is_empty()
+                {
+                    break request[0].1.to_vec();
+                }
+            };
+
+            // OCSPRequest -> TBSRequest -> requestList -> Request -> CertID
+            let mut cert_id = request.as_slice();
+            for _ in 0..4 {
+                cert_id = elements(cert_id).unwrap()[0].0.data;
+            }
+            let cert_id = elements(cert_id).unwrap()[0].1.to_vec();
+            let serial = &elements(&cert_id).unwrap()[0].0;
+            let serial = elements(serial.data).unwrap()[3].0.data.to_vec();
+            let status = if BigUint::from_bytes_be(&serial) == BigUint::from(revoked_serial) {
+                let revocation_time = tlv(0x18, b"20260101000000Z");
+                tlv(0xa1, &revocation_time)
+            } else {
+                vec![0x80, 0x00]
+            };
+
+            let mut single = cert_id.clone();
+            single.extend(status);
+            single.extend(tlv(0x18, b"20260101000000Z"));
+            let mut tbs = tlv(0xa2, &tlv(0x04, &[0u8; 20]));
+            tbs.extend(tlv(0x18, b"20260101000000Z"));
+            tbs.extend(tlv(0x30, &tlv(0x30, &single)));
+            let tbs = tlv(0x30, &tbs);
+            let signature = signer.sign(&ring::rand::SystemRandom::new(), &tbs).unwrap();
+            let mut bit_string = vec![0u8];
+            bit_string.extend(signature.as_ref());
+
+            let mut basic = tbs.clone();
+            basic.extend(ECDSA_SHA256);
+            basic.extend(tlv(0x03, &bit_string));
+            let mut response_bytes = tlv(0x06, OCSP_BASIC_OID);
+            response_bytes.extend(tlv(0x04, &tlv(0x30, &basic)));
+            let mut response = tlv(0x0a, &[0]);
+            response.extend(tlv(0xa0, &tlv(0x30, &response_bytes)));
+            let response = tlv(0x30, &response);
+
+            let head = format!(
+                "HTTP/1.0 200 OK\r\nContent-Type: application/ocsp-response\r\n\
+                 Content-Length: {}\r\n\r\n",
+                response.len()
+            );
+            stream.write_all(head.as_bytes()).unwrap();
+            stream.write_all(&response).unwrap();
+        });
+    }
+
+    #[test]
+    fn test_ocsp_revoked_and_good() {
+        let mut ca_params = CertificateParams::default();
+        ca_params
+            .distinguished_name
+            .push(DnType::CommonName, "Aegis CA");
+        ca_params.is_ca = rcgen::IsCa::Ca(rcgen::BasicConstraints::Unconstrained);
+        let ca_key = KeyPair::generate().unwrap();
+        let ca = ca_params.self_signed(&ca_key).unwrap();
+        let issue = |serial: u64| {
+            let mut params = CertificateParams::default();
+            params.distinguished_name.push(DnType::CommonName, "client");
+            params.serial_number = Some(SerialNumber::from(serial));
+            let key = KeyPair::generate().unwrap();
+            CertManager::parse_der(params.signed_by(&key, &ca, &ca_key).unwrap().der()).unwrap()
+        };
+
+        for (serial, revoked) in [(1001, true), (1002, false)] {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let url = format!("http://{}/ocsp", listener.local_addr().unwrap());
+            respond_once(listener, &ca_key, 1001);
+            let checker =
+                OcspRevocationChecker::new(&url, CertManager::parse_der(ca.der()).unwrap())
+                    .unwrap();
+            assert_eq!(checker.is_revoked(&issue(serial)).unwrap(), revoked);
+        }
+
+        // A response signed by someone else is rejected
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/ocsp", listener.local_addr().unwrap());
+        respond_once(listener, &KeyPair::generate().unwrap(), 1001);
+        let checker =
+            OcspRevocationChecker::new(&url, CertManager::parse_der(ca.der()).unwrap()).unwrap();
+        assert!(checker.is_revoked(&issue(1002)).is_err());
+    }
+
+    #[test]
+    fn test_responder_url_parsing() {
+        let (ca_pem, _) = CertManager::generate_self_signed("Aegis CA", &[], 30).unwrap();
+        let ca = CertManager::parse_pem(ca_pem.as_bytes()).unwrap();
+
+        let checker = OcspRevocationChecker::new("http://ocsp.example.com", ca.clone()).unwrap();
+        assert_eq!(checker.authority, "ocsp.example.com:80");
+        assert_eq!(checker.path, "/");
+        let checker = OcspRevocationChecker::new("http://127.0.0.1:8080/ocsp", ca.clone()).unwrap();
+        assert_eq!(checker.authority, "127.0.0.1:8080");
+        assert_eq!(checker.path, "/ocsp");
+        assert!(OcspRevocationChecker::new("https://ocsp.example.com", ca).is_err());
+    }
+}
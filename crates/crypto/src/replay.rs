@@ -0,0 +1,160 @@
+//! Handshake Replay Protection
+//!
+//! Remembers SHA-256 digests of recently accepted handshake ciphertexts so a
+//! captured ciphertext cannot be submitted again within the window.
+//!
+//! This is defense in depth. The stronger guarantee is the ephemeral server
+//! key pair that [`PqcHandshake::server_init`](crate::tls::PqcHandshake::server_init)
+//! generates for every connection: a replayed ciphertext decapsulates to an
+//! unrelated secret under a fresh key. Deployments that reuse server key pairs
+//! across connections rely on this cache alone.
+
+use aegis_common::{SharedClock, SystemClock};
+use parking_lot::Mutex;
+use sha2::{Digest, Sha256};
+use std::collections::{HashSet, VecDeque};
+use std::time::{Duration, Instant};
+
+/// How long accepted ciphertexts are remembered by default
+pub const DEFAULT_REPLAY_WINDOW: Duration = Duration::from_secs(300);
+
+/// Most ciphertexts remembered by default (a few MiB of digests)
+pub const DEFAULT_REPLAY_CAPACITY: usize = 100_000;
+
+#[derive(Debug, Default)]
+struct Seen {
+    digests: HashSet<[u8; 32]>,
+    /// Digests in insertion order, for expiry
+    order: VecDeque<(Instant, [u8; 32])>,
+}
+
+/// Short-lived cache of seen handshake ciphertexts
+#[derive(Debug)]
+pub struct ReplayCache {
+    window: Duration,
+    capacity: usize,
+    seen: Mutex<Seen>,
+    clock: SharedClock,
+}
+
+impl ReplayCache {
+    /// Create a cache remembering ciphertexts for `window`
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            capacity: DEFAULT_REPLAY_CAPACITY,
+            seen: Mutex::new(Seen::default()),
+            clock: SystemClock::shared(),
+        }
+    }
+
+    /// Remember at most `capacity` ciphertexts, forgetting the oldest first
+    ///
+    /// Bounds memory under a flood of unique handshakes, at the cost of
+    /// shortening the effective window while the flood lasts.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    /// Use a custom time source
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// How long ciphertexts are remembered
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// Record `ciphertext`, returning `false` if it was already seen
+    pub fn check_and_insert(&self, ciphertext: &[u8]) -> bool {
+        let digest: [u8; 32] = Sha256::digest(ciphertext).into();
+        let now = self.clock.instant();
+        let mut seen = self.seen.lock();
+
+        while let Some((inserted_at, old)) = seen.order.front().copied() {
+            if now.saturating_duration_since(inserted_at) < self.window {
+                break;
+            }
+            seen.order.pop_front();
+            seen.digests.remove(&old);
+        }
+
+        if seen.digests.contains(&digest) {
+            return false;
+        }
+        while seen.digests.len() >= self.capacity {
+            let Some((_, old)) = seen.order.pop_front() else {
+                break;
+            };
+            seen.digests.remove(&old);
+        }
+        seen.digests.insert(digest);
+        seen.order.push_back((now, digest));
+        true
+    }
+
+    /// Number of ciphertexts currently remembered
+    pub fn len(&self) -> usize {
+        self.seen.lock().digests.len()
+    }
+
+    /// Whether no ciphertexts are remembered
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for ReplayCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_REPLAY_WINDOW)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aegis_common::MockClock;
+
+    #[test]
+    fn test_duplicate_rejected_within_window() {
+        let cache = ReplayCache::new(Duration::from_secs(60));
+        assert!(cache.check_and_insert(b"ciphertext-a"));
+        assert!(!cache.check_and_insert(b"ciphertext-a"));
+        assert!(cache.check_and_insert(b"ciphertext-b"));
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn test_entries_expire_after_window() {
+        let clock = MockClock::new();
+        let cache = ReplayCache::new(Duration::from_secs(60)).with_clock(clock.shared());
+
+        assert!(cache.check_and_insert(b"ciphertext-a"));
+        clock.advance(Duration::from_secs(30));
+        assert!(cache.check_and_insert(b"ciphertext-b"));
+
+        clock.advance(Duration::from_secs(30));
+        // "a" has aged out, "b" is still remembered
+        assert!(cache.check_and_insert(b"ciphertext-a"));
+        assert!(!cache.check_and_insert(b"ciphertext-b"));
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn test_capacity_evicts_oldest() {
+        let cache = ReplayCache::new(Duration::from_secs(60)).with_capacity(3);
+        for i in 0..10u32 {
+            assert!(cache.check_and_insert(&i.to_be_bytes()));
+            assert!(cache.len() <= 3);
+        }
+        // The three newest are still rejected, older ones were forgotten
+        for i in 7..10u32 {
+            assert!(!cache.check_and_insert(&i.to_be_bytes()));
+        }
+        assert!(cache.check_and_insert(&0u32.to_be_bytes()));
+        assert_eq!(cache.len(), 3);
+    }
+}
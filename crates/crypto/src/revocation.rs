@@ -0,0 +1,324 @@
+//! Certificate Revocation Checking
+//!
+//! A [`RevocationChecker`] decides whether a certificate that passed chain
+//! verification has since been revoked by its CA. [`CrlRevocationChecker`]
+//! looks serial numbers up in a certificate revocation list; with the `ocsp`
+//! feature, [`OcspRevocationChecker`](crate::ocsp::OcspRevocationChecker)
+//! asks the CA's OCSP responder instead.
+
+use crate::certmanager::ParsedCert;
+use aegis_common::{AegisError, Result};
+use std::collections::HashSet;
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::SystemTime;
+use tracing::{debug, info};
+use x509_parser::prelude::*;
+
+/// Source of certificate revocation status
+pub trait RevocationChecker: Send + Sync {
+    /// Whether `cert` has been revoked
+    ///
+    /// Returns an error when the status cannot be determined, e.g. because
+    /// the revocation list is out of date; callers should reject the
+    /// certificate in that case.
+    fn is_revoked(&self, cert: &ParsedCert) -> Result<bool>;
+}
+
+/// How client certificates are checked for revocation
+#[derive(Clone)]
+pub enum RevocationMode {
+    /// Look serial numbers up in a CRL file (PEM or DER)
+    Crl { path: String },
+    /// Ask an OCSP responder about certificates issued by the CA at
+    /// `issuer_path`
+    #[cfg(feature = "ocsp")]
+    Ocsp {
+        responder_url: String,
+        issuer_path: String,
+    },
+    /// Use a caller-supplied checker
+    Custom(Arc<dyn RevocationChecker>),
+}
+
+impl fmt::Debug for RevocationMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Crl { path } => f.debug_struct("Crl").field("path", path).finish(),
+            #[cfg(feature = "ocsp")]
+            Self::Ocsp {
+                responder_url,
+                issuer_path,
+            } => f
+                .debug_struct("Ocsp")
+                .field("responder_url", responder_url)
+                .field("issuer_path", issuer_path)
+                .finish(),
+            Self::Custom(_) => f.write_str("Custom"),
+        }
+    }
+}
+
+impl RevocationMode {
+    /// Build the checker this mode describes, loading any files it names
+    pub fn checker(&self) -> Result<Arc<dyn RevocationChecker>> {
+        match self {
+            Self::Crl { path } => Ok(Arc::new(CrlRevocationChecker::load_from_file(Path::new(
+                path,
+            ))?)),
+            #[cfg(feature = "ocsp")]
+            Self::Ocsp {
+                responder_url,
+                issuer_path,
+            } => {
+                let issuer =
+                    crate::certmanager::CertManager::load_from_file(Path::new(issuer_path))?;
+                Ok(Arc::new(crate::ocsp::OcspRevocationChecker::new(
+                    responder_url,
+                    issuer,
+                )?))
+            }
+            Self::Custom(checker) => Ok(checker.clone()),
+        }
+    }
+}
+
+/// Revocation checker backed by a certificate revocation list
+///
+/// Only certificates issued by the CRL's issuer are looked up; others are
+/// reported as not revoked. Once the list is past its `nextUpdate` time every
+/// lookup fails until a fresh one is loaded.
+#[derive(Debug, Clone)]
+pub struct CrlRevocationChecker {
+    /// Issuer Common Name
+    issuer_cn: String,
+    /// Revoked serial numbers, formatted like [`ParsedCert::serial`]
+    revoked: HashSet<String>,
+    /// When the issuer promises a newer list (UTC timestamp)
+    next_update: Option<i64>,
+    /// Raw DER bytes
+    der_bytes: Vec<u8>,
+}
+
+impl CrlRevocationChecker {
+    /// Parse a PEM-encoded CRL
+    pub fn from_pem(pem_data: &[u8]) -> Result<Self> {
+        let pem_parsed = ::pem::parse(pem_data)
+            .map_err(|e| AegisError::Crypto(format!("Failed to parse PEM: {}", e)))?;
+
+        Self::from_der(pem_parsed.contents())
+    }
+
+    /// Parse a DER-encoded CRL
+    pub fn from_der(der_data: &[u8]) -> Result<Self> {
+        let (_, crl) = CertificateRevocationList::from_der(der_data)
+            .map_err(|e| AegisError::Crypto(format!("Failed to parse CRL: {:?}", e)))?;
+
+        let issuer_cn = crl
+            .issuer()
+            .iter_common_name()
+            .next()
+            .and_then(|cn| cn.as_str().ok())
+            .unwrap_or("Unknown")
+            .to_string();
+        let revoked = crl
+            .iter_revoked_certificates()
+            .map(|revoked| revoked.user_certificate.to_string())
+            .collect();
+
+        Ok(Self {
+            issuer_cn,
+            revoked,
+            next_update: crl.next_update().map(|t| t.timestamp()),
+            der_bytes: der_data.to_vec(),
+        })
+    }
+
+    /// Load a CRL from file (PEM or DER)
+    pub fn load_from_file(path: &Path) -> Result<Self> {
+        let data = std::fs::read(path)
+            .map_err(|e| AegisError::Config(format!("Failed to read {}: {}", path.display(), e)))?;
+
+        let checker = if data.starts_with(b"-----BEGIN") {
+            Self::from_pem(&data)?
+        } else {
+            Self::from_der(&data)?
+        };
+        info!(
+            "Loaded CRL from {} with {} revoked certificates",
+            checker.issuer_cn,
+            checker.revoked.len()
+        );
+        Ok(checker)
+    }
+
+    /// Check that the CRL was signed by `issuer`, for lists obtained from an
+    /// untrusted source
+    pub fn verify_signature(&self, issuer: &ParsedCert) -> Result<()> {
+        let (_, crl) = CertificateRevocationList::from_der(&self.der_bytes)
+            .map_err(|e| AegisError::Crypto(format!("Failed to parse CRL: {:?}", e)))?;
+        let (_, issuer_x509) = X509Certificate::from_der(&issuer.der_bytes)
+            .map_err(|e| AegisError::Crypto(format!("Failed to parse X.509: {:?}", e)))?;
+
+        if crl.issuer().as_raw() != issuer_x509.subject().as_raw() {
+            return Err(AegisError::Crypto(format!(
+                "CRL issued by {} not {}",
+                self.issuer_cn, issuer.subject_cn
+            )));
+        }
+        crl.verify_signature(issuer_x509.public_key())
+            .map_err(|e| AegisError::Crypto(format!("CRL signature is invalid: {:?}", e)))
+    }
+
+    /// Issuer Common Name
+    pub fn issuer_cn(&self) -> &str {
+        &self.issuer_cn
+    }
+
+    /// Number of revoked certificates listed
+    pub fn revoked_count(&self) -> usize {
+        self.revoked.len()
+    }
+}
+
+impl RevocationChecker for CrlRevocationChecker {
+    fn is_revoked(&self, cert: &ParsedCert) -> Result<bool> {
+        if cert.issuer_cn != self.issuer_cn {
+            return Ok(false);
+        }
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+        if self.next_update.is_some_and(|next| now > next) {
+            return Err(AegisError::Crypto(format!(
+                "CRL from {} is out of date",
+                self.issuer_cn
+            )));
+        }
+
+        let revoked = self.revoked.contains(&cert.serial);
+        if revoked {
+            debug!(
+                "Certificate {} ({}) is revoked",
+                cert.subject_cn, cert.serial
+            );
+        }
+        Ok(revoked)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::certmanager::CertManager;
+    use rcgen::{
+        CertificateParams, CertificateRevocationListParams, DnType, KeyIdMethod, KeyPair,
+        RevokedCertParams, SerialNumber,
+    };
+
+    fn crl_params(
+        revoked_serials: &[u64],
+        next_update: ::time::OffsetDateTime,
+    ) -> CertificateRevocationListParams {
+        let now = ::time::OffsetDateTime::now_utc();
+        CertificateRevocationListParams {
+            this_update: now - ::time::Duration::hours(1),
+            next_update,
+            crl_number: SerialNumber::from(1u64),
+            issuing_distribution_point: None,
+            revoked_certs: revoked_serials
+                .iter()
+                .map(|serial| RevokedCertParams {
+                    serial_number: SerialNumber::from(*serial),
+                    revocation_time: now - ::time::Duration::minutes(5),
+                    reason_code: None,
+                    invalidity_date: None,
+                })
+                .collect(),
+            key_identifier_method: KeyIdMethod::Sha256,
+        }
+    }
+
+    fn test_ca(cn: &str) -> (rcgen::Certificate, KeyPair) {
+        let mut params = CertificateParams::default();
+        params.distinguished_name.push(DnType::CommonName, cn);
+        params.is_ca = rcgen::IsCa::Ca(rcgen::BasicConstraints::Unconstrained);
+        let key = KeyPair::generate().unwrap();
+        (params.self_signed(&key).unwrap(), key)
+    }
+
+    fn leaf(serial: u64, ca: &rcgen::Certificate, ca_key: &KeyPair) -> ParsedCert {
+        let mut params = CertificateParams::default();
+        params.distinguished_name.push(DnType::CommonName, "client");
+        params.serial_number = Some(SerialNumber::from(serial));
+        let key = KeyPair::generate().unwrap();
+        let cert = params.signed_by(&key, ca, ca_key).unwrap();
+        CertManager::parse_der(cert.der()).unwrap()
+    }
+
+    #[test]
+    fn test_crl_lists_revoked_serial() {
+        let (ca, ca_key) = test_ca("Aegis CA");
+        let next_update = ::time::OffsetDateTime::now_utc() + ::time::Duration::days(7);
+        let crl = crl_params(&[1001], next_update)
+            .signed_by(&ca, &ca_key)
+            .unwrap();
+
+        let checker = CrlRevocationChecker::from_pem(crl.pem().unwrap().as_bytes()).unwrap();
+        assert_eq!(checker.issuer_cn(), "Aegis CA");
+        assert_eq!(checker.revoked_count(), 1);
+        checker
+            .verify_signature(&CertManager::parse_der(ca.der()).unwrap())
+            .unwrap();
+
+        assert!(checker.is_revoked(&leaf(1001, &ca, &ca_key)).unwrap());
+        assert!(!checker.is_revoked(&leaf(1002, &ca, &ca_key)).unwrap());
+
+        // Same serial from another CA is not covered by this list
+        let (other, other_key) = test_ca("Other CA");
+        assert!(!checker.is_revoked(&leaf(1001, &other, &other_key)).unwrap());
+        assert!(
+            checker
+                .verify_signature(&CertManager::parse_der(other.der()).unwrap())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_stale_crl_fails_closed() {
+        let (ca, ca_key) = test_ca("Aegis CA");
+        let next_update = ::time::OffsetDateTime::now_utc() - ::time::Duration::minutes(1);
+        let mut params = crl_params(&[], next_update);
+        params.this_update = next_update - ::time::Duration::days(1);
+        let crl = params.signed_by(&ca, &ca_key).unwrap();
+
+        let checker = CrlRevocationChecker::from_der(crl.der()).unwrap();
+        let err = checker.is_revoked(&leaf(7, &ca, &ca_key)).unwrap_err();
+        assert!(err.to_string().contains("out of date"));
+    }
+
+    #[test]
+    fn test_crl_mode_loads_file() {
+        let (ca, ca_key) = test_ca("Aegis CA");
+        let next_update = ::time::OffsetDateTime::now_utc() + ::time::Duration::days(7);
+        let crl = crl_params(&[42], next_update)
+            .signed_by(&ca, &ca_key)
+            .unwrap();
+        let path = std::env::temp_dir().join(format!("aegis-crl-{}.pem", std::process::id()));
+        std::fs::write(&path, crl.pem().unwrap()).unwrap();
+
+        let mode = RevocationMode::Crl {
+            path: path.display().to_string(),
+        };
+        let checker = mode.checker().unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(checker.is_revoked(&leaf(42, &ca, &ca_key)).unwrap());
+
+        let missing = RevocationMode::Crl {
+            path: "/nonexistent/aegis.crl".to_string(),
+        };
+        assert!(matches!(missing.checker(), Err(AegisError::Config(_))));
+    }
+}
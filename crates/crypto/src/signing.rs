@@ -31,6 +31,7 @@ use pqcrypto_traits::sign::{
 };
 use serde::{Deserialize, Serialize};
 use tracing::{debug, instrument};
+use zeroize::ZeroizeOnDrop;
 
 /// Algorithm identifier for ML-DSA variants
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
@@ -123,6 +124,22 @@ impl MlDsaSignature {
     }
 }
 
+impl AsRef<[u8]> for MlDsaSignature {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+/// Verify `signature` with `signer`, treating malformed input as a bad signature
+fn verify_tagged<S: SigningKeyPair>(
+    signer: &S,
+    message: &[u8],
+    signature: &MlDsaSignature,
+) -> bool {
+    signature.algorithm == signer.algorithm()
+        && signer.verify(message, &signature.bytes).unwrap_or(false)
+}
+
 // ============================================================================
 // ML-DSA-44 Implementation
 // ============================================================================
@@ -211,6 +228,23 @@ impl std::fmt::Debug for MlDsa44Signer {
     }
 }
 
+impl crate::traits::Signer for MlDsa44Signer {
+    type Signature = MlDsaSignature;
+
+    fn sign(&self, message: &[u8]) -> Result<MlDsaSignature> {
+        let bytes = SigningKeyPair::sign(self, message)?;
+        Ok(MlDsaSignature::new(bytes, MlDsaAlgorithm::MlDsa44))
+    }
+
+    fn verify(&self, message: &[u8], signature: &MlDsaSignature) -> bool {
+        verify_tagged(self, message, signature)
+    }
+
+    fn algorithm_name(&self) -> &'static str {
+        MlDsaAlgorithm::MlDsa44.name()
+    }
+}
+
 // ============================================================================
 // ML-DSA-65 Implementation (Default)
 // ============================================================================
@@ -238,6 +272,97 @@ impl MlDsa65Signer {
     }
 }
 
+impl MlDsa65Signer {
+    /// Generate a serializable key pair
+    pub fn generate_keypair() -> Result<(MlDsa65PublicKey, MlDsa65SecretKey)> {
+        let signer = Self::generate()?;
+        Ok((
+            MlDsa65PublicKey {
+                bytes: signer.public_key.clone(),
+            },
+            MlDsa65SecretKey {
+                bytes: signer.secret_key.clone(),
+            },
+        ))
+    }
+
+    /// Create from a key pair produced by [`Self::generate_keypair`]
+    pub fn from_keypair(
+        public_key: &MlDsa65PublicKey,
+        secret_key: &MlDsa65SecretKey,
+    ) -> Result<Self> {
+        Self::from_keys(public_key.bytes.clone(), secret_key.bytes.clone())
+    }
+}
+
+/// ML-DSA-65 public key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MlDsa65PublicKey {
+    bytes: Vec<u8>,
+}
+
+impl MlDsa65PublicKey {
+    /// Serialize the public key to bytes
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.bytes.clone()
+    }
+
+    /// Deserialize from bytes
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        mldsa65::PublicKey::from_bytes(bytes)
+            .map_err(|e| AegisError::Crypto(format!("Invalid public key: {:?}", e)))?;
+        Ok(Self {
+            bytes: bytes.to_vec(),
+        })
+    }
+
+    /// Verifier for signatures made with the matching secret key
+    pub fn verifier(&self) -> MlDsaVerifier {
+        MlDsaVerifier {
+            public_key: self.bytes.clone(),
+            algorithm: MlDsaAlgorithm::MlDsa65,
+        }
+    }
+}
+
+impl AsRef<[u8]> for MlDsa65PublicKey {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+/// ML-DSA-65 secret key
+///
+/// Zeroed on drop via `ZeroizeOnDrop`.
+#[derive(ZeroizeOnDrop)]
+pub struct MlDsa65SecretKey {
+    bytes: Vec<u8>,
+}
+
+impl MlDsa65SecretKey {
+    /// Serialize the secret key to bytes
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.bytes.clone()
+    }
+
+    /// Deserialize from bytes
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        mldsa65::SecretKey::from_bytes(bytes)
+            .map_err(|e| AegisError::Crypto(format!("Invalid secret key: {:?}", e)))?;
+        Ok(Self {
+            bytes: bytes.to_vec(),
+        })
+    }
+}
+
+impl std::fmt::Debug for MlDsa65SecretKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MlDsa65SecretKey")
+            .field("bytes", &"[REDACTED]")
+            .finish()
+    }
+}
+
 impl SigningKeyPair for MlDsa65Signer {
     #[instrument(skip_all)]
     fn generate() -> Result<Self> {
@@ -299,6 +424,23 @@ impl std::fmt::Debug for MlDsa65Signer {
     }
 }
 
+impl crate::traits::Signer for MlDsa65Signer {
+    type Signature = MlDsaSignature;
+
+    fn sign(&self, message: &[u8]) -> Result<MlDsaSignature> {
+        let bytes = SigningKeyPair::sign(self, message)?;
+        Ok(MlDsaSignature::new(bytes, MlDsaAlgorithm::MlDsa65))
+    }
+
+    fn verify(&self, message: &[u8], signature: &MlDsaSignature) -> bool {
+        verify_tagged(self, message, signature)
+    }
+
+    fn algorithm_name(&self) -> &'static str {
+        MlDsaAlgorithm::MlDsa65.name()
+    }
+}
+
 // ============================================================================
 // ML-DSA-87 Implementation
 // ============================================================================
@@ -387,6 +529,23 @@ impl std::fmt::Debug for MlDsa87Signer {
     }
 }
 
+impl crate::traits::Signer for MlDsa87Signer {
+    type Signature = MlDsaSignature;
+
+    fn sign(&self, message: &[u8]) -> Result<MlDsaSignature> {
+        let bytes = SigningKeyPair::sign(self, message)?;
+        Ok(MlDsaSignature::new(bytes, MlDsaAlgorithm::MlDsa87))
+    }
+
+    fn verify(&self, message: &[u8], signature: &MlDsaSignature) -> bool {
+        verify_tagged(self, message, signature)
+    }
+
+    fn algorithm_name(&self) -> &'static str {
+        MlDsaAlgorithm::MlDsa87.name()
+    }
+}
+
 // ============================================================================
 // Signature Verifier (Public Key Only)
 // ============================================================================
@@ -462,6 +621,19 @@ impl MlDsaVerifier {
     }
 }
 
+impl crate::traits::Verifier for MlDsaVerifier {
+    type Signature = MlDsaSignature;
+
+    fn verify(&self, message: &[u8], signature: &MlDsaSignature) -> bool {
+        signature.algorithm == self.algorithm
+            && MlDsaVerifier::verify(self, message, &signature.bytes).unwrap_or(false)
+    }
+
+    fn algorithm_name(&self) -> &'static str {
+        self.algorithm.name()
+    }
+}
+
 impl std::fmt::Debug for MlDsaVerifier {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("MlDsaVerifier")
@@ -1681,4 +1853,76 @@ mod tests {
         assert!(!verifier87.verify(msg, &sig44).unwrap_or(false));
         assert!(!verifier87.verify(msg, &sig65).unwrap_or(false));
     }
+
+    #[test]
+    fn test_signer_trait_roundtrip() {
+        use crate::traits::{Signer, Verifier};
+
+        let (public_key, secret_key) = MlDsa65Signer::generate_keypair().unwrap();
+        let signer = MlDsa65Signer::from_keypair(&public_key, &secret_key).unwrap();
+        let message = b"signed through the trait";
+
+        let signature = Signer::sign(&signer, message).unwrap();
+        assert_eq!(signature.algorithm, MlDsaAlgorithm::MlDsa65);
+        assert!(Signer::verify(&signer, message, &signature));
+        assert!(Verifier::verify(
+            &public_key.verifier(),
+            message,
+            &signature
+        ));
+        assert_eq!(Signer::algorithm_name(&signer), "ML-DSA-65");
+
+        let dyn_signer: &dyn Signer<Signature = MlDsaSignature> = &signer;
+        assert!(dyn_signer.verify(message, &dyn_signer.sign(message).unwrap()));
+    }
+
+    #[test]
+    fn test_signer_trait_rejects_tampering() {
+        use crate::traits::{Signer, Verifier};
+
+        let signer = MlDsa65Signer::generate().unwrap();
+        let signature = Signer::sign(&signer, b"original").unwrap();
+        assert!(!Signer::verify(&signer, b"tampered", &signature));
+
+        let mut corrupted = signature.clone();
+        corrupted.bytes[0] ^= 0xFF;
+        assert!(!Signer::verify(&signer, b"original", &corrupted));
+
+        // Right bytes, wrong algorithm tag
+        let mut relabelled = signature.clone();
+        relabelled.algorithm = MlDsaAlgorithm::MlDsa87;
+        assert!(!Signer::verify(&signer, b"original", &relabelled));
+
+        let verifier =
+            MlDsaVerifier::new(signer.public_key().to_vec(), MlDsaAlgorithm::MlDsa65).unwrap();
+        assert!(!Verifier::verify(&verifier, b"original", &relabelled));
+        assert!(!Verifier::verify(
+            &verifier,
+            b"original",
+            &MlDsaSignature::new(vec![0; 3], MlDsaAlgorithm::MlDsa65)
+        ));
+    }
+
+    #[test]
+    fn test_mldsa65_key_serialization_roundtrip() {
+        use crate::traits::Signer;
+
+        let (public_key, secret_key) = MlDsa65Signer::generate_keypair().unwrap();
+        let public_restored = MlDsa65PublicKey::from_bytes(&public_key.to_bytes()).unwrap();
+        let secret_restored = MlDsa65SecretKey::from_bytes(&secret_key.to_bytes()).unwrap();
+        assert_eq!(public_restored, public_key);
+        assert_eq!(
+            public_restored.as_ref().len(),
+            MlDsaAlgorithm::MlDsa65.public_key_size()
+        );
+
+        let signer = MlDsa65Signer::from_keypair(&public_restored, &secret_restored).unwrap();
+        let signature = Signer::sign(&signer, b"after restore").unwrap();
+        let original = MlDsa65Signer::from_keypair(&public_key, &secret_key).unwrap();
+        assert!(Signer::verify(&original, b"after restore", &signature));
+
+        assert!(MlDsa65PublicKey::from_bytes(&[0; 10]).is_err());
+        assert!(MlDsa65SecretKey::from_bytes(&[0; 10]).is_err());
+        assert!(format!("{:?}", secret_key).contains("REDACTED"));
+    }
 }
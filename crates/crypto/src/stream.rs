@@ -1,8 +1,9 @@
 use aes_gcm::{
-    Aes256Gcm, Key, Nonce,
-    aead::{Aead, AeadCore, KeyInit, OsRng},
+    Aes128Gcm, Aes256Gcm, Key, Nonce,
+    aead::{Aead, AeadCore, KeyInit, OsRng, Payload, consts::U12},
 };
 use bytes::{Buf, BufMut, BytesMut};
+use metrics::counter;
 use std::cmp;
 use std::io;
 use std::pin::Pin;
@@ -15,17 +16,113 @@ const NONCE_SIZE: usize = 12; // 96-bit nonce for AES-GCM
 const FRAME_OVERHEAD: usize = U32_SIZE + NONCE_SIZE + 16;
 const MAX_FRAME_SIZE: usize = 64 * 1024; // 64KB max payload
 
+/// Metric names recorded by [`EncryptedStream`]
+pub mod metric_names {
+    /// Frames processed, labelled `direction` (`encrypt` or `decrypt`)
+    pub const FRAMES: &str = "aegis_stream_frames_total";
+    /// Bytes processed, labelled `direction` and `form`: `plaintext`, or
+    /// `ciphertext` for the frame on the wire including its header
+    pub const BYTES: &str = "aegis_stream_bytes_total";
+    /// Inbound frames that failed authentication
+    pub const DECRYPT_FAILURES: &str = "aegis_stream_decrypt_failures_total";
+}
+
+fn record_frame(direction: &'static str, plaintext_len: usize, frame_len: usize) {
+    counter!(metric_names::FRAMES, "direction" => direction).increment(1);
+    counter!(metric_names::BYTES, "direction" => direction, "form" => "plaintext")
+        .increment(plaintext_len as u64);
+    counter!(metric_names::BYTES, "direction" => direction, "form" => "ciphertext")
+        .increment((U32_SIZE + frame_len) as u64);
+}
+
+/// `data` behind a big-endian `u32` length, zero-filled to a multiple of
+/// `block_size`
+fn pad(data: &[u8], block_size: usize) -> Vec<u8> {
+    let padded_len = (U32_SIZE + data.len()).div_ceil(block_size) * block_size;
+    let mut padded = Vec::with_capacity(padded_len);
+    padded.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    padded.extend_from_slice(data);
+    padded.resize(padded_len, 0);
+    padded
+}
+
+/// The data inside a plaintext built by [`pad`]
+fn unpad(padded: &[u8]) -> io::Result<&[u8]> {
+    let invalid = || io::Error::new(io::ErrorKind::InvalidData, "Invalid frame padding");
+    let (len, rest) = padded.split_first_chunk::<U32_SIZE>().ok_or_else(invalid)?;
+    rest.get(..u32::from_be_bytes(*len) as usize)
+        .ok_or_else(invalid)
+}
+
+/// AES-GCM instance for one direction, sized by its key
+#[derive(Clone)]
+enum FrameCipher {
+    Aes128(Box<Aes128Gcm>),
+    Aes256(Box<Aes256Gcm>),
+}
+
+impl FrameCipher {
+    /// AES-128-GCM for a 16-byte key, AES-256-GCM for a 32-byte key
+    fn new(key: &[u8]) -> Self {
+        if key.len() == 16 {
+            Self::Aes128(Box::new(Aes128Gcm::new(Key::<Aes128Gcm>::from_slice(key))))
+        } else {
+            Self::Aes256(Box::new(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key))))
+        }
+    }
+
+    fn encrypt(
+        &self,
+        nonce: &Nonce<U12>,
+        payload: Payload<'_, '_>,
+    ) -> aes_gcm::aead::Result<Vec<u8>> {
+        match self {
+            Self::Aes128(cipher) => cipher.encrypt(nonce, payload),
+            Self::Aes256(cipher) => cipher.encrypt(nonce, payload),
+        }
+    }
+
+    fn decrypt(
+        &self,
+        nonce: &Nonce<U12>,
+        payload: Payload<'_, '_>,
+    ) -> aes_gcm::aead::Result<Vec<u8>> {
+        match self {
+            Self::Aes128(cipher) => cipher.decrypt(nonce, payload),
+            Self::Aes256(cipher) => cipher.decrypt(nonce, payload),
+        }
+    }
+}
+
+/// Stream of AES-GCM encrypted, length-prefixed frames
+///
+/// 16-byte keys select AES-128-GCM and 32-byte keys AES-256-GCM.
+///
+/// With [`with_padding`](Self::with_padding) every frame's plaintext is a
+/// length prefix, the data and zero fill up to a multiple of the block size,
+/// so frame lengths only reveal the data size to within a block.
+///
+/// Each direction numbers its frames from zero and binds the number into the
+/// frame as additional authenticated data. The number is not sent; the
+/// reader supplies the one it expects next, so a dropped, duplicated,
+/// replayed or reordered frame fails authentication with
+/// [`io::ErrorKind::InvalidData`].
 pub struct EncryptedStream<S> {
     stream: S,
-    encryptor: Aes256Gcm,
-    decryptor: Aes256Gcm,
+    encryptor: FrameCipher,
+    decryptor: FrameCipher,
 
     // Read state
     read_buffer: BytesMut,
     decrypted_buffer: BytesMut,
+    read_seq: u64,
 
     // Write state
     write_buffer: BytesMut,
+    write_seq: u64,
+
+    /// Block size frame plaintexts are padded to, if padding is on
+    padding: Option<usize>,
 }
 
 impl<S> EncryptedStream<S> {
@@ -34,8 +131,7 @@ impl<S> EncryptedStream<S> {
     /// Prefer `new_bidirectional()` to ensure distinct client/server keys.
     pub fn new(stream: S, key: &[u8]) -> Self {
         // Use the same key for both directions (symmetric)
-        let key = Key::<Aes256Gcm>::from_slice(key);
-        let cipher = Aes256Gcm::new(key);
+        let cipher = FrameCipher::new(key);
 
         Self {
             stream,
@@ -44,6 +140,9 @@ impl<S> EncryptedStream<S> {
             read_buffer: BytesMut::with_capacity(MAX_FRAME_SIZE * 2),
             decrypted_buffer: BytesMut::with_capacity(MAX_FRAME_SIZE * 2),
             write_buffer: BytesMut::with_capacity(MAX_FRAME_SIZE * 2),
+            read_seq: 0,
+            write_seq: 0,
+            padding: None,
         }
     }
 
@@ -52,23 +151,32 @@ impl<S> EncryptedStream<S> {
     /// `encrypt_key` is used for writing (outbound), `decrypt_key` for reading (inbound).
     /// This matches the TLS convention where client and server use distinct keys.
     pub fn new_bidirectional(stream: S, encrypt_key: &[u8], decrypt_key: &[u8]) -> Self {
-        let enc_key = Key::<Aes256Gcm>::from_slice(encrypt_key);
-        let dec_key = Key::<Aes256Gcm>::from_slice(decrypt_key);
-
         Self {
             stream,
-            encryptor: Aes256Gcm::new(enc_key),
-            decryptor: Aes256Gcm::new(dec_key),
+            encryptor: FrameCipher::new(encrypt_key),
+            decryptor: FrameCipher::new(decrypt_key),
             read_buffer: BytesMut::with_capacity(MAX_FRAME_SIZE * 2),
             decrypted_buffer: BytesMut::with_capacity(MAX_FRAME_SIZE * 2),
             write_buffer: BytesMut::with_capacity(MAX_FRAME_SIZE * 2),
+            read_seq: 0,
+            write_seq: 0,
+            padding: None,
         }
     }
 
+    /// Pad each frame's plaintext to a multiple of `block_size` bytes
+    ///
+    /// Both ends must use the same setting: a padded frame read without it
+    /// carries the padding, and an unpadded one read with it fails. The
+    /// block size is clamped to `1..=64 KiB`.
+    pub fn with_padding(mut self, block_size: usize) -> Self {
+        self.padding = Some(block_size.clamp(1, MAX_FRAME_SIZE));
+        self
+    }
+
     #[cfg(test)]
     pub fn new_with_capacity(stream: S, key: &[u8], capacity: usize) -> Self {
-        let key = Key::<Aes256Gcm>::from_slice(key);
-        let cipher = Aes256Gcm::new(key);
+        let cipher = FrameCipher::new(key);
 
         Self {
             stream,
@@ -77,6 +185,9 @@ impl<S> EncryptedStream<S> {
             read_buffer: BytesMut::with_capacity(capacity),
             decrypted_buffer: BytesMut::with_capacity(capacity),
             write_buffer: BytesMut::with_capacity(capacity),
+            read_seq: 0,
+            write_seq: 0,
+            padding: None,
         }
     }
 }
@@ -196,7 +307,10 @@ impl<S: AsyncRead + Unpin> AsyncRead for EncryptedStream<S> {
             // Extract nonce and ciphertext
             let nonce = Nonce::from_slice(&me.read_buffer[..NONCE_SIZE]).to_owned(); // copy nonce
             // Extract ciphertext (remainder of frame_len) including tag
-            let payload = &me.read_buffer[NONCE_SIZE..frame_len];
+            let payload = Payload {
+                msg: &me.read_buffer[NONCE_SIZE..frame_len],
+                aad: &me.read_seq.to_be_bytes(),
+            };
 
             match me.decryptor.decrypt(&nonce, payload) {
                 Ok(plaintext) => {
@@ -205,12 +319,19 @@ impl<S: AsyncRead + Unpin> AsyncRead for EncryptedStream<S> {
                     // if plaintext.len() >= 8 {
                     //      println!("EncryptedStream: First 8 bytes: {:02X?}", &plaintext[..8]);
                     // }
-                    me.decrypted_buffer.extend_from_slice(&plaintext);
+                    let data = match me.padding {
+                        Some(_) => unpad(&plaintext)?,
+                        None => &plaintext,
+                    };
+                    record_frame("decrypt", data.len(), frame_len);
+                    me.decrypted_buffer.extend_from_slice(data);
                     me.read_buffer.advance(frame_len);
+                    me.read_seq += 1;
                     // Loop continues to serve from decrypted_buffer
                 }
                 Err(_) => {
                     // println!("EncryptedStream: Decryption failed!");
+                    counter!(metric_names::DECRYPT_FAILURES).increment(1);
                     return Poll::Ready(Err(io::Error::new(
                         io::ErrorKind::InvalidData,
                         "Decryption failed",
@@ -248,13 +369,35 @@ impl<S: AsyncWrite + Unpin> AsyncWrite for EncryptedStream<S> {
 
         // println!("EncryptedStream: Encrypting {} bytes", buf.len());
 
+        // Never wrap around and reuse a sequence number
+        let seq = me.write_seq;
+        let next_seq = seq
+            .checked_add(1)
+            .ok_or_else(|| io::Error::other("Frame sequence number exhausted"))?;
+
+        // A padded frame carries as much of `buf` as fits in MAX_FRAME_SIZE
+        let (data, padded) = match me.padding {
+            Some(block_size) => {
+                let capacity = MAX_FRAME_SIZE / block_size * block_size - U32_SIZE;
+                let data = &buf[..buf.len().min(capacity)];
+                (data, Some(pad(data, block_size)))
+            }
+            None => (buf, None),
+        };
+
         let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let payload = Payload {
+            msg: padded.as_deref().unwrap_or(data),
+            aad: &seq.to_be_bytes(),
+        };
         let ciphertext_tag = me
             .encryptor
-            .encrypt(&nonce, buf)
+            .encrypt(&nonce, payload)
             .map_err(|e| io::Error::other(format!("Encryption failed: {e}")))?;
+        me.write_seq = next_seq;
 
         let frame_len = NONCE_SIZE + ciphertext_tag.len();
+        record_frame("encrypt", data.len(), frame_len);
         // println!("EncryptedStream: Writing frame len: {} (overhead: {})", frame_len, FRAME_OVERHEAD);
 
         // Write Header: Length(4) + Nonce(12) + CiphertextTag(...)
@@ -265,7 +408,7 @@ impl<S: AsyncWrite + Unpin> AsyncWrite for EncryptedStream<S> {
         // 3. Try to write immediately (opt)
         // We fake success here to batch, relying on next call or flush to send data.
         // This is compliant with AsyncWrite, provided we do eventually write it.
-        Poll::Ready(Ok(buf.len()))
+        Poll::Ready(Ok(data.len()))
     }
 
     fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
@@ -332,6 +475,97 @@ mod tests {
         assert_eq!(&decrypted, payload);
     }
 
+    #[tokio::test]
+    async fn test_stream_roundtrip_aes128() {
+        let key = [0x42u8; 16];
+        let payload = b"Hello, 128-bit stream!";
+
+        let mut network_buffer = Vec::new();
+        let mut cursor = std::io::Cursor::new(&mut network_buffer);
+        {
+            let mut writer = EncryptedStream::new(&mut cursor, &key);
+            writer.write_all(payload).await.unwrap();
+            writer.flush().await.unwrap();
+        }
+
+        let mut read_cursor = std::io::Cursor::new(&network_buffer);
+        let mut reader = EncryptedStream::new(&mut read_cursor, &key);
+        let mut decrypted = vec![0u8; payload.len()];
+        reader.read_exact(&mut decrypted).await.unwrap();
+        assert_eq!(&decrypted, payload);
+
+        // A 256-bit reader with the key zero-extended must not accept it
+        let mut wide_key = [0u8; 32];
+        wide_key[..16].copy_from_slice(&key);
+        let mut read_cursor = std::io::Cursor::new(&network_buffer);
+        let mut reader = EncryptedStream::new(&mut read_cursor, &wide_key);
+        let err = reader.read_exact(&mut decrypted).await.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    /// Lengths of the frames in `wire`, read from their headers
+    fn frame_lengths(mut wire: &[u8]) -> Vec<usize> {
+        let mut lengths = Vec::new();
+        while wire.len() >= U32_SIZE {
+            let len = u32::from_be_bytes(wire[..U32_SIZE].try_into().unwrap()) as usize;
+            lengths.push(len);
+            wire = &wire[U32_SIZE + len..];
+        }
+        lengths
+    }
+
+    #[tokio::test]
+    async fn test_padded_frames_roundtrip_exactly() {
+        let key = [0x42u8; 32];
+        let messages: [&[u8]; 5] = [b"a", b"", b"exactly twelve", &[7u8; 60], &[9u8; 61]];
+
+        let mut network_buffer = Vec::new();
+        {
+            let mut writer = EncryptedStream::new(&mut network_buffer, &key).with_padding(64);
+            for message in messages {
+                writer.write_all(message).await.unwrap();
+            }
+            writer.flush().await.unwrap();
+        }
+
+        let expected: Vec<u8> = messages.concat();
+        let mut reader =
+            EncryptedStream::new(std::io::Cursor::new(&network_buffer), &key).with_padding(64);
+        let mut decrypted = Vec::new();
+        reader.read_to_end(&mut decrypted).await.unwrap();
+        assert_eq!(decrypted, expected);
+    }
+
+    #[tokio::test]
+    async fn test_padded_frame_sizes_are_quantized() {
+        let key = [0x42u8; 32];
+        let block = 256;
+        let mut network_buffer = Vec::new();
+        {
+            let mut writer = EncryptedStream::new(&mut network_buffer, &key).with_padding(block);
+            for len in [1, 100, 252, 253, 700] {
+                writer.write_all(&vec![0xAB; len]).await.unwrap();
+            }
+            writer.flush().await.unwrap();
+        }
+
+        let padded: Vec<usize> = frame_lengths(&network_buffer)
+            .into_iter()
+            .map(|len| len - NONCE_SIZE - 16)
+            .collect();
+        // 4-byte length prefix + data, rounded up to the block
+        assert_eq!(padded, vec![256, 256, 256, 512, 768]);
+
+        // Unpadded frames leak the exact size
+        let mut plain_buffer = Vec::new();
+        {
+            let mut writer = EncryptedStream::new(&mut plain_buffer, &key);
+            writer.write_all(&[0xAB; 100]).await.unwrap();
+            writer.flush().await.unwrap();
+        }
+        assert_eq!(frame_lengths(&plain_buffer), vec![NONCE_SIZE + 100 + 16]);
+    }
+
     #[tokio::test]
     async fn test_large_payload_chunking() {
         let key = [0x11u8; 32];
@@ -1348,4 +1582,71 @@ mod tests {
         let _ = reader.flush().await;
         let _ = reader.shutdown().await;
     }
+
+    /// Write each message as its own frame and split the output into frames
+    async fn sequenced_frames(key: &[u8; 32], messages: &[&[u8]]) -> Vec<Vec<u8>> {
+        let mut network_buffer = Vec::new();
+        {
+            let mut writer = EncryptedStream::new(&mut network_buffer, key);
+            for message in messages {
+                writer.write_all(message).await.unwrap();
+            }
+            writer.flush().await.unwrap();
+        }
+
+        let mut frames = Vec::new();
+        let mut rest = network_buffer.as_slice();
+        while !rest.is_empty() {
+            let len = u32::from_be_bytes(rest[..U32_SIZE].try_into().unwrap()) as usize;
+            frames.push(rest[..U32_SIZE + len].to_vec());
+            rest = &rest[U32_SIZE + len..];
+        }
+        frames
+    }
+
+    async fn read_frames(key: &[u8; 32], frames: &[Vec<u8>]) -> io::Result<Vec<u8>> {
+        let mut reader = EncryptedStream::new(io::Cursor::new(frames.concat()), key);
+        let mut out = Vec::new();
+        reader.read_to_end(&mut out).await.map(|_| out)
+    }
+
+    #[tokio::test]
+    async fn test_sequenced_frames_roundtrip() {
+        let key = [0x5Au8; 32];
+        let frames = sequenced_frames(&key, &[b"one", b"two", b"three"]).await;
+        assert_eq!(frames.len(), 3);
+        assert_eq!(read_frames(&key, &frames).await.unwrap(), b"onetwothree");
+    }
+
+    #[tokio::test]
+    async fn test_reordered_frames_rejected() {
+        let key = [0x5Bu8; 32];
+        let mut frames = sequenced_frames(&key, &[b"one", b"two", b"three"]).await;
+        frames.swap(1, 2);
+        let err = read_frames(&key, &frames).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[tokio::test]
+    async fn test_dropped_frame_rejected() {
+        let key = [0x5Cu8; 32];
+        let mut frames = sequenced_frames(&key, &[b"one", b"two", b"three"]).await;
+        frames.remove(1);
+        let err = read_frames(&key, &frames).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[tokio::test]
+    async fn test_replayed_frame_rejected() {
+        let key = [0x5Du8; 32];
+        let mut frames = sequenced_frames(&key, &[b"one", b"two"]).await;
+        frames.insert(1, frames[0].clone());
+        let err = read_frames(&key, &frames).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        // A frame replayed into a fresh stream only decrypts as the first frame
+        let frames = sequenced_frames(&key, &[b"one", b"two"]).await;
+        let err = read_frames(&key, &frames[1..]).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
 }
@@ -3,9 +3,15 @@
 //! This module provides integration between our hybrid PQC key exchange
 //! and the TLS layer using rustls.
 
-use crate::hybrid_kex::{HybridCiphertext, HybridKeyExchange, HybridPublicKey, HybridSecretKey};
+use crate::cipher::CipherAlgorithm;
+use crate::hybrid_kex::{
+    HybridCiphertext, HybridKeyExchange, HybridPublicKey, HybridSecretKey, HybridSharedSecret,
+    SecurityLevel,
+};
+use crate::replay::ReplayCache;
 use aegis_common::{AegisError, Result};
-use tracing::{debug, info, instrument};
+use sha2::{Digest, Sha256};
+use tracing::{debug, info, instrument, warn};
 
 /// PQC-enabled TLS configuration
 #[derive(Debug, Clone)]
@@ -16,6 +22,8 @@ pub struct PqcTlsConfig {
     pub mtls_required: bool,
     /// Algorithm selection
     pub algorithm: PqcAlgorithm,
+    /// Symmetric cipher for the channel; both ends must be configured alike
+    pub cipher: CipherAlgorithm,
 }
 
 impl Default for PqcTlsConfig {
@@ -24,6 +32,7 @@ impl Default for PqcTlsConfig {
             pqc_enabled: true,
             mtls_required: false,
             algorithm: PqcAlgorithm::HybridMlKem768,
+            cipher: CipherAlgorithm::Aes256Gcm,
         }
     }
 }
@@ -50,8 +59,101 @@ pub enum PqcAlgorithm {
     HybridKyber1024,
 }
 
+impl PqcAlgorithm {
+    /// ML-KEM parameter set the handshake uses for this algorithm
+    ///
+    /// The 1024 variants select ML-KEM-1024; everything else runs the
+    /// ML-KEM-768 hybrid exchange.
+    #[allow(deprecated)]
+    pub fn security_level(self) -> SecurityLevel {
+        match self {
+            Self::HybridMlKem1024 | Self::HybridKyber1024 => SecurityLevel::High,
+            _ => SecurityLevel::Standard,
+        }
+    }
+}
+
+/// Which end of the handshake a [`SecureChannel`] belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelRole {
+    /// Encapsulated against the server's public key
+    Client,
+    /// Generated the ephemeral keypair and decapsulated
+    Server,
+}
+
+impl ChannelRole {
+    /// Split a shared secret into this end's `(send, receive)` keys
+    ///
+    /// The client-to-server and server-to-client keys come from separate
+    /// HKDF labels, so each direction has its own key and nonce space and a
+    /// ciphertext reflected back at its sender fails to decrypt.
+    pub fn directional_keys(self, shared_secret: &HybridSharedSecret) -> ([u8; 32], [u8; 32]) {
+        let c2s = shared_secret.derive_client_key();
+        let s2c = shared_secret.derive_server_key();
+        match self {
+            Self::Client => (c2s, s2c),
+            Self::Server => (s2c, c2s),
+        }
+    }
+
+    /// Like [`Self::directional_keys`], with the handshake transcript hash
+    /// mixed into each key's HKDF info
+    pub fn directional_keys_with_transcript(
+        self,
+        shared_secret: &HybridSharedSecret,
+        transcript_hash: &[u8; 32],
+    ) -> ([u8; 32], [u8; 32]) {
+        let c2s = shared_secret.derive_client_key_with_transcript(transcript_hash);
+        let s2c = shared_secret.derive_server_key_with_transcript(transcript_hash);
+        match self {
+            Self::Client => (c2s, s2c),
+            Self::Server => (s2c, c2s),
+        }
+    }
+}
+
+/// Running SHA-256 over the handshake messages in the order they were sent
+///
+/// Each message is length-prefixed so the boundaries between them are part
+/// of the hash. Both ends feed in the exact bytes that went over the wire;
+/// if an attacker altered any of them the two hashes, and therefore the
+/// channel keys, differ.
+#[derive(Clone, Default)]
+pub struct HandshakeTranscript {
+    hasher: Sha256,
+}
+
+impl HandshakeTranscript {
+    /// Start an empty transcript
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append one handshake message
+    pub fn update(&mut self, message: &[u8]) {
+        self.hasher.update((message.len() as u64).to_be_bytes());
+        self.hasher.update(message);
+    }
+
+    /// Hash of the messages appended so far
+    pub fn hash(&self) -> [u8; 32] {
+        self.hasher.clone().finalize().into()
+    }
+}
+
+impl std::fmt::Debug for HandshakeTranscript {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HandshakeTranscript")
+            .field("hash", &hex::encode(self.hash()))
+            .finish()
+    }
+}
+
 /// A secure channel established after PQC handshake
 pub struct SecureChannel {
+    /// Which end of the handshake this channel belongs to
+    role: ChannelRole,
     /// Cipher for outbound encryption
     send_cipher: crate::cipher::Cipher,
     /// Cipher for inbound decryption
@@ -63,23 +165,48 @@ pub struct SecureChannel {
 }
 
 impl SecureChannel {
+    /// Create `role`'s end of a channel keyed from a handshake's shared secret
+    pub fn new(
+        shared_secret: &HybridSharedSecret,
+        role: ChannelRole,
+        channel_id: u64,
+        algorithm: PqcAlgorithm,
+        cipher: CipherAlgorithm,
+    ) -> Self {
+        let (send_key, recv_key) = role.directional_keys(shared_secret);
+        Self::new_bidirectional(role, send_key, recv_key, channel_id, algorithm, cipher)
+    }
+
+    /// Create `role`'s end of a channel whose keys are bound to `transcript`
+    pub fn new_with_transcript(
+        shared_secret: &HybridSharedSecret,
+        transcript: &HandshakeTranscript,
+        role: ChannelRole,
+        channel_id: u64,
+        algorithm: PqcAlgorithm,
+        cipher: CipherAlgorithm,
+    ) -> Self {
+        let (send_key, recv_key) =
+            role.directional_keys_with_transcript(shared_secret, &transcript.hash());
+        Self::new_bidirectional(role, send_key, recv_key, channel_id, algorithm, cipher)
+    }
+
     /// Create a secure channel with distinct keys for sending and receiving
+    ///
+    /// 128-bit ciphers use the first 16 bytes of each key.
     pub(crate) fn new_bidirectional(
+        role: ChannelRole,
         send_key_bytes: [u8; 32],
         recv_key_bytes: [u8; 32],
         channel_id: u64,
         algorithm: PqcAlgorithm,
+        cipher: CipherAlgorithm,
     ) -> Self {
-        let send_key = crate::cipher::EncryptionKey::from_raw(
-            send_key_bytes,
-            crate::cipher::CipherAlgorithm::Aes256Gcm,
-        );
-        let recv_key = crate::cipher::EncryptionKey::from_raw(
-            recv_key_bytes,
-            crate::cipher::CipherAlgorithm::Aes256Gcm,
-        );
+        let send_key = crate::cipher::EncryptionKey::from_raw(send_key_bytes, cipher);
+        let recv_key = crate::cipher::EncryptionKey::from_raw(recv_key_bytes, cipher);
 
         Self {
+            role,
             send_cipher: crate::cipher::Cipher::new(send_key),
             recv_cipher: crate::cipher::Cipher::new(recv_key),
             channel_id,
@@ -97,6 +224,11 @@ impl SecureChannel {
         self.recv_cipher.decrypt(ciphertext)
     }
 
+    /// Get which end of the handshake this channel belongs to
+    pub fn role(&self) -> ChannelRole {
+        self.role
+    }
+
     /// Get the channel identifier
     pub fn channel_id(&self) -> u64 {
         self.channel_id
@@ -107,15 +239,26 @@ impl SecureChannel {
         self.algorithm
     }
 
+    /// Get the symmetric cipher protecting the channel
+    pub fn cipher(&self) -> crate::cipher::CipherAlgorithm {
+        self.send_cipher.key().algorithm()
+    }
+
     /// Get the outbound encryption key
     pub fn send_key(&self) -> &crate::cipher::EncryptionKey {
         self.send_cipher.key()
     }
+
+    /// Get the inbound decryption key
+    pub fn recv_key(&self) -> &crate::cipher::EncryptionKey {
+        self.recv_cipher.key()
+    }
 }
 
 impl std::fmt::Debug for SecureChannel {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("SecureChannel")
+            .field("role", &self.role)
             .field("channel_id", &self.channel_id)
             .field("algorithm", &self.algorithm)
             .finish()
@@ -127,18 +270,26 @@ pub struct PqcHandshake {
     kex: HybridKeyExchange,
     config: PqcTlsConfig,
     channel_counter: std::sync::atomic::AtomicU64,
+    replay_cache: ReplayCache,
 }
 
 impl PqcHandshake {
     /// Create a new handshake handler
     pub fn new(config: PqcTlsConfig) -> Self {
         Self {
-            kex: HybridKeyExchange::new(),
+            kex: HybridKeyExchange::new_with_level(config.algorithm.security_level()),
             config,
             channel_counter: std::sync::atomic::AtomicU64::new(1),
+            replay_cache: ReplayCache::default(),
         }
     }
 
+    /// Use a custom cache for rejecting replayed client ciphertexts
+    pub fn with_replay_cache(mut self, replay_cache: ReplayCache) -> Self {
+        self.replay_cache = replay_cache;
+        self
+    }
+
     /// Server: Generate keypair for incoming connection and sign with identity key
     #[instrument(skip(self, identity_key))]
     pub fn server_init(
@@ -156,9 +307,12 @@ impl PqcHandshake {
         let sig_bytes = identity_key.sign(pk.as_ref())?;
         let signature = crate::signing::MlDsaSignature::new(sig_bytes, identity_key.algorithm());
 
+        let mut transcript = HandshakeTranscript::new();
+        transcript.update(&pk.to_bytes());
         let state = ServerHandshakeState {
             secret_key: sk,
             algorithm: self.config.algorithm,
+            transcript,
         };
 
         info!(
@@ -169,14 +323,30 @@ impl PqcHandshake {
     }
 
     /// Client: Complete handshake with server's public key
-    #[instrument(skip(self, server_pk, server_identity_pk, signature))]
+    ///
+    /// The channel keys are bound to a transcript of `server_pk.to_bytes()`
+    /// and the returned ciphertext's `to_bytes()`, which is what
+    /// [`ServerHello`](crate::connection::ServerHello) puts on the wire.
     pub fn client_complete(
         &self,
         server_pk: &HybridPublicKey,
         server_identity_pk: &[u8],
         signature: &crate::signing::MlDsaSignature,
+    ) -> Result<(HybridCiphertext, SecureChannel)> {
+        self.client_complete_from_bytes(&server_pk.to_bytes(), server_identity_pk, signature)
+    }
+
+    /// Client: Complete handshake with the server's public key exactly as
+    /// received
+    #[instrument(skip(self, server_pk_bytes, server_identity_pk, signature))]
+    pub fn client_complete_from_bytes(
+        &self,
+        server_pk_bytes: &[u8],
+        server_identity_pk: &[u8],
+        signature: &crate::signing::MlDsaSignature,
     ) -> Result<(HybridCiphertext, SecureChannel)> {
         debug!("Client completing PQC handshake");
+        let server_pk = HybridPublicKey::from_bytes(server_pk_bytes)?;
 
         // First authenticate the server's identity
         let verifier =
@@ -185,50 +355,79 @@ impl PqcHandshake {
             )?;
 
         use crate::signing::SigningKeyPair; // bring verifier methods into scope
-        if !verifier.verify(server_pk.as_ref(), signature.as_bytes())? {
+        if !verifier.verify(server_pk_bytes, signature.as_bytes())? {
             return Err(AegisError::Crypto(
                 "MITM Detected: Invalid server signature during handshake".to_string(),
             ));
         }
 
-        let (ciphertext, shared_secret) = self.kex.encapsulate(server_pk)?;
+        let (ciphertext, shared_secret) = self.kex.encapsulate(&server_pk)?;
 
-        // Client sends with client_key, receives with server_key
-        let send_key = shared_secret.derive_client_key();
-        let recv_key = shared_secret.derive_server_key();
+        let mut transcript = HandshakeTranscript::new();
+        transcript.update(server_pk_bytes);
+        transcript.update(&ciphertext.to_bytes());
 
         let channel_id = self
             .channel_counter
             .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
 
-        let channel =
-            SecureChannel::new_bidirectional(send_key, recv_key, channel_id, self.config.algorithm);
+        let channel = SecureChannel::new_with_transcript(
+            &shared_secret,
+            &transcript,
+            ChannelRole::Client,
+            channel_id,
+            self.config.algorithm,
+            self.config.cipher,
+        );
 
         info!("Client handshake complete, channel_id={}", channel_id);
         Ok((ciphertext, channel))
     }
 
     /// Server: Complete handshake with client's ciphertext
-    #[instrument(skip(self, ciphertext, state))]
+    ///
+    /// Ciphertexts already seen within the replay window are rejected.
     pub fn server_complete(
         &self,
         ciphertext: &HybridCiphertext,
         state: ServerHandshakeState,
+    ) -> Result<SecureChannel> {
+        self.server_complete_from_bytes(&ciphertext.to_bytes(), state)
+    }
+
+    /// Server: Complete handshake with the client's ciphertext exactly as
+    /// received
+    #[instrument(skip(self, ciphertext_bytes, state))]
+    pub fn server_complete_from_bytes(
+        &self,
+        ciphertext_bytes: &[u8],
+        mut state: ServerHandshakeState,
     ) -> Result<SecureChannel> {
         debug!("Server completing PQC handshake");
+        let ciphertext = HybridCiphertext::from_bytes(ciphertext_bytes)?;
 
-        let shared_secret = self.kex.decapsulate(ciphertext, &state.secret_key)?;
+        if !self.replay_cache.check_and_insert(ciphertext_bytes) {
+            warn!("Rejected replayed handshake ciphertext");
+            return Err(AegisError::Crypto(
+                "Replayed handshake ciphertext".to_string(),
+            ));
+        }
 
-        // Server sends with server_key, receives with client_key
-        let send_key = shared_secret.derive_server_key();
-        let recv_key = shared_secret.derive_client_key();
+        let shared_secret = self.kex.decapsulate(&ciphertext, &state.secret_key)?;
+        state.transcript.update(ciphertext_bytes);
 
         let channel_id = self
             .channel_counter
             .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
 
-        let channel =
-            SecureChannel::new_bidirectional(send_key, recv_key, channel_id, state.algorithm);
+        let channel = SecureChannel::new_with_transcript(
+            &shared_secret,
+            &state.transcript,
+            ChannelRole::Server,
+            channel_id,
+            state.algorithm,
+            self.config.cipher,
+        );
 
         info!("Server handshake complete, channel_id={}", channel_id);
         Ok(channel)
@@ -239,6 +438,8 @@ impl PqcHandshake {
 pub struct ServerHandshakeState {
     secret_key: HybridSecretKey,
     algorithm: PqcAlgorithm,
+    /// Transcript so far: the public key sent to the client
+    transcript: HandshakeTranscript,
 }
 
 impl std::fmt::Debug for ServerHandshakeState {
@@ -246,6 +447,7 @@ impl std::fmt::Debug for ServerHandshakeState {
         f.debug_struct("ServerHandshakeState")
             .field("secret_key", &"[REDACTED]")
             .field("algorithm", &self.algorithm)
+            .field("transcript", &self.transcript)
             .finish()
     }
 }
@@ -253,7 +455,6 @@ impl std::fmt::Debug for ServerHandshakeState {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::cipher::CipherAlgorithm;
 
     #[test]
     fn test_pqc_handshake_roundtrip() {
@@ -293,6 +494,215 @@ mod tests {
         assert_eq!(client_channel.algorithm(), server_channel.algorithm());
     }
 
+    #[test]
+    fn test_pqc_handshake_roundtrip_mlkem1024() {
+        use crate::signing::{MlDsa65Signer, SigningKeyPair};
+        let config = PqcTlsConfig {
+            algorithm: PqcAlgorithm::HybridMlKem1024,
+            ..Default::default()
+        };
+        let server_handshake = PqcHandshake::new(config.clone());
+        let client_handshake = PqcHandshake::new(config);
+        let identity_key = MlDsa65Signer::generate().unwrap();
+
+        let (server_pk, signature, server_state) =
+            server_handshake.server_init(&identity_key).unwrap();
+        assert_eq!(
+            server_pk.mlkem_bytes().len(),
+            pqcrypto_mlkem::mlkem1024::public_key_bytes()
+        );
+        let (ciphertext, client_channel) = client_handshake
+            .client_complete(&server_pk, identity_key.public_key(), &signature)
+            .unwrap();
+        assert_eq!(
+            ciphertext.mlkem_ciphertext.len(),
+            pqcrypto_mlkem::mlkem1024::ciphertext_bytes()
+        );
+        let server_channel = server_handshake
+            .server_complete(&ciphertext, server_state)
+            .unwrap();
+
+        let encrypted = client_channel.encrypt(b"level 5").unwrap();
+        assert_eq!(server_channel.decrypt(&encrypted).unwrap(), b"level 5");
+        assert_eq!(server_channel.algorithm(), PqcAlgorithm::HybridMlKem1024);
+    }
+
+    #[test]
+    fn test_pqc_handshake_rejects_mismatched_algorithms() {
+        use crate::signing::{MlDsa65Signer, SigningKeyPair};
+        let handshake = |algorithm| {
+            PqcHandshake::new(PqcTlsConfig {
+                algorithm,
+                ..Default::default()
+            })
+        };
+        let server_1024 = handshake(PqcAlgorithm::HybridMlKem1024);
+        let server_768 = handshake(PqcAlgorithm::HybridMlKem768);
+        let client_768 = handshake(PqcAlgorithm::HybridMlKem768);
+        let client_1024 = handshake(PqcAlgorithm::HybridMlKem1024);
+        let identity_key = MlDsa65Signer::generate().unwrap();
+
+        // A 768 client cannot encapsulate against a 1024 server key
+        let (server_pk, signature, _) = server_1024.server_init(&identity_key).unwrap();
+        let err = client_768
+            .client_complete(&server_pk, identity_key.public_key(), &signature)
+            .unwrap_err();
+        assert!(matches!(&err, AegisError::Crypto(msg) if msg.contains("different algorithm")));
+
+        // A 768 server cannot decapsulate a 1024 ciphertext
+        let (server_pk, signature, _) = server_1024.server_init(&identity_key).unwrap();
+        let (ciphertext, _) = client_1024
+            .client_complete(&server_pk, identity_key.public_key(), &signature)
+            .unwrap();
+        let (_, _, state_768) = server_768.server_init(&identity_key).unwrap();
+        let err = server_768
+            .server_complete(&ciphertext, state_768)
+            .unwrap_err();
+        assert!(matches!(&err, AegisError::Crypto(msg) if msg.contains("different algorithm")));
+    }
+
+    #[test]
+    fn test_directional_keys_reject_reflection() {
+        use crate::signing::{MlDsa65Signer, SigningKeyPair};
+        let server_handshake = PqcHandshake::new(PqcTlsConfig::default());
+        let client_handshake = PqcHandshake::new(PqcTlsConfig::default());
+        let identity_key = MlDsa65Signer::generate().unwrap();
+
+        let (server_pk, signature, server_state) =
+            server_handshake.server_init(&identity_key).unwrap();
+        let (ciphertext, client_channel) = client_handshake
+            .client_complete(&server_pk, identity_key.public_key(), &signature)
+            .unwrap();
+        let server_channel = server_handshake
+            .server_complete(&ciphertext, server_state)
+            .unwrap();
+        assert_eq!(client_channel.role(), ChannelRole::Client);
+        assert_eq!(server_channel.role(), ChannelRole::Server);
+
+        // Each end's send key is the other's receive key, and never its own
+        assert_eq!(
+            client_channel.send_key().as_bytes(),
+            server_channel.recv_key().as_bytes()
+        );
+        assert_ne!(
+            server_channel.send_key().as_bytes(),
+            server_channel.recv_key().as_bytes()
+        );
+
+        // A server ciphertext reflected back at the server does not decrypt
+        let sealed = server_channel.encrypt(b"s2c").unwrap();
+        assert!(server_channel.decrypt(&sealed).is_err());
+        assert_eq!(client_channel.decrypt(&sealed).unwrap(), b"s2c");
+
+        // Nor does a client ciphertext reflected back at the client
+        let sealed = client_channel.encrypt(b"c2s").unwrap();
+        assert!(client_channel.decrypt(&sealed).is_err());
+        assert_eq!(server_channel.decrypt(&sealed).unwrap(), b"c2s");
+    }
+
+    #[test]
+    fn test_transcript_mismatch_yields_incompatible_channels() {
+        let kex = HybridKeyExchange::new();
+        let (server_pk, server_sk) = kex.generate_keypair().unwrap();
+        let (ciphertext, client_secret) = kex.encapsulate(&server_pk).unwrap();
+        let server_secret = kex.decapsulate(&ciphertext, &server_sk).unwrap();
+        assert_eq!(client_secret.as_bytes(), server_secret.as_bytes());
+
+        // The client saw a public key with one byte flipped in transit
+        let sent = server_pk.to_bytes();
+        let mut received = sent.clone();
+        received[40] ^= 0x01;
+
+        let mut server_transcript = HandshakeTranscript::new();
+        server_transcript.update(&sent);
+        server_transcript.update(&ciphertext.to_bytes());
+        let mut client_transcript = HandshakeTranscript::new();
+        client_transcript.update(&received);
+        client_transcript.update(&ciphertext.to_bytes());
+        assert_ne!(server_transcript.hash(), client_transcript.hash());
+
+        // Same shared secret, but the keys no longer line up
+        let algorithm = PqcAlgorithm::HybridMlKem768;
+        let client = SecureChannel::new_with_transcript(
+            &client_secret,
+            &client_transcript,
+            ChannelRole::Client,
+            1,
+            algorithm,
+            CipherAlgorithm::Aes256Gcm,
+        );
+        let server = SecureChannel::new_with_transcript(
+            &server_secret,
+            &server_transcript,
+            ChannelRole::Server,
+            1,
+            algorithm,
+            CipherAlgorithm::Aes256Gcm,
+        );
+        assert!(
+            server
+                .decrypt(&client.encrypt(b"first frame").unwrap())
+                .is_err()
+        );
+        assert!(client.decrypt(&server.encrypt(b"reply").unwrap()).is_err());
+
+        // With matching transcripts the same secrets interoperate
+        let client = SecureChannel::new_with_transcript(
+            &client_secret,
+            &server_transcript,
+            ChannelRole::Client,
+            1,
+            algorithm,
+            CipherAlgorithm::Aes256Gcm,
+        );
+        let sealed = client.encrypt(b"first frame").unwrap();
+        assert_eq!(server.decrypt(&sealed).unwrap(), b"first frame");
+    }
+
+    #[test]
+    fn test_handshake_from_wire_bytes() {
+        use crate::signing::{MlDsa65Signer, SigningKeyPair};
+        let server_handshake = PqcHandshake::new(PqcTlsConfig::default());
+        let client_handshake = PqcHandshake::new(PqcTlsConfig::default());
+        let identity_key = MlDsa65Signer::generate().unwrap();
+
+        let (server_pk, signature, server_state) =
+            server_handshake.server_init(&identity_key).unwrap();
+        let pk_bytes = server_pk.to_bytes();
+
+        // A flipped public key byte is caught by the identity signature
+        let mut tampered = pk_bytes.clone();
+        tampered[40] ^= 0x01;
+        assert!(
+            client_handshake
+                .client_complete_from_bytes(&tampered, identity_key.public_key(), &signature)
+                .is_err()
+        );
+
+        let (ciphertext, client_channel) = client_handshake
+            .client_complete_from_bytes(&pk_bytes, identity_key.public_key(), &signature)
+            .unwrap();
+        let server_channel = server_handshake
+            .server_complete_from_bytes(&ciphertext.to_bytes(), server_state)
+            .unwrap();
+        let sealed = client_channel.encrypt(b"over the wire").unwrap();
+        assert_eq!(server_channel.decrypt(&sealed).unwrap(), b"over the wire");
+
+        // A flipped ciphertext byte leaves the channels unable to talk
+        let (server_pk, signature, server_state) =
+            server_handshake.server_init(&identity_key).unwrap();
+        let (ciphertext, client_channel) = client_handshake
+            .client_complete(&server_pk, identity_key.public_key(), &signature)
+            .unwrap();
+        let mut ciphertext_bytes = ciphertext.to_bytes();
+        ciphertext_bytes[0] ^= 0x01;
+        let server_channel = server_handshake
+            .server_complete_from_bytes(&ciphertext_bytes, server_state)
+            .unwrap();
+        let sealed = client_channel.encrypt(b"first frame").unwrap();
+        assert!(server_channel.decrypt(&sealed).is_err());
+    }
+
     #[test]
     fn test_default_config() {
         let config = PqcTlsConfig::default();
@@ -457,10 +867,12 @@ mod tests {
     #[test]
     fn test_secure_channel_debug() {
         let channel = SecureChannel::new_bidirectional(
+            ChannelRole::Client,
             [0u8; 32],
             [0u8; 32],
             123,
             PqcAlgorithm::HybridMlKem768,
+            CipherAlgorithm::Aes256Gcm,
         );
         let debug_str = format!("{:?}", channel);
         assert!(debug_str.contains("SecureChannel"));
@@ -471,10 +883,12 @@ mod tests {
     #[test]
     fn test_secure_channel_encrypt_decrypt() {
         let channel = SecureChannel::new_bidirectional(
+            ChannelRole::Client,
             [42u8; 32],
             [42u8; 32],
             999,
             PqcAlgorithm::HybridMlKem768,
+            CipherAlgorithm::Aes256Gcm,
         );
         let plaintext = b"Hello, PQC world!";
         let ciphertext = channel.encrypt(plaintext).unwrap();
@@ -484,8 +898,14 @@ mod tests {
 
     #[test]
     fn test_secure_channel_properties() {
-        let channel =
-            SecureChannel::new_bidirectional([1u8; 32], [1u8; 32], 456, PqcAlgorithm::MlKem768Only);
+        let channel = SecureChannel::new_bidirectional(
+            ChannelRole::Client,
+            [1u8; 32],
+            [1u8; 32],
+            456,
+            PqcAlgorithm::MlKem768Only,
+            CipherAlgorithm::Aes256Gcm,
+        );
         assert_eq!(channel.channel_id(), 456);
         assert_eq!(channel.algorithm(), PqcAlgorithm::MlKem768Only);
     }
@@ -517,6 +937,7 @@ mod tests {
             pqc_enabled: false,
             mtls_required: true,
             algorithm: PqcAlgorithm::X25519Only,
+            cipher: CipherAlgorithm::Aes128Gcm,
         };
         assert!(!config.pqc_enabled);
         assert!(config.mtls_required);
@@ -532,29 +953,72 @@ mod tests {
 
     #[test]
     fn test_secure_channel_different_ids() {
-        let ch1 =
-            SecureChannel::new_bidirectional([0u8; 32], [0u8; 32], 1, PqcAlgorithm::HybridMlKem768);
-        let ch2 =
-            SecureChannel::new_bidirectional([0u8; 32], [0u8; 32], 2, PqcAlgorithm::HybridMlKem768);
+        let ch1 = SecureChannel::new_bidirectional(
+            ChannelRole::Client,
+            [0u8; 32],
+            [0u8; 32],
+            1,
+            PqcAlgorithm::HybridMlKem768,
+            CipherAlgorithm::Aes256Gcm,
+        );
+        let ch2 = SecureChannel::new_bidirectional(
+            ChannelRole::Client,
+            [0u8; 32],
+            [0u8; 32],
+            2,
+            PqcAlgorithm::HybridMlKem768,
+            CipherAlgorithm::Aes256Gcm,
+        );
         assert_ne!(ch1.channel_id(), ch2.channel_id());
     }
 
     #[test]
     fn test_secure_channel_large_plaintext() {
-        let channel =
-            SecureChannel::new_bidirectional([0u8; 32], [0u8; 32], 1, PqcAlgorithm::HybridMlKem768);
+        let channel = SecureChannel::new_bidirectional(
+            ChannelRole::Client,
+            [0u8; 32],
+            [0u8; 32],
+            1,
+            PqcAlgorithm::HybridMlKem768,
+            CipherAlgorithm::Aes256Gcm,
+        );
         let plaintext = vec![0xAB; 100_000]; // 100 KB
         let ciphertext = channel.encrypt(&plaintext).unwrap();
         let decrypted = channel.decrypt(&ciphertext).unwrap();
         assert_eq!(decrypted, plaintext);
     }
 
+    #[test]
+    fn test_handshake_with_aes128_gcm() {
+        use crate::signing::{MlDsa65Signer, SigningKeyPair};
+
+        let config = PqcTlsConfig {
+            cipher: CipherAlgorithm::Aes128Gcm,
+            ..Default::default()
+        };
+        let server = PqcHandshake::new(config.clone());
+        let client = PqcHandshake::new(config);
+        let identity_key = MlDsa65Signer::generate().unwrap();
+
+        let (server_pk, signature, server_state) = server.server_init(&identity_key).unwrap();
+        let (ciphertext, client_channel) = client
+            .client_complete(&server_pk, identity_key.public_key(), &signature)
+            .unwrap();
+        let server_channel = server.server_complete(&ciphertext, server_state).unwrap();
+
+        assert_eq!(client_channel.cipher(), CipherAlgorithm::Aes128Gcm);
+        assert_eq!(client_channel.send_key().as_bytes().len(), 16);
+        let sealed = client_channel.encrypt(b"short keys").unwrap();
+        assert_eq!(server_channel.decrypt(&sealed).unwrap(), b"short keys");
+    }
+
     #[test]
     fn test_pqc_tls_config_clone() {
         let config = PqcTlsConfig {
             pqc_enabled: true,
             mtls_required: false,
             algorithm: PqcAlgorithm::HybridMlKem1024,
+            cipher: CipherAlgorithm::Aes256Gcm,
         };
         let cloned = config.clone();
         assert_eq!(config.pqc_enabled, cloned.pqc_enabled);
@@ -599,8 +1063,14 @@ mod tests {
     #[test]
     fn test_secure_channel_encryption_key() {
         let key_bytes = [7u8; 32];
-        let channel =
-            SecureChannel::new_bidirectional(key_bytes, key_bytes, 1, PqcAlgorithm::HybridMlKem768);
+        let channel = SecureChannel::new_bidirectional(
+            ChannelRole::Client,
+            key_bytes,
+            key_bytes,
+            1,
+            PqcAlgorithm::HybridMlKem768,
+            CipherAlgorithm::Aes256Gcm,
+        );
         let key = channel.send_key();
         assert_eq!(key.algorithm(), CipherAlgorithm::Aes256Gcm);
         let _ = format!("{:?}", key);
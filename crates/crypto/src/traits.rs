@@ -1,4 +1,4 @@
-//! Key Exchange trait definitions
+//! Key Exchange and signature trait definitions
 
 use aegis_common::Result;
 
@@ -23,6 +23,34 @@ pub trait KeyExchange: Send + Sync {
     fn algorithm_name(&self) -> &'static str;
 }
 
+/// Trait for digital signature algorithms holding a key pair
+pub trait Signer: Send + Sync {
+    /// The type representing a signature
+    type Signature: AsRef<[u8]>;
+
+    /// Sign a message
+    fn sign(&self, message: &[u8]) -> Result<Self::Signature>;
+
+    /// Whether `signature` is valid for `message`; malformed signatures are
+    /// simply invalid
+    fn verify(&self, message: &[u8], signature: &Self::Signature) -> bool;
+
+    /// Return the algorithm name
+    fn algorithm_name(&self) -> &'static str;
+}
+
+/// Trait for verifying signatures with a public key only
+pub trait Verifier: Send + Sync {
+    /// The type representing a signature
+    type Signature: AsRef<[u8]>;
+
+    /// Whether `signature` is valid for `message`
+    fn verify(&self, message: &[u8], signature: &Self::Signature) -> bool;
+
+    /// Return the algorithm name
+    fn algorithm_name(&self) -> &'static str;
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
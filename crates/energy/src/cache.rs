@@ -1,50 +1,241 @@
 //! TTL-based cache for carbon intensity data
+//!
+//! Entries stop being served by [`CarbonIntensityCache::get`] once their TTL
+//! or the measurement's own validity runs out. With a stale window they are
+//! kept that much longer for [`CarbonIntensityCache::get_allow_stale`], so
+//! callers can keep routing on the last known value while they refresh it.
+//!
+//! A cache built with [`CarbonIntensityCache::with_persistence`] is restored
+//! from a JSON file on startup, so a restart doesn't have to re-query every
+//! region from the energy API at once.
 
 use crate::types::{CarbonIntensity, Region};
+use aegis_common::{SharedClock, SystemClock};
 use moka::future::Cache;
+use moka::notification::RemovalCause;
+use moka::policy::EvictionPolicy;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::io;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::Duration;
-use tracing::{debug, instrument};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant, SystemTime};
+use tokio::task::JoinHandle;
+use tracing::{debug, instrument, warn};
+
+const DEFAULT_MAX_ENTRIES: u64 = 1000;
+
+/// Cached intensity and when it was inserted, per the cache's clock
+#[derive(Clone)]
+struct CacheEntry {
+    intensity: Arc<CarbonIntensity>,
+    inserted_at: Instant,
+}
+
+/// On-disk form of a cache entry; `cached_at` is wall-clock time since
+/// `Instant`s don't survive a restart
+#[derive(Serialize, Deserialize)]
+struct PersistedEntry {
+    intensity: CarbonIntensity,
+    cached_at: SystemTime,
+}
+
+/// An intensity from [`CarbonIntensityCache::get_allow_stale`]
+#[derive(Debug, Clone)]
+pub struct CachedIntensity {
+    pub intensity: Arc<CarbonIntensity>,
+    /// Past its TTL or validity; refresh it before relying on it for long
+    pub stale: bool,
+}
+
+/// Lookup and eviction counts since the cache was created
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Lookups answered with a fresh entry
+    pub hits: u64,
+    /// Lookups that found nothing usable
+    pub misses: u64,
+    /// [`get_allow_stale`](CarbonIntensityCache::get_allow_stale) lookups
+    /// answered with a stale entry
+    pub stale_hits: u64,
+    /// Entries dropped to stay within `max_entries`
+    pub evictions: u64,
+}
+
+#[derive(Default)]
+struct Counters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    stale_hits: AtomicU64,
+    evictions: AtomicU64,
+}
 
 /// Cache for carbon intensity lookups
 #[derive(Clone)]
 pub struct CarbonIntensityCache {
-    cache: Cache<String, Arc<CarbonIntensity>>,
+    cache: Cache<String, CacheEntry>,
     default_ttl: Duration,
+    stale_window: Duration,
+    max_entries: u64,
+    counters: Arc<Counters>,
+    persist_path: Option<PathBuf>,
+    clock: SharedClock,
 }
 
 impl CarbonIntensityCache {
     /// Create a new cache with the specified TTL
     pub fn new(ttl_seconds: u64) -> Self {
-        let cache = Cache::builder()
-            .time_to_live(Duration::from_secs(ttl_seconds))
-            .max_capacity(1000) // Max 1000 regions cached
-            .build();
-
+        let default_ttl = Duration::from_secs(ttl_seconds);
+        let counters = Arc::new(Counters::default());
         Self {
-            cache,
-            default_ttl: Duration::from_secs(ttl_seconds),
+            cache: Self::build_cache(default_ttl, DEFAULT_MAX_ENTRIES, &counters),
+            default_ttl,
+            stale_window: Duration::ZERO,
+            max_entries: DEFAULT_MAX_ENTRIES,
+            counters,
+            persist_path: None,
+            clock: SystemClock::shared(),
         }
     }
 
+    /// Create a cache backed by the JSON file at `path`
+    ///
+    /// Entries already in the file are loaded unless they are older than the
+    /// TTL. A missing or unreadable file just means starting empty.
+    pub async fn with_persistence(ttl_seconds: u64, path: impl Into<PathBuf>) -> Self {
+        let mut cache = Self::new(ttl_seconds);
+        let path = path.into();
+        match cache.load(&path).await {
+            Ok(loaded) => debug!(path = %path.display(), loaded, "Restored carbon intensity cache"),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => {
+                warn!(path = %path.display(), error = %e, "Failed to restore carbon intensity cache")
+            }
+        }
+        cache.persist_path = Some(path);
+        cache
+    }
+
+    /// Keep entries for `window` past their TTL, for
+    /// [`get_allow_stale`](Self::get_allow_stale)
+    ///
+    /// Drops anything already cached.
+    pub fn with_stale_window(mut self, window: Duration) -> Self {
+        self.stale_window = window;
+        self.cache = Self::build_cache(self.default_ttl + window, self.max_entries, &self.counters);
+        self
+    }
+
+    /// Hold at most `max_entries` regions, evicting the least recently used
+    ///
+    /// Drops anything already cached.
+    pub fn with_max_entries(mut self, max_entries: u64) -> Self {
+        self.max_entries = max_entries;
+        self.cache = Self::build_cache(
+            self.default_ttl + self.stale_window,
+            max_entries,
+            &self.counters,
+        );
+        self
+    }
+
+    fn build_cache(
+        retention: Duration,
+        max_entries: u64,
+        counters: &Arc<Counters>,
+    ) -> Cache<String, CacheEntry> {
+        let counters = counters.clone();
+        Cache::builder()
+            .time_to_live(retention)
+            .max_capacity(max_entries)
+            .eviction_policy(EvictionPolicy::lru())
+            .eviction_listener(move |_, _, cause| {
+                if cause == RemovalCause::Size {
+                    counters.evictions.fetch_add(1, Ordering::Relaxed);
+                }
+            })
+            .build()
+    }
+
+    /// Use a custom time source for TTL and validity checks
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
     /// Get cached carbon intensity for a region
     #[instrument(skip(self))]
     pub async fn get(&self, region: &Region) -> Option<Arc<CarbonIntensity>> {
-        let key = Self::cache_key(region);
-        let result = self.cache.get(&key).await;
-
-        if let Some(ref intensity) = result {
-            if !intensity.is_valid() {
-                debug!(region_id = %region.id, "Cached intensity expired");
-                self.cache.invalidate(&key).await;
-                return None;
+        match self.lookup(region).await {
+            Some(cached) if !cached.stale => {
+                self.counters.hits.fetch_add(1, Ordering::Relaxed);
+                Some(cached.intensity)
             }
-            debug!(region_id = %region.id, "Cache hit");
-        } else {
+            _ => {
+                self.counters.misses.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        }
+    }
+
+    /// Get cached carbon intensity for a region, even if it has expired
+    /// within the stale window
+    #[instrument(skip(self))]
+    pub async fn get_allow_stale(&self, region: &Region) -> Option<CachedIntensity> {
+        let cached = self.lookup(region).await;
+        let counter = match &cached {
+            Some(cached) if cached.stale => &self.counters.stale_hits,
+            Some(_) => &self.counters.hits,
+            None => &self.counters.misses,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        cached
+    }
+
+    /// Cached entry for `region`, dropping it once past the stale window
+    async fn lookup(&self, region: &Region) -> Option<CachedIntensity> {
+        let key = Self::cache_key(region);
+        let Some(entry) = self.cache.get(&key).await else {
             debug!(region_id = %region.id, "Cache miss");
+            return None;
+        };
+
+        let age = self
+            .clock
+            .instant()
+            .saturating_duration_since(entry.inserted_at);
+        if age >= self.default_ttl + self.stale_window {
+            debug!(region_id = %region.id, "Cached intensity expired");
+            self.cache.invalidate(&key).await;
+            return None;
+        }
+        let stale =
+            age >= self.default_ttl || !entry.intensity.is_valid_at(self.clock.now().into());
+        if stale && self.stale_window.is_zero() {
+            debug!(region_id = %region.id, "Cached intensity expired");
+            self.cache.invalidate(&key).await;
         }
 
-        result
+        debug!(region_id = %region.id, stale, "Cache hit");
+        Some(CachedIntensity {
+            intensity: entry.intensity,
+            stale,
+        })
+    }
+
+    /// Lookup and eviction counts so far
+    ///
+    /// Runs the cache's pending maintenance first, so evictions caused by
+    /// recent inserts are included.
+    pub async fn stats(&self) -> CacheStats {
+        self.cache.run_pending_tasks().await;
+        CacheStats {
+            hits: self.counters.hits.load(Ordering::Relaxed),
+            misses: self.counters.misses.load(Ordering::Relaxed),
+            stale_hits: self.counters.stale_hits.load(Ordering::Relaxed),
+            evictions: self.counters.evictions.load(Ordering::Relaxed),
+        }
     }
 
     /// Store carbon intensity in cache
@@ -52,7 +243,88 @@ impl CarbonIntensityCache {
     pub async fn put(&self, intensity: CarbonIntensity) {
         let key = Self::cache_key(&intensity.region);
         debug!(region_id = %intensity.region.id, value = %intensity.value, "Caching intensity");
-        self.cache.insert(key, Arc::new(intensity)).await;
+        let entry = CacheEntry {
+            intensity: Arc::new(intensity),
+            inserted_at: self.clock.instant(),
+        };
+        self.cache.insert(key, entry).await;
+    }
+
+    /// Write all entries to the persistence file, if one is configured
+    pub async fn persist(&self) -> io::Result<()> {
+        let Some(path) = &self.persist_path else {
+            return Ok(());
+        };
+        let now = self.clock.now();
+        let instant = self.clock.instant();
+        let entries: Vec<PersistedEntry> = self
+            .cache
+            .iter()
+            .map(|(_, entry)| PersistedEntry {
+                intensity: (*entry.intensity).clone(),
+                cached_at: now - instant.saturating_duration_since(entry.inserted_at),
+            })
+            .collect();
+        let json = serde_json::to_vec(&entries).map_err(io::Error::other)?;
+
+        // Write then rename so a crash mid-write can't leave a torn file
+        let tmp = path.with_extension("tmp");
+        tokio::fs::write(&tmp, json).await?;
+        tokio::fs::rename(&tmp, path).await?;
+        debug!(path = %path.display(), entries = entries.len(), "Persisted carbon intensity cache");
+        Ok(())
+    }
+
+    /// Persist every `interval`, and once more when `shutdown` resolves
+    pub fn spawn_persistence<F>(&self, interval: Duration, shutdown: F) -> JoinHandle<()>
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let cache = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+            tokio::pin!(shutdown);
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {}
+                    _ = &mut shutdown => break,
+                }
+                if let Err(e) = cache.persist().await {
+                    warn!(error = %e, "Failed to persist carbon intensity cache");
+                }
+            }
+            if let Err(e) = cache.persist().await {
+                warn!(error = %e, "Failed to persist carbon intensity cache on shutdown");
+            }
+        })
+    }
+
+    /// Insert the entries from `path` that are still within the TTL
+    async fn load(&self, path: &Path) -> io::Result<usize> {
+        let bytes = tokio::fs::read(path).await?;
+        let entries: Vec<PersistedEntry> = serde_json::from_slice(&bytes)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let now = self.clock.now();
+        let instant = self.clock.instant();
+        let mut loaded = 0;
+        for entry in entries {
+            let age = now.duration_since(entry.cached_at).unwrap_or_default();
+            if age >= self.default_ttl {
+                continue;
+            }
+            let Some(inserted_at) = instant.checked_sub(age) else {
+                continue;
+            };
+            let key = Self::cache_key(&entry.intensity.region);
+            let entry = CacheEntry {
+                intensity: Arc::new(entry.intensity),
+                inserted_at,
+            };
+            self.cache.insert(key, entry).await;
+            loaded += 1;
+        }
+        Ok(loaded)
     }
 
     /// Get cached intensity or fetch from provider
@@ -118,6 +390,7 @@ impl Default for CarbonIntensityCache {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aegis_common::{Clock, MockClock};
 
     fn create_test_intensity(region_id: &str, value: f64) -> CarbonIntensity {
         CarbonIntensity {
@@ -126,6 +399,8 @@ mod tests {
             timestamp: chrono::Utc::now(),
             valid_for_seconds: 300,
             rating: None,
+            renewable_percentage: None,
+            power_breakdown: None,
         }
     }
 
@@ -275,17 +550,157 @@ mod tests {
 
     #[tokio::test]
     async fn test_cache_get_expired_entry() {
-        let cache = CarbonIntensityCache::new(60);
+        let clock = MockClock::new();
+        let cache = CarbonIntensityCache::new(600).with_clock(clock.shared());
         let mut intensity = create_test_intensity("EXPIRED", 100.0);
-
-        // Manually set timestamp to past
-        intensity.timestamp = chrono::Utc::now() - chrono::Duration::seconds(400); // > 300s default validity
+        intensity.timestamp = clock.now().into();
 
         cache.put(intensity.clone()).await;
+        assert!(cache.get(&intensity.region).await.is_some());
 
-        // Should be auto-invalidated
-        let result = cache.get(&intensity.region).await;
-        assert!(result.is_none());
+        // Past the 300s validity of the measurement, within the cache TTL
+        clock.advance(Duration::from_secs(301));
+        assert!(cache.get(&intensity.region).await.is_none());
         assert!(cache.get(&intensity.region).await.is_none());
     }
+
+    #[tokio::test]
+    async fn test_cache_ttl_uses_clock() {
+        let clock = MockClock::new();
+        let cache = CarbonIntensityCache::new(60).with_clock(clock.shared());
+        let mut intensity = create_test_intensity("TTL", 100.0);
+        intensity.timestamp = clock.now().into();
+        let region = intensity.region.clone();
+
+        cache.put(intensity).await;
+        clock.advance(Duration::from_secs(59));
+        assert!(cache.get(&region).await.is_some());
+
+        clock.advance(Duration::from_secs(1));
+        assert!(cache.get(&region).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_persistence_restores_unexpired_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("carbon-cache.json");
+        let clock = MockClock::starting_at(SystemTime::now() - Duration::from_secs(90));
+        let writer = CarbonIntensityCache::with_persistence(60, &path)
+            .await
+            .with_clock(clock.shared());
+        assert!(writer.is_empty());
+
+        writer.put(create_test_intensity("OLD", 400.0)).await;
+        clock.advance(Duration::from_secs(90));
+        writer.put(create_test_intensity("NEW", 100.0)).await;
+        writer.persist().await.unwrap();
+
+        let reader = CarbonIntensityCache::with_persistence(60, &path).await;
+        assert!(reader.get(&Region::new("OLD", "Test OLD")).await.is_none());
+        let restored = reader.get(&Region::new("NEW", "Test NEW")).await.unwrap();
+        assert_eq!(restored.value, 100.0);
+    }
+
+    #[tokio::test]
+    async fn test_persistence_ignores_corrupt_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("carbon-cache.json");
+        std::fs::write(&path, b"not json").unwrap();
+
+        let cache = CarbonIntensityCache::with_persistence(60, &path).await;
+        assert!(cache.is_empty());
+        cache.put(create_test_intensity("FR", 50.0)).await;
+        cache.persist().await.unwrap();
+
+        let reloaded = CarbonIntensityCache::with_persistence(60, &path).await;
+        assert!(reloaded.get(&Region::new("FR", "Test FR")).await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_spawn_persistence_writes_on_shutdown() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("carbon-cache.json");
+        let cache = CarbonIntensityCache::with_persistence(60, &path).await;
+        cache.put(create_test_intensity("DE", 300.0)).await;
+
+        let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+        let handle = cache.spawn_persistence(Duration::from_secs(3600), async {
+            let _ = rx.await;
+        });
+        tx.send(()).unwrap();
+        handle.await.unwrap();
+
+        let reloaded = CarbonIntensityCache::with_persistence(60, &path).await;
+        assert!(reloaded.get(&Region::new("DE", "Test DE")).await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_get_allow_stale_flags_expired_entry() {
+        let clock = MockClock::new();
+        let cache = CarbonIntensityCache::new(60)
+            .with_stale_window(Duration::from_secs(120))
+            .with_clock(clock.shared());
+        let mut intensity = create_test_intensity("SWR", 100.0);
+        intensity.timestamp = clock.now().into();
+        let region = intensity.region.clone();
+        cache.put(intensity).await;
+
+        let fresh = cache.get_allow_stale(&region).await.unwrap();
+        assert!(!fresh.stale);
+
+        clock.advance(Duration::from_secs(90));
+        assert!(cache.get(&region).await.is_none());
+        let stale = cache.get_allow_stale(&region).await.unwrap();
+        assert!(stale.stale);
+        assert_eq!(stale.intensity.value, 100.0);
+
+        // Past TTL + stale window
+        clock.advance(Duration::from_secs(90));
+        assert!(cache.get_allow_stale(&region).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_max_entries_evicts_least_recently_used() {
+        let cache = CarbonIntensityCache::new(60).with_max_entries(2);
+        cache.put(create_test_intensity("A", 1.0)).await;
+        cache.put(create_test_intensity("B", 2.0)).await;
+        cache.stats().await;
+        assert!(cache.get(&Region::new("A", "A")).await.is_some());
+        cache.stats().await;
+
+        cache.put(create_test_intensity("C", 3.0)).await;
+        assert_eq!(cache.stats().await.evictions, 1);
+        assert!(cache.get(&Region::new("B", "B")).await.is_none());
+        assert!(cache.get(&Region::new("A", "A")).await.is_some());
+        assert!(cache.get(&Region::new("C", "C")).await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_stats_accounting() {
+        let clock = MockClock::new();
+        let cache = CarbonIntensityCache::new(60)
+            .with_stale_window(Duration::from_secs(60))
+            .with_clock(clock.shared());
+        let mut intensity = create_test_intensity("STATS", 100.0);
+        intensity.timestamp = clock.now().into();
+        let region = intensity.region.clone();
+
+        assert!(cache.get(&region).await.is_none());
+        cache.put(intensity).await;
+        assert!(cache.get(&region).await.is_some());
+        assert!(cache.get_allow_stale(&region).await.is_some());
+        clock.advance(Duration::from_secs(61));
+        assert!(cache.get(&region).await.is_none());
+        assert!(cache.get_allow_stale(&region).await.is_some());
+
+        assert_eq!(
+            cache.stats().await,
+            CacheStats {
+                hits: 2,
+                misses: 2,
+                stale_hits: 1,
+                evictions: 0,
+            }
+        );
+    }
 }
@@ -0,0 +1,254 @@
+//! Object-safe energy API client
+//!
+//! [`EnergyApiClient`] returns `impl Future`, so it cannot be used as a
+//! trait object. [`DynEnergyApiClient`] boxes those futures and is
+//! implemented for every client, letting providers picked at runtime live
+//! behind `Box<dyn DynEnergyApiClient>` or `Arc<dyn DynEnergyApiClient>`.
+//! Both of those implement `EnergyApiClient` again, so they plug straight
+//! into generic consumers like the carbon router and green-wait scheduler.
+
+use crate::client::EnergyApiClient;
+use crate::types::{CarbonIntensity, EnergyApiError, ForecastPoint, Region};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+/// Boxed `Send` future returned by [`DynEnergyApiClient`]
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Object-safe form of [`EnergyApiClient`]
+///
+/// Both traits share method names, so import only the one you call through;
+/// with both in scope, calls on a `Box<dyn DynEnergyApiClient>` are ambiguous.
+pub trait DynEnergyApiClient: Send + Sync {
+    /// Get current carbon intensity for a region
+    fn get_carbon_intensity<'a>(
+        &'a self,
+        region: &'a Region,
+    ) -> BoxFuture<'a, Result<CarbonIntensity, EnergyApiError>>;
+
+    /// Get carbon intensity for coordinates (reverse geocoding)
+    fn get_carbon_intensity_by_location(
+        &self,
+        latitude: f64,
+        longitude: f64,
+    ) -> BoxFuture<'_, Result<CarbonIntensity, EnergyApiError>>;
+
+    /// Get the region for given coordinates
+    fn get_region_for_location(
+        &self,
+        latitude: f64,
+        longitude: f64,
+    ) -> BoxFuture<'_, Result<Region, EnergyApiError>>;
+
+    /// Get carbon forecast for the next N hours
+    fn get_carbon_forecast<'a>(
+        &'a self,
+        region: &'a Region,
+        hours: u32,
+    ) -> BoxFuture<'a, Result<Vec<ForecastPoint>, EnergyApiError>>;
+}
+
+impl<C: EnergyApiClient> DynEnergyApiClient for C {
+    fn get_carbon_intensity<'a>(
+        &'a self,
+        region: &'a Region,
+    ) -> BoxFuture<'a, Result<CarbonIntensity, EnergyApiError>> {
+        Box::pin(EnergyApiClient::get_carbon_intensity(self, region))
+    }
+
+    fn get_carbon_intensity_by_location(
+        &self,
+        latitude: f64,
+        longitude: f64,
+    ) -> BoxFuture<'_, Result<CarbonIntensity, EnergyApiError>> {
+        Box::pin(EnergyApiClient::get_carbon_intensity_by_location(
+            self, latitude, longitude,
+        ))
+    }
+
+    fn get_region_for_location(
+        &self,
+        latitude: f64,
+        longitude: f64,
+    ) -> BoxFuture<'_, Result<Region, EnergyApiError>> {
+        Box::pin(EnergyApiClient::get_region_for_location(
+            self, latitude, longitude,
+        ))
+    }
+
+    fn get_carbon_forecast<'a>(
+        &'a self,
+        region: &'a Region,
+        hours: u32,
+    ) -> BoxFuture<'a, Result<Vec<ForecastPoint>, EnergyApiError>> {
+        Box::pin(EnergyApiClient::get_carbon_forecast(self, region, hours))
+    }
+}
+
+macro_rules! impl_energy_api_client_for_dyn {
+    ($ty:ty) => {
+        impl EnergyApiClient for $ty {
+            async fn get_carbon_intensity(
+                &self,
+                region: &Region,
+            ) -> Result<CarbonIntensity, EnergyApiError> {
+                DynEnergyApiClient::get_carbon_intensity(&**self, region).await
+            }
+
+            async fn get_carbon_intensity_by_location(
+                &self,
+                latitude: f64,
+                longitude: f64,
+            ) -> Result<CarbonIntensity, EnergyApiError> {
+                DynEnergyApiClient::get_carbon_intensity_by_location(&**self, latitude, longitude)
+                    .await
+            }
+
+            async fn get_region_for_location(
+                &self,
+                latitude: f64,
+                longitude: f64,
+            ) -> Result<Region, EnergyApiError> {
+                DynEnergyApiClient::get_region_for_location(&**self, latitude, longitude).await
+            }
+
+            async fn get_carbon_forecast(
+                &self,
+                region: &Region,
+                hours: u32,
+            ) -> Result<Vec<ForecastPoint>, EnergyApiError> {
+                DynEnergyApiClient::get_carbon_forecast(&**self, region, hours).await
+            }
+        }
+    };
+}
+
+impl_energy_api_client_for_dyn!(Box<dyn DynEnergyApiClient>);
+impl_energy_api_client_for_dyn!(Arc<dyn DynEnergyApiClient>);
+
+#[cfg(test)]
+mod tests {
+    use super::DynEnergyApiClient;
+    use crate::client::ElectricityMapsClient;
+    use crate::types::{CarbonIntensity, EnergyApiError, ForecastPoint, Region};
+    use std::sync::Arc;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    struct FixedClient {
+        zone: &'static str,
+        intensity: f64,
+    }
+
+    impl FixedClient {
+        fn reading(&self, region: &Region) -> CarbonIntensity {
+            CarbonIntensity {
+                region: region.clone(),
+                value: self.intensity,
+                timestamp: chrono::Utc::now(),
+                valid_for_seconds: 300,
+                rating: None,
+                renewable_percentage: None,
+                power_breakdown: None,
+            }
+        }
+    }
+
+    impl crate::EnergyApiClient for FixedClient {
+        async fn get_carbon_intensity(
+            &self,
+            region: &Region,
+        ) -> Result<CarbonIntensity, EnergyApiError> {
+            Ok(self.reading(region))
+        }
+
+        async fn get_carbon_intensity_by_location(
+            &self,
+            _latitude: f64,
+            _longitude: f64,
+        ) -> Result<CarbonIntensity, EnergyApiError> {
+            Ok(self.reading(&Region::new(self.zone, self.zone)))
+        }
+
+        async fn get_region_for_location(
+            &self,
+            _latitude: f64,
+            _longitude: f64,
+        ) -> Result<Region, EnergyApiError> {
+            Ok(Region::new(self.zone, self.zone))
+        }
+
+        async fn get_carbon_forecast(
+            &self,
+            _region: &Region,
+            _hours: u32,
+        ) -> Result<Vec<ForecastPoint>, EnergyApiError> {
+            Ok(Vec::new())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_heterogeneous_clients_behind_trait_objects() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/carbon-intensity/latest"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "zone": "DE",
+                "carbonIntensity": 250.5,
+                "datetime": "2025-12-25T14:00:00Z",
+                "updatedAt": "2025-12-25T14:05:00Z"
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let clients: Vec<Box<dyn DynEnergyApiClient>> = vec![
+            Box::new(
+                ElectricityMapsClient::new("test_key".to_string()).with_base_url(mock_server.uri()),
+            ),
+            Box::new(FixedClient {
+                zone: "FR",
+                intensity: 55.0,
+            }),
+        ];
+
+        let region = Region::new("DE", "Germany");
+        let mut values = Vec::new();
+        for client in &clients {
+            values.push(client.get_carbon_intensity(&region).await.unwrap().value);
+        }
+        assert_eq!(values, vec![250.5, 55.0]);
+
+        let found = clients[1].get_region_for_location(48.8, 2.3).await.unwrap();
+        assert_eq!(found.id, "FR");
+        assert!(
+            clients[1]
+                .get_carbon_forecast(&region, 24)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_trait_objects_satisfy_generic_bound() {
+        async fn intensity_of<C: crate::EnergyApiClient>(client: &C) -> f64 {
+            client
+                .get_carbon_intensity(&Region::new("X", "X"))
+                .await
+                .unwrap()
+                .value
+        }
+
+        let boxed: Box<dyn DynEnergyApiClient> = Box::new(FixedClient {
+            zone: "X",
+            intensity: 10.0,
+        });
+        let shared: Arc<dyn DynEnergyApiClient> = Arc::new(FixedClient {
+            zone: "X",
+            intensity: 20.0,
+        });
+        assert_eq!(intensity_of(&boxed).await, 10.0);
+        assert_eq!(intensity_of(&shared).await, 20.0);
+    }
+}
@@ -0,0 +1,530 @@
+//! Failover across energy API providers
+//!
+//! [`FallbackEnergyClient`] holds providers in priority order. A request goes
+//! to the provider that last answered for its region, or else the first one,
+//! and moves down the list when a provider fails. Providers name the same grid
+//! differently (WattTime's `CAISO_NORTH` is Electricity Maps' `US-CAL-CISO`),
+//! so each provider can map the region ids callers use onto its own.
+//!
+//! A provider that reports [`EnergyApiError::RateLimitExceeded`] is skipped
+//! until the `retry_after_seconds` it asked for have passed.
+
+use crate::client::EnergyApiClient;
+use crate::dyn_client::{BoxFuture, DynEnergyApiClient};
+use crate::types::{CarbonIntensity, EnergyApiError, ForecastPoint, Region};
+use aegis_common::{SharedClock, SystemClock};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+struct Provider {
+    name: String,
+    client: Arc<dyn DynEnergyApiClient>,
+    /// Caller region id to this provider's region id
+    region_ids: HashMap<String, String>,
+}
+
+impl Provider {
+    /// `region` as this provider knows it
+    fn region(&self, region: &Region) -> Region {
+        match self.region_ids.get(&region.id) {
+            Some(id) => Region {
+                id: id.clone(),
+                ..region.clone()
+            },
+            None => region.clone(),
+        }
+    }
+
+    /// Translate a region returned by this provider back to the caller's id
+    fn normalize(&self, mut region: Region) -> Region {
+        if let Some((id, _)) = self.region_ids.iter().find(|(_, id)| **id == region.id) {
+            region.id = id.clone();
+        }
+        region
+    }
+}
+
+/// Energy API client that fails over between providers
+pub struct FallbackEnergyClient {
+    providers: Vec<Provider>,
+    /// Region id to index of the provider that last answered for it
+    last_success: tokio::sync::RwLock<HashMap<String, usize>>,
+    /// Index of the provider that answered the most recent request
+    last_used: tokio::sync::RwLock<Option<usize>>,
+    /// Provider index to when its rate limit cooldown ends
+    cooldowns: tokio::sync::RwLock<HashMap<usize, Instant>>,
+    clock: SharedClock,
+}
+
+impl Default for FallbackEnergyClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FallbackEnergyClient {
+    pub fn new() -> Self {
+        Self {
+            providers: Vec::new(),
+            last_success: Default::default(),
+            last_used: Default::default(),
+            cooldowns: Default::default(),
+            clock: SystemClock::shared(),
+        }
+    }
+
+    /// Use a custom time source for rate limit cooldowns
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Add a provider; providers are tried in the order they were added
+    pub fn with_provider<C>(self, name: impl Into<String>, client: C) -> Self
+    where
+        C: EnergyApiClient + 'static,
+    {
+        self.with_mapped_provider(name, client, &[])
+    }
+
+    /// Add a provider that knows some regions by other ids, given as
+    /// `(caller id, provider id)` pairs
+    pub fn with_mapped_provider<C>(
+        mut self,
+        name: impl Into<String>,
+        client: C,
+        region_ids: &[(&str, &str)],
+    ) -> Self
+    where
+        C: EnergyApiClient + 'static,
+    {
+        self.providers.push(Provider {
+            name: name.into(),
+            client: Arc::new(client),
+            region_ids: region_ids
+                .iter()
+                .map(|(id, provider_id)| (id.to_string(), provider_id.to_string()))
+                .collect(),
+        });
+        self
+    }
+
+    /// Name of the provider that last answered for `region_id`
+    pub async fn preferred_provider(&self, region_id: &str) -> Option<String> {
+        let index = *self.last_success.read().await.get(region_id)?;
+        Some(self.providers[index].name.clone())
+    }
+
+    /// Name of the provider that answered the most recent request
+    pub async fn last_provider_used(&self) -> Option<String> {
+        let index = (*self.last_used.read().await)?;
+        Some(self.providers[index].name.clone())
+    }
+
+    /// Provider indices to try, starting with the last one that worked
+    async fn order(&self, region_id: Option<&str>) -> Vec<usize> {
+        let preferred = match region_id {
+            Some(id) => self.last_success.read().await.get(id).copied(),
+            None => None,
+        };
+        preferred
+            .into_iter()
+            .chain((0..self.providers.len()).filter(|i| Some(*i) != preferred))
+            .collect()
+    }
+
+    async fn try_providers<T, F>(
+        &self,
+        region_id: Option<&str>,
+        call: F,
+    ) -> Result<T, EnergyApiError>
+    where
+        F: for<'p> Fn(&'p Provider) -> BoxFuture<'p, Result<T, EnergyApiError>>,
+    {
+        let mut last_error = None;
+        let mut soonest_retry: Option<Duration> = None;
+        for index in self.order(region_id).await {
+            let provider = &self.providers[index];
+            let now = self.clock.instant();
+            if let Some(&until) = self.cooldowns.read().await.get(&index)
+                && until > now
+            {
+                debug!(provider = %provider.name, "Energy provider rate limited, skipping");
+                let wait = until - now;
+                soonest_retry = Some(soonest_retry.map_or(wait, |soonest| soonest.min(wait)));
+                continue;
+            }
+            match call(provider).await {
+                Ok(value) => {
+                    *self.last_used.write().await = Some(index);
+                    if let Some(id) = region_id {
+                        debug!(provider = %provider.name, region = %id, "Energy provider answered");
+                        self.last_success
+                            .write()
+                            .await
+                            .insert(id.to_string(), index);
+                    }
+                    return Ok(value);
+                }
+                Err(e) if fails_over(&e) => {
+                    if let EnergyApiError::RateLimitExceeded {
+                        retry_after_seconds,
+                    } = e
+                    {
+                        let until = self.clock.instant() + Duration::from_secs(retry_after_seconds);
+                        self.cooldowns.write().await.insert(index, until);
+                    }
+                    warn!(
+                        provider = %provider.name,
+                        error = %e,
+                        "Energy API provider failed, trying the next one"
+                    );
+                    last_error = Some(e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        if let Some(error) = last_error {
+            return Err(error);
+        }
+        // Every provider is cooling down
+        if let Some(wait) = soonest_retry {
+            return Err(EnergyApiError::RateLimitExceeded {
+                retry_after_seconds: wait.as_secs_f64().ceil() as u64,
+            });
+        }
+        Err(EnergyApiError::ConfigError(
+            "No energy API providers configured".to_string(),
+        ))
+    }
+}
+
+/// Whether another provider might succeed where this one failed
+///
+/// Bad coordinates are the caller's mistake and fail the same everywhere;
+/// anything else may be specific to the provider.
+fn fails_over(error: &EnergyApiError) -> bool {
+    !matches!(error, EnergyApiError::InvalidCoordinates { .. })
+}
+
+impl EnergyApiClient for FallbackEnergyClient {
+    async fn get_carbon_intensity(
+        &self,
+        region: &Region,
+    ) -> Result<CarbonIntensity, EnergyApiError> {
+        let mut reading = self
+            .try_providers(Some(&region.id), |provider| {
+                let provider_region = provider.region(region);
+                Box::pin(async move {
+                    DynEnergyApiClient::get_carbon_intensity(&*provider.client, &provider_region)
+                        .await
+                })
+            })
+            .await?;
+        reading.region = region.clone();
+        Ok(reading)
+    }
+
+    async fn get_carbon_intensity_by_location(
+        &self,
+        latitude: f64,
+        longitude: f64,
+    ) -> Result<CarbonIntensity, EnergyApiError> {
+        self.try_providers(None, |provider| {
+            Box::pin(async move {
+                let mut reading = DynEnergyApiClient::get_carbon_intensity_by_location(
+                    &*provider.client,
+                    latitude,
+                    longitude,
+                )
+                .await?;
+                reading.region = provider.normalize(reading.region);
+                Ok(reading)
+            })
+        })
+        .await
+    }
+
+    async fn get_region_for_location(
+        &self,
+        latitude: f64,
+        longitude: f64,
+    ) -> Result<Region, EnergyApiError> {
+        self.try_providers(None, |provider| {
+            Box::pin(async move {
+                let region = DynEnergyApiClient::get_region_for_location(
+                    &*provider.client,
+                    latitude,
+                    longitude,
+                )
+                .await?;
+                Ok(provider.normalize(region))
+            })
+        })
+        .await
+    }
+
+    async fn get_carbon_forecast(
+        &self,
+        region: &Region,
+        hours: u32,
+    ) -> Result<Vec<ForecastPoint>, EnergyApiError> {
+        self.try_providers(Some(&region.id), |provider| {
+            let provider_region = provider.region(region);
+            Box::pin(async move {
+                DynEnergyApiClient::get_carbon_forecast(&*provider.client, &provider_region, hours)
+                    .await
+            })
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FallbackEnergyClient;
+    use crate::client::EnergyApiClient;
+    use crate::types::{CarbonIntensity, EnergyApiError, ForecastPoint, Region};
+    use aegis_common::MockClock;
+    use std::sync::Arc;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    /// Answers with a fixed intensity, or fails when `intensity` is `None`,
+    /// recording the region ids it was asked about
+    struct ScriptedClient {
+        intensity: Option<f64>,
+        rate_limited: bool,
+        seen: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl ScriptedClient {
+        fn new(intensity: Option<f64>) -> (Self, Arc<Mutex<Vec<String>>>) {
+            let seen = Arc::new(Mutex::new(Vec::new()));
+            let client = Self {
+                intensity,
+                rate_limited: false,
+                seen: seen.clone(),
+            };
+            (client, seen)
+        }
+
+        /// Always fails with a 60 second rate limit
+        fn rate_limited() -> (Self, Arc<Mutex<Vec<String>>>) {
+            let (mut client, seen) = Self::new(None);
+            client.rate_limited = true;
+            (client, seen)
+        }
+
+        fn answer(&self, region: &Region) -> Result<CarbonIntensity, EnergyApiError> {
+            self.seen.lock().unwrap().push(region.id.clone());
+            if self.rate_limited {
+                return Err(EnergyApiError::RateLimitExceeded {
+                    retry_after_seconds: 60,
+                });
+            }
+            let value = self.intensity.ok_or_else(|| EnergyApiError::ApiError {
+                message: "provider down".to_string(),
+            })?;
+            Ok(CarbonIntensity {
+                region: region.clone(),
+                value,
+                timestamp: chrono::Utc::now(),
+                valid_for_seconds: 300,
+                rating: None,
+                renewable_percentage: None,
+                power_breakdown: None,
+            })
+        }
+    }
+
+    impl EnergyApiClient for ScriptedClient {
+        async fn get_carbon_intensity(
+            &self,
+            region: &Region,
+        ) -> Result<CarbonIntensity, EnergyApiError> {
+            self.answer(region)
+        }
+
+        async fn get_carbon_intensity_by_location(
+            &self,
+            latitude: f64,
+            longitude: f64,
+        ) -> Result<CarbonIntensity, EnergyApiError> {
+            Region::validate_coordinates(latitude, longitude)?;
+            self.answer(&Region::new("US-CAL-CISO", "California"))
+        }
+
+        async fn get_region_for_location(
+            &self,
+            _latitude: f64,
+            _longitude: f64,
+        ) -> Result<Region, EnergyApiError> {
+            Ok(Region::new("US-CAL-CISO", "California"))
+        }
+
+        async fn get_carbon_forecast(
+            &self,
+            region: &Region,
+            _hours: u32,
+        ) -> Result<Vec<ForecastPoint>, EnergyApiError> {
+            self.answer(region).map(|_| Vec::new())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_falls_back_and_remembers_working_provider() {
+        let (primary, primary_seen) = ScriptedClient::new(None);
+        let (secondary, secondary_seen) = ScriptedClient::new(Some(180.0));
+        let client = FallbackEnergyClient::new()
+            .with_provider("watttime", primary)
+            .with_mapped_provider(
+                "electricitymaps",
+                secondary,
+                &[("CAISO_NORTH", "US-CAL-CISO")],
+            );
+        let region = Region::new("CAISO_NORTH", "Northern California");
+
+        let reading = client.get_carbon_intensity(&region).await.unwrap();
+        assert_eq!(reading.value, 180.0);
+        assert_eq!(reading.region.id, "CAISO_NORTH");
+        assert_eq!(*secondary_seen.lock().unwrap(), vec!["US-CAL-CISO"]);
+        assert_eq!(
+            client.preferred_provider("CAISO_NORTH").await.as_deref(),
+            Some("electricitymaps")
+        );
+
+        // The working provider is asked first from now on
+        client.get_carbon_intensity(&region).await.unwrap();
+        assert_eq!(primary_seen.lock().unwrap().len(), 1);
+        assert_eq!(secondary_seen.lock().unwrap().len(), 2);
+        assert_eq!(client.preferred_provider("DE").await, None);
+    }
+
+    #[tokio::test]
+    async fn test_last_provider_used_records_fallback() {
+        let (primary, _) = ScriptedClient::new(None);
+        let (secondary, _) = ScriptedClient::new(Some(95.0));
+        let client = FallbackEnergyClient::new()
+            .with_provider("primary", primary)
+            .with_provider("secondary", secondary);
+        assert_eq!(client.last_provider_used().await, None);
+
+        let reading = client
+            .get_carbon_intensity(&Region::new("DE", "Germany"))
+            .await
+            .unwrap();
+        assert_eq!(reading.value, 95.0);
+        assert_eq!(
+            client.last_provider_used().await.as_deref(),
+            Some("secondary")
+        );
+
+        client.get_region_for_location(52.5, 13.4).await.unwrap();
+        assert_eq!(
+            client.last_provider_used().await.as_deref(),
+            Some("primary")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_provider_is_skipped_during_cooldown() {
+        let clock = MockClock::new();
+        let (limited, limited_seen) = ScriptedClient::rate_limited();
+        let (backup, backup_seen) = ScriptedClient::new(Some(120.0));
+        let client = FallbackEnergyClient::new()
+            .with_provider("limited", limited)
+            .with_provider("backup", backup)
+            .with_clock(clock.shared());
+
+        // Different regions, so the remembered provider doesn't change the order
+        for id in ["DE", "FR", "PL"] {
+            let reading = client
+                .get_carbon_intensity(&Region::new(id, id))
+                .await
+                .unwrap();
+            assert_eq!(reading.value, 120.0);
+        }
+        assert_eq!(limited_seen.lock().unwrap().len(), 1);
+        assert_eq!(backup_seen.lock().unwrap().len(), 3);
+
+        clock.advance(Duration::from_secs(61));
+        client
+            .get_carbon_intensity(&Region::new("ES", "Spain"))
+            .await
+            .unwrap();
+        assert_eq!(limited_seen.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_all_providers_cooling_down_reports_rate_limit() {
+        let clock = MockClock::new();
+        let (limited, limited_seen) = ScriptedClient::rate_limited();
+        let client = FallbackEnergyClient::new()
+            .with_provider("limited", limited)
+            .with_clock(clock.shared());
+        let region = Region::new("DE", "Germany");
+
+        assert!(client.get_carbon_intensity(&region).await.is_err());
+        clock.advance(Duration::from_secs(20));
+        let err = client.get_carbon_intensity(&region).await.unwrap_err();
+        assert!(matches!(
+            err,
+            EnergyApiError::RateLimitExceeded {
+                retry_after_seconds: 40
+            }
+        ));
+        assert_eq!(limited_seen.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_location_results_use_caller_region_ids() {
+        let (secondary, _) = ScriptedClient::new(Some(180.0));
+        let client = FallbackEnergyClient::new().with_mapped_provider(
+            "electricitymaps",
+            secondary,
+            &[("CAISO_NORTH", "US-CAL-CISO")],
+        );
+
+        let region = client.get_region_for_location(38.5, -121.5).await.unwrap();
+        assert_eq!(region.id, "CAISO_NORTH");
+        let reading = client
+            .get_carbon_intensity_by_location(38.5, -121.5)
+            .await
+            .unwrap();
+        assert_eq!(reading.region.id, "CAISO_NORTH");
+    }
+
+    #[tokio::test]
+    async fn test_errors_when_every_provider_fails() {
+        let (primary, _) = ScriptedClient::new(None);
+        let (secondary, secondary_seen) = ScriptedClient::new(Some(180.0));
+        let client = FallbackEnergyClient::new()
+            .with_provider("primary", primary)
+            .with_provider("secondary", secondary);
+
+        // Invalid coordinates are not retried elsewhere
+        let err = client
+            .get_carbon_intensity_by_location(120.0, 0.0)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, EnergyApiError::InvalidCoordinates { .. }));
+        assert!(secondary_seen.lock().unwrap().is_empty());
+
+        let (only, _) = ScriptedClient::new(None);
+        let client = FallbackEnergyClient::new().with_provider("only", only);
+        let err = client
+            .get_carbon_intensity(&Region::new("DE", "Germany"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, EnergyApiError::ApiError { .. }));
+
+        let err = FallbackEnergyClient::new()
+            .get_carbon_forecast(&Region::new("DE", "Germany"), 24)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, EnergyApiError::ConfigError(_)));
+    }
+}
@@ -1,12 +1,27 @@
 //! Aegis Energy - Carbon-Aware Energy API Integration
 //!
-//! This crate provides integration with energy grid APIs (WattTime, Electricity Maps)
-//! for carbon-aware traffic routing in Aegis-Flow.
+//! This crate provides integration with energy grid APIs (WattTime, Electricity Maps,
+//! the UK Carbon Intensity API) for carbon-aware traffic routing in Aegis-Flow.
 
 mod cache;
 mod client;
+mod dyn_client;
+mod fallback;
+mod secrets;
 mod types;
 
-pub use cache::CarbonIntensityCache;
-pub use client::{ElectricityMapsClient, EnergyApiClient, WattTimeClient};
-pub use types::{CarbonIntensity, EnergyApiError, EnergyApiProvider, Region, ForecastPoint};
+pub use cache::{CacheStats, CachedIntensity, CarbonIntensityCache};
+pub use client::{
+    CarbonIntensityOrgClient, ElectricityMapsClient, EnergyApiClient, FileBackedClient,
+    RetryingClient, WattTimeClient,
+};
+pub use dyn_client::{BoxFuture, DynEnergyApiClient};
+pub use fallback::FallbackEnergyClient;
+pub use secrets::{
+    EnvSecretsProvider, FileSecretsProvider, HttpSecretsProvider, SecretsProvider,
+    SharedSecretsProvider,
+};
+pub use types::{
+    CarbonIntensity, EnergyApiError, EnergyApiProvider, ForecastPoint, JOULES_PER_KWH, Region,
+    WattTimeSignalType, grams_for_joules, nearest_region,
+};
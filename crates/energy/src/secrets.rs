@@ -0,0 +1,326 @@
+//! Credential resolution for energy API clients
+//!
+//! API keys and logins can come from a [`SecretsProvider`] instead of being
+//! passed in as plain strings. Clients look the secret up on first use, keep
+//! it for a configurable TTL and fetch it again afterwards, so a rotated key
+//! is picked up without restarting the process.
+
+use crate::dyn_client::BoxFuture;
+use crate::types::EnergyApiError;
+use aegis_common::Clock;
+use reqwest::Client;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+/// Source of named secrets
+pub trait SecretsProvider: Send + Sync {
+    /// Look up the current value of a secret
+    fn get_secret<'a>(&'a self, name: &'a str) -> BoxFuture<'a, Result<String, EnergyApiError>>;
+}
+
+/// Secrets provider shared between clients
+pub type SharedSecretsProvider = Arc<dyn SecretsProvider>;
+
+/// Reads secrets from environment variables
+#[derive(Debug, Clone, Default)]
+pub struct EnvSecretsProvider {
+    prefix: String,
+}
+
+impl EnvSecretsProvider {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Prepend `prefix` to every variable name, e.g. `AEGIS_`
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+}
+
+impl SecretsProvider for EnvSecretsProvider {
+    fn get_secret<'a>(&'a self, name: &'a str) -> BoxFuture<'a, Result<String, EnergyApiError>> {
+        Box::pin(async move {
+            let var = format!("{}{}", self.prefix, name);
+            std::env::var(&var).map_err(|_| {
+                EnergyApiError::ConfigError(format!("Secret not set in environment: {var}"))
+            })
+        })
+    }
+}
+
+/// Reads each secret from a file named after it, as with mounted Docker or
+/// Kubernetes secrets
+///
+/// Trailing whitespace, including the final newline, is stripped.
+#[derive(Debug, Clone)]
+pub struct FileSecretsProvider {
+    dir: PathBuf,
+}
+
+impl FileSecretsProvider {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+impl SecretsProvider for FileSecretsProvider {
+    fn get_secret<'a>(&'a self, name: &'a str) -> BoxFuture<'a, Result<String, EnergyApiError>> {
+        Box::pin(async move {
+            let path = self.dir.join(name);
+            let value = tokio::fs::read_to_string(&path).await.map_err(|e| {
+                EnergyApiError::ConfigError(format!(
+                    "Failed to read secret {}: {e}",
+                    path.display()
+                ))
+            })?;
+            Ok(value.trim_end().to_string())
+        })
+    }
+}
+
+/// Fetches secrets from an HTTP secret store such as Vault
+///
+/// Each secret is read with `GET {base_url}/{name}`. JSON responses are
+/// searched for a `value` field, either at the top level, under `data`
+/// (Vault KV v1) or under `data.data` (Vault KV v2); any other body is used
+/// verbatim.
+pub struct HttpSecretsProvider {
+    client: Client,
+    base_url: String,
+    token: Option<String>,
+}
+
+impl HttpSecretsProvider {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            client: Client::new(),
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            token: None,
+        }
+    }
+
+    /// Authenticate to the store with a token sent as `X-Vault-Token` and
+    /// as a bearer token
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    fn extract_value(body: &str) -> Option<String> {
+        let Ok(json) = serde_json::from_str::<serde_json::Value>(body) else {
+            return Some(body.trim_end().to_string());
+        };
+        ["/data/data/value", "/data/value", "/value"]
+            .iter()
+            .find_map(|pointer| json.pointer(pointer))
+            .and_then(|value| value.as_str())
+            .map(str::to_string)
+    }
+}
+
+impl SecretsProvider for HttpSecretsProvider {
+    fn get_secret<'a>(&'a self, name: &'a str) -> BoxFuture<'a, Result<String, EnergyApiError>> {
+        Box::pin(async move {
+            let mut request = self.client.get(format!("{}/{}", self.base_url, name));
+            if let Some(token) = &self.token {
+                request = request.header("X-Vault-Token", token).bearer_auth(token);
+            }
+            let response = request.send().await?;
+
+            match response.status() {
+                reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN => {
+                    return Err(EnergyApiError::AuthenticationError);
+                }
+                reqwest::StatusCode::NOT_FOUND => {
+                    return Err(EnergyApiError::ConfigError(format!(
+                        "Secret not found: {name}"
+                    )));
+                }
+                status if !status.is_success() => {
+                    return Err(EnergyApiError::ApiError {
+                        message: format!("Secret store returned {status}"),
+                    });
+                }
+                _ => {}
+            }
+
+            let body = response.text().await?;
+            Self::extract_value(&body).ok_or_else(|| {
+                EnergyApiError::ParseError(format!("Missing value for secret {name}"))
+            })
+        })
+    }
+}
+
+/// Secret value kept until its TTL runs out
+pub(crate) struct CachedValue {
+    value: String,
+    fetched_at: Instant,
+}
+
+/// A credential held by a client: either fixed at construction or looked up
+/// through a [`SecretsProvider`]
+pub(crate) enum Credential {
+    Static(String),
+    Provided {
+        provider: SharedSecretsProvider,
+        name: String,
+        cached: tokio::sync::RwLock<Option<CachedValue>>,
+    },
+}
+
+impl Credential {
+    /// How long a fetched secret is used before it is fetched again
+    pub(crate) const DEFAULT_TTL: Duration = Duration::from_secs(5 * 60);
+
+    pub(crate) fn provided(provider: SharedSecretsProvider, name: impl Into<String>) -> Self {
+        Self::Provided {
+            provider,
+            name: name.into(),
+            cached: tokio::sync::RwLock::new(None),
+        }
+    }
+
+    /// Current value, fetched from the provider if the cached copy is older
+    /// than `ttl`
+    pub(crate) async fn resolve(
+        &self,
+        ttl: Duration,
+        clock: &dyn Clock,
+    ) -> Result<String, EnergyApiError> {
+        let (provider, name, cached) = match self {
+            Self::Static(value) => return Ok(value.clone()),
+            Self::Provided {
+                provider,
+                name,
+                cached,
+            } => (provider, name, cached),
+        };
+        let fresh = |entry: &Option<CachedValue>| {
+            entry
+                .as_ref()
+                .filter(|c| clock.instant() < c.fetched_at + ttl)
+                .map(|c| c.value.clone())
+        };
+
+        if let Some(value) = fresh(&*cached.read().await) {
+            return Ok(value);
+        }
+        let mut guard = cached.write().await;
+        if let Some(value) = fresh(&guard) {
+            return Ok(value);
+        }
+
+        debug!(secret = %name, "Fetching credential from secrets provider");
+        let fetched_at = clock.instant();
+        let value = provider.get_secret(name).await?;
+        *guard = Some(CachedValue {
+            value: value.clone(),
+            fetched_at,
+        });
+        Ok(value)
+    }
+
+    /// Drop the cached value so the next use fetches it again, e.g. after
+    /// the API rejected it
+    pub(crate) async fn invalidate(&self) {
+        if let Self::Provided { cached, .. } = self {
+            *cached.write().await = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aegis_common::MockClock;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_env_provider_with_prefix() {
+        // SAFETY: the variable name is unique to this test
+        unsafe { std::env::set_var("AEGIS_TEST_SECRETS_API_KEY", "from-env") };
+        let provider = EnvSecretsProvider::new().with_prefix("AEGIS_TEST_SECRETS_");
+        assert_eq!(provider.get_secret("API_KEY").await.unwrap(), "from-env");
+        assert!(matches!(
+            provider.get_secret("MISSING").await,
+            Err(EnergyApiError::ConfigError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_file_provider_strips_trailing_newline() {
+        let dir = std::env::temp_dir().join(format!("aegis-secrets-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("api_key"), "from-file\n").unwrap();
+
+        let provider = FileSecretsProvider::new(&dir);
+        assert_eq!(provider.get_secret("api_key").await.unwrap(), "from-file");
+        assert!(provider.get_secret("missing").await.is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_http_provider_reads_vault_kv2() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/secret/data/watttime"))
+            .and(header("X-Vault-Token", "root"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": { "data": { "value": "from-vault" } }
+            })))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/secret/data/plain"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("plain-text\n"))
+            .mount(&mock_server)
+            .await;
+
+        let provider = HttpSecretsProvider::new(format!("{}/v1/secret/data/", mock_server.uri()))
+            .with_token("root");
+        assert_eq!(provider.get_secret("watttime").await.unwrap(), "from-vault");
+        assert_eq!(provider.get_secret("plain").await.unwrap(), "plain-text");
+        assert!(matches!(
+            provider.get_secret("missing").await,
+            Err(EnergyApiError::ConfigError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_http_provider_rejects_json_without_value() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": { "other": "x" }
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let provider = HttpSecretsProvider::new(mock_server.uri());
+        assert!(matches!(
+            provider.get_secret("key").await,
+            Err(EnergyApiError::ParseError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_static_credential_never_expires() {
+        let clock = MockClock::new();
+        let credential = Credential::Static("fixed".to_string());
+        clock.advance(Duration::from_secs(3600));
+        assert_eq!(
+            credential
+                .resolve(Duration::from_secs(1), &clock)
+                .await
+                .unwrap(),
+            "fixed"
+        );
+    }
+}
@@ -1,6 +1,7 @@
 //! Types for carbon intensity and energy API responses
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use thiserror::Error;
 
 /// Energy API provider selection
@@ -10,6 +11,35 @@ pub enum EnergyApiProvider {
     #[default]
     WattTime,
     ElectricityMaps,
+    /// UK National Grid ESO Carbon Intensity API (no API key needed)
+    CarbonIntensityOrg,
+}
+
+/// Which WattTime emissions signal to report
+///
+/// The two answer different questions. Marginal emissions (MOER) are what
+/// the grid emits for one more kWh right now, so they tell you when and where
+/// shifting load actually cuts emissions; use them for routing and
+/// scheduling. Average emissions (AOER) spread the grid's total emissions
+/// over all load, which is what carbon accounting and reporting expect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum WattTimeSignalType {
+    /// Marginal operating emissions rate (`co2_moer`)
+    #[default]
+    Marginal,
+    /// Average operating emissions rate (`co2_aoer`)
+    Average,
+}
+
+impl WattTimeSignalType {
+    /// Value of WattTime's `signal_type` query parameter
+    pub fn as_query(&self) -> &'static str {
+        match self {
+            Self::Marginal => "co2_moer",
+            Self::Average => "co2_aoer",
+        }
+    }
 }
 
 /// Represents a geographic region for carbon intensity lookup
@@ -35,11 +65,69 @@ impl Region {
         }
     }
 
-    pub fn with_coordinates(mut self, lat: f64, lon: f64) -> Self {
+    /// Attach coordinates, rejecting values outside lat [-90, 90] / lon [-180, 180]
+    pub fn with_coordinates(mut self, lat: f64, lon: f64) -> Result<Self, EnergyApiError> {
+        Self::validate_coordinates(lat, lon)?;
         self.latitude = Some(lat);
         self.longitude = Some(lon);
-        self
+        Ok(self)
+    }
+
+    /// Check that a latitude/longitude pair is on the globe
+    pub fn validate_coordinates(lat: f64, lon: f64) -> Result<(), EnergyApiError> {
+        if (-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon) {
+            Ok(())
+        } else {
+            Err(EnergyApiError::InvalidCoordinates {
+                latitude: lat,
+                longitude: lon,
+            })
+        }
     }
+
+    /// Great-circle (haversine) distance to `other`, or `None` if either
+    /// region has no coordinates
+    pub fn distance_km(&self, other: &Region) -> Option<f64> {
+        let (lat, lon) = other.latitude.zip(other.longitude)?;
+        self.distance_to_km(lat, lon)
+    }
+
+    /// Haversine distance to a coordinate pair, `None` without own coordinates
+    pub(crate) fn distance_to_km(&self, lat: f64, lon: f64) -> Option<f64> {
+        let (own_lat, own_lon) = self.latitude.zip(self.longitude)?;
+        Some(haversine_km(own_lat, own_lon, lat, lon))
+    }
+}
+
+/// Mean Earth radius used for great-circle distances
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+/// Great-circle distance in kilometres between two coordinate pairs
+fn haversine_km(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let (lat1, lat2) = (lat1.to_radians(), lat2.to_radians());
+    let d_lat = lat2 - lat1;
+    let d_lon = (lon2 - lon1).to_radians();
+    let a = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+}
+
+/// The region in `regions` closest to the target coordinates
+///
+/// Regions without coordinates are skipped; returns `None` if none have any.
+pub fn nearest_region(target_lat: f64, target_lon: f64, regions: &[Region]) -> Option<&Region> {
+    regions
+        .iter()
+        .filter_map(|region| Some((region.distance_to_km(target_lat, target_lon)?, region)))
+        .min_by(|(a, _), (b, _)| a.total_cmp(b))
+        .map(|(_, region)| region)
+}
+
+/// Joules in one kilowatt-hour
+pub const JOULES_PER_KWH: f64 = 3_600_000.0;
+
+/// Grams of CO2eq emitted by `joules` of energy at `intensity_g_per_kwh`
+pub fn grams_for_joules(joules: f64, intensity_g_per_kwh: f64) -> f64 {
+    joules / JOULES_PER_KWH * intensity_g_per_kwh
 }
 
 /// Carbon intensity measurement
@@ -55,16 +143,31 @@ pub struct CarbonIntensity {
     pub valid_for_seconds: u64,
     /// Relative rating (if available): "very_low", "low", "medium", "high", "very_high"
     pub rating: Option<String>,
+    /// Share of generation from renewable sources, 0-100 (if the provider reports a power breakdown)
+    #[serde(default)]
+    pub renewable_percentage: Option<f64>,
+    /// Share of generation per source (e.g. "wind", "solar", "gas"), 0-100
+    #[serde(default)]
+    pub power_breakdown: Option<HashMap<String, f64>>,
 }
 
 impl CarbonIntensity {
     /// Check if this measurement is still valid
     pub fn is_valid(&self) -> bool {
-        let now = chrono::Utc::now();
+        self.is_valid_at(chrono::Utc::now())
+    }
+
+    /// Check if this measurement is valid at the given time
+    pub fn is_valid_at(&self, now: chrono::DateTime<chrono::Utc>) -> bool {
         let valid_until = self.timestamp + chrono::Duration::seconds(self.valid_for_seconds as i64);
         now < valid_until
     }
 
+    /// Grams of CO2eq emitted by `joules` of energy at this intensity
+    pub fn grams_for_joules(&self, joules: f64) -> f64 {
+        grams_for_joules(joules, self.value)
+    }
+
     /// Get a normalized score (0.0 = cleanest, 1.0 = dirtiest)
     /// Based on typical ranges: 0-50 very low, 50-150 low, 150-300 medium, 300-500 high, 500+ very high
     pub fn normalized_score(&self) -> f64 {
@@ -102,6 +205,10 @@ pub enum EnergyApiError {
     #[error("Region not found: {region_id}")]
     RegionNotFound { region_id: String },
 
+    /// The API answered but carried no emissions value for the region
+    #[error("No carbon intensity data for region: {region_id}")]
+    NoData { region_id: String },
+
     #[error("API response parsing error: {0}")]
     ParseError(String),
 
@@ -110,6 +217,27 @@ pub enum EnergyApiError {
 
     #[error("Configuration error: {0}")]
     ConfigError(String),
+
+    #[error(
+        "Invalid coordinates ({latitude}, {longitude}): latitude must be in [-90, 90], longitude in [-180, 180]"
+    )]
+    InvalidCoordinates { latitude: f64, longitude: f64 },
+}
+
+impl EnergyApiError {
+    /// Whether repeating the same request later may succeed
+    ///
+    /// Bad credentials, unknown regions and invalid input fail the same way
+    /// every time.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            EnergyApiError::HttpError(_)
+                | EnergyApiError::MiddlewareError(_)
+                | EnergyApiError::RateLimitExceeded { .. }
+                | EnergyApiError::ApiError { .. }
+        )
+    }
 }
 
 impl From<reqwest_middleware::Error> for EnergyApiError {
@@ -135,6 +263,8 @@ pub struct WattTimeIndexResponse {
     pub ba: String,
     pub percent: Option<f64>,
     pub moer: Option<f64>,
+    /// Average emissions, present when `co2_aoer` was requested
+    pub aoer: Option<f64>,
     pub point_time: String,
 }
 
@@ -176,19 +306,146 @@ pub struct ElectricityMapsForecastResponse {
     pub forecast: Vec<ElectricityMapsForecastData>,
 }
 
+/// Electricity Maps `power-breakdown/latest` response; production is in MW
+/// and sources without data are `null`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ElectricityMapsPowerBreakdownResponse {
+    #[serde(default)]
+    pub power_production_breakdown: HashMap<String, Option<f64>>,
+    #[serde(default)]
+    pub renewable_percentage: Option<f64>,
+}
+
+/// Carbon Intensity API (carbonintensity.org.uk) values for one
+/// half-hour period, in gCO2/kWh
+///
+/// Regional responses only carry a forecast; `actual` is national only.
+#[derive(Debug, Deserialize)]
+pub struct CarbonIntensityOrgIntensity {
+    pub forecast: Option<f64>,
+    #[serde(default)]
+    pub actual: Option<f64>,
+    /// "very low", "low", "moderate", "high" or "very high"
+    pub index: Option<String>,
+}
+
+/// Share of generation from one fuel type
+#[derive(Debug, Deserialize)]
+pub struct CarbonIntensityOrgFuel {
+    pub fuel: String,
+    pub perc: f64,
+}
+
+/// One half-hour settlement period; times look like `2018-01-20T12:00Z`
+#[derive(Debug, Deserialize)]
+pub struct CarbonIntensityOrgPeriod {
+    pub from: String,
+    pub to: String,
+    pub intensity: CarbonIntensityOrgIntensity,
+    #[serde(default)]
+    pub generationmix: Vec<CarbonIntensityOrgFuel>,
+}
+
+/// Carbon Intensity API national response (`/intensity`, `/intensity/{from}/fw48h`)
+#[derive(Debug, Deserialize)]
+pub struct CarbonIntensityOrgNationalResponse {
+    pub data: Vec<CarbonIntensityOrgPeriod>,
+}
+
+/// A DNO (distribution network operator) region and its periods
+#[derive(Debug, Deserialize)]
+pub struct CarbonIntensityOrgRegionData {
+    pub regionid: u32,
+    pub shortname: String,
+    pub data: Vec<CarbonIntensityOrgPeriod>,
+}
+
+/// Carbon Intensity API regional response (`/regional/regionid/{id}`,
+/// `/regional/postcode/{postcode}`)
+#[derive(Debug, Deserialize)]
+pub struct CarbonIntensityOrgRegionalResponse {
+    pub data: Vec<CarbonIntensityOrgRegionData>,
+}
+
+/// Carbon Intensity API regional forecast response
+/// (`/regional/intensity/{from}/fw48h/regionid/{id}`)
+#[derive(Debug, Deserialize)]
+pub struct CarbonIntensityOrgRegionalForecastResponse {
+    pub data: CarbonIntensityOrgRegionData,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn city(name: &str, lat: f64, lon: f64) -> Region {
+        Region::new(name, name).with_coordinates(lat, lon).unwrap()
+    }
+
+    #[test]
+    fn test_region_distance_km() {
+        let london = city("london", 51.5074, -0.1278);
+        let paris = city("paris", 48.8566, 2.3522);
+        let new_york = city("new_york", 40.7128, -74.0060);
+        let sydney = city("sydney", -33.8688, 151.2093);
+
+        let within = |d: Option<f64>, km: f64| (d.unwrap() - km).abs() < km * 0.01;
+        assert!(within(london.distance_km(&paris), 344.0));
+        assert!(within(london.distance_km(&new_york), 5570.0));
+        assert!(within(new_york.distance_km(&sydney), 15_990.0));
+        assert_eq!(london.distance_km(&paris), paris.distance_km(&london));
+        assert_eq!(london.distance_km(&london), Some(0.0));
+        assert_eq!(london.distance_km(&Region::new("X", "Unknown")), None);
+        assert_eq!(Region::new("X", "Unknown").distance_km(&london), None);
+    }
+
+    #[test]
+    fn test_nearest_region() {
+        let regions = [
+            Region::new("NONE", "No coordinates"),
+            city("DE", 52.52, 13.405),
+            city("FR", 48.8566, 2.3522),
+            city("US-NY", 40.7128, -74.0060),
+        ];
+
+        // Brussels is closer to Paris than Berlin
+        assert_eq!(nearest_region(50.8503, 4.3517, &regions).unwrap().id, "FR");
+        // Boston
+        assert_eq!(nearest_region(42.3601, -71.0589, &regions).unwrap().id, "US-NY");
+        assert!(nearest_region(0.0, 0.0, &regions[..1]).is_none());
+        assert!(nearest_region(0.0, 0.0, &[]).is_none());
+    }
+
     #[test]
     fn test_region_creation() {
         let region = Region::new("CAISO_NORTH", "California ISO - North")
-            .with_coordinates(37.7749, -122.4194);
+            .with_coordinates(37.7749, -122.4194)
+            .unwrap();
 
         assert_eq!(region.id, "CAISO_NORTH");
         assert_eq!(region.latitude, Some(37.7749));
     }
 
+    #[test]
+    fn test_region_coordinates_validation() {
+        for (lat, lon) in [(90.0, 180.0), (-90.0, -180.0), (0.0, 0.0)] {
+            assert!(Region::new("R", "R").with_coordinates(lat, lon).is_ok());
+        }
+
+        for (lat, lon) in [
+            (500.0, 0.0),
+            (-90.1, 0.0),
+            (0.0, 180.5),
+            (0.0, -181.0),
+            (f64::NAN, 0.0),
+            (0.0, f64::INFINITY),
+        ] {
+            let err = Region::new("R", "R").with_coordinates(lat, lon).unwrap_err();
+            assert!(matches!(err, EnergyApiError::InvalidCoordinates { .. }));
+        }
+    }
+
     #[test]
     fn test_carbon_intensity_normalized_score() {
         let region = Region::new("TEST", "Test Region");
@@ -199,6 +456,8 @@ mod tests {
             timestamp: chrono::Utc::now(),
             valid_for_seconds: 300,
             rating: Some("low".to_string()),
+            renewable_percentage: None,
+            power_breakdown: None,
         };
 
         let high = CarbonIntensity {
@@ -207,6 +466,8 @@ mod tests {
             timestamp: chrono::Utc::now(),
             valid_for_seconds: 300,
             rating: Some("high".to_string()),
+            renewable_percentage: None,
+            power_breakdown: None,
         };
 
         assert!(low.normalized_score() < 0.1);
@@ -223,6 +484,8 @@ mod tests {
             timestamp: chrono::Utc::now(),
             valid_for_seconds: 300,
             rating: None,
+            renewable_percentage: None,
+            power_breakdown: None,
         };
 
         let expired = CarbonIntensity {
@@ -231,10 +494,38 @@ mod tests {
             timestamp: chrono::Utc::now() - chrono::Duration::seconds(600),
             valid_for_seconds: 300,
             rating: None,
+            renewable_percentage: None,
+            power_breakdown: None,
         };
 
         assert!(valid.is_valid());
         assert!(!expired.is_valid());
+
+        let later = valid.timestamp + chrono::Duration::seconds(300);
+        assert!(valid.is_valid_at(later - chrono::Duration::seconds(1)));
+        assert!(!valid.is_valid_at(later));
+    }
+
+    #[test]
+    fn test_grams_for_joules() {
+        // 1 kWh at 400 gCO2/kWh
+        assert_eq!(grams_for_joules(3_600_000.0, 400.0), 400.0);
+        // 1800 J = 0.0005 kWh, at 250 gCO2/kWh = 0.125 g
+        assert!((grams_for_joules(1800.0, 250.0) - 0.125).abs() < 1e-12);
+        assert_eq!(grams_for_joules(0.0, 400.0), 0.0);
+        assert_eq!(grams_for_joules(1000.0, 0.0), 0.0);
+
+        let intensity = CarbonIntensity {
+            region: Region::new("TEST", "Test"),
+            value: 150.0,
+            timestamp: chrono::Utc::now(),
+            valid_for_seconds: 300,
+            rating: None,
+            renewable_percentage: None,
+            power_breakdown: None,
+        };
+        // 36 kJ = 0.01 kWh, at 150 gCO2/kWh = 1.5 g
+        assert!((intensity.grams_for_joules(36_000.0) - 1.5).abs() < 1e-12);
     }
 
     #[test]
@@ -270,6 +561,14 @@ mod tests {
         assert_eq!(provider, EnergyApiProvider::WattTime);
     }
 
+    #[test]
+    fn test_watttime_signal_type_serde() {
+        assert_eq!(WattTimeSignalType::default(), WattTimeSignalType::Marginal);
+        let average: WattTimeSignalType = serde_json::from_str("\"average\"").unwrap();
+        assert_eq!(average, WattTimeSignalType::Average);
+        assert_eq!(average.as_query(), "co2_aoer");
+    }
+
     #[test]
     fn test_carbon_intensity_clamping() {
         let region = Region::new("TEST", "Test");
@@ -279,6 +578,8 @@ mod tests {
             timestamp: chrono::Utc::now(),
             valid_for_seconds: 300,
             rating: None,
+            renewable_percentage: None,
+            power_breakdown: None,
         };
         // Should be clamped to 1.0
         assert_eq!(very_high.normalized_score(), 1.0);
@@ -307,6 +608,8 @@ mod tests {
             timestamp: chrono::Utc::now(),
             valid_for_seconds: 300,
             rating: Some("low".to_string()),
+            renewable_percentage: None,
+            power_breakdown: None,
         };
         let json = serde_json::to_string(&intensity).unwrap();
         let parsed: CarbonIntensity = serde_json::from_str(&json).unwrap();
@@ -2,13 +2,43 @@
 //!
 //! Arrow-backed alignment records for BAM data.
 
-use crate::Result;
 use crate::schema::GenomicSchema;
+use crate::{GenomicsError, Result};
 use arrow_array::{ArrayRef, Int64Array, RecordBatch, StringArray, UInt8Array, UInt16Array};
+use noodles::sam;
+use noodles::sam::alignment::io::Write as _;
 use std::sync::Arc;
 
+/// Standard SAM flag bits
+pub mod flags {
+    /// Template has multiple segments in sequencing
+    pub const PAIRED: u16 = 0x1;
+    /// Each segment properly aligned according to the aligner
+    pub const PROPER_PAIR: u16 = 0x2;
+    /// Segment unmapped
+    pub const UNMAPPED: u16 = 0x4;
+    /// Next segment in the template unmapped
+    pub const MATE_UNMAPPED: u16 = 0x8;
+    /// Sequence is reverse complemented
+    pub const REVERSE: u16 = 0x10;
+    /// Sequence of the next segment is reverse complemented
+    pub const MATE_REVERSE: u16 = 0x20;
+    /// First segment in the template
+    pub const READ1: u16 = 0x40;
+    /// Last segment in the template
+    pub const READ2: u16 = 0x80;
+    /// Secondary alignment
+    pub const SECONDARY: u16 = 0x100;
+    /// Not passing quality controls
+    pub const QC_FAIL: u16 = 0x200;
+    /// PCR or optical duplicate
+    pub const DUPLICATE: u16 = 0x400;
+    /// Supplementary alignment
+    pub const SUPPLEMENTARY: u16 = 0x800;
+}
+
 /// A single BAM alignment record
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct AlignmentRecord {
     /// Query name
     pub qname: String,
@@ -52,6 +82,64 @@ impl AlignmentRecord {
         }
     }
 
+    /// Parse the 11 mandatory fields of a SAM text line
+    ///
+    /// `*` marks absent names, CIGAR, sequence and qualities; an `=` mate
+    /// reference is resolved to the read's own reference.
+    pub fn from_sam_line(line: &str) -> Result<Self> {
+        let fields: Vec<&str> = line.trim_end_matches(['\r', '\n']).split('\t').collect();
+        if fields.len() < 11 {
+            return Err(GenomicsError::InvalidFormat(format!(
+                "SAM line has {} fields, expected >= 11",
+                fields.len()
+            )));
+        }
+
+        fn parse<T: std::str::FromStr>(value: &str, field: &str) -> Result<T> {
+            value
+                .parse()
+                .map_err(|_| GenomicsError::ParseError(format!("Invalid {}: {}", field, value)))
+        }
+        fn optional(value: &str) -> Option<String> {
+            (value != "*").then(|| value.to_string())
+        }
+
+        let rname = optional(fields[2]);
+        let rnext = match fields[6] {
+            "=" => rname.clone(),
+            other => optional(other),
+        };
+
+        Ok(Self {
+            qname: fields[0].to_string(),
+            flag: parse(fields[1], "FLAG")?,
+            rname,
+            pos: parse(fields[3], "POS")?,
+            mapq: parse(fields[4], "MAPQ")?,
+            cigar: optional(fields[5]),
+            rnext,
+            pnext: parse(fields[7], "PNEXT")?,
+            tlen: parse(fields[8], "TLEN")?,
+            seq: optional(fields[9]).unwrap_or_default(),
+            qual: optional(fields[10]).unwrap_or_default(),
+        })
+    }
+
+    /// Convert a decoded noodles record (BAM, CRAM or SAM)
+    ///
+    /// Renders the record through the SAM text writer so every format maps
+    /// to the same field representation.
+    pub fn from_sam_record(
+        header: &sam::Header,
+        record: &dyn sam::alignment::Record,
+    ) -> Result<Self> {
+        let mut writer = sam::io::Writer::new(Vec::new());
+        writer.write_alignment_record(header, record)?;
+        let line = String::from_utf8(writer.into_inner())
+            .map_err(|e| GenomicsError::ParseError(format!("Non UTF-8 SAM record: {}", e)))?;
+        Self::from_sam_line(&line)
+    }
+
     /// Set reference name
     pub fn with_rname(mut self, rname: &str) -> Self {
         self.rname = Some(rname.to_string());
@@ -70,14 +158,49 @@ impl AlignmentRecord {
         self
     }
 
+    /// Check whether all bits of `mask` are set in the flag
+    pub fn has_flags(&self, mask: u16) -> bool {
+        (self.flag & mask) == mask
+    }
+
     /// Check if read is mapped
     pub fn is_mapped(&self) -> bool {
-        (self.flag & 0x4) == 0
+        !self.is_unmapped()
+    }
+
+    /// Check if read is unmapped
+    pub fn is_unmapped(&self) -> bool {
+        self.has_flags(flags::UNMAPPED)
+    }
+
+    /// Check if read is part of a pair
+    pub fn is_paired(&self) -> bool {
+        self.has_flags(flags::PAIRED)
+    }
+
+    /// Check if read is in a properly aligned pair
+    pub fn is_proper_pair(&self) -> bool {
+        self.has_flags(flags::PROPER_PAIR)
     }
 
     /// Check if read is reverse strand
     pub fn is_reverse(&self) -> bool {
-        (self.flag & 0x10) != 0
+        self.has_flags(flags::REVERSE)
+    }
+
+    /// Check if this is a secondary alignment
+    pub fn is_secondary(&self) -> bool {
+        self.has_flags(flags::SECONDARY)
+    }
+
+    /// Check if this is a supplementary alignment
+    pub fn is_supplementary(&self) -> bool {
+        self.has_flags(flags::SUPPLEMENTARY)
+    }
+
+    /// Check if read is a PCR or optical duplicate
+    pub fn is_duplicate(&self) -> bool {
+        self.has_flags(flags::DUPLICATE)
     }
 }
 
@@ -205,6 +328,70 @@ mod tests {
         assert!(record.is_mapped());
     }
 
+    #[test]
+    fn test_flag_accessors() {
+        // 99 = paired, proper pair, mate reverse, read1
+        let record = AlignmentRecord::new("pair1", 99, 100, "ACGT");
+        assert!(record.is_paired());
+        assert!(record.is_proper_pair());
+        assert!(record.is_mapped());
+        assert!(!record.is_unmapped());
+        assert!(!record.is_reverse());
+        assert!(!record.is_secondary());
+        assert!(!record.is_supplementary());
+        assert!(!record.is_duplicate());
+
+        // 1107 = paired, proper pair, reverse, read1, duplicate
+        let record = AlignmentRecord::new("dup", 1107, 100, "ACGT");
+        assert!(record.is_duplicate());
+        assert!(record.is_reverse());
+
+        let record = AlignmentRecord::new("sec", flags::SECONDARY | flags::UNMAPPED, 0, "A");
+        assert!(record.is_secondary());
+        assert!(record.is_unmapped());
+        assert!(!record.is_paired());
+
+        let record = AlignmentRecord::new("supp", 2048, 100, "A");
+        assert!(record.is_supplementary());
+        assert!(record.has_flags(flags::SUPPLEMENTARY));
+        assert!(!record.has_flags(flags::SUPPLEMENTARY | flags::PAIRED));
+    }
+
+    #[test]
+    fn test_from_sam_line() {
+        let line = "read1\t99\tchr1\t100\t60\t4M\t=\t300\t204\tACGT\tIIII";
+        let record = AlignmentRecord::from_sam_line(line).unwrap();
+        assert_eq!(record.qname, "read1");
+        assert_eq!(record.flag, 99);
+        assert_eq!(record.rname.as_deref(), Some("chr1"));
+        assert_eq!(record.pos, 100);
+        assert_eq!(record.mapq, 60);
+        assert_eq!(record.cigar.as_deref(), Some("4M"));
+        assert_eq!(record.rnext.as_deref(), Some("chr1"));
+        assert_eq!(record.pnext, 300);
+        assert_eq!(record.tlen, 204);
+        assert_eq!(record.seq, "ACGT");
+        assert_eq!(record.qual, "IIII");
+
+        let unmapped = AlignmentRecord::from_sam_line("u\t4\t*\t0\t255\t*\t*\t0\t0\t*\t*").unwrap();
+        assert!(unmapped.is_unmapped());
+        assert_eq!(unmapped.rname, None);
+        assert_eq!(unmapped.cigar, None);
+        assert!(unmapped.seq.is_empty());
+    }
+
+    #[test]
+    fn test_from_sam_line_invalid() {
+        assert!(matches!(
+            AlignmentRecord::from_sam_line("read1\t0\tchr1"),
+            Err(GenomicsError::InvalidFormat(_))
+        ));
+        assert!(matches!(
+            AlignmentRecord::from_sam_line("r\tx\tchr1\t1\t0\t*\t*\t0\t0\tA\tI"),
+            Err(GenomicsError::ParseError(_))
+        ));
+    }
+
     #[test]
     fn test_unmapped_read() {
         let record = AlignmentRecord::new("read2", 4, 0, "NNNN");
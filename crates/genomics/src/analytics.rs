@@ -2,12 +2,23 @@
 //!
 //! DataFrame analytics for genomic data using Polars.
 
+use crate::alignment::{AlignmentBatchBuilder, flags};
+use crate::coordinate::GenomicInterval;
 use crate::variant::VariantBatchBuilder;
 
 use arrow::ipc::writer::FileWriter;
+use arrow::record_batch::RecordBatch;
+use arrow_schema::{DataType as ArrowType, Schema as ArrowSchema};
 use polars::io::SerReader;
 use polars::prelude::*;
 use std::io::Cursor;
+use std::sync::Arc;
+
+/// Variant analytics shared across tasks or threads
+///
+/// All queries take `&self`, so wrap the analytics once and clone the `Arc`
+/// instead of the frame.
+pub type SharedVariantAnalytics = Arc<VariantAnalytics>;
 
 /// Variant analytics using Polars
 #[derive(Default)]
@@ -18,19 +29,89 @@ pub struct VariantAnalytics {
 impl VariantAnalytics {
     /// Create from VariantBatchBuilder
     pub fn from_builder(builder: &VariantBatchBuilder) -> crate::Result<Self> {
-        let batch = builder.build()?;
-
-        // Convert Arrow RecordBatch to Polars DataFrame via IPC to handle version mismatches
-        let mut buf = Vec::new();
-        {
-            let mut writer = FileWriter::try_new(&mut buf, &batch.schema())?;
-            writer.write(&batch)?;
-            writer.finish()?;
+        Self::from_record_batches(&[builder.build()?])
+    }
+
+    /// Create from variant record batches, e.g. read back from Arrow IPC
+    ///
+    /// Every batch must carry the columns the queries read, with the types of
+    /// [`GenomicSchema::variant`](crate::schema::GenomicSchema::variant);
+    /// anything else is rejected here rather than failing inside Polars later.
+    pub fn from_record_batches(batches: &[RecordBatch]) -> crate::Result<Self> {
+        let mut df = DataFrame::default();
+        for batch in batches {
+            validate_variant_schema(&batch.schema())?;
+            let next = batch_to_dataframe(batch)?;
+            if df.width() == 0 {
+                df = next;
+            } else {
+                df.vstack_mut(&next)?;
+            }
+        }
+        df.align_chunks_par();
+        Ok(Self { df })
+    }
+
+    /// Cheap copy that shares the underlying Arrow buffers
+    ///
+    /// Polars columns are reference counted, so only the column handles are
+    /// copied; the data itself is not.
+    pub fn clone_view(&self) -> Self {
+        Self {
+            df: self.df.clone(),
         }
+    }
 
-        let cursor = Cursor::new(buf);
-        let df = IpcReader::new(cursor).finish()?;
+    /// Wrap into a [`SharedVariantAnalytics`]
+    pub fn into_shared(self) -> SharedVariantAnalytics {
+        Arc::new(self)
+    }
 
+    /// Vertically concatenate this set with `others`
+    ///
+    /// Empty (default) sets are skipped; any other schema difference is an error.
+    pub fn concat(&self, others: &[VariantAnalytics]) -> crate::Result<Self> {
+        let mut df = self.df.clone();
+        for other in others {
+            if other.df.width() == 0 {
+                continue;
+            }
+            if df.width() == 0 {
+                df = other.df.clone();
+                continue;
+            }
+            if df.schema() != other.df.schema() {
+                return Err(crate::GenomicsError::InvalidFormat(format!(
+                    "Cannot concatenate variant sets with different schemas: {:?} vs {:?}",
+                    df.get_column_names(),
+                    other.df.get_column_names()
+                )));
+            }
+            df.vstack_mut(&other.df)?;
+        }
+        df.align_chunks_par();
+        Ok(Self { df })
+    }
+
+    /// Drop duplicate chrom/pos/ref/alt rows, keeping the highest-quality record
+    pub fn dedup_by_position(&self) -> crate::Result<Self> {
+        let df = self
+            .df
+            .clone()
+            .lazy()
+            .sort(
+                ["qual"],
+                SortMultipleOptions::default()
+                    .with_order_descending(true)
+                    .with_nulls_last(true)
+                    .with_maintain_order(true),
+            )
+            .unique_stable(
+                Some(cols(["chrom", "pos", "ref", "alt"])),
+                UniqueKeepStrategy::First,
+            )
+            .sort(["chrom", "pos"], SortMultipleOptions::default())
+            .collect()?;
         Ok(Self { df })
     }
 
@@ -73,15 +154,18 @@ impl VariantAnalytics {
     }
 
     /// Get variants in a region
-    pub fn filter_by_region(&self, chrom: &str, start: i64, end: i64) -> crate::Result<usize> {
+    ///
+    /// Variant positions are stored 1-based as in VCF; the interval carries
+    /// its own convention so BED-style ranges can be passed directly.
+    pub fn filter_by_region(&self, region: &GenomicInterval) -> crate::Result<usize> {
         let ctx = self.df.clone().lazy();
 
         let filtered = ctx
             .filter(
                 col("chrom")
-                    .eq(lit(chrom))
-                    .and(col("pos").gt_eq(lit(start)))
-                    .and(col("pos").lt_eq(lit(end))),
+                    .eq(lit(region.chrom.as_str()))
+                    .and(col("pos").gt_eq(lit(region.start.one_based())))
+                    .and(col("pos").lt_eq(lit(region.end.one_based()))),
             )
             .collect()?;
 
@@ -113,6 +197,72 @@ impl VariantAnalytics {
         Ok((snps, indels))
     }
 
+    /// Compute a [`VcfSummary`]
+    ///
+    /// Variant counts come from one grouped scan over the frame; quality
+    /// statistics are then read from the `qual` column.
+    pub fn summary(&self) -> crate::Result<VcfSummary> {
+        let is_snp = col("ref")
+            .str()
+            .len_bytes()
+            .eq(lit(1u32))
+            .and(col("alt").str().len_bytes().eq(lit(1u32)));
+        let is_transition = is_snp.clone().and(
+            (col("ref").eq(lit("A")).and(col("alt").eq(lit("G"))))
+                .or(col("ref").eq(lit("G")).and(col("alt").eq(lit("A"))))
+                .or(col("ref").eq(lit("C")).and(col("alt").eq(lit("T"))))
+                .or(col("ref").eq(lit("T")).and(col("alt").eq(lit("C")))),
+        );
+        let is_indel = col("ref")
+            .str()
+            .len_bytes()
+            .neq(col("alt").str().len_bytes());
+
+        let grouped = self
+            .df
+            .clone()
+            .lazy()
+            .group_by([col("chrom")])
+            .agg([
+                len().cast(DataType::UInt64).alias("count"),
+                is_snp.cast(DataType::UInt64).sum().alias("snps"),
+                is_transition
+                    .cast(DataType::UInt64)
+                    .sum()
+                    .alias("transitions"),
+                is_indel.cast(DataType::UInt64).sum().alias("indels"),
+            ])
+            .sort(["chrom"], SortMultipleOptions::default())
+            .collect()?;
+
+        let chroms = grouped.column("chrom")?.str()?;
+        let counts = grouped.column("count")?.u64()?;
+        let snps = grouped.column("snps")?.u64()?;
+        let transitions = grouped.column("transitions")?.u64()?;
+        let indels = grouped.column("indels")?.u64()?;
+
+        let mut summary = VcfSummary::default();
+        for i in 0..grouped.height() {
+            let count = counts.get(i).unwrap_or(0) as usize;
+            if let Some(chrom) = chroms.get(i) {
+                summary.per_chromosome.push((chrom.to_string(), count));
+            }
+            summary.total += count;
+            summary.snps += snps.get(i).unwrap_or(0) as usize;
+            summary.transitions += transitions.get(i).unwrap_or(0) as usize;
+            summary.indels += indels.get(i).unwrap_or(0) as usize;
+        }
+        summary.transversions = summary.snps - summary.transitions;
+        summary.ti_tv = (summary.transversions > 0)
+            .then(|| summary.transitions as f64 / summary.transversions as f64);
+
+        let qual = self.df.column("qual")?.f64()?;
+        summary.mean_quality = qual.mean();
+        summary.median_quality = qual.median();
+
+        Ok(summary)
+    }
+
     /// Get quality statistics
     pub fn quality_stats(&self) -> crate::Result<QualityStats> {
         let qual_col = self.df.column("qual")?.f64()?;
@@ -132,6 +282,226 @@ impl VariantAnalytics {
     }
 }
 
+/// Columns [`VariantAnalytics`] queries read, with their expected types
+const VARIANT_COLUMNS: [(&str, ArrowType); 5] = [
+    ("chrom", ArrowType::Utf8),
+    ("pos", ArrowType::Int64),
+    ("ref", ArrowType::Utf8),
+    ("alt", ArrowType::Utf8),
+    ("qual", ArrowType::Float64),
+];
+
+/// Check `schema` has every column in [`VARIANT_COLUMNS`] with the right type
+fn validate_variant_schema(schema: &ArrowSchema) -> crate::Result<()> {
+    let mut missing = Vec::new();
+    let mut mismatched = Vec::new();
+    for (name, expected) in &VARIANT_COLUMNS {
+        match schema.field_with_name(name) {
+            Ok(field) if field.data_type() == expected => {}
+            Ok(field) => mismatched.push(format!(
+                "{} (expected {}, found {})",
+                name,
+                expected,
+                field.data_type()
+            )),
+            Err(_) => missing.push(*name),
+        }
+    }
+    if missing.is_empty() && mismatched.is_empty() {
+        return Ok(());
+    }
+
+    let mut problems = Vec::new();
+    if !missing.is_empty() {
+        problems.push(format!("missing columns: {}", missing.join(", ")));
+    }
+    if !mismatched.is_empty() {
+        problems.push(format!("mismatched columns: {}", mismatched.join(", ")));
+    }
+    Err(crate::GenomicsError::InvalidFormat(format!(
+        "Variant batch schema is incompatible with analytics; {}",
+        problems.join("; ")
+    )))
+}
+
+/// Convert an Arrow RecordBatch to a Polars DataFrame
+///
+/// Goes through IPC to handle arrow version mismatches between the crates.
+fn batch_to_dataframe(batch: &RecordBatch) -> crate::Result<DataFrame> {
+    let mut buf = Vec::new();
+    {
+        let mut writer = FileWriter::try_new(&mut buf, &batch.schema())?;
+        writer.write(batch)?;
+        writer.finish()?;
+    }
+
+    let cursor = Cursor::new(buf);
+    Ok(IpcReader::new(cursor).finish()?)
+}
+
+/// Alignment analytics using Polars
+#[derive(Default)]
+pub struct AlignmentAnalytics {
+    df: DataFrame,
+}
+
+impl AlignmentAnalytics {
+    /// Create from AlignmentBatchBuilder
+    pub fn from_builder(builder: &AlignmentBatchBuilder) -> crate::Result<Self> {
+        let df = batch_to_dataframe(&builder.build()?)?;
+        Ok(Self { df })
+    }
+
+    /// Get total read count
+    pub fn count(&self) -> usize {
+        self.df.height()
+    }
+
+    /// Count reads with every bit of `required` set and none of `excluded`
+    ///
+    /// Mirrors `samtools view -c -f <required> -F <excluded>`; see
+    /// [`crate::alignment::flags`] for the bit values.
+    pub fn filter_flags(&self, required: u16, excluded: u16) -> crate::Result<usize> {
+        if self.df.width() == 0 {
+            return Ok(0);
+        }
+        let flags = self.df.column("flag")?.u16()?;
+        Ok(flags
+            .into_iter()
+            .flatten()
+            .filter(|flag| flag & required == required && flag & excluded == 0)
+            .count())
+    }
+
+    /// Mapping-quality histogram with up to `bins` equal-width buckets
+    ///
+    /// Buckets span `0..=max MAPQ` with an integer width, so fewer than
+    /// `bins` are returned when that many would leave some empty or
+    /// overlapping. Unmapped reads and MAPQ 255 (unavailable) are left out
+    /// since they carry no mapping quality.
+    pub fn mapq_histogram(&self, bins: usize) -> crate::Result<Vec<MapqBin>> {
+        if bins == 0 {
+            return Err(crate::GenomicsError::InvalidFormat(
+                "Histogram needs at least one bin".to_string(),
+            ));
+        }
+        if self.df.width() == 0 {
+            return Ok(Vec::new());
+        }
+
+        let flag_col = self.df.column("flag")?.u16()?;
+        let mapq_col = self.df.column("mapq")?.u8()?;
+        let mapqs: Vec<u8> = flag_col
+            .into_iter()
+            .zip(mapq_col)
+            .filter_map(|(flag, mapq)| match (flag, mapq) {
+                (Some(flag), Some(mapq)) if flag & flags::UNMAPPED == 0 && mapq != 255 => {
+                    Some(mapq)
+                }
+                _ => None,
+            })
+            .collect();
+        let Some(&max) = mapqs.iter().max() else {
+            return Ok(Vec::new());
+        };
+
+        let span = max as usize + 1;
+        let width = span.div_ceil(bins);
+        let bins = span.div_ceil(width);
+        let mut histogram: Vec<MapqBin> = (0..bins)
+            .map(|i| MapqBin {
+                lower: (i * width).min(max as usize) as u8,
+                upper: ((i + 1) * width - 1).min(max as usize) as u8,
+                count: 0,
+            })
+            .collect();
+        for mapq in mapqs {
+            histogram[mapq as usize / width].count += 1;
+        }
+        Ok(histogram)
+    }
+
+    /// Insert-size statistics over properly paired reads
+    ///
+    /// Only primary, mapped reads with `PROPER_PAIR` set and a positive
+    /// template length count, so each pair contributes once.
+    pub fn insert_size_stats(&self) -> crate::Result<InsertSizeStats> {
+        if self.df.width() == 0 {
+            return Ok(InsertSizeStats::default());
+        }
+
+        let excluded = flags::UNMAPPED | flags::SECONDARY | flags::SUPPLEMENTARY;
+        let flag_col = self.df.column("flag")?.u16()?;
+        let tlen_col = self.df.column("tlen")?.i64()?;
+        let mask: BooleanChunked = flag_col
+            .into_iter()
+            .zip(tlen_col)
+            .map(|(flag, tlen)| match (flag, tlen) {
+                (Some(flag), Some(tlen)) => {
+                    flag & flags::PROPER_PAIR != 0 && flag & excluded == 0 && tlen > 0
+                }
+                _ => false,
+            })
+            .collect();
+
+        let sizes = tlen_col.filter(&mask)?.cast(&DataType::Float64)?;
+        let sizes = sizes.f64()?;
+        Ok(InsertSizeStats {
+            count: sizes.len(),
+            mean: sizes.mean().unwrap_or(0.0),
+            median: sizes.median().unwrap_or(0.0),
+            stddev: sizes.std(1).unwrap_or(0.0),
+        })
+    }
+}
+
+/// One bucket of a MAPQ histogram
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MapqBin {
+    /// Lowest MAPQ in the bucket
+    pub lower: u8,
+    /// Highest MAPQ in the bucket (inclusive)
+    pub upper: u8,
+    /// Number of reads in the bucket
+    pub count: usize,
+}
+
+/// Insert-size statistics
+#[derive(Debug, Clone, Default)]
+pub struct InsertSizeStats {
+    /// Number of pairs measured
+    pub count: usize,
+    /// Mean insert size
+    pub mean: f64,
+    /// Median insert size
+    pub median: f64,
+    /// Sample standard deviation
+    pub stddev: f64,
+}
+
+/// Whole-dataset summary in the spirit of `bcftools stats`
+#[derive(Debug, Clone, Default)]
+pub struct VcfSummary {
+    /// Total number of variants
+    pub total: usize,
+    /// Single-nucleotide variants (1bp ref and alt)
+    pub snps: usize,
+    /// Insertions and deletions (ref and alt lengths differ)
+    pub indels: usize,
+    /// Transition SNPs (A<->G, C<->T)
+    pub transitions: usize,
+    /// Transversion SNPs
+    pub transversions: usize,
+    /// Transition/transversion ratio, `None` when there are no transversions
+    pub ti_tv: Option<f64>,
+    /// Variants per chromosome, sorted by chromosome name
+    pub per_chromosome: Vec<(String, usize)>,
+    /// Mean quality over variants with a quality score
+    pub mean_quality: Option<f64>,
+    /// Median quality over variants with a quality score
+    pub median_quality: Option<f64>,
+}
+
 /// Quality score statistics
 #[derive(Debug, Clone, Default)]
 pub struct QualityStats {
@@ -148,6 +518,7 @@ pub struct QualityStats {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::alignment::{AlignmentRecord, flags};
     use crate::variant::{VariantBatchBuilder, VariantRecord};
 
     fn create_test_analytics() -> VariantAnalytics {
@@ -191,11 +562,30 @@ mod tests {
     #[test]
     fn test_filter_by_region() {
         let analytics = create_test_analytics();
-        let count = analytics.filter_by_region("chr1", 0, 250).unwrap();
+        let count = analytics
+            .filter_by_region(&GenomicInterval::one_based_inclusive("chr1", 0, 250))
+            .unwrap();
 
         assert_eq!(count, 2); // chr1:100 and chr1:200
     }
 
+    #[test]
+    fn test_filter_by_region_bed_matches_vcf_range() {
+        let analytics = create_test_analytics();
+        // BED [99, 200) covers the same bases as 1-based 100..=200
+        let bed = GenomicInterval::zero_based_half_open("chr1", 99, 200);
+        let vcf = GenomicInterval::one_based_inclusive("chr1", 100, 200);
+        assert_eq!(analytics.filter_by_region(&bed).unwrap(), 2);
+        assert_eq!(
+            analytics.filter_by_region(&bed).unwrap(),
+            analytics.filter_by_region(&vcf).unwrap()
+        );
+
+        // Half-open end excludes chr1:200, a naive 1-based reading would not
+        let bed = GenomicInterval::zero_based_half_open("chr1", 99, 199);
+        assert_eq!(analytics.filter_by_region(&bed).unwrap(), 1);
+    }
+
     #[test]
     fn test_variant_type_counts() {
         let analytics = create_test_analytics();
@@ -215,6 +605,322 @@ mod tests {
         assert_eq!(stats.min, 30.0);
         assert_eq!(stats.max, 99.0);
     }
+    #[test]
+    fn test_summary_known_dataset() {
+        let mut builder = VariantBatchBuilder::new();
+        builder.push(VariantRecord::new("chr1", 100, "A", "G").with_qual(10.0)); // Ti
+        builder.push(VariantRecord::new("chr1", 200, "C", "T").with_qual(20.0)); // Ti
+        builder.push(VariantRecord::new("chr1", 300, "A", "C").with_qual(30.0)); // Tv
+        builder.push(VariantRecord::new("chr2", 400, "G", "T").with_qual(40.0)); // Tv
+        builder.push(VariantRecord::new("chr2", 500, "T", "C")); // Ti, no qual
+        builder.push(VariantRecord::new("chr2", 600, "AT", "A").with_qual(90.0)); // deletion
+        builder.push(VariantRecord::new("chrX", 700, "G", "GCA").with_qual(50.0)); // insertion
+        let analytics = VariantAnalytics::from_builder(&builder).unwrap();
+
+        let summary = analytics.summary().unwrap();
+        assert_eq!(summary.total, 7);
+        assert_eq!(summary.snps, 5);
+        assert_eq!(summary.indels, 2);
+        assert_eq!(summary.transitions, 3);
+        assert_eq!(summary.transversions, 2);
+        assert!((summary.ti_tv.unwrap() - 1.5).abs() < 1e-9);
+        assert_eq!(
+            summary.per_chromosome,
+            vec![
+                ("chr1".to_string(), 3),
+                ("chr2".to_string(), 3),
+                ("chrX".to_string(), 1)
+            ]
+        );
+        // (10 + 20 + 30 + 40 + 90 + 50) / 6 = 40, median of 6 values = (30 + 40) / 2
+        assert!((summary.mean_quality.unwrap() - 40.0).abs() < 1e-9);
+        assert!((summary.median_quality.unwrap() - 35.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_summary_empty() {
+        let analytics = VariantAnalytics::from_builder(&VariantBatchBuilder::new()).unwrap();
+        let summary = analytics.summary().unwrap();
+        assert_eq!(summary.total, 0);
+        assert_eq!(summary.ti_tv, None);
+        assert_eq!(summary.mean_quality, None);
+        assert!(summary.per_chromosome.is_empty());
+    }
+
+    #[test]
+    fn test_concat_and_dedup() {
+        let first = create_test_analytics();
+        let mut builder = VariantBatchBuilder::new();
+        // Shares chr1:100 A>T with the first set, at a higher quality
+        builder.push(VariantRecord::new("chr1", 100, "A", "T").with_qual(120.0));
+        builder.push(VariantRecord::new("chr3", 500, "G", "A").with_qual(60.0));
+        let second = VariantAnalytics::from_builder(&builder).unwrap();
+
+        let merged = first.concat(&[second]).unwrap();
+        assert_eq!(merged.count(), 6);
+
+        let deduped = merged.dedup_by_position().unwrap();
+        assert_eq!(deduped.count(), 5);
+        assert_eq!(
+            deduped
+                .filter_by_region(&GenomicInterval::one_based_inclusive("chr1", 100, 100))
+                .unwrap(),
+            1
+        );
+        // The surviving chr1:100 record is the 120.0 one
+        assert_eq!(deduped.quality_stats().unwrap().max, 120.0);
+        assert_eq!(deduped.filter_by_quality(99.0).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_dedup_keeps_distinct_alleles() {
+        let mut builder = VariantBatchBuilder::new();
+        builder.push(VariantRecord::new("chr1", 100, "A", "T").with_qual(10.0));
+        builder.push(VariantRecord::new("chr1", 100, "A", "G").with_qual(20.0));
+        let analytics = VariantAnalytics::from_builder(&builder).unwrap();
+        assert_eq!(analytics.dedup_by_position().unwrap().count(), 2);
+    }
+
+    #[test]
+    fn test_concat_with_empty_and_mismatched_schema() {
+        let analytics = create_test_analytics();
+        let merged = VariantAnalytics::default()
+            .concat(&[analytics, VariantAnalytics::default()])
+            .unwrap();
+        assert_eq!(merged.count(), 4);
+
+        let other = VariantAnalytics {
+            df: df!("chrom" => ["chr1"], "pos" => [1i64]).unwrap(),
+        };
+        assert!(matches!(
+            merged.concat(&[other]),
+            Err(crate::GenomicsError::InvalidFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_from_record_batches_rejects_missing_qual() {
+        use arrow_array::{ArrayRef, Int64Array, StringArray};
+
+        let batch = RecordBatch::try_from_iter([
+            (
+                "chrom",
+                Arc::new(StringArray::from(vec!["chr1"])) as ArrayRef,
+            ),
+            ("pos", Arc::new(Int64Array::from(vec![100])) as ArrayRef),
+            ("ref", Arc::new(StringArray::from(vec!["A"])) as ArrayRef),
+            ("alt", Arc::new(StringArray::from(vec!["T"])) as ArrayRef),
+        ])
+        .unwrap();
+
+        let err = VariantAnalytics::from_record_batches(&[batch])
+            .err()
+            .unwrap();
+        assert!(matches!(err, crate::GenomicsError::InvalidFormat(_)));
+        assert!(err.to_string().contains("missing columns: qual"), "{err}");
+    }
+
+    #[test]
+    fn test_from_record_batches_rejects_mismatched_types() {
+        use arrow_array::{ArrayRef, Int64Array, StringArray};
+
+        let batch = RecordBatch::try_from_iter([
+            (
+                "chrom",
+                Arc::new(StringArray::from(vec!["chr1"])) as ArrayRef,
+            ),
+            ("pos", Arc::new(StringArray::from(vec!["100"])) as ArrayRef),
+            ("ref", Arc::new(StringArray::from(vec!["A"])) as ArrayRef),
+            ("alt", Arc::new(StringArray::from(vec!["T"])) as ArrayRef),
+            ("qual", Arc::new(Int64Array::from(vec![30])) as ArrayRef),
+        ])
+        .unwrap();
+
+        let err = VariantAnalytics::from_record_batches(&[batch])
+            .err()
+            .unwrap();
+        let message = err.to_string();
+        assert!(
+            message.contains("pos (expected Int64, found Utf8)"),
+            "{message}"
+        );
+        assert!(
+            message.contains("qual (expected Float64, found Int64)"),
+            "{message}"
+        );
+    }
+
+    #[test]
+    fn test_from_record_batches_concatenates() {
+        let mut first = VariantBatchBuilder::new();
+        first.push(VariantRecord::new("chr1", 100, "A", "T").with_qual(99.0));
+        let mut second = VariantBatchBuilder::new();
+        second.push(VariantRecord::new("chr2", 200, "G", "C").with_qual(50.0));
+
+        let analytics = VariantAnalytics::from_record_batches(&[
+            first.build().unwrap(),
+            second.build().unwrap(),
+        ])
+        .unwrap();
+        assert_eq!(analytics.count(), 2);
+        assert_eq!(analytics.quality_stats().unwrap().count, 2);
+        assert_eq!(
+            VariantAnalytics::from_record_batches(&[]).unwrap().count(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_analytics_is_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<VariantAnalytics>();
+        assert_send_sync::<SharedVariantAnalytics>();
+    }
+
+    #[test]
+    fn test_clone_view_shares_buffers() {
+        let analytics = create_test_analytics();
+        let view = analytics.clone_view();
+        assert_eq!(view.count(), analytics.count());
+
+        let original = analytics.df.column("pos").unwrap().i64().unwrap();
+        let shared = view.df.column("pos").unwrap().i64().unwrap();
+        let original_ptr = original.downcast_iter().next().unwrap().values().as_ptr();
+        let shared_ptr = shared.downcast_iter().next().unwrap().values().as_ptr();
+        assert_eq!(original_ptr, shared_ptr);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_shared_analytics_concurrent_queries() {
+        let shared = create_test_analytics().into_shared();
+
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let analytics = Arc::clone(&shared);
+                tokio::task::spawn_blocking(move || match i % 4 {
+                    0 => analytics.count(),
+                    1 => analytics.filter_by_quality(50.0).unwrap(),
+                    2 => analytics.count_by_chromosome().unwrap().len(),
+                    _ => analytics.summary().unwrap().snps,
+                })
+            })
+            .collect();
+
+        let mut results = Vec::new();
+        for handle in handles {
+            results.push(handle.await.unwrap());
+        }
+        assert_eq!(results, vec![4, 3, 2, 3, 4, 3, 2, 3]);
+    }
+
+    #[test]
+    fn test_alignment_filter_flags() {
+        let mut builder = AlignmentBatchBuilder::new();
+        builder.push(AlignmentRecord::new("r1", 99, 100, "ACGT")); // paired, proper, read1
+        builder.push(AlignmentRecord::new("r1", 147, 300, "ACGT")); // paired, proper, reverse, read2
+        builder.push(AlignmentRecord::new("r2", 1139, 500, "ACGT")); // 99 + reverse + duplicate
+        builder.push(AlignmentRecord::new("r3", 4, 0, "NNNN")); // unmapped
+        builder.push(AlignmentRecord::new("r4", 256, 700, "ACGT")); // secondary
+        let analytics = AlignmentAnalytics::from_builder(&builder).unwrap();
+
+        assert_eq!(analytics.count(), 5);
+        assert_eq!(analytics.filter_flags(flags::PAIRED, 0).unwrap(), 3);
+        assert_eq!(
+            analytics
+                .filter_flags(flags::PROPER_PAIR, flags::DUPLICATE)
+                .unwrap(),
+            2
+        );
+        assert_eq!(analytics.filter_flags(flags::REVERSE, 0).unwrap(), 2);
+        assert_eq!(analytics.filter_flags(0, flags::UNMAPPED).unwrap(), 4);
+        assert_eq!(analytics.filter_flags(flags::SECONDARY, 0).unwrap(), 1);
+        assert_eq!(analytics.filter_flags(0, 0).unwrap(), 5);
+        assert_eq!(AlignmentAnalytics::default().filter_flags(0, 0).unwrap(), 0);
+    }
+
+    fn create_qc_alignments() -> AlignmentAnalytics {
+        fn read(qname: &str, flag: u16, mapq: u8, tlen: i64) -> AlignmentRecord {
+            AlignmentRecord {
+                tlen,
+                ..AlignmentRecord::new(qname, flag, 100, "ACGT").with_mapq(mapq)
+            }
+        }
+
+        let mut builder = AlignmentBatchBuilder::new();
+        builder.push(read("p1", 99, 60, 200));
+        builder.push(read("p1", 147, 60, -200));
+        builder.push(read("p2", 99, 30, 300));
+        builder.push(read("p2", 147, 30, -300));
+        builder.push(read("p3", 99, 10, 400));
+        builder.push(read("p4", 97, 20, 5000)); // paired but not proper
+        builder.push(read("p5", 99 | flags::SECONDARY, 40, 250)); // secondary
+        builder.push(read("u1", flags::UNMAPPED, 0, 0));
+        AlignmentAnalytics::from_builder(&builder).unwrap()
+    }
+
+    #[test]
+    fn test_mapq_histogram() {
+        let analytics = create_qc_alignments();
+        // MAPQ 0..=60 in three buckets of width 21, unmapped read left out
+        let histogram = analytics.mapq_histogram(3).unwrap();
+        assert_eq!(
+            histogram,
+            vec![
+                MapqBin {
+                    lower: 0,
+                    upper: 20,
+                    count: 2
+                },
+                MapqBin {
+                    lower: 21,
+                    upper: 41,
+                    count: 3
+                },
+                MapqBin {
+                    lower: 42,
+                    upper: 60,
+                    count: 2
+                },
+            ]
+        );
+
+        assert!(analytics.mapq_histogram(0).is_err());
+        assert!(
+            AlignmentAnalytics::default()
+                .mapq_histogram(4)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_mapq_histogram_more_bins_than_values() {
+        let analytics = create_qc_alignments();
+        for (bins, expected_len, width) in [(100, 61, 1), (61, 61, 1), (40, 31, 2)] {
+            let histogram = analytics.mapq_histogram(bins).unwrap();
+            assert_eq!(histogram.len(), expected_len, "bins={bins}");
+            for (i, bin) in histogram.iter().enumerate() {
+                assert_eq!(bin.lower as usize, i * width);
+                assert!(bin.upper >= bin.lower);
+            }
+            assert_eq!(histogram.last().unwrap().upper, 60);
+            assert_eq!(histogram.iter().map(|b| b.count).sum::<usize>(), 7);
+        }
+    }
+
+    #[test]
+    fn test_insert_size_stats() {
+        let stats = create_qc_alignments().insert_size_stats().unwrap();
+        // Proper, primary, positive-TLEN reads: 200, 300, 400
+        assert_eq!(stats.count, 3);
+        assert!((stats.mean - 300.0).abs() < 1e-9);
+        assert!((stats.median - 300.0).abs() < 1e-9);
+        assert!((stats.stddev - 100.0).abs() < 1e-9);
+
+        let empty = AlignmentAnalytics::default().insert_size_stats().unwrap();
+        assert_eq!(empty.count, 0);
+    }
+
     #[test]
     fn test_empty_analytics() {
         let builder = VariantBatchBuilder::new();
@@ -248,11 +954,15 @@ mod tests {
     fn test_filter_by_region_boundaries() {
         let analytics = create_test_analytics();
         // Test exact boundaries
-        let count = analytics.filter_by_region("chr1", 100, 200).unwrap();
+        let count = analytics
+            .filter_by_region(&GenomicInterval::one_based_inclusive("chr1", 100, 200))
+            .unwrap();
         assert_eq!(count, 2);
 
         // Test no matches
-        let count = analytics.filter_by_region("chr1", 500, 600).unwrap();
+        let count = analytics
+            .filter_by_region(&GenomicInterval::one_based_inclusive("chr1", 500, 600))
+            .unwrap();
         assert_eq!(count, 0);
     }
 
@@ -261,7 +971,8 @@ mod tests {
         let mut builder = VariantBatchBuilder::new();
         for i in 0..1000 {
             builder.push(
-                VariantRecord::new("chr1", i * 10, "A", "T").with_qual(50.0 + (i % 50) as f64),
+                VariantRecord::new("chr1", (i + 1) * 10, "A", "T")
+                    .with_qual(50.0 + (i % 50) as f64),
             );
         }
         let analytics = VariantAnalytics::from_builder(&builder).unwrap();
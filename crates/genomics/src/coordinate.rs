@@ -0,0 +1,171 @@
+//! Genomic Coordinates
+//!
+//! VCF and SAM text use 1-based positions while BED and BAM internals are
+//! 0-based. [`Coordinate`] stores a single normalized position so the two
+//! conventions can only be mixed through explicit conversions.
+
+use std::fmt;
+
+/// Coordinate convention of a raw position
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoordinateSystem {
+    /// First base is 1 (VCF, SAM text, GFF)
+    OneBased,
+    /// First base is 0 (BED, BAM internals)
+    ZeroBased,
+}
+
+/// A single base position, independent of the convention it was read in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Coordinate(i64);
+
+impl Coordinate {
+    /// Create from a 1-based position
+    pub const fn from_one_based(pos: i64) -> Self {
+        Self(pos - 1)
+    }
+
+    /// Create from a 0-based position
+    pub const fn from_zero_based(pos: i64) -> Self {
+        Self(pos)
+    }
+
+    /// Create from a position in the given convention
+    pub const fn new(pos: i64, system: CoordinateSystem) -> Self {
+        match system {
+            CoordinateSystem::OneBased => Self::from_one_based(pos),
+            CoordinateSystem::ZeroBased => Self::from_zero_based(pos),
+        }
+    }
+
+    /// Position in 1-based convention
+    pub const fn one_based(self) -> i64 {
+        self.0 + 1
+    }
+
+    /// Position in 0-based convention
+    pub const fn zero_based(self) -> i64 {
+        self.0
+    }
+
+    /// Position in the given convention
+    pub const fn get(self, system: CoordinateSystem) -> i64 {
+        match system {
+            CoordinateSystem::OneBased => self.one_based(),
+            CoordinateSystem::ZeroBased => self.zero_based(),
+        }
+    }
+}
+
+/// A closed range of bases on one chromosome
+///
+/// Construct from either a VCF-style 1-based inclusive range or a BED-style
+/// 0-based half-open range; both describe the same bases.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GenomicInterval {
+    /// Chromosome name
+    pub chrom: String,
+    /// First base in the interval
+    pub start: Coordinate,
+    /// Last base in the interval
+    pub end: Coordinate,
+}
+
+impl GenomicInterval {
+    /// Interval covering `start..=end` in 1-based coordinates
+    pub fn one_based_inclusive(chrom: impl Into<String>, start: i64, end: i64) -> Self {
+        Self {
+            chrom: chrom.into(),
+            start: Coordinate::from_one_based(start),
+            end: Coordinate::from_one_based(end),
+        }
+    }
+
+    /// Interval covering `start..end` in 0-based coordinates (BED)
+    pub fn zero_based_half_open(chrom: impl Into<String>, start: i64, end: i64) -> Self {
+        Self {
+            chrom: chrom.into(),
+            start: Coordinate::from_zero_based(start),
+            end: Coordinate::from_zero_based(end - 1),
+        }
+    }
+
+    /// Number of bases covered
+    pub fn len(&self) -> i64 {
+        (self.end.zero_based() - self.start.zero_based() + 1).max(0)
+    }
+
+    /// Whether the interval covers no bases
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Whether `pos` on `chrom` falls inside the interval
+    pub fn contains(&self, chrom: &str, pos: Coordinate) -> bool {
+        self.chrom == chrom && self.start <= pos && pos <= self.end
+    }
+
+    /// `(start, end)` as a BED-style 0-based half-open range
+    pub fn to_bed(&self) -> (i64, i64) {
+        (self.start.zero_based(), self.end.zero_based() + 1)
+    }
+}
+
+impl fmt::Display for GenomicInterval {
+    /// Formats as a samtools-style 1-based region, e.g. `chr1:100-200`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}-{}",
+            self.chrom,
+            self.start.one_based(),
+            self.end.one_based()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_coordinate_conversions() {
+        let c = Coordinate::from_one_based(100);
+        assert_eq!(c.zero_based(), 99);
+        assert_eq!(c.one_based(), 100);
+        assert_eq!(c, Coordinate::from_zero_based(99));
+        assert_eq!(
+            Coordinate::new(1, CoordinateSystem::OneBased).zero_based(),
+            0
+        );
+        assert_eq!(c.get(CoordinateSystem::ZeroBased), 99);
+        assert_eq!(c.get(CoordinateSystem::OneBased), 100);
+    }
+
+    #[test]
+    fn test_bed_and_vcf_intervals_are_equivalent() {
+        let bed = GenomicInterval::zero_based_half_open("chr1", 99, 200);
+        let vcf = GenomicInterval::one_based_inclusive("chr1", 100, 200);
+        assert_eq!(bed, vcf);
+        assert_eq!(bed.len(), 101);
+        assert_eq!(vcf.to_bed(), (99, 200));
+        assert_eq!(vcf.to_string(), "chr1:100-200");
+    }
+
+    #[test]
+    fn test_interval_contains() {
+        let interval = GenomicInterval::one_based_inclusive("chr1", 100, 200);
+        assert!(interval.contains("chr1", Coordinate::from_one_based(100)));
+        assert!(interval.contains("chr1", Coordinate::from_one_based(200)));
+        assert!(!interval.contains("chr1", Coordinate::from_one_based(201)));
+        assert!(!interval.contains("chr1", Coordinate::from_zero_based(98)));
+        assert!(!interval.contains("chr2", Coordinate::from_one_based(150)));
+    }
+
+    #[test]
+    fn test_empty_bed_interval() {
+        let interval = GenomicInterval::zero_based_half_open("chr1", 10, 10);
+        assert!(interval.is_empty());
+        assert!(!interval.contains("chr1", Coordinate::from_zero_based(10)));
+    }
+}
@@ -0,0 +1,219 @@
+//! CRAM Reader
+//!
+//! Streams CRAM alignments as [`AlignmentRecord`]s. CRAM stores read bases
+//! as differences against a reference, so a [`FastaReader`] holding the
+//! reference used at compression time is required to rebuild sequences.
+
+use crate::alignment::{AlignmentBatchBuilder, AlignmentRecord};
+use crate::fasta_reader::FastaReader;
+use crate::{GenomicsError, Result};
+use noodles::{cram, fasta, sam};
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use tracing::{debug, info};
+
+/// Streaming CRAM reader
+///
+/// Decodes one container at a time, so memory stays bounded by the
+/// container size rather than the file size.
+pub struct CramReader<R> {
+    inner: cram::io::Reader<R>,
+    header: sam::Header,
+    repository: fasta::Repository,
+    container: cram::io::reader::Container,
+    pending: VecDeque<AlignmentRecord>,
+    finished: bool,
+}
+
+impl CramReader<File> {
+    /// Open a CRAM file
+    pub fn from_path(path: impl AsRef<Path>, reference: &FastaReader) -> Result<Self> {
+        let file = File::open(path)?;
+        Self::new(file, reference)
+    }
+}
+
+impl<R: Read> CramReader<R> {
+    /// Create a reader and read the file definition and SAM header
+    ///
+    /// Every `@SQ` sequence in the header must be present in `reference`.
+    pub fn new(reader: R, reference: &FastaReader) -> Result<Self> {
+        let mut inner = cram::io::Reader::new(reader);
+        let header = inner
+            .read_header()
+            .map_err(|e| GenomicsError::InvalidFormat(format!("Invalid CRAM header: {}", e)))?;
+
+        for name in header.reference_sequences().keys() {
+            let name = String::from_utf8_lossy(name);
+            if reference.sequence(&name).is_none() {
+                return Err(GenomicsError::InvalidFormat(format!(
+                    "Reference is missing CRAM sequence {}",
+                    name
+                )));
+            }
+        }
+
+        info!(
+            "Opened CRAM: {} reference sequences",
+            header.reference_sequences().len()
+        );
+
+        Ok(Self {
+            inner,
+            header,
+            repository: reference.repository(),
+            container: Default::default(),
+            pending: VecDeque::new(),
+            finished: false,
+        })
+    }
+
+    /// SAM header stored in the CRAM file
+    pub fn header(&self) -> &sam::Header {
+        &self.header
+    }
+
+    /// Read the next record, or `None` at end of stream
+    pub fn read_record(&mut self) -> Result<Option<AlignmentRecord>> {
+        while self.pending.is_empty() && !self.finished {
+            self.read_container()?;
+        }
+        Ok(self.pending.pop_front())
+    }
+
+    /// Iterate over the remaining records
+    pub fn records(&mut self) -> impl Iterator<Item = Result<AlignmentRecord>> + '_ {
+        std::iter::from_fn(move || self.read_record().transpose())
+    }
+
+    /// Read up to `max_records` records into `builder`
+    ///
+    /// Returns the number of records read; 0 means the stream is exhausted.
+    pub fn read_batch(
+        &mut self,
+        builder: &mut AlignmentBatchBuilder,
+        max_records: usize,
+    ) -> Result<usize> {
+        let mut count = 0;
+        while count < max_records {
+            match self.read_record()? {
+                Some(record) => builder.push(record),
+                None => break,
+            }
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    fn read_container(&mut self) -> Result<()> {
+        if self.inner.read_container(&mut self.container)? == 0 {
+            self.finished = true;
+            return Ok(());
+        }
+
+        let decode_err =
+            |e: std::io::Error| GenomicsError::ParseError(format!("CRAM decode failed: {}", e));
+        let compression_header = self.container.compression_header().map_err(decode_err)?;
+
+        for slice in self.container.slices() {
+            let slice = slice.map_err(decode_err)?;
+            let (core_data, external_data) = slice.decode_blocks().map_err(decode_err)?;
+            let records = slice
+                .records(
+                    self.repository.clone(),
+                    &self.header,
+                    &compression_header,
+                    &core_data,
+                    &external_data,
+                )
+                .map_err(decode_err)?;
+
+            for record in &records {
+                self.pending
+                    .push_back(AlignmentRecord::from_sam_record(&self.header, record)?);
+            }
+        }
+
+        debug!("Decoded CRAM container: {} records", self.pending.len());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use noodles::bam;
+    use std::path::PathBuf;
+
+    // reads.cram / reads.bam hold the same five records against ref.fa
+    fn fixture(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/data")
+            .join(name)
+    }
+
+    fn reference() -> FastaReader {
+        FastaReader::from_path(fixture("ref.fa")).unwrap()
+    }
+
+    fn bam_baseline() -> Vec<AlignmentRecord> {
+        let mut reader = File::open(fixture("reads.bam"))
+            .map(bam::io::Reader::new)
+            .unwrap();
+        let header = reader.read_header().unwrap();
+        reader
+            .records()
+            .map(|record| AlignmentRecord::from_sam_record(&header, &record.unwrap()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_cram_matches_bam_baseline() {
+        let mut reader = CramReader::from_path(fixture("reads.cram"), &reference()).unwrap();
+        assert_eq!(reader.header().reference_sequences().len(), 2);
+
+        let records: Vec<_> = reader.records().collect::<Result<_>>().unwrap();
+        assert_eq!(records.len(), 5);
+        assert_eq!(records, bam_baseline());
+
+        // Bases differing from the reference are restored
+        assert_eq!(records[1].qname, "snv");
+        assert_eq!(records[1].seq, "GATGTT");
+        assert_eq!(records[3].cigar.as_deref(), Some("2S4M1I3M2D4M"));
+        assert_eq!(records[3].seq, "AACGCCACCGGTGC");
+        assert_eq!(records[0].rnext.as_deref(), Some("chr1"));
+        assert_eq!(records[0].tlen, 28);
+        assert!(records[4].is_unmapped());
+    }
+
+    #[test]
+    fn test_cram_read_batch_streams_in_chunks() {
+        let mut reader = CramReader::from_path(fixture("reads.cram"), &reference()).unwrap();
+        let mut builder = AlignmentBatchBuilder::new();
+
+        assert_eq!(reader.read_batch(&mut builder, 3).unwrap(), 3);
+        assert_eq!(reader.read_batch(&mut builder, 3).unwrap(), 2);
+        assert_eq!(reader.read_batch(&mut builder, 3).unwrap(), 0);
+        assert_eq!(builder.len(), 5);
+    }
+
+    #[test]
+    fn test_cram_requires_matching_reference() {
+        let reference = FastaReader::from_records(vec![fasta::Record::new(
+            fasta::record::Definition::new("chr1", None),
+            fasta::record::Sequence::from(b"ACGT".to_vec()),
+        )]);
+        let result = CramReader::from_path(fixture("reads.cram"), &reference);
+        assert!(
+            matches!(result, Err(GenomicsError::InvalidFormat(ref msg)) if msg.contains("chr2"))
+        );
+    }
+
+    #[test]
+    fn test_cram_invalid_input() {
+        let result = CramReader::new(&b"not a cram file"[..], &FastaReader::default());
+        assert!(matches!(result, Err(GenomicsError::InvalidFormat(_))));
+    }
+}
@@ -0,0 +1,107 @@
+//! FASTA Reference Reader
+//!
+//! Loads reference sequences from FASTA for formats that store reads
+//! relative to a reference, such as CRAM.
+
+use crate::{GenomicsError, Result};
+use noodles::fasta;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use tracing::info;
+
+/// In-memory set of FASTA reference sequences
+#[derive(Debug, Clone, Default)]
+pub struct FastaReader {
+    records: Vec<fasta::Record>,
+}
+
+impl FastaReader {
+    /// Read all sequences from a FASTA file
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self> {
+        let file = File::open(path)?;
+        Self::from_reader(BufReader::new(file))
+    }
+
+    /// Read all sequences from a FASTA stream
+    pub fn from_reader<R: BufRead>(reader: R) -> Result<Self> {
+        let mut reader = fasta::io::Reader::new(reader);
+        let records = reader
+            .records()
+            .collect::<std::io::Result<Vec<_>>>()
+            .map_err(|e| GenomicsError::ParseError(format!("Invalid FASTA: {}", e)))?;
+
+        info!("Loaded {} reference sequences from FASTA", records.len());
+
+        Ok(Self { records })
+    }
+
+    /// Create from already parsed records
+    pub fn from_records(records: Vec<fasta::Record>) -> Self {
+        Self { records }
+    }
+
+    /// Get a sequence by name
+    pub fn sequence(&self, name: &str) -> Option<&[u8]> {
+        self.records
+            .iter()
+            .find(|record| record.name() == name.as_bytes())
+            .map(|record| record.sequence().as_ref())
+    }
+
+    /// Sequence names in file order
+    pub fn names(&self) -> impl Iterator<Item = String> + '_ {
+        self.records
+            .iter()
+            .map(|record| String::from_utf8_lossy(record.name()).into_owned())
+    }
+
+    /// Get the number of sequences
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Check if empty
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Parsed FASTA records
+    pub fn records(&self) -> &[fasta::Record] {
+        &self.records
+    }
+
+    /// Reference repository for noodles readers and writers
+    pub(crate) fn repository(&self) -> fasta::Repository {
+        fasta::Repository::new(self.records.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FASTA: &str = ">chr1 test\nACGTACGT\nACGT\n>chr2\nGGGG\n";
+
+    #[test]
+    fn test_fasta_from_reader() {
+        let reader = FastaReader::from_reader(FASTA.as_bytes()).unwrap();
+        assert_eq!(reader.len(), 2);
+        assert_eq!(reader.names().collect::<Vec<_>>(), vec!["chr1", "chr2"]);
+        assert_eq!(reader.sequence("chr1"), Some(&b"ACGTACGTACGT"[..]));
+        assert_eq!(reader.sequence("chr2"), Some(&b"GGGG"[..]));
+        assert_eq!(reader.sequence("chr3"), None);
+    }
+
+    #[test]
+    fn test_fasta_invalid() {
+        let result = FastaReader::from_reader("not a fasta file\n".as_bytes());
+        assert!(matches!(result, Err(GenomicsError::ParseError(_))));
+    }
+
+    #[test]
+    fn test_fasta_missing_file() {
+        let result = FastaReader::from_path("/nonexistent/reference.fa");
+        assert!(matches!(result, Err(GenomicsError::IoError(_))));
+    }
+}
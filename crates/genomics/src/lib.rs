@@ -4,7 +4,7 @@
 //!
 //! # Features
 //! - Zero-copy Arrow RecordBatch for genomic data
-//! - BAM/VCF parsing with noodles
+//! - BAM/CRAM/VCF parsing with noodles
 //! - Polars DataFrame for analytics
 //!
 //! # Example
@@ -18,13 +18,22 @@
 pub mod alignment;
 pub mod analytics;
 pub mod bam_parser;
+pub mod coordinate;
+pub mod cram_reader;
+pub mod fasta_reader;
 pub mod schema;
 pub mod variant;
 pub mod vcf_parser;
 
 pub use alignment::{AlignmentBatchBuilder, AlignmentRecord};
-pub use analytics::VariantAnalytics;
+pub use analytics::{
+    AlignmentAnalytics, InsertSizeStats, MapqBin, SharedVariantAnalytics, VariantAnalytics,
+    VcfSummary,
+};
 pub use bam_parser::BamHeader;
+pub use coordinate::{Coordinate, CoordinateSystem, GenomicInterval};
+pub use cram_reader::CramReader;
+pub use fasta_reader::FastaReader;
 pub use schema::{GenomicSchema, SchemaType};
 pub use variant::{VariantBatchBuilder, VariantRecord};
 pub use vcf_parser::VcfParser;
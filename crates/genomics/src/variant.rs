@@ -2,8 +2,8 @@
 //!
 //! Arrow-backed variant records for VCF data.
 
-use crate::Result;
 use crate::schema::GenomicSchema;
+use crate::{GenomicsError, Result};
 use arrow_array::{ArrayRef, Float64Array, Int64Array, RecordBatch, StringArray};
 use std::sync::Arc;
 
@@ -66,6 +66,33 @@ impl VariantRecord {
         self.info = Some(info.to_string());
         self
     }
+
+    /// Check the required fields: non-empty chrom/ref/alt and a positive position
+    pub fn validate(&self) -> Result<()> {
+        match validate_fields(&self.chrom, self.pos, &self.reference, &self.alternate) {
+            Some(msg) => Err(GenomicsError::InvalidFormat(msg)),
+            None => Ok(()),
+        }
+    }
+}
+
+fn validate_fields(chrom: &str, pos: i64, reference: &str, alternate: &str) -> Option<String> {
+    let problem = if chrom.is_empty() {
+        "empty chrom"
+    } else if pos <= 0 {
+        "non-positive position"
+    } else if reference.is_empty() {
+        "empty ref allele"
+    } else if alternate.is_empty() {
+        "empty alt allele"
+    } else {
+        return None;
+    };
+
+    Some(format!(
+        "Invalid variant {}:{} {}/{}: {}",
+        chrom, pos, reference, alternate, problem
+    ))
 }
 
 /// Builder for creating Arrow RecordBatch from variants
@@ -79,6 +106,8 @@ pub struct VariantBatchBuilder {
     quals: Vec<Option<f64>>,
     filters: Vec<Option<String>>,
     infos: Vec<Option<String>>,
+    skip_invalid: bool,
+    skipped: usize,
 }
 
 impl VariantBatchBuilder {
@@ -98,11 +127,48 @@ impl VariantBatchBuilder {
             quals: Vec::with_capacity(capacity),
             filters: Vec::with_capacity(capacity),
             infos: Vec::with_capacity(capacity),
+            skip_invalid: false,
+            skipped: 0,
         }
     }
 
+    /// Drop and count invalid records on push instead of failing `build`
+    pub fn with_skip_invalid(mut self, skip: bool) -> Self {
+        self.skip_invalid = skip;
+        self
+    }
+
+    /// Number of invalid records dropped in skip mode
+    pub fn skipped_count(&self) -> usize {
+        self.skipped
+    }
+
     /// Add a variant record
+    ///
+    /// Invalid records are dropped and counted in skip mode; otherwise they
+    /// are kept and reported by `build`.
     pub fn push(&mut self, record: VariantRecord) {
+        if self.skip_invalid && record.validate().is_err() {
+            self.skipped += 1;
+            return;
+        }
+        self.push_unchecked(record);
+    }
+
+    /// Add a variant record, rejecting it immediately if invalid
+    pub fn try_push(&mut self, record: VariantRecord) -> Result<()> {
+        if let Err(e) = record.validate() {
+            if self.skip_invalid {
+                self.skipped += 1;
+                return Ok(());
+            }
+            return Err(e);
+        }
+        self.push_unchecked(record);
+        Ok(())
+    }
+
+    fn push_unchecked(&mut self, record: VariantRecord) {
         self.chroms.push(record.chrom);
         self.positions.push(record.pos);
         self.ids.push(record.id);
@@ -125,25 +191,65 @@ impl VariantBatchBuilder {
 
     /// Build an Arrow RecordBatch
     pub fn build(&self) -> Result<RecordBatch> {
+        self.build_range(0, self.len())
+    }
+
+    /// Build several RecordBatches of at most `rows_per_batch` rows each
+    ///
+    /// Useful for streaming large datasets without materializing one huge batch.
+    pub fn build_chunked(&self, rows_per_batch: usize) -> Result<Vec<RecordBatch>> {
+        if rows_per_batch == 0 {
+            return Err(GenomicsError::InvalidFormat(
+                "rows_per_batch must be greater than zero".to_string(),
+            ));
+        }
+
+        (0..self.len())
+            .step_by(rows_per_batch)
+            .map(|start| self.build_range(start, (start + rows_per_batch).min(self.len())))
+            .collect()
+    }
+
+    fn build_range(&self, start: usize, end: usize) -> Result<RecordBatch> {
+        for i in start..end {
+            if let Some(msg) = validate_fields(
+                &self.chroms[i],
+                self.positions[i],
+                &self.refs[i],
+                &self.alts[i],
+            ) {
+                return Err(GenomicsError::InvalidFormat(format!(
+                    "record {}: {}",
+                    i, msg
+                )));
+            }
+        }
+
         let schema = GenomicSchema::variant();
 
         let columns: Vec<ArrayRef> = vec![
-            Arc::new(StringArray::from(self.chroms.clone())),
-            Arc::new(Int64Array::from(self.positions.clone())),
+            Arc::new(StringArray::from(self.chroms[start..end].to_vec())),
+            Arc::new(Int64Array::from(self.positions[start..end].to_vec())),
             Arc::new(StringArray::from(
-                self.ids.iter().map(|s| s.as_deref()).collect::<Vec<_>>(),
+                self.ids[start..end]
+                    .iter()
+                    .map(|s| s.as_deref())
+                    .collect::<Vec<_>>(),
             )),
-            Arc::new(StringArray::from(self.refs.clone())),
-            Arc::new(StringArray::from(self.alts.clone())),
-            Arc::new(Float64Array::from(self.quals.clone())),
+            Arc::new(StringArray::from(self.refs[start..end].to_vec())),
+            Arc::new(StringArray::from(self.alts[start..end].to_vec())),
+            Arc::new(Float64Array::from(self.quals[start..end].to_vec())),
             Arc::new(StringArray::from(
-                self.filters
+                self.filters[start..end]
                     .iter()
                     .map(|s| s.as_deref())
                     .collect::<Vec<_>>(),
             )),
             Arc::new(StringArray::from(
-                self.infos.iter().map(|s| s.as_deref()).collect::<Vec<_>>(),
+                self.infos[start..end]
+                    .iter()
+                    .map(|s| s.as_deref())
+                    .collect::<Vec<_>>(),
             )),
         ];
 
@@ -160,6 +266,24 @@ impl VariantBatchBuilder {
         self.quals.clear();
         self.filters.clear();
         self.infos.clear();
+        self.skipped = 0;
+    }
+}
+
+impl FromIterator<VariantRecord> for VariantBatchBuilder {
+    fn from_iter<I: IntoIterator<Item = VariantRecord>>(iter: I) -> Self {
+        let iter = iter.into_iter();
+        let mut builder = Self::with_capacity(iter.size_hint().0);
+        builder.extend(iter);
+        builder
+    }
+}
+
+impl Extend<VariantRecord> for VariantBatchBuilder {
+    fn extend<I: IntoIterator<Item = VariantRecord>>(&mut self, iter: I) {
+        for record in iter {
+            self.push(record);
+        }
     }
 }
 
@@ -302,6 +426,107 @@ mod tests {
         assert_eq!(builder.len(), 5);
     }
 
+    #[test]
+    fn test_builder_from_iter() {
+        let builder: VariantBatchBuilder = (1..=2500)
+            .map(|i| VariantRecord::new("chr1", i, "A", "G"))
+            .collect();
+        assert_eq!(builder.len(), 2500);
+
+        let batch = builder.build().unwrap();
+        assert_eq!(batch.num_rows(), 2500);
+    }
+
+    #[test]
+    fn test_builder_extend() {
+        let mut builder =
+            VariantBatchBuilder::from_iter(vec![VariantRecord::new("chr1", 1, "A", "T")]);
+        builder.extend((2..=5).map(|i| VariantRecord::new("chr2", i, "C", "G")));
+        assert_eq!(builder.len(), 5);
+    }
+
+    #[test]
+    fn test_build_chunked() {
+        let builder: VariantBatchBuilder = (1..=2500)
+            .map(|i| VariantRecord::new("chr1", i, "A", "G"))
+            .collect();
+
+        let batches = builder.build_chunked(1000).unwrap();
+        assert_eq!(batches.len(), 3);
+        assert_eq!(batches[0].num_rows(), 1000);
+        assert_eq!(batches[2].num_rows(), 500);
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 2500);
+
+        // Chunks preserve record order
+        let first_pos = batches[1]
+            .column(1)
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap()
+            .value(0);
+        assert_eq!(first_pos, 1001);
+    }
+
+    #[test]
+    fn test_build_chunked_empty_and_zero() {
+        let builder = VariantBatchBuilder::new();
+        assert!(builder.build_chunked(10).unwrap().is_empty());
+        assert!(matches!(
+            builder.build_chunked(0),
+            Err(GenomicsError::InvalidFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_build_rejects_negative_position() {
+        let mut builder = VariantBatchBuilder::new();
+        builder.push(VariantRecord::new("chr1", 100, "A", "T"));
+        builder.push(VariantRecord::new("chr1", -5, "G", "C"));
+
+        match builder.build() {
+            Err(GenomicsError::InvalidFormat(msg)) => {
+                assert!(msg.contains("record 1"));
+                assert!(msg.contains("chr1:-5"));
+            }
+            other => panic!("Expected InvalidFormat, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_validate_required_fields() {
+        assert!(VariantRecord::new("chr1", 1, "A", "T").validate().is_ok());
+        assert!(VariantRecord::new("", 1, "A", "T").validate().is_err());
+        assert!(VariantRecord::new("chr1", 0, "A", "T").validate().is_err());
+        assert!(VariantRecord::new("chr1", 1, "", "T").validate().is_err());
+        assert!(VariantRecord::new("chr1", 1, "A", "").validate().is_err());
+    }
+
+    #[test]
+    fn test_try_push_rejects_invalid() {
+        let mut builder = VariantBatchBuilder::new();
+        assert!(
+            builder
+                .try_push(VariantRecord::new("", 1, "A", "T"))
+                .is_err()
+        );
+        assert!(builder.is_empty());
+    }
+
+    #[test]
+    fn test_skip_invalid_counts_and_omits() {
+        let mut builder = VariantBatchBuilder::new().with_skip_invalid(true);
+        builder.push(VariantRecord::new("chr1", 100, "A", "T"));
+        builder.push(VariantRecord::new("chr1", -5, "G", "C"));
+        builder
+            .try_push(VariantRecord::new("chr2", 300, "", "A"))
+            .unwrap();
+        builder.push(VariantRecord::new("chr2", 400, "C", "G"));
+
+        assert_eq!(builder.len(), 2);
+        assert_eq!(builder.skipped_count(), 2);
+        assert_eq!(builder.build().unwrap().num_rows(), 2);
+    }
+
     #[test]
     fn test_variant_record_debug_format() {
         let record = VariantRecord::new("chr1", 100, "A", "T");
@@ -23,6 +23,7 @@ fn bench_region_score(c: &mut Criterion) {
             let score = RegionScore {
                 region_id: "us-east-1".to_string(),
                 carbon_intensity: 150.0,
+                latency_ms: None,
                 score: 0.3,
                 recommended: true,
             };
@@ -37,18 +38,21 @@ fn bench_routing_decision(c: &mut Criterion) {
         RegionScore {
             region_id: "us-east-1".to_string(),
             carbon_intensity: 350.0,
+            latency_ms: None,
             score: 0.7,
             recommended: false,
         },
         RegionScore {
             region_id: "us-west-2".to_string(),
             carbon_intensity: 150.0,
+            latency_ms: None,
             score: 0.3,
             recommended: true,
         },
         RegionScore {
             region_id: "eu-north-1".to_string(),
             carbon_intensity: 50.0,
+            latency_ms: None,
             score: 0.1,
             recommended: true,
         },
@@ -119,6 +123,7 @@ fn bench_select_greenest_region_100(c: &mut Criterion) {
         regions.push(RegionScore {
             region_id: format!("region-{}", i),
             carbon_intensity: 50.0 + (i as f64 * 5.0) % 400.0,
+            latency_ms: None,
             score: (50.0 + (i as f64 * 5.0) % 400.0) / 500.0,
             recommended: (50.0 + (i as f64 * 5.0) % 400.0) < 200.0,
         });
@@ -0,0 +1,254 @@
+//! Routing Hot Path Benchmark
+//!
+//! Measures the routing decisions made per request against realistic state:
+//! `CarbonRouter` with many scored regions, including while a refresh is in
+//! flight, and `GreenWaitScheduler::process_ready_jobs` draining a full
+//! queue. The small-input group asserts the results before timing, so
+//! `cargo test --bench routing_hot_path` doubles as a smoke test.
+
+use aegis_energy::{
+    CarbonIntensity, CarbonIntensityCache, EnergyApiClient, EnergyApiError, ForecastPoint, Region,
+};
+use aegis_proxy::green_wait::{DeferredJob, GreenWaitConfig, GreenWaitScheduler, JobPriority};
+use aegis_proxy::{CarbonRouter, CarbonRouterConfig};
+use criterion::{BatchSize, BenchmarkId, Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::NamedTempFile;
+use tokio::runtime::Runtime;
+
+/// Answers with a fixed intensity per region, after `delay`
+#[derive(Clone, Default)]
+struct BenchClient {
+    delay: Duration,
+}
+
+/// 50-499 gCO2/kWh, spread deterministically over `region-{n}`
+fn intensity_for(region_id: &str) -> f64 {
+    let n: u64 = region_id.trim_start_matches("region-").parse().unwrap_or(0);
+    50.0 + ((n * 37) % 450) as f64
+}
+
+impl EnergyApiClient for BenchClient {
+    async fn get_carbon_intensity(
+        &self,
+        region: &Region,
+    ) -> Result<CarbonIntensity, EnergyApiError> {
+        if !self.delay.is_zero() {
+            tokio::time::sleep(self.delay).await;
+        }
+        Ok(CarbonIntensity {
+            region: region.clone(),
+            value: intensity_for(&region.id),
+            timestamp: chrono::Utc::now(),
+            valid_for_seconds: 300,
+            rating: None,
+            renewable_percentage: None,
+            power_breakdown: None,
+        })
+    }
+
+    async fn get_carbon_intensity_by_location(
+        &self,
+        _lat: f64,
+        _lon: f64,
+    ) -> Result<CarbonIntensity, EnergyApiError> {
+        unimplemented!()
+    }
+
+    async fn get_region_for_location(
+        &self,
+        _lat: f64,
+        _lon: f64,
+    ) -> Result<Region, EnergyApiError> {
+        unimplemented!()
+    }
+
+    async fn get_carbon_forecast(
+        &self,
+        _region: &Region,
+        _hours: u32,
+    ) -> Result<Vec<ForecastPoint>, EnergyApiError> {
+        unimplemented!()
+    }
+}
+
+/// Router with `count` registered and scored regions
+async fn router_with_regions(
+    count: usize,
+    client: BenchClient,
+    cache: CarbonIntensityCache,
+) -> CarbonRouter<BenchClient> {
+    let router = CarbonRouter::new(CarbonRouterConfig::default(), client, cache);
+    for i in 0..count {
+        router
+            .register_region(Region::new(format!("region-{i}"), format!("Region {i}")))
+            .await;
+    }
+    router.refresh_carbon_data().await.unwrap();
+    router
+}
+
+/// Scheduler whose queue holds `jobs` jobs, every other one in a green region
+fn scheduler_with_full_queue(
+    rt: &Runtime,
+    jobs: usize,
+) -> (GreenWaitScheduler<BenchClient>, NamedTempFile) {
+    let config = GreenWaitConfig {
+        enabled: true,
+        default_threshold: 150.0,
+        max_queue_size: jobs,
+        ..Default::default()
+    };
+    let temp_file = NamedTempFile::new().unwrap();
+    let scheduler = GreenWaitScheduler::new(
+        config,
+        BenchClient::default(),
+        CarbonIntensityCache::new(300),
+        temp_file.path(),
+    )
+    .unwrap();
+
+    rt.block_on(async {
+        for i in 0..jobs {
+            let region = if i % 2 == 0 { "green" } else { "dirty" };
+            let job = DeferredJob::new(
+                format!("job-{i}"),
+                JobPriority::Normal,
+                Region::new(region, region),
+                150.0,
+                vec![],
+            );
+            scheduler.submit(job).await;
+        }
+        scheduler.update_region_intensity("green", 10.0).await;
+        scheduler.update_region_intensity("dirty", 400.0).await;
+    });
+    (scheduler, temp_file)
+}
+
+/// Check the fixtures behave before timing them on a small input
+fn bench_small_input(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let router = rt.block_on(router_with_regions(
+        4,
+        BenchClient::default(),
+        CarbonIntensityCache::new(300),
+    ));
+
+    // region-0 is the greenest at 50 gCO2/kWh, region-3 the dirtiest
+    assert_eq!(
+        rt.block_on(router.select_greenest_region()).as_deref(),
+        Some("region-0")
+    );
+    let greenest = rt.block_on(router.get_routing_weight("region-0"));
+    let dirtiest = rt.block_on(router.get_routing_weight("region-3"));
+    assert!(greenest > dirtiest, "{greenest} <= {dirtiest}");
+
+    let (scheduler, _temp_file) = scheduler_with_full_queue(&rt, 4);
+    let ready = rt.block_on(scheduler.process_ready_jobs());
+    assert_eq!(ready.len(), 2);
+    assert!(ready.iter().all(|job| job.region.id == "green"));
+
+    c.bench_function("routing/small_input/select_greenest_region", |b| {
+        b.iter(|| rt.block_on(async { black_box(router.select_greenest_region().await) }))
+    });
+}
+
+fn bench_carbon_router(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let mut group = c.benchmark_group("routing/carbon_router");
+
+    for count in [10, 100, 1000] {
+        let router = rt.block_on(router_with_regions(
+            count,
+            BenchClient::default(),
+            CarbonIntensityCache::new(300),
+        ));
+        let target = format!("region-{}", count / 2);
+
+        group.bench_with_input(
+            BenchmarkId::new("select_greenest_region", count),
+            &router,
+            |b, router| {
+                b.iter(|| rt.block_on(async { black_box(router.select_greenest_region().await) }))
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("get_routing_weight", count),
+            &router,
+            |b, router| {
+                b.iter(|| {
+                    rt.block_on(async { black_box(router.get_routing_weight(&target).await) })
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+/// Routing decisions while a slow refresh keeps running in the background
+fn bench_carbon_router_during_refresh(c: &mut Criterion) {
+    let rt = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(2)
+        .enable_all()
+        .build()
+        .unwrap();
+    // A zero TTL sends every refresh to the (slow) API
+    let router = Arc::new(rt.block_on(router_with_regions(
+        100,
+        BenchClient {
+            delay: Duration::from_millis(1),
+        },
+        CarbonIntensityCache::new(0),
+    )));
+
+    let refresher = router.clone();
+    let refresh = rt.spawn(async move {
+        loop {
+            refresher.refresh_carbon_data().await.unwrap();
+        }
+    });
+
+    let mut group = c.benchmark_group("routing/carbon_router_during_refresh");
+    group.bench_function("select_greenest_region", |b| {
+        b.iter(|| rt.block_on(async { black_box(router.select_greenest_region().await) }))
+    });
+    group.bench_function("get_routing_weight", |b| {
+        b.iter(|| rt.block_on(async { black_box(router.get_routing_weight("region-50").await) }))
+    });
+    group.finish();
+
+    refresh.abort();
+}
+
+fn bench_process_ready_jobs_full_queue(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let mut group = c.benchmark_group("routing/green_wait");
+    // Filling the persistent queue dominates each sample
+    group.sample_size(10);
+
+    group.bench_function("process_ready_jobs_full_queue_1000", |b| {
+        b.iter_batched(
+            || scheduler_with_full_queue(&rt, 1000),
+            |(scheduler, _temp_file)| {
+                rt.block_on(async { black_box(scheduler.process_ready_jobs().await) })
+            },
+            BatchSize::PerIteration,
+        )
+    });
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_small_input,
+    bench_carbon_router,
+    bench_carbon_router_during_refresh,
+    bench_process_ready_jobs_full_queue,
+);
+
+criterion_main!(benches);
@@ -1,13 +1,18 @@
+use crate::carbon_router::{CarbonRouter, RegionSnapshot};
+use aegis_energy::EnergyApiClient;
 use axum::{
     Json, Router,
     extract::{Path, State},
     http::StatusCode,
     response::IntoResponse,
+    response::sse::{Event, KeepAlive, Sse},
     routing::{delete, get, post, put},
 };
+use futures_util::{Stream, stream};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
+use tokio::sync::broadcast::error::RecvError;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServerBlock {
@@ -105,6 +110,76 @@ pub async fn get_status(State(state): State<ConfigState>) -> impl IntoResponse {
     Json(status)
 }
 
+// GET /admin/carbon/snapshot
+pub async fn list_carbon_snapshots<C: EnergyApiClient + 'static>(
+    State(router): State<Arc<CarbonRouter<C>>>,
+) -> impl IntoResponse {
+    let mut snapshots: Vec<RegionSnapshot> = Vec::new();
+    for region in router.get_regions().await {
+        if let Some(snapshot) = router.region_snapshot(&region.id).await {
+            snapshots.push(snapshot);
+        }
+    }
+    Json(snapshots)
+}
+
+// GET /admin/carbon/snapshot/:region
+pub async fn get_carbon_snapshot<C: EnergyApiClient + 'static>(
+    State(router): State<Arc<CarbonRouter<C>>>,
+    Path(region): Path<String>,
+) -> impl IntoResponse {
+    match router.region_snapshot(&region).await {
+        Some(snapshot) => (StatusCode::OK, Json(snapshot)).into_response(),
+        None => (StatusCode::NOT_FOUND, "Not found").into_response(),
+    }
+}
+
+// GET /admin/carbon/decisions
+pub async fn list_carbon_decisions<C: EnergyApiClient + 'static>(
+    State(router): State<Arc<CarbonRouter<C>>>,
+) -> impl IntoResponse {
+    Json(router.recent_decisions().await)
+}
+
+// GET /admin/carbon/stream
+//
+// One `carbon_intensity` event per region score published by a refresh.
+// Slow clients skip the updates they fell behind on; the subscription ends
+// when the client disconnects and the stream is dropped.
+pub async fn stream_carbon_updates<C: EnergyApiClient + 'static>(
+    State(router): State<Arc<CarbonRouter<C>>>,
+) -> Sse<impl Stream<Item = Result<Event, axum::Error>>> {
+    let events = stream::unfold(router.subscribe_updates(), |mut updates| async move {
+        loop {
+            match updates.recv().await {
+                Ok(score) => {
+                    let event = Event::default().event("carbon_intensity").json_data(&score);
+                    return Some((event, updates));
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::debug!("Carbon stream client lagged, skipped {} updates", skipped);
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    });
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
+
+/// Routes exposing [`CarbonRouter::region_snapshot`], the routing decision
+/// log and live score updates for dashboards
+pub fn carbon_routes<C: EnergyApiClient + 'static>(router: Arc<CarbonRouter<C>>) -> Router {
+    Router::new()
+        .route("/admin/carbon/snapshot", get(list_carbon_snapshots::<C>))
+        .route("/admin/carbon/decisions", get(list_carbon_decisions::<C>))
+        .route("/admin/carbon/stream", get(stream_carbon_updates::<C>))
+        .route(
+            "/admin/carbon/snapshot/{region}",
+            get(get_carbon_snapshot::<C>),
+        )
+        .with_state(router)
+}
+
 pub fn create_router(state: ConfigState) -> Router {
     Router::new()
         .route("/config/servers", get(list_servers).post(add_server))
@@ -215,4 +290,189 @@ mod tests {
 
         assert_eq!(state.read().unwrap().version, 1);
     }
+
+    /// Energy client with fixed current and forecast intensities
+    struct FixedForecast;
+
+    impl EnergyApiClient for FixedForecast {
+        async fn get_carbon_intensity(
+            &self,
+            region: &aegis_energy::Region,
+        ) -> Result<aegis_energy::CarbonIntensity, aegis_energy::EnergyApiError> {
+            Ok(aegis_energy::CarbonIntensity {
+                region: region.clone(),
+                value: 180.0,
+                timestamp: chrono::Utc::now(),
+                valid_for_seconds: 300,
+                rating: None,
+                renewable_percentage: Some(40.0),
+                power_breakdown: None,
+            })
+        }
+
+        async fn get_carbon_intensity_by_location(
+            &self,
+            _latitude: f64,
+            _longitude: f64,
+        ) -> Result<aegis_energy::CarbonIntensity, aegis_energy::EnergyApiError> {
+            unimplemented!()
+        }
+
+        async fn get_region_for_location(
+            &self,
+            _latitude: f64,
+            _longitude: f64,
+        ) -> Result<aegis_energy::Region, aegis_energy::EnergyApiError> {
+            unimplemented!()
+        }
+
+        async fn get_carbon_forecast(
+            &self,
+            _region: &aegis_energy::Region,
+            _hours: u32,
+        ) -> Result<Vec<aegis_energy::ForecastPoint>, aegis_energy::EnergyApiError> {
+            let at = |hours| chrono::Utc::now() + chrono::Duration::hours(hours);
+            Ok(vec![
+                aegis_energy::ForecastPoint {
+                    timestamp: at(1),
+                    predicted_intensity: 150.0,
+                    confidence: None,
+                },
+                aegis_energy::ForecastPoint {
+                    timestamp: at(2),
+                    predicted_intensity: 90.0,
+                    confidence: None,
+                },
+            ])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_carbon_snapshot_endpoint() {
+        let router = Arc::new(CarbonRouter::new(
+            crate::carbon_router::CarbonRouterConfig::default(),
+            FixedForecast,
+            aegis_energy::CarbonIntensityCache::new(300),
+        ));
+        router
+            .register_region(aegis_energy::Region::new("eu-north", "EU North"))
+            .await;
+        router.refresh_carbon_data().await.unwrap();
+
+        let resp = carbon_routes(router.clone())
+            .oneshot(
+                Request::builder()
+                    .uri("/admin/carbon/snapshot/eu-north")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = resp.into_body().collect().await.unwrap().to_bytes();
+        let snapshot: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(snapshot["score"]["carbon_intensity"], 180.0);
+        assert_eq!(snapshot["renewable_percentage"], 40.0);
+        assert_eq!(snapshot["forecast"].as_array().unwrap().len(), 2);
+        assert_eq!(
+            snapshot["valley_time"],
+            snapshot["forecast"][1]["timestamp"]
+        );
+
+        let resp = carbon_routes(router.clone())
+            .oneshot(
+                Request::builder()
+                    .uri("/admin/carbon/snapshot")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = resp.into_body().collect().await.unwrap().to_bytes();
+        let all: Vec<serde_json::Value> = serde_json::from_slice(&body).unwrap();
+        assert_eq!(all.len(), 1);
+
+        let resp = carbon_routes(router)
+            .oneshot(
+                Request::builder()
+                    .uri("/admin/carbon/snapshot/unknown")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_carbon_decisions_endpoint() {
+        let router = Arc::new(CarbonRouter::new(
+            crate::carbon_router::CarbonRouterConfig::default(),
+            FixedForecast,
+            aegis_energy::CarbonIntensityCache::new(300),
+        ));
+        router
+            .register_region(aegis_energy::Region::new("eu-north", "EU North"))
+            .await;
+        router.refresh_carbon_data().await.unwrap();
+        router.select_greenest_region().await;
+
+        let resp = carbon_routes(router)
+            .oneshot(
+                Request::builder()
+                    .uri("/admin/carbon/decisions")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = resp.into_body().collect().await.unwrap().to_bytes();
+        let decisions: Vec<serde_json::Value> = serde_json::from_slice(&body).unwrap();
+        assert_eq!(decisions.len(), 1);
+        assert_eq!(decisions[0]["chosen"], "eu-north");
+        assert_eq!(decisions[0]["reason"], "greenest");
+        assert_eq!(decisions[0]["candidates"][0]["carbon_intensity"], 180.0);
+    }
+
+    #[tokio::test]
+    async fn test_carbon_stream_pushes_refreshed_intensity() {
+        let router = Arc::new(CarbonRouter::new(
+            crate::carbon_router::CarbonRouterConfig::default(),
+            FixedForecast,
+            aegis_energy::CarbonIntensityCache::new(300),
+        ));
+        router
+            .register_region(aegis_energy::Region::new("eu-north", "EU North"))
+            .await;
+
+        let resp = carbon_routes(router.clone())
+            .oneshot(
+                Request::builder()
+                    .uri("/admin/carbon/stream")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers()["content-type"], "text/event-stream");
+
+        router.refresh_carbon_data().await.unwrap();
+        let mut body = resp.into_body();
+        let frame = tokio::time::timeout(std::time::Duration::from_secs(5), body.frame())
+            .await
+            .expect("no event received")
+            .unwrap()
+            .unwrap();
+        let event = String::from_utf8(frame.into_data().unwrap().to_vec()).unwrap();
+        assert!(event.starts_with("event: carbon_intensity\n"), "{event}");
+        let data = event
+            .lines()
+            .find_map(|line| line.strip_prefix("data: "))
+            .unwrap();
+        let score: serde_json::Value = serde_json::from_str(data).unwrap();
+        assert_eq!(score["region_id"], "eu-north");
+        assert_eq!(score["carbon_intensity"], 180.0);
+    }
 }
@@ -0,0 +1,501 @@
+//! Carbon-Aware Admission Control
+//!
+//! Decides per request whether to serve it now, defer it to a greener window
+//! through the [`GreenWaitScheduler`], or reject it. While the grid is clean
+//! everything is admitted; once carbon intensity climbs past the configured
+//! threshold only sufficiently urgent requests are served immediately.
+
+use crate::green_wait::{
+    DeferredJob, GreenWaitScheduler, JobPriority, PayloadCodec, PayloadError, ScheduleResult,
+};
+use aegis_energy::{EnergyApiClient, Region};
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper::{Request, Response, StatusCode, header::RETRY_AFTER, http::request::Parts};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tracing::{debug, info, warn};
+
+/// Admission controller configuration
+#[derive(Debug, Clone)]
+pub struct AdmissionConfig {
+    /// Region whose carbon intensity gates admission
+    pub region: Region,
+    /// Request header carrying an explicit priority (e.g. `x-priority: low`)
+    pub priority_header: String,
+    /// Honour `priority_header`
+    ///
+    /// Clients can claim any priority through the header, including
+    /// `critical` to bypass deferral, so turn this off unless a trusted hop
+    /// in front of the proxy sets or strips it.
+    pub trust_priority_header: bool,
+    /// Path prefix rules; the longest matching prefix wins
+    pub path_priorities: Vec<(String, JobPriority)>,
+    /// Priority for requests matching neither header nor path rule
+    pub default_priority: JobPriority,
+    /// Carbon intensity (gCO2/kWh) above which admission is restricted
+    pub high_intensity_threshold: f64,
+    /// Least urgent priority still admitted at high intensity
+    pub min_admitted_priority: JobPriority,
+}
+
+impl Default for AdmissionConfig {
+    fn default() -> Self {
+        Self {
+            region: Region::new("default", "Default"),
+            priority_header: "x-priority".to_string(),
+            trust_priority_header: true,
+            path_priorities: Vec::new(),
+            default_priority: JobPriority::Normal,
+            high_intensity_threshold: 300.0,
+            min_admitted_priority: JobPriority::High,
+        }
+    }
+}
+
+/// Outcome of an admission check
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AdmissionDecision {
+    /// Serve the request now
+    Admit,
+    /// Request was queued for a greener window
+    Defer { job_id: String, position: usize },
+    /// Request cannot be served or queued
+    Reject,
+}
+
+impl AdmissionDecision {
+    /// Response to send instead of proxying, `None` when admitted
+    pub fn into_response(self) -> Option<Response<Full<Bytes>>> {
+        let response = match self {
+            AdmissionDecision::Admit => return None,
+            AdmissionDecision::Defer { job_id, position } => Response::builder()
+                .status(StatusCode::ACCEPTED)
+                .header("content-type", "application/json")
+                .body(Full::new(Bytes::from(
+                    serde_json::json!({
+                        "status": "deferred",
+                        "job_id": job_id,
+                        "position": position,
+                    })
+                    .to_string(),
+                ))),
+            AdmissionDecision::Reject => Response::builder()
+                .status(StatusCode::SERVICE_UNAVAILABLE)
+                .header(RETRY_AFTER, 60)
+                .header("content-type", "application/json")
+                .body(Full::new(Bytes::from(
+                    "{\"error\":\"admission_rejected\",\"message\":\"Carbon intensity too high\"}",
+                ))),
+        };
+        Some(response.unwrap())
+    }
+}
+
+/// Headers that only describe the original connection and are not replayed
+const HOP_BY_HOP_HEADERS: &[&str] = &[
+    "connection",
+    "keep-alive",
+    "proxy-authenticate",
+    "proxy-authorization",
+    "proxy-connection",
+    "te",
+    "trailer",
+    "transfer-encoding",
+    "upgrade",
+];
+
+/// A deferred request, stored as the job payload so it can be replayed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeferredRequest {
+    /// Request method
+    pub method: String,
+    /// Request URI, including the query
+    pub uri: String,
+    /// End-to-end headers in their original order
+    pub headers: Vec<(String, Vec<u8>)>,
+    /// Request body
+    pub body: Vec<u8>,
+}
+
+impl DeferredRequest {
+    /// Capture a request, dropping hop-by-hop headers
+    pub fn from_parts(parts: &Parts, body: &[u8]) -> Self {
+        Self {
+            method: parts.method.to_string(),
+            uri: parts.uri.to_string(),
+            headers: parts
+                .headers
+                .iter()
+                .filter(|(name, _)| !HOP_BY_HOP_HEADERS.contains(&name.as_str()))
+                .map(|(name, value)| (name.to_string(), value.as_bytes().to_vec()))
+                .collect(),
+            body: body.to_vec(),
+        }
+    }
+
+    /// Decode the request queued by [`AdmissionController::admit`]
+    pub fn from_job(job: &DeferredJob) -> Result<Self, PayloadError> {
+        job.decode_payload(PayloadCodec::Bincode)
+    }
+
+    /// Rebuild the request for replay
+    pub fn into_request(self) -> hyper::http::Result<Request<Full<Bytes>>> {
+        let mut builder = Request::builder()
+            .method(self.method.as_str())
+            .uri(self.uri);
+        for (name, value) in self.headers {
+            builder = builder.header(name, value);
+        }
+        builder.body(Full::new(Bytes::from(self.body)))
+    }
+}
+
+/// Carbon-aware admission controller
+pub struct AdmissionController<C: EnergyApiClient> {
+    config: AdmissionConfig,
+    scheduler: Arc<GreenWaitScheduler<C>>,
+    next_job: AtomicU64,
+}
+
+impl<C: EnergyApiClient + Send + Sync + 'static> AdmissionController<C> {
+    /// Create a controller deferring through `scheduler`
+    pub fn new(config: AdmissionConfig, scheduler: Arc<GreenWaitScheduler<C>>) -> Self {
+        Self {
+            config,
+            scheduler,
+            next_job: AtomicU64::new(0),
+        }
+    }
+
+    /// Resolve a request's priority from its header, then path rules
+    ///
+    /// The header is ignored unless `trust_priority_header` is set.
+    pub fn priority_for(&self, parts: &Parts) -> JobPriority {
+        if self.config.trust_priority_header
+            && let Some(priority) = parts
+                .headers
+                .get(&self.config.priority_header)
+                .and_then(|v| v.to_str().ok())
+                .and_then(JobPriority::from_name)
+        {
+            return priority;
+        }
+
+        let path = parts.uri.path();
+        self.config
+            .path_priorities
+            .iter()
+            .filter(|(prefix, _)| path.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, priority)| *priority)
+            .unwrap_or(self.config.default_priority)
+    }
+
+    /// Decide whether to admit, defer or reject a request
+    ///
+    /// Deferred requests are queued with a [`DeferredRequest`] as the job
+    /// payload, so the driver's consumer can replay them. When the region's
+    /// intensity is unknown the request is admitted.
+    pub async fn admit(&self, parts: &Parts, body: Bytes) -> AdmissionDecision {
+        let priority = self.priority_for(parts);
+        let intensity = self
+            .scheduler
+            .get_region_intensity(&self.config.region.id)
+            .await;
+
+        let Some(intensity) = intensity else {
+            return AdmissionDecision::Admit;
+        };
+        if intensity <= self.config.high_intensity_threshold
+            || priority <= self.config.min_admitted_priority
+        {
+            debug!(?priority, intensity, "Admitting request");
+            return AdmissionDecision::Admit;
+        }
+
+        let job_id = format!(
+            "{} {}#{}",
+            parts.method,
+            parts.uri.path(),
+            self.next_job.fetch_add(1, Ordering::Relaxed)
+        );
+        let job = match DeferredJob::new_typed(
+            job_id.clone(),
+            priority,
+            self.config.region.clone(),
+            self.config.high_intensity_threshold,
+            &DeferredRequest::from_parts(parts, &body),
+            PayloadCodec::Bincode,
+        ) {
+            Ok(job) => job,
+            Err(e) => {
+                warn!(job_id = %job_id, error = %e, "Failed to encode deferred request, rejecting it");
+                return AdmissionDecision::Reject;
+            }
+        };
+
+        match self.scheduler.submit(job).await {
+            ScheduleResult::Queued { position } => {
+                info!(job_id = %job_id, ?priority, intensity, "Request deferred to green window");
+                AdmissionDecision::Defer { job_id, position }
+            }
+            ScheduleResult::ExecutedImmediately | ScheduleResult::Disabled => {
+                AdmissionDecision::Admit
+            }
+            ScheduleResult::QueueFull => {
+                warn!(job_id = %job_id, ?priority, intensity, "Green-Wait queue full, rejecting request");
+                AdmissionDecision::Reject
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::green_wait::GreenWaitConfig;
+    use aegis_energy::{CarbonIntensity, CarbonIntensityCache, EnergyApiError, ForecastPoint};
+    use hyper::Request;
+
+    struct MockClient;
+
+    impl EnergyApiClient for MockClient {
+        async fn get_carbon_intensity(
+            &self,
+            region: &Region,
+        ) -> Result<CarbonIntensity, EnergyApiError> {
+            Err(EnergyApiError::RegionNotFound {
+                region_id: region.id.clone(),
+            })
+        }
+
+        async fn get_carbon_intensity_by_location(
+            &self,
+            _lat: f64,
+            _lon: f64,
+        ) -> Result<CarbonIntensity, EnergyApiError> {
+            Err(EnergyApiError::ConfigError("unsupported".to_string()))
+        }
+
+        async fn get_region_for_location(
+            &self,
+            _lat: f64,
+            _lon: f64,
+        ) -> Result<Region, EnergyApiError> {
+            Err(EnergyApiError::ConfigError("unsupported".to_string()))
+        }
+
+        async fn get_carbon_forecast(
+            &self,
+            _region: &Region,
+            _hours: u32,
+        ) -> Result<Vec<ForecastPoint>, EnergyApiError> {
+            Ok(vec![])
+        }
+    }
+
+    async fn controller(
+        intensity: Option<f64>,
+        max_queue_size: usize,
+    ) -> (AdmissionController<MockClient>, tempfile::NamedTempFile) {
+        let db = tempfile::NamedTempFile::new().unwrap();
+        let config = GreenWaitConfig {
+            max_queue_size,
+            ..Default::default()
+        };
+        let scheduler = GreenWaitScheduler::new(
+            config,
+            MockClient,
+            CarbonIntensityCache::new(300),
+            db.path(),
+        )
+        .unwrap();
+        if let Some(intensity) = intensity {
+            scheduler
+                .update_region_intensity("eu-west", intensity)
+                .await;
+        }
+
+        let config = AdmissionConfig {
+            region: Region::new("eu-west", "EU West"),
+            path_priorities: vec![
+                ("/api".to_string(), JobPriority::High),
+                ("/api/reports".to_string(), JobPriority::Low),
+            ],
+            ..Default::default()
+        };
+        (AdmissionController::new(config, Arc::new(scheduler)), db)
+    }
+
+    fn parts(path: &str, priority: Option<&str>) -> Parts {
+        let mut builder = Request::builder().method("POST").uri(path);
+        if let Some(priority) = priority {
+            builder = builder.header("x-priority", priority);
+        }
+        builder.body(()).unwrap().into_parts().0
+    }
+
+    #[tokio::test]
+    async fn test_priority_resolution() {
+        let (controller, _db) = controller(None, 10).await;
+
+        assert_eq!(
+            controller.priority_for(&parts("/", None)),
+            JobPriority::Normal
+        );
+        assert_eq!(
+            controller.priority_for(&parts("/api/users", None)),
+            JobPriority::High
+        );
+        assert_eq!(
+            controller.priority_for(&parts("/api/reports/daily", None)),
+            JobPriority::Low
+        );
+        // Header overrides path rules; unknown values fall back to them
+        assert_eq!(
+            controller.priority_for(&parts("/api/reports", Some("critical"))),
+            JobPriority::Critical
+        );
+        assert_eq!(
+            controller.priority_for(&parts("/api/users", Some("bogus"))),
+            JobPriority::High
+        );
+    }
+
+    #[tokio::test]
+    async fn test_low_intensity_admits_everything() {
+        let (controller, _db) = controller(Some(100.0), 10).await;
+
+        for priority in ["critical", "high", "normal", "low", "background"] {
+            let decision = controller
+                .admit(&parts("/jobs", Some(priority)), Bytes::new())
+                .await;
+            assert_eq!(decision, AdmissionDecision::Admit, "{}", priority);
+        }
+        assert_eq!(controller.scheduler.queue_length().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_high_intensity_defers_low_priority() {
+        let (controller, _db) = controller(Some(450.0), 10).await;
+
+        let decision = controller
+            .admit(&parts("/jobs", Some("critical")), Bytes::new())
+            .await;
+        assert_eq!(decision, AdmissionDecision::Admit);
+        let decision = controller
+            .admit(&parts("/api/users", None), Bytes::new())
+            .await;
+        assert_eq!(decision, AdmissionDecision::Admit);
+
+        let decision = controller
+            .admit(&parts("/api/reports", None), Bytes::from_static(b"payload"))
+            .await;
+        let AdmissionDecision::Defer { job_id, position } = decision.clone() else {
+            panic!("expected deferral, got {:?}", decision);
+        };
+        assert_eq!(job_id, "POST /api/reports#0");
+        assert_eq!(position, 0);
+
+        let response = decision.into_response().unwrap();
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+        // Deferred job is released once the grid turns green
+        controller
+            .scheduler
+            .update_region_intensity("eu-west", 100.0)
+            .await;
+        let ready = controller.scheduler.process_ready_jobs().await;
+        assert_eq!(ready.len(), 1);
+        assert_eq!(ready[0].priority, JobPriority::Low);
+        let request = DeferredRequest::from_job(&ready[0]).unwrap();
+        assert_eq!(request.body, b"payload");
+    }
+
+    #[tokio::test]
+    async fn test_priority_header_ignored_unless_trusted() {
+        let (mut controller, _db) = controller(None, 10).await;
+        controller.config.trust_priority_header = false;
+
+        assert_eq!(
+            controller.priority_for(&parts("/api/reports", Some("critical"))),
+            JobPriority::Low
+        );
+    }
+
+    #[tokio::test]
+    async fn test_driver_replays_deferred_request() {
+        let (controller, _db) = controller(Some(450.0), 10).await;
+        let request = Request::builder()
+            .method("PUT")
+            .uri("/api/reports/daily?format=csv")
+            .header("content-type", "text/csv")
+            .header("authorization", "Bearer token")
+            .header("connection", "keep-alive")
+            .body(())
+            .unwrap();
+        let (parts, ()) = request.into_parts();
+        let decision = controller
+            .admit(&parts, Bytes::from_static(b"a,b\n1,2\n"))
+            .await;
+        assert!(matches!(decision, AdmissionDecision::Defer { .. }));
+
+        controller
+            .scheduler
+            .update_region_intensity("eu-west", 100.0)
+            .await;
+        let lifecycle = crate::lifecycle::LifecycleManager::new();
+        let (mut rx, handle) = controller
+            .scheduler
+            .spawn_driver(lifecycle.shutdown_receiver());
+        let job = tokio::time::timeout(std::time::Duration::from_secs(3), rx.recv())
+            .await
+            .expect("job not released")
+            .unwrap();
+
+        let replayed = DeferredRequest::from_job(&job)
+            .unwrap()
+            .into_request()
+            .unwrap();
+        assert_eq!(replayed.method(), "PUT");
+        assert_eq!(replayed.uri(), "/api/reports/daily?format=csv");
+        assert_eq!(replayed.headers()["content-type"], "text/csv");
+        assert_eq!(replayed.headers()["authorization"], "Bearer token");
+        assert!(!replayed.headers().contains_key("connection"));
+        let body = http_body_util::BodyExt::collect(replayed.into_body())
+            .await
+            .unwrap()
+            .to_bytes();
+        assert_eq!(&body[..], b"a,b\n1,2\n");
+
+        lifecycle.initiate_shutdown().await;
+        handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_high_intensity_rejects_when_queue_full() {
+        let (controller, _db) = controller(Some(450.0), 1).await;
+
+        let decision = controller.admit(&parts("/jobs", None), Bytes::new()).await;
+        assert!(matches!(decision, AdmissionDecision::Defer { .. }));
+
+        let decision = controller.admit(&parts("/jobs", None), Bytes::new()).await;
+        assert_eq!(decision, AdmissionDecision::Reject);
+        let response = decision.into_response().unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert!(response.headers().contains_key(RETRY_AFTER));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_intensity_admits() {
+        let (controller, _db) = controller(None, 10).await;
+
+        let decision = controller
+            .admit(&parts("/jobs", Some("background")), Bytes::new())
+            .await;
+        assert_eq!(decision, AdmissionDecision::Admit);
+        assert!(AdmissionDecision::Admit.into_response().is_none());
+    }
+}
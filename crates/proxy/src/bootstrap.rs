@@ -108,6 +108,7 @@ where
     // They don't take shutdown.
     // We need to run them IN SELECT with the shutdown signal.
 
+    let server_lifecycle = lifecycle.clone();
     let server_task = async move {
         // Spawn configured L4 Streams
         for stream_cfg in &config.streams {
@@ -134,8 +135,8 @@ where
 
         if config.pqc_enabled {
             info!("🛡️ PQC mode enabled - using hybrid key exchange");
-            let pqc_server = PqcProxyServer::new(config);
-            pqc_server.run().await
+            let pqc_server = PqcProxyServer::new(config).with_lifecycle(server_lifecycle);
+            Ok(pqc_server.run().await?)
         } else {
             info!("🔓 PQC disabled - using plain HTTP/2 proxy");
 
@@ -178,14 +179,30 @@ where
                 manager.clone().start_background_renewal();
             }
 
+            let listen_addr = crate::listener::join_host_port(&config.host, config.port);
             let http_config = HttpProxyConfig {
-                listen_addr: format!("{}:{}", config.host, config.port).parse().unwrap(),
+                listen_addr: crate::listener::parse_socket_addr(&listen_addr).unwrap(),
                 upstream_addr: config.upstream_addr.clone(),
+                upstream_protocol: config.upstream_protocol,
+                circuit_breaker: config.circuit_breaker.clone(),
                 acme_manager,
                 tls_server_config,
+                listener: config.listener.clone(),
+                energy_budget: config.energy_budget.as_ref().map(|budget| {
+                    std::sync::Arc::new(crate::energy_budget::EnergyBudget::new(
+                        budget.ceiling_joules,
+                        std::time::Duration::from_secs(budget.window_secs),
+                    ))
+                }),
+                max_header_bytes: config.max_header_bytes,
+                request_timeout: config
+                    .request_timeout_ms
+                    .map(std::time::Duration::from_millis),
+                routes: config.routes.clone(),
+                cors: config.cors.clone(),
                 ..Default::default()
             };
-            let http_proxy = HttpProxy::new(http_config);
+            let http_proxy = HttpProxy::new(http_config).with_lifecycle(server_lifecycle);
             http_proxy.run().await
         }
     };
@@ -194,7 +211,14 @@ where
         result = server_task => result,
         _ = shutdown => {
             info!("🛑 Bootstrapping interrupt received - shutting down");
-            Ok(())
+            match lifecycle.initiate_shutdown().await {
+                Some(report) if report.forced => Err(anyhow::anyhow!(
+                    "Shutdown forcibly dropped {} connections after {:?}",
+                    report.remaining_connections,
+                    report.drain_duration
+                )),
+                _ => Ok(()),
+            }
         }
     }
 }
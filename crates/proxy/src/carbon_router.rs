@@ -3,10 +3,18 @@
 //! Routes traffic based on carbon intensity data from energy APIs.
 //! Implements spatial arbitrage - selecting regions with lowest carbon footprint.
 
-use aegis_energy::{CarbonIntensityCache, EnergyApiClient, Region};
-use std::collections::HashMap;
+use crate::config::ConfigError;
+use crate::lifecycle::ShutdownReceiver;
+use crate::metrics;
+use aegis_common::{SharedClock, SystemClock};
+use aegis_energy::{CarbonIntensity, CarbonIntensityCache, EnergyApiClient, ForecastPoint, Region};
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::{RwLock, broadcast};
+use tokio::task::JoinHandle;
 use tracing::{debug, info, warn};
 
 /// Carbon-aware router configuration
@@ -18,12 +26,49 @@ pub struct CarbonRouterConfig {
     pub threshold: f64,
     /// Maximum acceptable carbon intensity (hard limit)
     pub max_intensity: f64,
-    /// Prefer renewable energy sources
+    /// Prefer renewable energy sources (enables `renewable_bonus`)
     pub prefer_renewable: bool,
+    /// Score reduction for a fully renewable region
+    ///
+    /// When `prefer_renewable` is set, a region's score is
+    /// `min(intensity / max_intensity, 1.0) - renewable_bonus * renewable_percentage / 100`,
+    /// clamped to `0.0..=1.0`. With the default of 0.1, a 100% renewable region
+    /// outranks a 0% renewable one unless the latter's intensity is lower by more
+    /// than 10% of `max_intensity`. Regions without renewable data get no bonus.
+    pub renewable_bonus: f64,
     /// Region preferences (fallback order)
+    ///
+    /// Breaks ties between equally scored regions, and when no region is
+    /// within `max_intensity` (or none has carbon data) the first registered
+    /// entry is selected instead of none.
     pub preferred_regions: Vec<String>,
     /// Weight factor for carbon intensity in routing decisions (0.0-1.0)
+    ///
+    /// A region with a known latency scores
+    /// `carbon_weight * carbon_score + (1 - carbon_weight) * min(latency_ms / max_latency_ms, 1.0)`;
+    /// without a latency measurement the carbon score is used on its own.
     pub carbon_weight: f64,
+    /// Latency (ms) that normalises to the worst latency score
+    pub max_latency_ms: f64,
+    /// Log and export routing recommendations without acting on them
+    pub dry_run: bool,
+    /// Upstream address for each region id, used by `select_upstream`
+    pub region_upstreams: HashMap<String, String>,
+    /// How much greener (gCO2/kWh) an alternative must be to replace the current region
+    ///
+    /// Compared as `(current.score - candidate.score) * max_intensity`, which is
+    /// the plain intensity difference when no renewable bonus or latency applies.
+    pub switch_margin: f64,
+    /// How long an alternative must stay greener by `switch_margin` before switching
+    pub min_switch_duration: Duration,
+    /// Consecutive refreshes an alternative must stay greener by `switch_margin`
+    /// before switching, on top of `min_switch_duration`
+    ///
+    /// Counted when selections observe a new refresh; a selection that finds the
+    /// alternative no longer ahead restarts the count. `0` disables the check.
+    pub switch_hold_refreshes: u32,
+    /// Most recent routing decisions kept for `recent_decisions`; `0` disables the log
+    pub decision_log_capacity: usize,
 }
 
 impl Default for CarbonRouterConfig {
@@ -33,25 +78,280 @@ impl Default for CarbonRouterConfig {
             threshold: 200.0,     // 200 gCO2/kWh is considered "moderate"
             max_intensity: 500.0, // Above this is high-carbon
             prefer_renewable: true,
+            renewable_bonus: 0.1,
             preferred_regions: vec![],
             carbon_weight: 0.5, // Balance between latency and carbon
+            max_latency_ms: 500.0,
+            dry_run: false,
+            region_upstreams: HashMap::new(),
+            switch_margin: 0.0,
+            min_switch_duration: Duration::ZERO,
+            switch_hold_refreshes: 0,
+            decision_log_capacity: 256,
         }
     }
 }
 
+impl CarbonRouterConfig {
+    /// Start from the defaults and build a validated configuration
+    pub fn builder() -> CarbonRouterConfigBuilder {
+        CarbonRouterConfigBuilder::default()
+    }
+
+    /// Check that weights and intensities describe meaningful scores
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let invalid = |msg: String| Err(ConfigError::ValidationError(msg));
+        if !(0.0..=1.0).contains(&self.carbon_weight) {
+            return invalid(format!(
+                "carbon_weight must be within 0.0-1.0, got {}",
+                self.carbon_weight
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.renewable_bonus) {
+            return invalid(format!(
+                "renewable_bonus must be within 0.0-1.0, got {}",
+                self.renewable_bonus
+            ));
+        }
+        for (name, value) in [
+            ("threshold", self.threshold),
+            ("max_intensity", self.max_intensity),
+            ("switch_margin", self.switch_margin),
+            ("max_latency_ms", self.max_latency_ms),
+        ] {
+            if !value.is_finite() || value < 0.0 {
+                return invalid(format!(
+                    "{} must be a non-negative intensity, got {}",
+                    name, value
+                ));
+            }
+        }
+        // Scores are normalised by max_intensity and max_latency_ms
+        if self.max_intensity == 0.0 {
+            return invalid("max_intensity must be positive".to_string());
+        }
+        if self.max_latency_ms == 0.0 {
+            return invalid("max_latency_ms must be positive".to_string());
+        }
+        if self.threshold > self.max_intensity {
+            return invalid(format!(
+                "threshold ({}) must not exceed max_intensity ({})",
+                self.threshold, self.max_intensity
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Builder for [`CarbonRouterConfig`] that validates on [`build`](Self::build)
+#[derive(Debug, Clone, Default)]
+pub struct CarbonRouterConfigBuilder {
+    config: CarbonRouterConfig,
+}
+
+impl CarbonRouterConfigBuilder {
+    /// Enable or disable carbon-aware routing
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.config.enabled = enabled;
+        self
+    }
+
+    /// Intensity (gCO2/kWh) below which regions are preferred
+    pub fn with_threshold(mut self, threshold: f64) -> Self {
+        self.config.threshold = threshold;
+        self
+    }
+
+    /// Hard limit on acceptable intensity (gCO2/kWh)
+    pub fn with_max_intensity(mut self, max_intensity: f64) -> Self {
+        self.config.max_intensity = max_intensity;
+        self
+    }
+
+    /// Favour renewable regions by up to `bonus` of the normalised score
+    pub fn with_renewable_bonus(mut self, prefer_renewable: bool, bonus: f64) -> Self {
+        self.config.prefer_renewable = prefer_renewable;
+        self.config.renewable_bonus = bonus;
+        self
+    }
+
+    /// Fallback order of regions
+    pub fn with_preferred_regions(mut self, regions: Vec<String>) -> Self {
+        self.config.preferred_regions = regions;
+        self
+    }
+
+    /// Weight of carbon intensity in routing decisions (0.0-1.0)
+    pub fn with_carbon_weight(mut self, carbon_weight: f64) -> Self {
+        self.config.carbon_weight = carbon_weight;
+        self
+    }
+
+    /// Latency (ms) at or above which a region gets the worst latency score
+    pub fn with_max_latency_ms(mut self, max_latency_ms: f64) -> Self {
+        self.config.max_latency_ms = max_latency_ms;
+        self
+    }
+
+    /// Only log and export recommendations
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.config.dry_run = dry_run;
+        self
+    }
+
+    /// Route `region_id` to `upstream` in `select_upstream`
+    pub fn with_region_upstream(
+        mut self,
+        region_id: impl Into<String>,
+        upstream: impl Into<String>,
+    ) -> Self {
+        self.config
+            .region_upstreams
+            .insert(region_id.into(), upstream.into());
+        self
+    }
+
+    /// Hysteresis before switching to a greener region
+    pub fn with_switch_hysteresis(mut self, margin: f64, min_duration: Duration) -> Self {
+        self.config.switch_margin = margin;
+        self.config.min_switch_duration = min_duration;
+        self
+    }
+
+    /// Consecutive refreshes an alternative must lead by `switch_margin`
+    pub fn with_switch_hold_refreshes(mut self, refreshes: u32) -> Self {
+        self.config.switch_hold_refreshes = refreshes;
+        self
+    }
+
+    /// Number of routing decisions kept for `/admin/carbon/decisions`
+    pub fn with_decision_log_capacity(mut self, capacity: usize) -> Self {
+        self.config.decision_log_capacity = capacity;
+        self
+    }
+
+    /// Validate and return the configuration
+    pub fn build(self) -> Result<CarbonRouterConfig, ConfigError> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
+}
+
 /// Represents a routable region with its carbon data
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct RegionScore {
     /// Region identifier
     pub region_id: String,
     /// Current carbon intensity
     pub carbon_intensity: f64,
-    /// Normalized score (0.0 = best, 1.0 = worst)
+    /// Last measured latency to the region, if any
+    pub latency_ms: Option<f64>,
+    /// Normalized score blending carbon and latency (0.0 = best, 1.0 = worst)
     pub score: f64,
     /// Is this region currently recommended
     pub recommended: bool,
 }
 
+/// Whether a [`CarbonRouter`] is able to route, and if not why
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RouterState {
+    /// Carbon routing is turned off in the configuration
+    Disabled,
+    /// No region has been registered
+    NoRegions,
+    /// Regions are registered but none has been scored yet
+    NoData,
+    /// At least one region has carbon data
+    Ready,
+}
+
+impl std::fmt::Display for RouterState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Disabled => write!(f, "disabled"),
+            Self::NoRegions => write!(f, "no regions registered"),
+            Self::NoData => write!(f, "no carbon data yet"),
+            Self::Ready => write!(f, "ready"),
+        }
+    }
+}
+
+/// Why [`CarbonRouter::select_greenest_region`] picked what it did
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DecisionReason {
+    /// The best-scoring region was selected
+    Greenest,
+    /// No region qualified, so a preferred region was selected
+    Fallback,
+    /// The current region was kept although another scored better
+    HysteresisHold,
+    /// No region qualified and no preferred region is registered
+    NoCandidate,
+}
+
+/// A region's standing when a routing decision was made
+#[derive(Debug, Clone, Serialize)]
+pub struct DecisionCandidate {
+    pub region_id: String,
+    pub carbon_intensity: f64,
+    pub score: f64,
+}
+
+/// One entry of the routing decision log
+#[derive(Debug, Clone, Serialize)]
+pub struct RoutingDecision {
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    /// Every scored region, ordered by region id
+    pub candidates: Vec<DecisionCandidate>,
+    pub chosen: Option<String>,
+    pub reason: DecisionReason,
+}
+
+/// Current and forecast carbon data for one region, for dashboards
+#[derive(Debug, Clone, Serialize)]
+pub struct RegionSnapshot {
+    /// Score from the last refresh
+    pub score: RegionScore,
+    /// Share of renewable generation (0-100), if the provider reports it
+    pub renewable_percentage: Option<f64>,
+    /// When the current intensity was measured
+    pub measured_at: chrono::DateTime<chrono::Utc>,
+    /// Seconds between `measured_at` and the snapshot
+    pub data_age_secs: u64,
+    /// Forecast for the next [`CarbonRouter::SNAPSHOT_FORECAST_HOURS`] hours,
+    /// empty if the provider has none
+    pub forecast: Vec<ForecastPoint>,
+    /// Time of the lowest forecast intensity, earliest on ties
+    pub valley_time: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Details of the last measurement behind a [`RegionScore`]
+#[derive(Debug, Clone)]
+struct Observation {
+    renewable_percentage: Option<f64>,
+    measured_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Region currently routed to, and a challenger waiting out the hysteresis
+#[derive(Debug, Default)]
+struct RegionSelection {
+    current: Option<String>,
+    pending: Option<PendingSwitch>,
+}
+
+/// Challenger that has led the current region by more than `switch_margin`
+#[derive(Debug)]
+struct PendingSwitch {
+    region_id: String,
+    since: Instant,
+    /// Distinct refreshes it has been seen leading in
+    refreshes: u32,
+    /// Refresh generation it was last seen leading in
+    generation: u64,
+}
+
 /// Carbon-aware router for spatial arbitrage
 pub struct CarbonRouter<C: EnergyApiClient> {
     config: CarbonRouterConfig,
@@ -61,11 +361,32 @@ pub struct CarbonRouter<C: EnergyApiClient> {
     cache: Arc<CarbonIntensityCache>,
     /// Region scores (cached for quick lookup)
     region_scores: Arc<RwLock<HashMap<String, RegionScore>>>,
+    /// Measurements behind `region_scores`, for snapshots
+    observations: Arc<RwLock<HashMap<String, Observation>>>,
+    /// Latest latency per region (ms), kept across refreshes
+    latencies: Arc<RwLock<HashMap<String, f64>>>,
     /// Registered regions
     regions: Arc<RwLock<Vec<Region>>>,
+    /// Hysteresis state for `select_greenest_region`
+    selection: Arc<RwLock<RegionSelection>>,
+    /// Time source for `min_switch_duration`
+    clock: SharedClock,
+    /// Completed `refresh_carbon_data` calls, for `switch_hold_refreshes`
+    refresh_generation: AtomicU64,
+    /// Ring buffer of the last `decision_log_capacity` decisions
+    decisions: Arc<RwLock<VecDeque<RoutingDecision>>>,
+    /// Scores published by each refresh, for live dashboards
+    updates: broadcast::Sender<RegionScore>,
 }
 
 impl<C: EnergyApiClient + Send + Sync> CarbonRouter<C> {
+    /// Forecast horizon of [`region_snapshot`](Self::region_snapshot)
+    pub const SNAPSHOT_FORECAST_HOURS: u32 = 24;
+
+    /// Updates buffered per [`subscribe_updates`](Self::subscribe_updates)
+    /// receiver before the oldest are dropped
+    pub const UPDATE_BUFFER: usize = 64;
+
     /// Create a new carbon router
     pub fn new(config: CarbonRouterConfig, client: C, cache: CarbonIntensityCache) -> Self {
         Self {
@@ -74,20 +395,65 @@ impl<C: EnergyApiClient + Send + Sync> CarbonRouter<C> {
             cache: Arc::new(cache),
             // Pre-allocate for typical number of regions (5-10)
             region_scores: Arc::new(RwLock::new(HashMap::with_capacity(10))),
+            observations: Arc::new(RwLock::new(HashMap::with_capacity(10))),
+            latencies: Arc::new(RwLock::new(HashMap::with_capacity(10))),
             regions: Arc::new(RwLock::new(Vec::with_capacity(10))),
+            selection: Arc::new(RwLock::new(RegionSelection::default())),
+            clock: SystemClock::shared(),
+            refresh_generation: AtomicU64::new(0),
+            decisions: Arc::new(RwLock::new(VecDeque::new())),
+            updates: broadcast::channel(Self::UPDATE_BUFFER).0,
         }
     }
 
+    /// Use a custom time source for switch hysteresis
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
     /// Check if carbon routing is enabled
     pub fn is_enabled(&self) -> bool {
         self.config.enabled
     }
 
+    /// Whether routing only records its recommendations
+    pub fn is_dry_run(&self) -> bool {
+        self.config.dry_run
+    }
+
     /// Get the carbon threshold
     pub fn threshold(&self) -> f64 {
         self.config.threshold
     }
 
+    /// Intensity above which a region is never selected
+    pub fn max_intensity(&self) -> f64 {
+        self.config.max_intensity
+    }
+
+    /// Whether routing can happen, distinguishing misconfiguration from
+    /// missing data
+    pub async fn state(&self) -> RouterState {
+        if !self.config.enabled {
+            RouterState::Disabled
+        } else if self.regions.read().await.is_empty() {
+            RouterState::NoRegions
+        } else if self.region_scores.read().await.is_empty() {
+            RouterState::NoData
+        } else {
+            RouterState::Ready
+        }
+    }
+
+    /// Receive every region score published by `refresh_carbon_data`
+    ///
+    /// A receiver more than [`UPDATE_BUFFER`](Self::UPDATE_BUFFER) updates
+    /// behind loses the oldest ones and gets `RecvError::Lagged`.
+    pub fn subscribe_updates(&self) -> broadcast::Receiver<RegionScore> {
+        self.updates.subscribe()
+    }
+
     /// Register a region for carbon-aware routing
     pub async fn register_region(&self, region: Region) {
         let mut regions = self.regions.write().await;
@@ -101,23 +467,20 @@ impl<C: EnergyApiClient + Send + Sync> CarbonRouter<C> {
     }
 
     /// Update carbon intensity for all registered regions
+    ///
+    /// Scores are fetched without holding any lock and published together
+    /// at the end, so routing decisions never wait on the energy API.
     pub async fn refresh_carbon_data(&self) -> Result<(), aegis_energy::EnergyApiError> {
         let regions = self.regions.read().await.clone();
-        let mut scores = self.region_scores.write().await;
+        let latencies = self.latencies.read().await.clone();
+        let mut updated = Vec::with_capacity(regions.len());
 
         for region in &regions {
+            let latency_ms = latencies.get(&region.id).copied();
+
             // Try cache first
             if let Some(cached) = self.cache.get(region).await {
-                let score = self.calculate_score(cached.value);
-                scores.insert(
-                    region.id.clone(),
-                    RegionScore {
-                        region_id: region.id.clone(),
-                        carbon_intensity: cached.value,
-                        score,
-                        recommended: cached.value < self.config.threshold,
-                    },
-                );
+                updated.push(self.region_score(&cached, latency_ms));
                 continue;
             }
 
@@ -125,16 +488,7 @@ impl<C: EnergyApiClient + Send + Sync> CarbonRouter<C> {
             match self.client.get_carbon_intensity(region).await {
                 Ok(intensity) => {
                     self.cache.put(intensity.clone()).await;
-                    let score = self.calculate_score(intensity.value);
-                    scores.insert(
-                        region.id.clone(),
-                        RegionScore {
-                            region_id: region.id.clone(),
-                            carbon_intensity: intensity.value,
-                            score,
-                            recommended: intensity.value < self.config.threshold,
-                        },
-                    );
+                    updated.push(self.region_score(&intensity, latency_ms));
                     debug!(
                         "📊 Updated carbon data for {}: {} gCO2/kWh",
                         region.id, intensity.value
@@ -146,44 +500,415 @@ impl<C: EnergyApiClient + Send + Sync> CarbonRouter<C> {
             }
         }
 
+        let mut scores = self.region_scores.write().await;
+        let mut observations = self.observations.write().await;
+        for (score, observation) in updated {
+            observations.insert(score.region_id.clone(), observation);
+            // No subscribers is not an error
+            let _ = self.updates.send(score.clone());
+            scores.insert(score.region_id.clone(), score);
+        }
+        self.refresh_generation.fetch_add(1, Ordering::Relaxed);
+
         Ok(())
     }
 
-    /// Calculate normalized score (0.0 = greenest, 1.0 = highest carbon)
-    fn calculate_score(&self, intensity: f64) -> f64 {
-        // Normalize to 0-1 range based on max_intensity
-        (intensity / self.config.max_intensity).min(1.0)
+    /// Refresh carbon data every `interval` until `shutdown` fires
+    ///
+    /// The first refresh runs immediately. After each refresh the
+    /// `aegis_carbon_intensity_g_kwh` gauge is updated for every scored
+    /// region; failures are logged and retried on the next tick. The
+    /// router's [`state`](Self::state) is logged after the first refresh
+    /// and whenever it changes.
+    pub fn spawn_refresh_task(
+        self: Arc<Self>,
+        interval: Duration,
+        mut shutdown: ShutdownReceiver,
+    ) -> JoinHandle<()>
+    where
+        C: 'static,
+    {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            let mut last_state = None;
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {}
+                    _ = shutdown.recv() => break,
+                }
+                if let Err(e) = self.refresh_carbon_data().await {
+                    warn!("⚠️ Carbon data refresh failed: {}", e);
+                    continue;
+                }
+                let state = self.state().await;
+                if last_state != Some(state) {
+                    match state {
+                        RouterState::Ready => info!("🌍 Carbon routing {}", state),
+                        _ => warn!("⚠️ Carbon routing inactive: {}", state),
+                    }
+                    last_state = Some(state);
+                }
+                for score in self.region_scores.read().await.values() {
+                    metrics::update_carbon_intensity(&score.region_id, score.carbon_intensity);
+                }
+            }
+            debug!("Carbon refresh task stopped");
+        })
     }
 
-    /// Select the best region based on carbon intensity
-    pub async fn select_greenest_region(&self) -> Option<String> {
-        let scores = self.region_scores.read().await;
+    fn region_score(
+        &self,
+        intensity: &CarbonIntensity,
+        latency_ms: Option<f64>,
+    ) -> (RegionScore, Observation) {
+        let carbon_score = self.calculate_score(intensity.value, intensity.renewable_percentage);
+        let score = RegionScore {
+            region_id: intensity.region.id.clone(),
+            carbon_intensity: intensity.value,
+            latency_ms,
+            score: self.blend_score(carbon_score, latency_ms),
+            recommended: intensity.value < self.config.threshold,
+        };
+        let observation = Observation {
+            renewable_percentage: intensity.renewable_percentage,
+            measured_at: intensity.timestamp,
+        };
+        (score, observation)
+    }
 
-        if scores.is_empty() {
-            return None;
+    /// Record the latest measured latency to a region
+    ///
+    /// The region's score is re-blended immediately if it has carbon data,
+    /// and the latency is kept for later refreshes. Negative or non-finite
+    /// values are ignored.
+    pub async fn update_region_latency(&self, region_id: &str, ms: f64) {
+        if !ms.is_finite() || ms < 0.0 {
+            warn!("⚠️ Ignoring invalid latency {} ms for {}", ms, region_id);
+            return;
         }
+        self.latencies
+            .write()
+            .await
+            .insert(region_id.to_string(), ms);
 
-        // Find region with lowest carbon intensity
-        scores
+        let mut scores = self.region_scores.write().await;
+        let observations = self.observations.read().await;
+        if let Some(score) = scores.get_mut(region_id) {
+            let renewable_percentage = observations
+                .get(region_id)
+                .and_then(|o| o.renewable_percentage);
+            let carbon_score = self.calculate_score(score.carbon_intensity, renewable_percentage);
+            score.latency_ms = Some(ms);
+            score.score = self.blend_score(carbon_score, Some(ms));
+        }
+    }
+
+    /// Current score, renewable share and data age of a region, plus its
+    /// forecast and the time of the forecast's lowest intensity
+    ///
+    /// `None` until the region has been scored by a refresh. A failed
+    /// forecast request leaves `forecast` empty rather than failing the
+    /// snapshot.
+    pub async fn region_snapshot(&self, region_id: &str) -> Option<RegionSnapshot> {
+        let score = self.region_scores.read().await.get(region_id).cloned()?;
+        let observation = self.observations.read().await.get(region_id).cloned()?;
+        let region = self
+            .regions
+            .read()
+            .await
             .iter()
-            .filter(|(_, s)| s.carbon_intensity <= self.config.max_intensity)
-            .min_by(|(_, a), (_, b)| {
+            .find(|r| r.id == region_id)
+            .cloned()
+            .unwrap_or_else(|| Region::new(region_id, region_id));
+
+        let forecast = match self
+            .client
+            .get_carbon_forecast(&region, Self::SNAPSHOT_FORECAST_HOURS)
+            .await
+        {
+            Ok(forecast) => forecast,
+            Err(e) => {
+                warn!(
+                    "⚠️ Failed to fetch carbon forecast for {}: {}",
+                    region_id, e
+                );
+                Vec::new()
+            }
+        };
+        // Forecasts are ordered by time, so the first minimum is the earliest
+        let valley_time = forecast
+            .iter()
+            .reduce(|best, p| {
+                if p.predicted_intensity < best.predicted_intensity {
+                    p
+                } else {
+                    best
+                }
+            })
+            .map(|p| p.timestamp);
+
+        let now = chrono::DateTime::<chrono::Utc>::from(self.clock.now());
+        let data_age_secs = (now - observation.measured_at).num_seconds().max(0) as u64;
+
+        Some(RegionSnapshot {
+            score,
+            renewable_percentage: observation.renewable_percentage,
+            measured_at: observation.measured_at,
+            data_age_secs,
+            forecast,
+            valley_time,
+        })
+    }
+
+    /// Calculate normalized score (0.0 = greenest, 1.0 = highest carbon)
+    ///
+    /// See [`CarbonRouterConfig::renewable_bonus`] for how the renewable share
+    /// adjusts the score.
+    fn calculate_score(&self, intensity: f64, renewable_percentage: Option<f64>) -> f64 {
+        // Normalize to 0-1 range based on max_intensity
+        let score = (intensity / self.config.max_intensity).min(1.0);
+        let bonus = match renewable_percentage {
+            Some(pct) if self.config.prefer_renewable => {
+                self.config.renewable_bonus * pct.clamp(0.0, 100.0) / 100.0
+            }
+            _ => 0.0,
+        };
+        (score - bonus).clamp(0.0, 1.0)
+    }
+
+    /// Blend a carbon score with latency as described on
+    /// [`CarbonRouterConfig::carbon_weight`]
+    fn blend_score(&self, carbon_score: f64, latency_ms: Option<f64>) -> f64 {
+        let Some(latency_ms) = latency_ms else {
+            return carbon_score;
+        };
+        let latency_score = (latency_ms / self.config.max_latency_ms).min(1.0);
+        let weight = self.config.carbon_weight;
+        weight * carbon_score + (1.0 - weight) * latency_score
+    }
+
+    /// Order by score, breaking ties on raw carbon intensity, then on
+    /// position in `preferred_regions`
+    fn compare_scores(&self, a: &RegionScore, b: &RegionScore) -> std::cmp::Ordering {
+        a.score
+            .partial_cmp(&b.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| {
                 a.carbon_intensity
                     .partial_cmp(&b.carbon_intensity)
                     .unwrap_or(std::cmp::Ordering::Equal)
             })
-            .map(|(id, _)| id.clone())
+            .then_with(|| {
+                self.preference_rank(&a.region_id)
+                    .cmp(&self.preference_rank(&b.region_id))
+            })
     }
 
-    /// Get regions sorted by carbon intensity (lowest first)
+    /// Position in `preferred_regions`; unlisted regions sort last
+    fn preference_rank(&self, region_id: &str) -> usize {
+        self.config
+            .preferred_regions
+            .iter()
+            .position(|id| id == region_id)
+            .unwrap_or(usize::MAX)
+    }
+
+    /// First entry of `preferred_regions` that is registered
+    async fn preferred_fallback(&self) -> Option<String> {
+        let regions = self.regions.read().await;
+        self.config
+            .preferred_regions
+            .iter()
+            .find(|id| regions.iter().any(|r| &r.id == *id))
+            .cloned()
+    }
+
+    /// Select the best region based on its score
+    ///
+    /// Once a region is selected it is kept until an alternative beats it by
+    /// more than `switch_margin` for at least `min_switch_duration` and
+    /// `switch_hold_refreshes` consecutive refreshes, or it exceeds
+    /// `max_intensity`. When no region qualifies, the first
+    /// registered entry of `preferred_regions` is selected.
+    ///
+    /// In dry-run mode the recommendation is logged and exported as
+    /// `aegis_carbon_routing_recommendation{region}`, and `None` is returned
+    /// so callers keep their default route.
+    pub async fn select_greenest_region(&self) -> Option<String> {
+        let recommended = {
+            let scores = self.region_scores.read().await;
+
+            // Find region with lowest score
+            let best = scores
+                .values()
+                .filter(|s| s.carbon_intensity <= self.config.max_intensity)
+                .min_by(|a, b| self.compare_scores(a, b));
+            let fallback = match best {
+                Some(_) => None,
+                None => self.preferred_fallback().await,
+            };
+
+            let mut selection = self.selection.write().await;
+            let reason = if let Some(fallback) = fallback {
+                let reason = if scores.is_empty() {
+                    "no carbon data"
+                } else {
+                    "every region exceeds max_intensity"
+                };
+                warn!(
+                    "⚠️ Carbon routing falling back to preferred region {} ({})",
+                    fallback, reason
+                );
+                selection.current = Some(fallback);
+                selection.pending = None;
+                DecisionReason::Fallback
+            } else {
+                self.apply_hysteresis(&mut selection, &scores, best)
+            };
+            self.record_decision(&scores, selection.current.clone(), reason)
+                .await;
+            selection.current.clone()
+        };
+
+        if self.config.dry_run {
+            if let Some(region_id) = &recommended {
+                info!("🧪 Dry run: carbon routing would select {}", region_id);
+                crate::metrics::record_carbon_routing_recommendation(region_id);
+            }
+            return None;
+        }
+
+        recommended
+    }
+
+    /// Region currently selected, after hysteresis
+    pub async fn current_region(&self) -> Option<String> {
+        self.selection.read().await.current.clone()
+    }
+
+    /// Logged routing decisions, oldest first
+    pub async fn recent_decisions(&self) -> Vec<RoutingDecision> {
+        self.decisions.read().await.iter().cloned().collect()
+    }
+
+    async fn record_decision(
+        &self,
+        scores: &HashMap<String, RegionScore>,
+        chosen: Option<String>,
+        reason: DecisionReason,
+    ) {
+        let capacity = self.config.decision_log_capacity;
+        if capacity == 0 {
+            return;
+        }
+        let mut candidates: Vec<DecisionCandidate> = scores
+            .values()
+            .map(|s| DecisionCandidate {
+                region_id: s.region_id.clone(),
+                carbon_intensity: s.carbon_intensity,
+                score: s.score,
+            })
+            .collect();
+        candidates.sort_by(|a, b| a.region_id.cmp(&b.region_id));
+        let decision = RoutingDecision {
+            timestamp: self.clock.now().into(),
+            candidates,
+            chosen,
+            reason,
+        };
+
+        let mut decisions = self.decisions.write().await;
+        while decisions.len() >= capacity {
+            decisions.pop_front();
+        }
+        decisions.push_back(decision);
+    }
+
+    fn apply_hysteresis(
+        &self,
+        selection: &mut RegionSelection,
+        scores: &HashMap<String, RegionScore>,
+        best: Option<&RegionScore>,
+    ) -> DecisionReason {
+        let Some(best) = best else {
+            selection.current = None;
+            selection.pending = None;
+            return DecisionReason::NoCandidate;
+        };
+
+        let current = selection
+            .current
+            .as_ref()
+            .and_then(|id| scores.get(id))
+            .filter(|s| s.carbon_intensity <= self.config.max_intensity);
+
+        let Some(current) = current else {
+            // Nothing usable selected yet: take the best region outright
+            selection.current = Some(best.region_id.clone());
+            selection.pending = None;
+            return DecisionReason::Greenest;
+        };
+
+        let improvement = (current.score - best.score) * self.config.max_intensity;
+        if best.region_id == current.region_id {
+            selection.pending = None;
+            return DecisionReason::Greenest;
+        }
+        if improvement <= self.config.switch_margin {
+            selection.pending = None;
+            return DecisionReason::HysteresisHold;
+        }
+
+        let now = self.clock.instant();
+        let generation = self.refresh_generation.load(Ordering::Relaxed);
+        let pending = match &mut selection.pending {
+            Some(pending) if pending.region_id == best.region_id => {
+                if generation > pending.generation {
+                    pending.refreshes += 1;
+                    pending.generation = generation;
+                }
+                pending
+            }
+            pending => pending.insert(PendingSwitch {
+                region_id: best.region_id.clone(),
+                since: now,
+                refreshes: 1,
+                generation,
+            }),
+        };
+
+        if now.saturating_duration_since(pending.since) >= self.config.min_switch_duration
+            && pending.refreshes >= self.config.switch_hold_refreshes
+        {
+            info!(
+                "🔀 Carbon routing switching from {} to {} ({:.1} gCO2/kWh greener)",
+                current.region_id, best.region_id, improvement
+            );
+            selection.current = Some(best.region_id.clone());
+            selection.pending = None;
+            return DecisionReason::Greenest;
+        }
+        DecisionReason::HysteresisHold
+    }
+
+    /// Resolve the upstream for the next request
+    ///
+    /// Returns the greenest region's entry in `region_upstreams`, or
+    /// `default_upstream` when routing is in dry-run mode, no region
+    /// qualifies, or the region has no upstream configured.
+    pub async fn select_upstream(&self, default_upstream: &str) -> String {
+        self.select_greenest_region()
+            .await
+            .and_then(|region_id| self.config.region_upstreams.get(&region_id).cloned())
+            .unwrap_or_else(|| default_upstream.to_string())
+    }
+
+    /// Get regions sorted by score (greenest first)
     pub async fn get_sorted_regions(&self) -> Vec<RegionScore> {
         let scores = self.region_scores.read().await;
         let mut sorted: Vec<RegionScore> = scores.values().cloned().collect();
-        sorted.sort_by(|a, b| {
-            a.carbon_intensity
-                .partial_cmp(&b.carbon_intensity)
-                .unwrap_or(std::cmp::Ordering::Equal)
-        });
+        sorted.sort_by(|a, b| self.compare_scores(a, b));
         sorted
     }
 
@@ -204,6 +929,9 @@ impl<C: EnergyApiClient + Send + Sync> CarbonRouter<C> {
 
     /// Calculate routing weight for a region (for weighted load balancing)
     /// Higher weight = more traffic should be sent to this region
+    ///
+    /// Follows the blended carbon/latency score; regions above
+    /// `max_intensity` get 0.
     pub async fn get_routing_weight(&self, region_id: &str) -> u32 {
         let scores = self.region_scores.read().await;
 
@@ -212,14 +940,61 @@ impl<C: EnergyApiClient + Send + Sync> CarbonRouter<C> {
                 return 0; // No traffic to high-carbon regions
             }
 
-            // Invert score: low carbon = high weight
+            // Invert score: low carbon and latency = high weight
             let inverted = 1.0 - score.score;
-            let weight = (inverted * 100.0 * self.config.carbon_weight) as u32;
+            let weight = (inverted * 100.0) as u32;
             weight.max(1) // Minimum weight of 1
         } else {
             50 // Default weight if no data
         }
     }
+
+    /// Split traffic across all regions in proportion to `1 / carbon_intensity`
+    ///
+    /// Weights of regions within `max_intensity` sum to exactly 100; regions
+    /// above it get 0. Intensities below 1 gCO2/kWh are treated as 1. Sorted
+    /// by descending weight, then region id.
+    pub async fn traffic_distribution(&self) -> Vec<(String, u32)> {
+        let scores = self.region_scores.read().await;
+
+        let inverse: Vec<(&str, f64)> = scores
+            .values()
+            .map(|s| {
+                let share = if s.carbon_intensity <= self.config.max_intensity {
+                    1.0 / s.carbon_intensity.max(1.0)
+                } else {
+                    0.0
+                };
+                (s.region_id.as_str(), share)
+            })
+            .collect();
+        let total: f64 = inverse.iter().map(|(_, share)| share).sum();
+
+        // Largest-remainder rounding so the weights add up to 100
+        let mut weights: Vec<(String, u32, f64)> = inverse
+            .iter()
+            .map(|(id, share)| {
+                let exact = if total > 0.0 {
+                    share / total * 100.0
+                } else {
+                    0.0
+                };
+                (id.to_string(), exact.floor() as u32, exact.fract())
+            })
+            .collect();
+        let assigned: u32 = weights.iter().map(|(_, w, _)| w).sum();
+        if total > 0.0 {
+            weights.sort_by(|a, b| b.2.total_cmp(&a.2).then_with(|| a.0.cmp(&b.0)));
+            for entry in weights.iter_mut().take(100 - assigned as usize) {
+                entry.1 += 1;
+            }
+        }
+
+        let mut distribution: Vec<(String, u32)> =
+            weights.into_iter().map(|(id, w, _)| (id, w)).collect();
+        distribution.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        distribution
+    }
 }
 
 #[cfg(test)]
@@ -227,10 +1002,68 @@ mod tests {
     use super::*;
     use aegis_energy::{CarbonIntensity, EnergyApiError};
 
+    #[test]
+    fn test_builder_accepts_valid_config() {
+        let config = CarbonRouterConfig::builder()
+            .with_enabled(true)
+            .with_threshold(150.0)
+            .with_max_intensity(400.0)
+            .with_carbon_weight(1.0)
+            .with_region_upstream("eu-north", "10.0.0.5:8080")
+            .with_switch_hysteresis(25.0, Duration::from_secs(60))
+            .build()
+            .unwrap();
+        assert!(config.enabled);
+        assert_eq!(config.threshold, 150.0);
+        assert_eq!(config.region_upstreams["eu-north"], "10.0.0.5:8080");
+        assert!(CarbonRouterConfig::builder().build().is_ok());
+
+        // A threshold equal to the hard limit is allowed
+        assert!(
+            CarbonRouterConfig::builder()
+                .with_threshold(300.0)
+                .with_max_intensity(300.0)
+                .build()
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_builder_rejects_invalid_config() {
+        for weight in [-0.1, 1.5, f64::NAN] {
+            assert!(matches!(
+                CarbonRouterConfig::builder()
+                    .with_carbon_weight(weight)
+                    .build(),
+                Err(ConfigError::ValidationError(msg)) if msg.contains("carbon_weight")
+            ));
+        }
+        assert!(matches!(
+            CarbonRouterConfig::builder()
+                .with_threshold(600.0)
+                .with_max_intensity(500.0)
+                .build(),
+            Err(ConfigError::ValidationError(msg)) if msg.contains("must not exceed")
+        ));
+        for builder in [
+            CarbonRouterConfig::builder().with_threshold(-1.0),
+            CarbonRouterConfig::builder().with_max_intensity(f64::INFINITY),
+            CarbonRouterConfig::builder()
+                .with_max_intensity(0.0)
+                .with_threshold(0.0),
+            CarbonRouterConfig::builder().with_switch_hysteresis(-5.0, Duration::ZERO),
+            CarbonRouterConfig::builder().with_renewable_bonus(true, 2.0),
+        ] {
+            assert!(builder.build().is_err());
+        }
+    }
+
     /// Mock client for testing
     struct MockEnergyClient {
         intensities: HashMap<String, f64>,
+        renewables: HashMap<String, f64>,
         failing_regions: std::collections::HashSet<String>,
+        forecast: Vec<aegis_energy::ForecastPoint>,
     }
 
     impl MockEnergyClient {
@@ -241,10 +1074,18 @@ mod tests {
             intensities.insert("eu-west".to_string(), 150.0); // Moderate
             Self {
                 intensities,
+                renewables: HashMap::new(),
                 failing_regions: std::collections::HashSet::new(),
+                forecast: Vec::new(),
             }
         }
 
+        fn with_region(mut self, region_id: &str, intensity: f64, renewable: f64) -> Self {
+            self.intensities.insert(region_id.to_string(), intensity);
+            self.renewables.insert(region_id.to_string(), renewable);
+            self
+        }
+
         fn set_failing(&mut self, region_id: &str) {
             self.failing_regions.insert(region_id.to_string());
         }
@@ -267,6 +1108,8 @@ mod tests {
                 timestamp: chrono::Utc::now(),
                 valid_for_seconds: 300,
                 rating: None,
+                renewable_percentage: self.renewables.get(&region.id).copied(),
+                power_breakdown: None,
             })
         }
 
@@ -302,10 +1145,46 @@ mod tests {
             _region: &Region,
             _hours: u32,
         ) -> Result<Vec<aegis_energy::ForecastPoint>, EnergyApiError> {
-            Ok(vec![])
+            Ok(self.forecast.clone())
         }
     }
 
+    #[tokio::test]
+    async fn test_region_snapshot_bundles_current_and_forecast() {
+        let now = chrono::Utc::now();
+        let point = |hours, intensity| aegis_energy::ForecastPoint {
+            timestamp: now + chrono::Duration::hours(hours),
+            predicted_intensity: intensity,
+            confidence: None,
+        };
+        let mut client = MockEnergyClient::new().with_region("solar", 120.0, 80.0);
+        client.forecast = vec![
+            point(1, 140.0),
+            point(2, 60.0),
+            point(3, 90.0),
+            point(5, 60.0),
+        ];
+        let router = CarbonRouter::new(
+            CarbonRouterConfig::default(),
+            client,
+            CarbonIntensityCache::new(300),
+        );
+        router.register_region(Region::new("solar", "Solar")).await;
+        assert!(router.region_snapshot("solar").await.is_none());
+
+        router.refresh_carbon_data().await.unwrap();
+        let snapshot = router.region_snapshot("solar").await.unwrap();
+
+        assert_eq!(snapshot.score.region_id, "solar");
+        assert_eq!(snapshot.score.carbon_intensity, 120.0);
+        assert_eq!(snapshot.renewable_percentage, Some(80.0));
+        assert!(snapshot.data_age_secs < 60);
+        assert_eq!(snapshot.forecast.len(), 4);
+        // Lowest forecast point, the earlier of the two 60.0 readings
+        assert_eq!(snapshot.valley_time, Some(now + chrono::Duration::hours(2)));
+        assert!(router.region_snapshot("unknown").await.is_none());
+    }
+
     #[test]
     fn test_default_config() {
         let config = CarbonRouterConfig::default();
@@ -315,6 +1194,577 @@ mod tests {
         assert!(config.prefer_renewable);
     }
 
+    async fn renewable_router(config: CarbonRouterConfig) -> CarbonRouter<MockEnergyClient> {
+        let client = MockEnergyClient::new()
+            .with_region("solar", 200.0, 90.0)
+            .with_region("coal", 200.0, 10.0);
+        let router = CarbonRouter::new(config, client, CarbonIntensityCache::new(300));
+        router.register_region(Region::new("solar", "Solar")).await;
+        router.register_region(Region::new("coal", "Coal")).await;
+        router.refresh_carbon_data().await.unwrap();
+        router
+    }
+
+    #[tokio::test]
+    async fn test_renewable_bonus_breaks_intensity_tie() {
+        let router = renewable_router(CarbonRouterConfig::default()).await;
+
+        assert_eq!(
+            router.select_greenest_region().await.as_deref(),
+            Some("solar")
+        );
+        let sorted = router.get_sorted_regions().await;
+        assert_eq!(sorted[0].region_id, "solar");
+        // 200/500 = 0.4, minus 0.1 * share
+        assert!((sorted[0].score - 0.31).abs() < 1e-9);
+        assert!((sorted[1].score - 0.39).abs() < 1e-9);
+        assert!(router.get_routing_weight("solar").await > router.get_routing_weight("coal").await);
+    }
+
+    #[tokio::test]
+    async fn test_renewable_bonus_disabled() {
+        for config in [
+            CarbonRouterConfig {
+                renewable_bonus: 0.0,
+                ..Default::default()
+            },
+            CarbonRouterConfig {
+                prefer_renewable: false,
+                ..Default::default()
+            },
+        ] {
+            let router = renewable_router(config).await;
+            let sorted = router.get_sorted_regions().await;
+            assert_eq!(sorted[0].score, sorted[1].score);
+            assert_eq!(
+                router.get_routing_weight("solar").await,
+                router.get_routing_weight("coal").await
+            );
+        }
+    }
+
+    /// Upstream that answers every request with `name`
+    async fn spawn_named_upstream(name: &'static str) -> String {
+        use hyper::server::conn::http1;
+        use hyper::service::service_fn;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let service = service_fn(move |_req| async move {
+                        Ok::<_, std::convert::Infallible>(hyper::Response::new(
+                            http_body_util::Full::new(bytes::Bytes::from(name)),
+                        ))
+                    });
+                    let _ = http1::Builder::new()
+                        .serve_connection(hyper_util::rt::TokioIo::new(stream), service)
+                        .await;
+                });
+            }
+        });
+        addr.to_string()
+    }
+
+    async fn proxy_body(upstream: &str) -> bytes::Bytes {
+        use http_body_util::BodyExt;
+
+        let req = hyper::Request::builder()
+            .uri("/api")
+            .body(http_body_util::Empty::<bytes::Bytes>::new())
+            .unwrap();
+        let resp = crate::http_proxy::handle_request(
+            req,
+            upstream,
+            None,
+            None,
+            Arc::new(crate::proxy_cache::TtlConfig::new(60)),
+            Arc::new(crate::proxy_cache::BypassCheck::default()),
+            None,
+            Arc::new(vec![]),
+            false,
+        )
+        .await
+        .unwrap();
+        resp.into_body().collect().await.unwrap().to_bytes()
+    }
+
+    async fn upstream_router(
+        dry_run: bool,
+        green_upstream: &str,
+    ) -> CarbonRouter<MockEnergyClient> {
+        let config = CarbonRouterConfig {
+            enabled: true,
+            dry_run,
+            region_upstreams: HashMap::from([("us-west".to_string(), green_upstream.to_string())]),
+            ..Default::default()
+        };
+        let router = CarbonRouter::new(
+            config,
+            MockEnergyClient::new(),
+            CarbonIntensityCache::new(300),
+        );
+        router
+            .register_region(Region::new("us-west", "US West"))
+            .await;
+        router
+            .register_region(Region::new("us-east", "US East"))
+            .await;
+        router.refresh_carbon_data().await.unwrap();
+        router
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_keeps_default_upstream() {
+        let handle = crate::metrics::init_metrics();
+        let default_upstream = spawn_named_upstream("default").await;
+        let green_upstream = spawn_named_upstream("green").await;
+
+        let router = upstream_router(true, &green_upstream).await;
+        assert_eq!(router.select_greenest_region().await, None);
+
+        let upstream = router.select_upstream(&default_upstream).await;
+        assert_eq!(upstream, default_upstream);
+        assert_eq!(proxy_body(&upstream).await, "default");
+
+        let rendered = handle.render();
+        assert!(rendered.contains(r#"aegis_carbon_routing_recommendation{region="us-west"}"#));
+        assert!(!rendered.contains(r#"aegis_carbon_routing_recommendation{region="us-east"}"#));
+    }
+
+    #[tokio::test]
+    async fn test_live_routing_uses_region_upstream() {
+        let default_upstream = spawn_named_upstream("default").await;
+        let green_upstream = spawn_named_upstream("green").await;
+
+        let router = upstream_router(false, &green_upstream).await;
+        let upstream = router.select_upstream(&default_upstream).await;
+        assert_eq!(upstream, green_upstream);
+        assert_eq!(proxy_body(&upstream).await, "green");
+    }
+
+    async fn set_intensity<C: EnergyApiClient + Send + Sync>(
+        router: &CarbonRouter<C>,
+        region_id: &str,
+        intensity: f64,
+    ) {
+        router.region_scores.write().await.insert(
+            region_id.to_string(),
+            RegionScore {
+                region_id: region_id.to_string(),
+                carbon_intensity: intensity,
+                latency_ms: None,
+                score: router.calculate_score(intensity, None),
+                recommended: intensity < router.config.threshold,
+            },
+        );
+    }
+
+    fn hysteresis_router(clock: &aegis_common::MockClock) -> CarbonRouter<MockEnergyClient> {
+        let config = CarbonRouterConfig {
+            switch_margin: 50.0,
+            min_switch_duration: Duration::from_secs(60),
+            ..Default::default()
+        };
+        CarbonRouter::new(
+            config,
+            MockEnergyClient::new(),
+            CarbonIntensityCache::new(300),
+        )
+        .with_clock(clock.shared())
+    }
+
+    #[tokio::test]
+    async fn test_hysteresis_ignores_oscillation_within_margin() {
+        let clock = aegis_common::MockClock::new();
+        let router = hysteresis_router(&clock);
+        set_intensity(&router, "us-west", 100.0).await;
+        set_intensity(&router, "us-east", 120.0).await;
+        assert_eq!(
+            router.select_greenest_region().await.as_deref(),
+            Some("us-west")
+        );
+
+        for east in [80.0, 120.0, 60.0, 110.0, 51.0, 90.0] {
+            set_intensity(&router, "us-east", east).await;
+            clock.advance(Duration::from_secs(120));
+            assert_eq!(
+                router.select_greenest_region().await.as_deref(),
+                Some("us-west")
+            );
+        }
+        assert_eq!(router.current_region().await.as_deref(), Some("us-west"));
+    }
+
+    #[tokio::test]
+    async fn test_hysteresis_switches_on_sustained_improvement() {
+        let clock = aegis_common::MockClock::new();
+        let router = hysteresis_router(&clock);
+        set_intensity(&router, "us-west", 100.0).await;
+        set_intensity(&router, "us-east", 120.0).await;
+        assert_eq!(
+            router.select_greenest_region().await.as_deref(),
+            Some("us-west")
+        );
+
+        // Greener by more than the margin, but not for long enough yet
+        set_intensity(&router, "us-east", 20.0).await;
+        assert_eq!(
+            router.select_greenest_region().await.as_deref(),
+            Some("us-west")
+        );
+        clock.advance(Duration::from_secs(30));
+        assert_eq!(
+            router.select_greenest_region().await.as_deref(),
+            Some("us-west")
+        );
+
+        // A brief reversal restarts the timer
+        set_intensity(&router, "us-east", 90.0).await;
+        assert_eq!(
+            router.select_greenest_region().await.as_deref(),
+            Some("us-west")
+        );
+        set_intensity(&router, "us-east", 20.0).await;
+        clock.advance(Duration::from_secs(30));
+        assert_eq!(
+            router.select_greenest_region().await.as_deref(),
+            Some("us-west")
+        );
+        clock.advance(Duration::from_secs(45));
+        assert_eq!(
+            router.select_greenest_region().await.as_deref(),
+            Some("us-west")
+        );
+
+        clock.advance(Duration::from_secs(15));
+        assert_eq!(
+            router.select_greenest_region().await.as_deref(),
+            Some("us-east")
+        );
+        assert_eq!(router.current_region().await.as_deref(), Some("us-east"));
+    }
+
+    /// Publish a jittery reading for us-east as a completed refresh
+    async fn refresh_east(router: &CarbonRouter<MockEnergyClient>, east: f64) -> Option<String> {
+        set_intensity(router, "us-east", east).await;
+        router.refresh_carbon_data().await.unwrap();
+        router.select_greenest_region().await
+    }
+
+    fn hold_router() -> CarbonRouter<MockEnergyClient> {
+        let config = CarbonRouterConfig::builder()
+            .with_switch_hysteresis(50.0, Duration::ZERO)
+            .with_switch_hold_refreshes(3)
+            .build()
+            .unwrap();
+        CarbonRouter::new(
+            config,
+            MockEnergyClient::new(),
+            CarbonIntensityCache::new(300),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_switch_hold_pins_region_under_jitter() {
+        let router = hold_router();
+        set_intensity(&router, "us-west", 200.0).await;
+        assert_eq!(
+            refresh_east(&router, 210.0).await.as_deref(),
+            Some("us-west")
+        );
+
+        // Noisy readings either within the margin or decisive for too few refreshes
+        for east in [
+            160.0, 230.0, 120.0, 100.0, 190.0, 90.0, 155.0, 60.0, 80.0, 175.0,
+        ] {
+            assert_eq!(
+                refresh_east(&router, east).await.as_deref(),
+                Some("us-west")
+            );
+        }
+
+        // Repeated selections within one refresh don't advance the count
+        assert_eq!(
+            refresh_east(&router, 100.0).await.as_deref(),
+            Some("us-west")
+        );
+        for _ in 0..5 {
+            assert_eq!(
+                router.select_greenest_region().await.as_deref(),
+                Some("us-west")
+            );
+        }
+        assert_eq!(
+            refresh_east(&router, 90.0).await.as_deref(),
+            Some("us-west")
+        );
+        assert_eq!(
+            refresh_east(&router, 110.0).await.as_deref(),
+            Some("us-east")
+        );
+        assert_eq!(router.current_region().await.as_deref(), Some("us-east"));
+    }
+
+    #[tokio::test]
+    async fn test_switch_hold_disabled_switches_on_first_refresh() {
+        let config = CarbonRouterConfig::builder()
+            .with_switch_hysteresis(50.0, Duration::ZERO)
+            .build()
+            .unwrap();
+        assert_eq!(config.switch_hold_refreshes, 0);
+        let router = CarbonRouter::new(
+            config,
+            MockEnergyClient::new(),
+            CarbonIntensityCache::new(300),
+        );
+        set_intensity(&router, "us-west", 200.0).await;
+        assert_eq!(
+            refresh_east(&router, 210.0).await.as_deref(),
+            Some("us-west")
+        );
+        assert_eq!(
+            refresh_east(&router, 100.0).await.as_deref(),
+            Some("us-east")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_decision_log_records_choice_and_hysteresis_hold() {
+        let clock = aegis_common::MockClock::new();
+        let router = hysteresis_router(&clock);
+        set_intensity(&router, "us-west", 100.0).await;
+        set_intensity(&router, "us-east", 120.0).await;
+        router.select_greenest_region().await;
+
+        set_intensity(&router, "us-east", 20.0).await;
+        router.select_greenest_region().await;
+
+        let decisions = router.recent_decisions().await;
+        assert_eq!(decisions.len(), 2);
+        assert_eq!(decisions[0].chosen.as_deref(), Some("us-west"));
+        assert_eq!(decisions[0].reason, DecisionReason::Greenest);
+        let ids: Vec<&str> = decisions[0]
+            .candidates
+            .iter()
+            .map(|c| c.region_id.as_str())
+            .collect();
+        assert_eq!(ids, ["us-east", "us-west"]);
+        assert_eq!(decisions[0].candidates[0].carbon_intensity, 120.0);
+
+        assert_eq!(decisions[1].chosen.as_deref(), Some("us-west"));
+        assert_eq!(decisions[1].reason, DecisionReason::HysteresisHold);
+        assert_eq!(decisions[1].candidates[0].carbon_intensity, 20.0);
+    }
+
+    #[tokio::test]
+    async fn test_decision_log_is_bounded_and_records_fallback() {
+        let config = CarbonRouterConfig::builder()
+            .with_preferred_regions(vec!["us-east".to_string()])
+            .with_decision_log_capacity(2)
+            .build()
+            .unwrap();
+        let router = CarbonRouter::new(
+            config,
+            MockEnergyClient::new(),
+            CarbonIntensityCache::new(300),
+        );
+        router
+            .register_region(Region::new("us-east", "US East"))
+            .await;
+        for _ in 0..3 {
+            router.select_greenest_region().await;
+        }
+
+        let decisions = router.recent_decisions().await;
+        assert_eq!(decisions.len(), 2);
+        assert!(
+            decisions
+                .iter()
+                .all(|d| d.reason == DecisionReason::Fallback
+                    && d.chosen.as_deref() == Some("us-east")
+                    && d.candidates.is_empty())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_refresh_task_populates_scores_until_shutdown() {
+        let router = Arc::new(CarbonRouter::new(
+            CarbonRouterConfig::default(),
+            MockEnergyClient::new(),
+            CarbonIntensityCache::new(300),
+        ));
+        router
+            .register_region(Region::new("us-west", "US West"))
+            .await;
+        router
+            .register_region(Region::new("eu-west", "EU West"))
+            .await;
+        let lifecycle = crate::lifecycle::LifecycleManager::new();
+        let handle = router
+            .clone()
+            .spawn_refresh_task(Duration::from_millis(10), lifecycle.shutdown_receiver());
+
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while router.region_scores.read().await.len() < 2 {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .expect("refresh task never populated region scores");
+        let generation = router.refresh_generation.load(Ordering::Relaxed);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(router.refresh_generation.load(Ordering::Relaxed) > generation);
+        assert_eq!(
+            router.region_scores.read().await["us-west"].carbon_intensity,
+            50.0
+        );
+
+        lifecycle.initiate_shutdown().await;
+        tokio::time::timeout(Duration::from_secs(5), handle)
+            .await
+            .expect("refresh task ignored shutdown")
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_router_state_explains_why_routing_is_idle() {
+        let disabled = CarbonRouter::new(
+            CarbonRouterConfig::default(),
+            MockEnergyClient::new(),
+            CarbonIntensityCache::new(300),
+        );
+        disabled
+            .register_region(Region::new("us-west", "US West"))
+            .await;
+        disabled.refresh_carbon_data().await.unwrap();
+        assert_eq!(disabled.state().await, RouterState::Disabled);
+
+        let config = CarbonRouterConfig::builder()
+            .with_enabled(true)
+            .build()
+            .unwrap();
+        let router = CarbonRouter::new(
+            config.clone(),
+            MockEnergyClient::new(),
+            CarbonIntensityCache::new(300),
+        );
+        router.refresh_carbon_data().await.unwrap();
+        assert_eq!(router.state().await, RouterState::NoRegions);
+        assert!(router.select_greenest_region().await.is_none());
+
+        let mut client = MockEnergyClient::new();
+        client.set_failing("us-west");
+        let router = CarbonRouter::new(config.clone(), client, CarbonIntensityCache::new(300));
+        router
+            .register_region(Region::new("us-west", "US West"))
+            .await;
+        router.refresh_carbon_data().await.unwrap();
+        assert_eq!(router.state().await, RouterState::NoData);
+
+        let router = CarbonRouter::new(
+            config,
+            MockEnergyClient::new(),
+            CarbonIntensityCache::new(300),
+        );
+        router
+            .register_region(Region::new("us-west", "US West"))
+            .await;
+        assert_eq!(router.state().await, RouterState::NoData);
+        router.refresh_carbon_data().await.unwrap();
+        assert_eq!(router.state().await, RouterState::Ready);
+        assert_eq!(RouterState::NoRegions.to_string(), "no regions registered");
+    }
+
+    #[tokio::test]
+    async fn test_updates_drop_oldest_for_slow_subscribers() {
+        let router = CarbonRouter::new(
+            CarbonRouterConfig::default(),
+            MockEnergyClient::new(),
+            CarbonIntensityCache::new(300),
+        );
+        router
+            .register_region(Region::new("us-west", "US West"))
+            .await;
+        let mut updates = router.subscribe_updates();
+
+        router.refresh_carbon_data().await.unwrap();
+        let score = updates.recv().await.unwrap();
+        assert_eq!(score.region_id, "us-west");
+        assert_eq!(score.carbon_intensity, 50.0);
+
+        let overflow = 5;
+        for _ in 0..CarbonRouter::<MockEnergyClient>::UPDATE_BUFFER + overflow {
+            router.refresh_carbon_data().await.unwrap();
+        }
+        assert!(matches!(
+            updates.recv().await,
+            Err(broadcast::error::RecvError::Lagged(n)) if n == overflow as u64
+        ));
+        assert_eq!(updates.recv().await.unwrap().region_id, "us-west");
+    }
+
+    #[tokio::test]
+    async fn test_hysteresis_leaves_region_over_max_intensity() {
+        let clock = aegis_common::MockClock::new();
+        let router = hysteresis_router(&clock);
+        set_intensity(&router, "us-west", 100.0).await;
+        set_intensity(&router, "us-east", 120.0).await;
+        assert_eq!(
+            router.select_greenest_region().await.as_deref(),
+            Some("us-west")
+        );
+
+        set_intensity(&router, "us-west", 900.0).await;
+        assert_eq!(
+            router.select_greenest_region().await.as_deref(),
+            Some("us-east")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_traffic_distribution_inverse_to_intensity() {
+        let router = CarbonRouter::new(
+            CarbonRouterConfig::default(),
+            MockEnergyClient::new(),
+            CarbonIntensityCache::new(300),
+        );
+        assert!(router.traffic_distribution().await.is_empty());
+
+        set_intensity(&router, "us-west", 50.0).await;
+        set_intensity(&router, "eu-west", 100.0).await;
+        set_intensity(&router, "ap-south", 150.0).await;
+        set_intensity(&router, "us-east", 600.0).await; // over max_intensity
+
+        let distribution = router.traffic_distribution().await;
+        let weights: HashMap<_, _> = distribution.iter().cloned().collect();
+        assert_eq!(distribution.len(), 4);
+        assert_eq!(distribution.iter().map(|(_, w)| w).sum::<u32>(), 100);
+
+        // 1/50 : 1/100 : 1/150 = 6 : 3 : 2
+        assert_eq!(distribution[0].0, "us-west");
+        assert_eq!(weights["us-west"], 55);
+        assert_eq!(weights["eu-west"], 27);
+        assert_eq!(weights["ap-south"], 18);
+        assert_eq!(weights["us-east"], 0);
+    }
+
+    #[tokio::test]
+    async fn test_traffic_distribution_all_over_limit() {
+        let router = CarbonRouter::new(
+            CarbonRouterConfig::default(),
+            MockEnergyClient::new(),
+            CarbonIntensityCache::new(300),
+        );
+        set_intensity(&router, "us-east", 700.0).await;
+        set_intensity(&router, "eu-east", 800.0).await;
+
+        let distribution = router.traffic_distribution().await;
+        assert_eq!(distribution.len(), 2);
+        assert!(distribution.iter().all(|(_, w)| *w == 0));
+    }
+
     #[tokio::test]
     async fn test_router_creation() {
         let config = CarbonRouterConfig {
@@ -350,6 +1800,86 @@ mod tests {
         assert_eq!(regions[0].id, "us-west");
     }
 
+    async fn preferred_router(
+        config: CarbonRouterConfig,
+        client: MockEnergyClient,
+        region_ids: &[&str],
+    ) -> CarbonRouter<MockEnergyClient> {
+        let router = CarbonRouter::new(config, client, CarbonIntensityCache::new(300));
+        for id in region_ids {
+            router.register_region(Region::new(*id, *id)).await;
+        }
+        router
+    }
+
+    #[tokio::test]
+    async fn test_falls_back_to_preferred_region_when_none_qualify() {
+        let config = CarbonRouterConfig {
+            threshold: 30.0,
+            max_intensity: 40.0,
+            preferred_regions: vec![
+                "ap-south".to_string(), // not registered
+                "eu-west".to_string(),
+                "us-west".to_string(),
+            ],
+            ..Default::default()
+        };
+        let router = preferred_router(
+            config,
+            MockEnergyClient::new(),
+            &["us-west", "us-east", "eu-west"],
+        )
+        .await;
+
+        // No carbon data yet
+        assert_eq!(
+            router.select_greenest_region().await.as_deref(),
+            Some("eu-west")
+        );
+
+        // All of us-west (50), us-east (350) and eu-west (150) exceed 40
+        router.refresh_carbon_data().await.unwrap();
+        assert_eq!(
+            router.select_greenest_region().await.as_deref(),
+            Some("eu-west")
+        );
+        assert_eq!(router.current_region().await.as_deref(), Some("eu-west"));
+    }
+
+    #[tokio::test]
+    async fn test_no_fallback_without_registered_preference() {
+        let config = CarbonRouterConfig {
+            threshold: 30.0,
+            max_intensity: 40.0,
+            preferred_regions: vec!["ap-south".to_string()],
+            ..Default::default()
+        };
+        let router = preferred_router(config, MockEnergyClient::new(), &["us-west"]).await;
+        router.refresh_carbon_data().await.unwrap();
+        assert_eq!(router.select_greenest_region().await, None);
+    }
+
+    #[tokio::test]
+    async fn test_preferred_order_breaks_carbon_tie() {
+        for (preferred, expected) in [(["b", "a"], "b"), (["a", "b"], "a")] {
+            let config = CarbonRouterConfig {
+                preferred_regions: preferred.iter().map(|id| id.to_string()).collect(),
+                ..Default::default()
+            };
+            let client = MockEnergyClient::new()
+                .with_region("a", 100.0, 20.0)
+                .with_region("b", 100.0, 20.0);
+            let router = preferred_router(config, client, &["a", "b", "us-east"]).await;
+            router.refresh_carbon_data().await.unwrap();
+
+            assert_eq!(
+                router.select_greenest_region().await.as_deref(),
+                Some(expected)
+            );
+            assert_eq!(router.get_sorted_regions().await[0].region_id, expected);
+        }
+    }
+
     #[tokio::test]
     async fn test_select_greenest_region() {
         let config = CarbonRouterConfig {
@@ -414,6 +1944,112 @@ mod tests {
         assert!(west_weight > east_weight);
     }
 
+    /// us-west is green but far (50 gCO2/kWh, 400 ms), us-east dirty but
+    /// close (350 gCO2/kWh, 20 ms), eu-west in between at 150 ms
+    async fn latency_router(config: CarbonRouterConfig) -> CarbonRouter<MockEnergyClient> {
+        let router = CarbonRouter::new(
+            config,
+            MockEnergyClient::new(),
+            CarbonIntensityCache::new(300),
+        );
+        for (region_id, latency) in [("us-west", 400.0), ("us-east", 20.0), ("eu-west", 150.0)] {
+            router
+                .register_region(Region::new(region_id, region_id))
+                .await;
+            router.update_region_latency(region_id, latency).await;
+        }
+        router.refresh_carbon_data().await.unwrap();
+        router
+    }
+
+    #[tokio::test]
+    async fn test_zero_carbon_weight_prefers_lowest_latency() {
+        let router = latency_router(CarbonRouterConfig {
+            carbon_weight: 0.0,
+            ..Default::default()
+        })
+        .await;
+
+        assert_eq!(
+            router.select_greenest_region().await.as_deref(),
+            Some("us-east")
+        );
+        let sorted = router.get_sorted_regions().await;
+        assert_eq!(sorted[0].latency_ms, Some(20.0));
+        assert!((sorted[0].score - 20.0 / 500.0).abs() < 1e-9);
+        assert!(
+            router.get_routing_weight("us-east").await > router.get_routing_weight("us-west").await
+        );
+    }
+
+    #[tokio::test]
+    async fn test_full_carbon_weight_ignores_latency() {
+        let router = latency_router(CarbonRouterConfig {
+            carbon_weight: 1.0,
+            ..Default::default()
+        })
+        .await;
+
+        assert_eq!(
+            router.select_greenest_region().await.as_deref(),
+            Some("us-west")
+        );
+        assert!((router.get_sorted_regions().await[0].score - 0.1).abs() < 1e-9);
+        assert!(
+            router.get_routing_weight("us-west").await > router.get_routing_weight("us-east").await
+        );
+    }
+
+    #[tokio::test]
+    async fn test_latency_blend_still_excludes_high_carbon() {
+        let router = latency_router(CarbonRouterConfig {
+            carbon_weight: 0.0,
+            max_intensity: 300.0,
+            threshold: 200.0,
+            ..Default::default()
+        })
+        .await;
+
+        // us-east is closest but above max_intensity
+        assert_eq!(
+            router.select_greenest_region().await.as_deref(),
+            Some("eu-west")
+        );
+        assert_eq!(router.get_routing_weight("us-east").await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_latency_update_rescores_immediately() {
+        let router = CarbonRouter::new(
+            CarbonRouterConfig::default(),
+            MockEnergyClient::new(),
+            CarbonIntensityCache::new(300),
+        );
+        router
+            .register_region(Region::new("us-west", "us-west"))
+            .await;
+        router.refresh_carbon_data().await.unwrap();
+        let carbon_only = router.get_sorted_regions().await[0].score;
+        assert!((carbon_only - 0.1).abs() < 1e-9);
+
+        // 0.5 * 0.1 + 0.5 * min(1000 / 500, 1.0)
+        router.update_region_latency("us-west", 1000.0).await;
+        let blended = router.get_sorted_regions().await[0].clone();
+        assert_eq!(blended.latency_ms, Some(1000.0));
+        assert!((blended.score - 0.55).abs() < 1e-9);
+
+        router.update_region_latency("us-west", f64::NAN).await;
+        assert_eq!(
+            router.get_sorted_regions().await[0].latency_ms,
+            Some(1000.0)
+        );
+
+        // Kept across refreshes
+        router.cache.clear().await;
+        router.refresh_carbon_data().await.unwrap();
+        assert!((router.get_sorted_regions().await[0].score - 0.55).abs() < 1e-9);
+    }
+
     #[tokio::test]
     async fn test_is_region_green() {
         let config = CarbonRouterConfig {
@@ -543,6 +2179,7 @@ mod tests {
         let score = RegionScore {
             region_id: "test-region".to_string(),
             carbon_intensity: 150.0,
+            latency_ms: None,
             score: 0.3,
             recommended: true,
         };
@@ -556,6 +2193,7 @@ mod tests {
         let score = RegionScore {
             region_id: "clone-test".to_string(),
             carbon_intensity: 100.0,
+            latency_ms: None,
             score: 0.2,
             recommended: false,
         };
@@ -684,6 +2322,7 @@ mod tests {
         let score = RegionScore {
             region_id: "us-west-2".to_string(),
             carbon_intensity: 150.0,
+            latency_ms: None,
             score: 0.3,
             recommended: true,
         };
@@ -696,6 +2335,7 @@ mod tests {
         let score = RegionScore {
             region_id: "eu-central".to_string(),
             carbon_intensity: 100.0,
+            latency_ms: None,
             score: 0.2,
             recommended: true,
         };
@@ -710,8 +2350,16 @@ mod tests {
             threshold: 100.0,
             max_intensity: 300.0,
             prefer_renewable: false,
+            renewable_bonus: 0.0,
             preferred_regions: vec![],
             carbon_weight: 0.3,
+            max_latency_ms: 500.0,
+            dry_run: false,
+            region_upstreams: HashMap::new(),
+            switch_margin: 25.0,
+            min_switch_duration: Duration::from_secs(60),
+            switch_hold_refreshes: 3,
+            decision_log_capacity: 16,
         };
 
         assert!(!config.enabled);
@@ -725,8 +2373,16 @@ mod tests {
             threshold: 0.0,
             max_intensity: 1000.0,
             prefer_renewable: true,
+            renewable_bonus: 1.0,
             preferred_regions: vec!["us-west-1".to_string()],
             carbon_weight: 1.0,
+            max_latency_ms: 500.0,
+            dry_run: true,
+            region_upstreams: HashMap::new(),
+            switch_margin: 0.0,
+            min_switch_duration: Duration::ZERO,
+            switch_hold_refreshes: 0,
+            decision_log_capacity: 0,
         };
 
         assert_eq!(config.threshold, 0.0);
@@ -739,6 +2395,7 @@ mod tests {
         let score = RegionScore {
             region_id: "high-carbon".to_string(),
             carbon_intensity: 450.0,
+            latency_ms: None,
             score: 0.9,
             recommended: false,
         };
@@ -752,6 +2409,7 @@ mod tests {
         let score = RegionScore {
             region_id: "renewable-region".to_string(),
             carbon_intensity: 0.0,
+            latency_ms: None,
             score: 0.0,
             recommended: true,
         };
@@ -774,6 +2432,7 @@ mod tests {
         let score = RegionScore {
             region_id: "test-region".to_string(),
             carbon_intensity: 150.0,
+            latency_ms: None,
             score: 0.5,
             recommended: true,
         };
@@ -830,6 +2489,8 @@ mod tests {
             timestamp: chrono::Utc::now(),
             valid_for_seconds: 300,
             rating: None,
+            renewable_percentage: None,
+            power_breakdown: None,
         };
         cache.put(cached_intensity).await;
 
@@ -893,6 +2554,7 @@ mod tests {
                 RegionScore {
                     region_id: "valid".to_string(),
                     carbon_intensity: 100.0,
+                    latency_ms: None,
                     score: 0.2,
                     recommended: true,
                 },
@@ -902,6 +2564,7 @@ mod tests {
                 RegionScore {
                     region_id: "nan".to_string(),
                     carbon_intensity: f64::NAN,
+                    latency_ms: None,
                     score: f64::NAN,
                     recommended: false, // NaN comparison usually false
                 },
@@ -940,13 +2603,14 @@ mod tests {
                 RegionScore {
                     region_id: "dirty".to_string(),
                     carbon_intensity: 999.0,
+                    latency_ms: None,
                     score: 0.999,
                     recommended: true,
                 },
             );
         }
 
-        // Weight = (1.0 - 0.999) * 100 * 1.0 = 0.001 * 100 = 0.1 cast to u32 = 0
+        // Weight = (1.0 - 0.999) * 100 = 0.1 cast to u32 = 0
         // Should be clamped to 1
         let weight = router.get_routing_weight("dirty").await;
         assert_eq!(weight, 1);
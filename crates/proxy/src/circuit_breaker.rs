@@ -1,4 +1,10 @@
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
 use std::time::{Duration, Instant};
+use tracing::warn;
+
+use crate::upstream::CircuitBreakerConfig;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CircuitState {
@@ -7,6 +13,17 @@ pub enum CircuitState {
     HalfOpen, // Testing if the upstream recovered
 }
 
+impl CircuitState {
+    /// Value exported on the breaker state gauge
+    pub fn as_gauge(&self) -> f64 {
+        match self {
+            CircuitState::Closed => 0.0,
+            CircuitState::HalfOpen => 1.0,
+            CircuitState::Open => 2.0,
+        }
+    }
+}
+
 pub struct CircuitBreaker {
     pub state: CircuitState,
     pub error_threshold_percent: u8,
@@ -33,6 +50,22 @@ impl CircuitBreaker {
         }
     }
 
+    /// Build a breaker from upstream configuration
+    pub fn from_config(config: &CircuitBreakerConfig) -> Self {
+        Self::new(
+            config.error_threshold_percent,
+            config.window_size_ms,
+            config.open_time_ms,
+        )
+    }
+
+    /// Adopt new thresholds without touching the current state or window
+    pub fn apply_config(&mut self, config: &CircuitBreakerConfig) {
+        self.error_threshold_percent = config.error_threshold_percent;
+        self.window_size = Duration::from_millis(config.window_size_ms);
+        self.open_time = Duration::from_millis(config.open_time_ms);
+    }
+
     pub fn acquire(&mut self) -> bool {
         let now = Instant::now();
         if now.duration_since(self.window_start) >= self.window_size {
@@ -91,6 +124,116 @@ impl CircuitBreaker {
     }
 }
 
+/// Circuit breakers guarding upstream forwarding, keyed by upstream target
+///
+/// Each proxy server owns its registry and hands it to the forwarding path
+/// with every request.
+#[derive(Default)]
+pub struct UpstreamBreakers {
+    breakers: Mutex<HashMap<String, CircuitBreaker>>,
+}
+
+impl UpstreamBreakers {
+    /// Guard `upstream` with a breaker
+    ///
+    /// Registering an upstream again updates its thresholds but keeps its
+    /// current state and window.
+    pub fn register(&self, upstream: &str, config: &CircuitBreakerConfig) {
+        match self.breakers.lock().entry(upstream.to_string()) {
+            Entry::Occupied(mut entry) => entry.get_mut().apply_config(config),
+            Entry::Vacant(entry) => {
+                crate::metrics::set_upstream_circuit_state(upstream, &CircuitState::Closed);
+                entry.insert(CircuitBreaker::from_config(config));
+            }
+        }
+    }
+
+    /// Current state of the breaker for `upstream`, if it has one
+    pub fn state(&self, upstream: &str) -> Option<CircuitState> {
+        self.breakers
+            .lock()
+            .get(upstream)
+            .map(|breaker| breaker.state.clone())
+    }
+
+    /// Ask to send a request to `upstream`
+    ///
+    /// Returns `None` while the breaker is open or a half-open probe is in
+    /// flight. Upstreams without a breaker always get a permit.
+    pub fn acquire(&self, upstream: &str) -> Option<BreakerPermit<'_>> {
+        let mut breakers = self.breakers.lock();
+        let Some(breaker) = breakers.get_mut(upstream) else {
+            return Some(BreakerPermit {
+                breakers: self,
+                upstream: None,
+            });
+        };
+
+        let before = breaker.state.clone();
+        let allowed = breaker.acquire();
+        if breaker.state != before {
+            crate::metrics::set_upstream_circuit_state(upstream, &breaker.state);
+        }
+        allowed.then(|| BreakerPermit {
+            breakers: self,
+            upstream: Some(upstream.to_string()),
+        })
+    }
+
+    fn record(&self, upstream: &str, success: bool) {
+        let mut breakers = self.breakers.lock();
+        let Some(breaker) = breakers.get_mut(upstream) else {
+            return;
+        };
+
+        let before = breaker.state.clone();
+        if success {
+            breaker.record_success();
+        } else {
+            breaker.record_failure();
+        }
+        if breaker.state != before {
+            if breaker.state == CircuitState::Open {
+                warn!("⚡ Circuit breaker for {} opened", upstream);
+            }
+            crate::metrics::set_upstream_circuit_state(upstream, &breaker.state);
+        }
+    }
+}
+
+/// Permission to send one request through a breaker
+///
+/// Dropping the permit without settling it counts as a failure, so a probe
+/// cancelled by a timeout cannot leave the breaker half-open forever.
+pub struct BreakerPermit<'a> {
+    breakers: &'a UpstreamBreakers,
+    upstream: Option<String>,
+}
+
+impl BreakerPermit<'_> {
+    /// Report the request as successful
+    pub fn success(mut self) {
+        if let Some(upstream) = self.upstream.take() {
+            self.breakers.record(&upstream, true);
+        }
+    }
+
+    /// Report the request as failed
+    pub fn failure(mut self) {
+        if let Some(upstream) = self.upstream.take() {
+            self.breakers.record(&upstream, false);
+        }
+    }
+}
+
+impl Drop for BreakerPermit<'_> {
+    fn drop(&mut self) {
+        if let Some(upstream) = self.upstream.take() {
+            self.breakers.record(&upstream, false);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -138,4 +281,75 @@ mod tests {
         assert_eq!(cb.state, CircuitState::Closed);
         assert!(cb.acquire()); // Fully open again
     }
+
+    fn fast_config() -> CircuitBreakerConfig {
+        CircuitBreakerConfig {
+            error_threshold_percent: 50,
+            window_size_ms: 10_000,
+            open_time_ms: 50,
+        }
+    }
+
+    #[test]
+    fn test_upstream_breakers_open_and_recover() {
+        let breakers = UpstreamBreakers::default();
+        breakers.register("backend:1", &fast_config());
+
+        for _ in 0..6 {
+            breakers.acquire("backend:1").unwrap().failure();
+        }
+        assert_eq!(breakers.state("backend:1"), Some(CircuitState::Open));
+        assert!(breakers.acquire("backend:1").is_none());
+
+        // State is per upstream
+        assert!(breakers.acquire("backend:2").is_some());
+        assert_eq!(breakers.state("backend:2"), None);
+
+        std::thread::sleep(Duration::from_millis(60));
+        let probe = breakers.acquire("backend:1").expect("half-open probe");
+        assert_eq!(breakers.state("backend:1"), Some(CircuitState::HalfOpen));
+        assert!(breakers.acquire("backend:1").is_none());
+        probe.success();
+
+        assert_eq!(breakers.state("backend:1"), Some(CircuitState::Closed));
+        assert!(breakers.acquire("backend:1").is_some());
+    }
+
+    #[test]
+    fn test_dropped_probe_reopens_breaker() {
+        let breakers = UpstreamBreakers::default();
+        breakers.register("backend:1", &fast_config());
+        for _ in 0..6 {
+            breakers.acquire("backend:1").unwrap().failure();
+        }
+
+        std::thread::sleep(Duration::from_millis(60));
+        drop(breakers.acquire("backend:1").expect("half-open probe"));
+        assert_eq!(breakers.state("backend:1"), Some(CircuitState::Open));
+    }
+
+    #[test]
+    fn test_register_updates_thresholds_and_keeps_state() {
+        let breakers = UpstreamBreakers::default();
+        breakers.register("backend:1", &fast_config());
+        for _ in 0..6 {
+            breakers.acquire("backend:1").unwrap().failure();
+        }
+
+        let updated = CircuitBreakerConfig {
+            error_threshold_percent: 90,
+            window_size_ms: 30_000,
+            open_time_ms: 60_000,
+        };
+        breakers.register("backend:1", &updated);
+        assert_eq!(breakers.state("backend:1"), Some(CircuitState::Open));
+        assert_eq!(CircuitState::Open.as_gauge(), 2.0);
+
+        let map = breakers.breakers.lock();
+        let breaker = &map["backend:1"];
+        assert_eq!(breaker.error_threshold_percent, 90);
+        assert_eq!(breaker.window_size, Duration::from_secs(30));
+        assert_eq!(breaker.open_time, Duration::from_secs(60));
+        assert_eq!(breaker.total_errors, 6);
+    }
 }
@@ -8,7 +8,8 @@ use serde::{Deserialize, Serialize};
 use serde_yaml as yaml;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::SystemTime;
+use std::sync::mpsc;
+use std::time::{Duration, SystemTime};
 use tracing::{debug, info, warn};
 
 /// Configuration file format
@@ -163,6 +164,9 @@ pub struct LogConfig {
     /// OTLP gRPC endpoint
     #[serde(default = "default_otlp_endpoint")]
     pub otlp_endpoint: String,
+    /// Optional JSON lines file receiving `connection_established` audit events
+    #[serde(default)]
+    pub audit_log: Option<String>,
 }
 
 fn default_otlp_endpoint() -> String {
@@ -181,6 +185,7 @@ impl Default for LogConfig {
             syslog: None,
             otel_enabled: true,
             otlp_endpoint: default_otlp_endpoint(),
+            audit_log: None,
         }
     }
 }
@@ -200,6 +205,10 @@ pub struct HealthConfig {
     /// Readiness endpoint path
     #[serde(default = "default_readiness_path")]
     pub readiness_path: String,
+    /// Bearer token required by `POST /admin/maintenance`; the endpoint is
+    /// disabled without one
+    #[serde(default)]
+    pub admin_token: Option<String>,
 }
 
 fn default_health_port() -> u16 {
@@ -219,6 +228,7 @@ impl Default for HealthConfig {
             port: default_health_port(),
             liveness_path: default_liveness_path(),
             readiness_path: default_readiness_path(),
+            admin_token: None,
         }
     }
 }
@@ -321,6 +331,119 @@ impl Default for XdsConfig {
     }
 }
 
+/// Listening socket configuration shared by the HTTP/2, PQC and QUIC listeners
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListenerConfig {
+    /// Set `SO_REUSEADDR` so restarts can bind over `TIME_WAIT` sockets
+    #[serde(default = "default_true")]
+    pub reuse_addr: bool,
+    /// Set `SO_REUSEPORT` (Unix only) so several processes can share the port
+    #[serde(default)]
+    pub reuse_port: bool,
+    /// Number of bind retries while the port is still in use
+    #[serde(default = "default_bind_retries")]
+    pub bind_retries: u32,
+    /// Initial delay between bind retries in milliseconds (doubles each attempt)
+    #[serde(default = "default_bind_backoff_ms")]
+    pub bind_backoff_ms: u64,
+    /// TCP listen backlog
+    #[serde(default = "default_backlog")]
+    pub backlog: i32,
+    /// Disable Nagle's algorithm on accepted connections
+    #[serde(default = "default_true")]
+    pub tcp_nodelay: bool,
+    /// TCP keepalive probing on accepted connections
+    #[serde(default)]
+    pub keepalive: TcpKeepaliveConfig,
+}
+
+/// TCP keepalive settings for accepted connections
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TcpKeepaliveConfig {
+    /// Enable `SO_KEEPALIVE`
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Idle time before the first probe in seconds
+    #[serde(default = "default_keepalive_idle")]
+    pub idle_secs: u64,
+    /// Interval between probes in seconds
+    #[serde(default = "default_keepalive_interval")]
+    pub interval_secs: u64,
+    /// Unanswered probes before the connection is dropped
+    #[serde(default = "default_keepalive_retries")]
+    pub retries: u32,
+}
+
+fn default_keepalive_idle() -> u64 {
+    60
+}
+fn default_keepalive_interval() -> u64 {
+    10
+}
+fn default_keepalive_retries() -> u32 {
+    5
+}
+
+impl Default for TcpKeepaliveConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            idle_secs: default_keepalive_idle(),
+            interval_secs: default_keepalive_interval(),
+            retries: default_keepalive_retries(),
+        }
+    }
+}
+
+fn default_bind_retries() -> u32 {
+    5
+}
+fn default_bind_backoff_ms() -> u64 {
+    100
+}
+fn default_backlog() -> i32 {
+    1024
+}
+
+impl Default for ListenerConfig {
+    fn default() -> Self {
+        Self {
+            reuse_addr: true,
+            reuse_port: false,
+            bind_retries: default_bind_retries(),
+            bind_backoff_ms: default_bind_backoff_ms(),
+            backlog: default_backlog(),
+            tcp_nodelay: true,
+            keepalive: TcpKeepaliveConfig::default(),
+        }
+    }
+}
+
+/// Default cap on the total size of request header names and values
+pub const DEFAULT_MAX_HEADER_BYTES: usize = 64 * 1024;
+
+fn default_handshake_timeout_ms() -> u64 {
+    10_000
+}
+
+fn default_max_header_bytes() -> usize {
+    DEFAULT_MAX_HEADER_BYTES
+}
+
+/// Energy budget enforced on the HTTP listener
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnergyBudgetConfig {
+    /// Estimated energy allowed per window in joules
+    pub ceiling_joules: f64,
+    /// Rolling window length in seconds
+    #[serde(default = "default_energy_budget_window")]
+    pub window_secs: u64,
+}
+
+fn default_energy_budget_window() -> u64 {
+    3600
+}
+
 /// Proxy server configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProxyConfig {
@@ -345,6 +468,12 @@ pub struct ProxyConfig {
     /// Upstream address to forward requests to
     #[serde(default = "default_upstream")]
     pub upstream_addr: String,
+    /// Protocol used to connect to the upstream (`h1`, `h2c`, `h2-tls`, `h3`)
+    #[serde(default)]
+    pub upstream_protocol: crate::upstream_client::UpstreamProtocol,
+    /// Circuit breaker around upstream forwarding
+    #[serde(default)]
+    pub circuit_breaker: Option<crate::upstream::CircuitBreakerConfig>,
     /// TLS configuration
     #[serde(default)]
     pub tls: TlsConfig,
@@ -369,6 +498,27 @@ pub struct ProxyConfig {
     /// xDS Dynamic Configuration
     #[serde(default)]
     pub xds: XdsConfig,
+    /// Listening socket options
+    #[serde(default)]
+    pub listener: ListenerConfig,
+    /// Shed load once estimated energy use exceeds this budget
+    #[serde(default)]
+    pub energy_budget: Option<EnergyBudgetConfig>,
+    /// Requests whose headers exceed this many bytes get `431`
+    #[serde(default = "default_max_header_bytes")]
+    pub max_header_bytes: usize,
+    /// Time allowed for a client to complete the PQC handshake
+    #[serde(default = "default_handshake_timeout_ms")]
+    pub handshake_timeout_ms: u64,
+    /// Global request timeout in milliseconds; routes may override it
+    #[serde(default)]
+    pub request_timeout_ms: Option<u64>,
+    /// Per-route method allowlists and timeouts
+    #[serde(rename = "route", default)]
+    pub routes: Vec<crate::route::RouteConfig>,
+    /// CORS policy for browser clients
+    #[serde(default)]
+    pub cors: Option<crate::cors::CorsConfig>,
 }
 
 fn default_host() -> String {
@@ -391,6 +541,8 @@ impl Default for ProxyConfig {
             quic_enabled: false,
             worker_threads: 0,
             upstream_addr: default_upstream(),
+            upstream_protocol: Default::default(),
+            circuit_breaker: None,
             tls: TlsConfig::default(),
             logging: LogConfig::default(),
             health: HealthConfig::default(),
@@ -399,6 +551,13 @@ impl Default for ProxyConfig {
             maps: Vec::new(),
             locations: Vec::new(),
             xds: XdsConfig::default(),
+            listener: ListenerConfig::default(),
+            energy_budget: None,
+            max_header_bytes: DEFAULT_MAX_HEADER_BYTES,
+            handshake_timeout_ms: default_handshake_timeout_ms(),
+            request_timeout_ms: None,
+            routes: Vec::new(),
+            cors: None,
         }
     }
 }
@@ -490,6 +649,53 @@ impl ProxyConfig {
                 "Upstream address is required".to_string(),
             ));
         }
+        if self.upstream_protocol == crate::upstream_client::UpstreamProtocol::H3 {
+            return Err(ConfigError::ValidationError(
+                "upstream_protocol \"h3\" is not supported yet".to_string(),
+            ));
+        }
+        if let Some(budget) = &self.energy_budget
+            && (!budget.ceiling_joules.is_finite()
+                || budget.ceiling_joules <= 0.0
+                || budget.window_secs == 0)
+        {
+            return Err(ConfigError::ValidationError(
+                "Energy budget needs a positive ceiling and window".to_string(),
+            ));
+        }
+        if let Some(breaker) = &self.circuit_breaker
+            && (breaker.error_threshold_percent == 0
+                || breaker.error_threshold_percent > 100
+                || breaker.window_size_ms == 0
+                || breaker.open_time_ms == 0)
+        {
+            return Err(ConfigError::ValidationError(
+                "Circuit breaker needs a threshold of 1-100% and positive window and open time"
+                    .to_string(),
+            ));
+        }
+        if self.request_timeout_ms == Some(0) || self.handshake_timeout_ms == 0 {
+            return Err(ConfigError::ValidationError(
+                "Request and handshake timeouts must be positive".to_string(),
+            ));
+        }
+        for route in &self.routes {
+            if !route.path.starts_with('/') || route.timeout_ms == Some(0) {
+                return Err(ConfigError::ValidationError(format!(
+                    "Route '{}' needs an absolute path and a positive timeout",
+                    route.path
+                )));
+            }
+        }
+        if let Some(cors) = &self.cors
+            && (cors.allowed_origins.is_empty()
+                || (cors.allow_credentials && cors.allowed_origins.iter().any(|o| o == "*")))
+        {
+            return Err(ConfigError::ValidationError(
+                "CORS needs at least one allowed origin, and credentials cannot be combined with \"*\""
+                    .to_string(),
+            ));
+        }
         if self.tls_enabled && self.tls.enabled {
             // Check that cert paths exist when TLS is enabled
             if !Path::new(&self.tls.cert_path).exists() {
@@ -511,6 +717,50 @@ impl ProxyConfig {
         Ok(config)
     }
 
+    /// Load and merge every configuration fragment in `dir`
+    ///
+    /// Fragments (`*.yaml`, `*.yml`, `*.toml`, `*.json`; hidden files are
+    /// skipped) are applied in file name order, so later files override
+    /// earlier ones. Tables merge key by key; arrays follow `arrays`. The
+    /// merged result gets environment overrides and validation like [`load`](Self::load).
+    pub fn load_dir(dir: &Path, arrays: ArrayMerge) -> Result<Self, ConfigError> {
+        info!("Loading configuration fragments from {}", dir.display());
+        let fragments = config_fragments(dir)?;
+        if fragments.is_empty() {
+            return Err(ConfigError::IoError(format!(
+                "No configuration fragments in {}",
+                dir.display()
+            )));
+        }
+
+        let mut merged = serde_json::Value::Object(Default::default());
+        for path in &fragments {
+            debug!("Merging configuration fragment {}", path.display());
+            merge_values(&mut merged, Self::load_value(path)?, arrays);
+        }
+        let mut config: Self = serde_json::from_value(merged)
+            .map_err(|e| ConfigError::ParseError(format!("Merged configuration: {}", e)))?;
+        config.apply_env_overrides();
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Parse a file into an untyped value for merging
+    fn load_value(path: &Path) -> Result<serde_json::Value, ConfigError> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            ConfigError::IoError(format!("Failed to read {}: {}", path.display(), e))
+        })?;
+        let parse_err = |e: &dyn std::fmt::Display| {
+            ConfigError::ParseError(format!("{}: {}", path.display(), e))
+        };
+        match ConfigFormat::from_path(path) {
+            Some(ConfigFormat::Yaml) => yaml::from_str(&content).map_err(|e| parse_err(&e)),
+            Some(ConfigFormat::Toml) => toml::from_str(&content).map_err(|e| parse_err(&e)),
+            Some(ConfigFormat::Json) => serde_json::from_str(&content).map_err(|e| parse_err(&e)),
+            None => Err(ConfigError::UnsupportedFormat(path.display().to_string())),
+        }
+    }
+
     /// Save configuration to file
     pub fn save_to_file(&self, path: &Path) -> Result<(), ConfigError> {
         let format = ConfigFormat::from_path(path)
@@ -556,12 +806,69 @@ impl std::fmt::Display for ConfigError {
 
 impl std::error::Error for ConfigError {}
 
+/// How arrays from a later configuration fragment combine with earlier ones
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ArrayMerge {
+    /// The later array replaces the earlier one
+    #[default]
+    Replace,
+    /// The later array's items are appended, e.g. to collect `route` entries
+    Append,
+}
+
+/// Configuration fragments in `dir`, in the order they are merged
+fn config_fragments(dir: &Path) -> Result<Vec<PathBuf>, ConfigError> {
+    let entries = std::fs::read_dir(dir)
+        .map_err(|e| ConfigError::IoError(format!("Failed to read {}: {}", dir.display(), e)))?;
+    let mut fragments: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| is_config_fragment(path) && path.is_file())
+        .collect();
+    fragments.sort();
+    Ok(fragments)
+}
+
+fn is_config_fragment(path: &Path) -> bool {
+    let hidden = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_none_or(|name| name.starts_with('.'));
+    !hidden && ConfigFormat::from_path(path).is_some()
+}
+
+/// Deep-merge `overlay` into `base`
+fn merge_values(base: &mut serde_json::Value, overlay: serde_json::Value, arrays: ArrayMerge) {
+    use serde_json::Value;
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_values(existing, value, arrays),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (Value::Array(base), Value::Array(overlay)) if arrays == ArrayMerge::Append => {
+            base.extend(overlay);
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// Poll interval used when native file events are unavailable
+pub const WATCH_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
 /// Hot-reloadable configuration manager
+#[derive(Clone)]
 pub struct ConfigManager {
     /// Current configuration
     config: Arc<RwLock<ProxyConfig>>,
-    /// Configuration file path
+    /// Configuration file path, or fragment directory
     config_path: Option<PathBuf>,
+    /// Set when `config_path` is a directory of fragments
+    fragments: Option<ArrayMerge>,
     /// Last modified time
     last_modified: Arc<RwLock<Option<SystemTime>>>,
 }
@@ -572,6 +879,7 @@ impl ConfigManager {
         Self {
             config: Arc::new(RwLock::new(ProxyConfig::default())),
             config_path: None,
+            fragments: None,
             last_modified: Arc::new(RwLock::new(None)),
         }
     }
@@ -585,10 +893,45 @@ impl ConfigManager {
         Ok(Self {
             config: Arc::new(RwLock::new(config)),
             config_path: Some(path.to_path_buf()),
+            fragments: None,
             last_modified: Arc::new(RwLock::new(modified)),
         })
     }
 
+    /// Create from a directory of fragments, replacing arrays on override
+    pub fn from_dir(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        Self::from_dir_with(path, ArrayMerge::default())
+    }
+
+    /// Create from a directory of fragments, merging arrays with `arrays`
+    pub fn from_dir_with(path: impl AsRef<Path>, arrays: ArrayMerge) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let config = ProxyConfig::load_dir(path, arrays)?;
+        let manager = Self {
+            config: Arc::new(RwLock::new(config)),
+            config_path: Some(path.to_path_buf()),
+            fragments: Some(arrays),
+            last_modified: Arc::new(RwLock::new(None)),
+        };
+        *manager.last_modified.write() = manager.source_modified(path);
+        Ok(manager)
+    }
+
+    /// Latest modification time of the file, or of the directory and its fragments
+    fn source_modified(&self, path: &Path) -> Option<SystemTime> {
+        let modified = |p: &Path| std::fs::metadata(p).and_then(|m| m.modified()).ok();
+        if self.fragments.is_none() {
+            return modified(path);
+        }
+        // Adding or removing a fragment updates the directory's own mtime
+        let fragments = config_fragments(path).unwrap_or_default();
+        fragments
+            .iter()
+            .filter_map(|fragment| modified(fragment))
+            .chain(modified(path))
+            .max()
+    }
+
     /// Get current configuration (clone)
     pub fn get(&self) -> ProxyConfig {
         self.config.read().clone()
@@ -605,7 +948,7 @@ impl ConfigManager {
             return false;
         };
 
-        let current_modified = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+        let current_modified = self.source_modified(path);
 
         let last = *self.last_modified.read();
 
@@ -630,7 +973,16 @@ impl ConfigManager {
             "Configuration change detected, reloading from {}",
             path.display()
         );
-        let new_config = ProxyConfig::load(path)?;
+        self.load_from(path)?;
+        Ok(true)
+    }
+
+    /// Replace the configuration with the file's (or fragments') current contents
+    fn load_from(&self, path: &Path) -> Result<(), ConfigError> {
+        let new_config = match self.fragments {
+            Some(arrays) => ProxyConfig::load_dir(path, arrays)?,
+            None => ProxyConfig::load(path)?,
+        };
 
         {
             let mut config = self.config.write();
@@ -639,11 +991,120 @@ impl ConfigManager {
 
         {
             let mut last_modified = self.last_modified.write();
-            *last_modified = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+            *last_modified = self.source_modified(path);
         }
 
         info!("Configuration reloaded successfully");
-        Ok(true)
+        Ok(())
+    }
+
+    /// Reload as soon as the configuration file changes
+    ///
+    /// The file's directory is watched so atomic replaces (write to a temp
+    /// file, then rename over the original) are seen too. Events arriving
+    /// within `debounce` of each other cause a single reload, after which
+    /// `on_reload` receives the new configuration; a file that fails to load
+    /// is logged and the previous configuration kept. Falls back to polling
+    /// every [`WATCH_POLL_INTERVAL`] where native events are unavailable.
+    /// Watching stops when the returned [`ConfigWatcher`] is dropped.
+    ///
+    /// A fragment directory is watched directly and reloads when any fragment
+    /// is added, changed or removed.
+    pub fn watch<F>(&self, debounce: Duration, on_reload: F) -> Result<ConfigWatcher, ConfigError>
+    where
+        F: Fn(&ProxyConfig) + Send + 'static,
+    {
+        use notify::Watcher;
+
+        let Some(path) = self.config_path.clone() else {
+            return Err(ConfigError::IoError(
+                "No configuration file to watch".to_string(),
+            ));
+        };
+        let file_name = path.file_name().map(|name| name.to_os_string());
+        let fragments = self.fragments.is_some();
+        let dir = match path.parent() {
+            _ if fragments => path.clone(),
+            Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        let watch_err = |e: notify::Error| {
+            ConfigError::IoError(format!("Failed to watch {}: {}", dir.display(), e))
+        };
+
+        let (tx, rx) = mpsc::channel::<notify::Result<notify::Event>>();
+        let native = notify::recommended_watcher(tx.clone()).and_then(|mut watcher| {
+            watcher.watch(&dir, notify::RecursiveMode::NonRecursive)?;
+            Ok(watcher)
+        });
+        let watcher: Box<dyn notify::Watcher + Send> = match native {
+            Ok(watcher) => Box::new(watcher),
+            Err(e) => {
+                warn!(
+                    "Native file watching unavailable ({}), polling {} every {:?}",
+                    e,
+                    path.display(),
+                    WATCH_POLL_INTERVAL
+                );
+                let mut watcher = notify::PollWatcher::new(
+                    tx,
+                    notify::Config::default().with_poll_interval(WATCH_POLL_INTERVAL),
+                )
+                .map_err(watch_err)?;
+                watcher
+                    .watch(&dir, notify::RecursiveMode::NonRecursive)
+                    .map_err(watch_err)?;
+                Box::new(watcher)
+            }
+        };
+
+        let affects_config = move |event: &notify::Event| {
+            if fragments {
+                return !event.kind.is_access()
+                    && event.paths.iter().any(|p| is_config_fragment(p));
+            }
+            (event.kind.is_create() || event.kind.is_modify())
+                && event
+                    .paths
+                    .iter()
+                    .any(|p| p.file_name() == file_name.as_deref())
+        };
+        let manager = self.clone();
+        std::thread::spawn(move || {
+            // Ends once the watcher, and with it the sender, is dropped
+            while let Ok(event) = rx.recv() {
+                match event {
+                    Ok(event) if affects_config(&event) => {}
+                    Ok(_) => continue,
+                    Err(e) => {
+                        warn!("Configuration watch error: {}", e);
+                        continue;
+                    }
+                }
+                // Swallow the burst of events a single save produces
+                while rx.recv_timeout(debounce).is_ok() {}
+
+                info!("Configuration file {} changed, reloading", path.display());
+                match manager.load_from(&path) {
+                    Ok(()) => on_reload(&manager.get()),
+                    Err(e) => warn!("Keeping previous configuration: {}", e),
+                }
+            }
+            debug!("Configuration watcher for {} stopped", path.display());
+        });
+
+        Ok(ConfigWatcher { _watcher: watcher })
+    }
+}
+
+/// Handle keeping a [`ConfigManager::watch`] subscription alive
+pub struct ConfigWatcher {
+    _watcher: Box<dyn notify::Watcher + Send>,
+}
+
+impl std::fmt::Debug for ConfigWatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConfigWatcher").finish_non_exhaustive()
     }
 }
 
@@ -1249,6 +1710,140 @@ upstream_addr: "test:8080"
         // But regardless, we exercised the code.
     }
 
+    #[test]
+    fn test_config_watch_reloads_on_change() {
+        let _lock = ENV_MUTEX.lock().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("aegis.yaml");
+        let write_host = |target: &Path, host: &str| {
+            let config = ProxyConfig {
+                host: host.to_string(),
+                ..Default::default()
+            };
+            std::fs::write(target, yaml::to_string(&config).unwrap()).unwrap();
+        };
+        write_host(&path, "10.0.0.1");
+
+        let manager = ConfigManager::from_file(&path).unwrap();
+        let (tx, rx) = mpsc::channel();
+        let _watcher = manager
+            .watch(Duration::from_millis(50), move |config| {
+                tx.send(config.host.clone()).unwrap();
+            })
+            .unwrap();
+        // Well below WATCH_POLL_INTERVAL, so only a file event can meet it
+        let bound = Duration::from_secs(1);
+
+        // Atomic replace: write a sibling file and rename it over the original
+        let staged = dir.path().join("aegis.yaml.tmp");
+        write_host(&staged, "10.0.0.2");
+        std::fs::rename(&staged, &path).unwrap();
+        assert_eq!(rx.recv_timeout(bound).unwrap(), "10.0.0.2");
+        assert_eq!(manager.get().host, "10.0.0.2");
+
+        // A burst of writes is debounced into a single reload
+        for i in 3..8 {
+            write_host(&path, &format!("10.0.0.{}", i));
+        }
+        assert_eq!(rx.recv_timeout(bound).unwrap(), "10.0.0.7");
+        assert!(rx.recv_timeout(Duration::from_millis(300)).is_err());
+
+        // An invalid file keeps the previous configuration
+        std::fs::write(&path, "port: 0\n").unwrap();
+        assert!(rx.recv_timeout(Duration::from_millis(300)).is_err());
+        assert_eq!(manager.get().host, "10.0.0.7");
+    }
+
+    fn write_fragments(dir: &Path) {
+        std::fs::write(
+            dir.join("10-base.yaml"),
+            "host: \"10.0.0.1\"\nport: 9000\nlogging:\n  level: debug\nroute:\n  - path: /a\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("20-override.toml"),
+            "port = 9100\n\n[logging]\njson_format = true\n\n[[route]]\npath = \"/b\"\n",
+        )
+        .unwrap();
+        // Not fragments
+        std::fs::write(dir.join(".30-hidden.yaml"), "port: 1\n").unwrap();
+        std::fs::write(dir.join("README.md"), "port: 1\n").unwrap();
+    }
+
+    #[test]
+    fn test_config_manager_from_dir_merges_fragments() {
+        let _lock = ENV_MUTEX.lock().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        write_fragments(dir.path());
+
+        let config = ConfigManager::from_dir(dir.path()).unwrap().get();
+        // The later fragment wins, untouched fields survive, tables merge deeply
+        assert_eq!(config.port, 9100);
+        assert_eq!(config.host, "10.0.0.1");
+        assert_eq!(config.logging.level, "debug");
+        assert!(config.logging.json_format);
+        let paths = |config: &ProxyConfig| {
+            config
+                .routes
+                .iter()
+                .map(|r| r.path.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(paths(&config), vec!["/b"]);
+
+        let config = ConfigManager::from_dir_with(dir.path(), ArrayMerge::Append)
+            .unwrap()
+            .get();
+        assert_eq!(paths(&config), vec!["/a", "/b"]);
+
+        let empty = tempfile::tempdir().unwrap();
+        assert!(matches!(
+            ConfigManager::from_dir(empty.path()),
+            Err(ConfigError::IoError(_))
+        ));
+    }
+
+    #[test]
+    fn test_config_manager_from_dir_rescans_on_new_fragment() {
+        let _lock = ENV_MUTEX.lock().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        write_fragments(dir.path());
+        let manager = ConfigManager::from_dir(dir.path()).unwrap();
+        assert!(!manager.check_for_changes());
+
+        let (tx, rx) = mpsc::channel();
+        let watcher = manager
+            .watch(Duration::from_millis(50), move |config| {
+                tx.send(config.upstream_addr.clone()).unwrap();
+            })
+            .unwrap();
+        std::fs::write(
+            dir.path().join("30-upstream.yaml"),
+            "upstream_addr: \"10.1.0.1:8080\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            rx.recv_timeout(Duration::from_secs(1)).unwrap(),
+            "10.1.0.1:8080"
+        );
+        assert_eq!(manager.get().port, 9100);
+        drop(watcher);
+
+        // Polling reload re-scans the directory as well
+        std::thread::sleep(Duration::from_millis(20));
+        std::fs::write(dir.path().join("40-port.toml"), "port = 9200\n").unwrap();
+        assert!(manager.check_for_changes());
+        assert!(manager.reload().unwrap());
+        assert_eq!(manager.get().port, 9200);
+        assert_eq!(manager.get().upstream_addr, "10.1.0.1:8080");
+    }
+
+    #[test]
+    fn test_config_watch_requires_file() {
+        let result = ConfigManager::new().watch(Duration::from_millis(50), |_| {});
+        assert!(matches!(result, Err(ConfigError::IoError(_))));
+    }
+
     #[test]
     fn test_config_manager_no_file() {
         let manager = ConfigManager::new();
@@ -1370,6 +1965,7 @@ upstream_addr: "test:8080"
         };
         assert_eq!(log.level, "debug");
         assert!(!log.json_format);
+        assert!(log.audit_log.is_none());
     }
 
     #[test]
@@ -1381,6 +1977,182 @@ upstream_addr: "test:8080"
         assert_eq!(health.readiness_path, "/ready");
     }
 
+    #[test]
+    fn test_listener_config_from_yaml() {
+        let yaml = r#"
+listener:
+  reuse_port: true
+  bind_retries: 2
+"#;
+        let config = ProxyConfig::parse(yaml, ConfigFormat::Yaml).unwrap();
+        assert!(config.listener.reuse_addr);
+        assert!(config.listener.reuse_port);
+        assert_eq!(config.listener.bind_retries, 2);
+        assert_eq!(config.listener.bind_backoff_ms, 100);
+        assert!(config.listener.tcp_nodelay);
+        assert!(config.listener.keepalive.enabled);
+    }
+
+    #[test]
+    fn test_max_header_bytes_config() {
+        assert_eq!(
+            ProxyConfig::default().max_header_bytes,
+            DEFAULT_MAX_HEADER_BYTES
+        );
+
+        let config = ProxyConfig::parse("max_header_bytes: 8192\n", ConfigFormat::Yaml).unwrap();
+        assert_eq!(config.max_header_bytes, 8192);
+    }
+
+    #[test]
+    fn test_handshake_timeout_config() {
+        let config = ProxyConfig::default();
+        assert_eq!(config.handshake_timeout_ms, 10_000);
+
+        let config = ProxyConfig::parse("handshake_timeout_ms: 0\n", ConfigFormat::Yaml).unwrap();
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::ValidationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_upstream_protocol_config() {
+        use crate::upstream_client::UpstreamProtocol;
+
+        assert_eq!(
+            ProxyConfig::default().upstream_protocol,
+            UpstreamProtocol::H1
+        );
+        let config = ProxyConfig::parse("upstream_protocol: h2-tls\n", ConfigFormat::Yaml).unwrap();
+        assert_eq!(config.upstream_protocol, UpstreamProtocol::H2Tls);
+        assert!(config.validate().is_ok());
+
+        let config =
+            ProxyConfig::parse("upstream_protocol = \"h3\"\n", ConfigFormat::Toml).unwrap();
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::ValidationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_circuit_breaker_config() {
+        let toml = r#"
+[circuit_breaker]
+error_threshold_percent = 25
+open_time_ms = 1000
+"#;
+        let config = ProxyConfig::parse(toml, ConfigFormat::Toml).unwrap();
+        let breaker = config.circuit_breaker.as_ref().unwrap();
+        assert_eq!(breaker.error_threshold_percent, 25);
+        assert_eq!(breaker.window_size_ms, 10000);
+        assert_eq!(breaker.open_time_ms, 1000);
+        assert!(config.validate().is_ok());
+
+        let config = ProxyConfig::parse(
+            "circuit_breaker:\n  error_threshold_percent: 150\n",
+            ConfigFormat::Yaml,
+        )
+        .unwrap();
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::ValidationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_route_config_from_toml() {
+        let toml = r#"
+request_timeout_ms = 30000
+
+[[route]]
+path = "/reports"
+allowed_methods = ["GET", "HEAD"]
+timeout_ms = 500
+"#;
+        let config = ProxyConfig::parse(toml, ConfigFormat::Toml).unwrap();
+        assert_eq!(config.request_timeout_ms, Some(30000));
+        assert_eq!(config.routes.len(), 1);
+        assert_eq!(config.routes[0].allowed_methods, vec!["GET", "HEAD"]);
+        assert_eq!(config.routes[0].timeout_ms, Some(500));
+        assert!(config.validate().is_ok());
+
+        let config =
+            ProxyConfig::parse("[[route]]\npath = \"reports\"\n", ConfigFormat::Toml).unwrap();
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::ValidationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_cors_config_from_toml() {
+        let toml = r#"
+[cors]
+allowed_origins = ["https://dashboard.example"]
+allowed_methods = ["GET"]
+allow_credentials = true
+max_age_secs = 600
+"#;
+        let config = ProxyConfig::parse(toml, ConfigFormat::Toml).unwrap();
+        let cors = config.cors.as_ref().unwrap();
+        assert_eq!(cors.allowed_origins, vec!["https://dashboard.example"]);
+        assert_eq!(cors.allowed_methods, vec!["GET"]);
+        assert_eq!(cors.max_age_secs, Some(600));
+        assert!(config.validate().is_ok());
+
+        let toml = "[cors]\nallowed_origins = [\"*\"]\nallow_credentials = true\n";
+        let config = ProxyConfig::parse(toml, ConfigFormat::Toml).unwrap();
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::ValidationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_energy_budget_config_from_yaml() {
+        let config = ProxyConfig::default();
+        assert!(config.energy_budget.is_none());
+
+        let yaml = r#"
+energy_budget:
+  ceiling_joules: 5000.0
+"#;
+        let config = ProxyConfig::parse(yaml, ConfigFormat::Yaml).unwrap();
+        let budget = config.energy_budget.as_ref().unwrap();
+        assert_eq!(budget.ceiling_joules, 5000.0);
+        assert_eq!(budget.window_secs, 3600);
+        assert!(config.validate().is_ok());
+
+        let yaml = r#"
+energy_budget:
+  ceiling_joules: 0.0
+"#;
+        let config = ProxyConfig::parse(yaml, ConfigFormat::Yaml).unwrap();
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::ValidationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_keepalive_config_from_toml() {
+        let toml = r#"
+[listener]
+tcp_nodelay = false
+
+[listener.keepalive]
+idle_secs = 30
+retries = 3
+"#;
+        let config = ProxyConfig::parse(toml, ConfigFormat::Toml).unwrap();
+        assert!(!config.listener.tcp_nodelay);
+        assert_eq!(config.listener.keepalive.idle_secs, 30);
+        assert_eq!(config.listener.keepalive.interval_secs, 10);
+        assert_eq!(config.listener.keepalive.retries, 3);
+    }
+
     #[test]
     fn test_save_to_file_yaml() {
         let config = ProxyConfig::default();
@@ -1463,6 +2235,7 @@ upstream_addr: "test:8080"
         let manager = ConfigManager {
             config: Arc::new(RwLock::new(config)),
             config_path: Some(path.clone()),
+            fragments: None,
             last_modified: Arc::new(RwLock::new(None)), // Force None state
         };
 
@@ -1478,6 +2251,7 @@ upstream_addr: "test:8080"
         let manager = ConfigManager {
             config: Arc::new(RwLock::new(ProxyConfig::default())),
             config_path: Some(std::path::PathBuf::from("/nonexistent/path/config.yaml")),
+            fragments: None,
             last_modified: Arc::new(RwLock::new(Some(std::time::SystemTime::now()))),
         };
 
@@ -0,0 +1,103 @@
+//! Connection-Level Security Details
+//!
+//! Each listener records how the client connected in a [`ConnectionInfo`]
+//! attached to every request as an extension, so request handling can set
+//! `X-Forwarded-Proto`, enforce encryption-only routes and log the transport.
+
+use hyper::Request;
+use rustls::ServerConnection;
+
+/// How the client reached the proxy
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConnectionInfo {
+    /// Whether the connection is encrypted (TLS or the PQC stream)
+    pub encrypted: bool,
+    /// Negotiated key exchange or cipher suite, when encrypted
+    pub algorithm: Option<String>,
+    /// Common name of the client certificate, when one was presented
+    pub peer_cert_cn: Option<String>,
+}
+
+impl ConnectionInfo {
+    /// Unencrypted connection
+    pub fn plaintext() -> Self {
+        Self::default()
+    }
+
+    /// Encrypted connection using `algorithm`
+    pub fn encrypted(algorithm: impl Into<String>) -> Self {
+        Self {
+            encrypted: true,
+            algorithm: Some(algorithm.into()),
+            peer_cert_cn: None,
+        }
+    }
+
+    /// Details of an established rustls server connection
+    pub fn from_tls(connection: &ServerConnection) -> Self {
+        let algorithm = connection
+            .negotiated_cipher_suite()
+            .map(|suite| format!("{:?}", suite.suite()))
+            .unwrap_or_else(|| "TLS".to_string());
+        let peer_cert_cn = connection
+            .peer_certificates()
+            .and_then(|certs| certs.first())
+            .and_then(|cert| common_name(cert.as_ref()));
+        Self {
+            peer_cert_cn,
+            ..Self::encrypted(algorithm)
+        }
+    }
+
+    /// Set the client certificate's common name
+    pub fn with_peer_cert_cn(mut self, cn: impl Into<String>) -> Self {
+        self.peer_cert_cn = Some(cn.into());
+        self
+    }
+
+    /// Value for `X-Forwarded-Proto`
+    pub fn forwarded_proto(&self) -> &'static str {
+        if self.encrypted { "https" } else { "http" }
+    }
+
+    /// Info attached to `req`, if its listener recorded any
+    pub fn of<B>(req: &Request<B>) -> Option<&Self> {
+        req.extensions().get::<Self>()
+    }
+}
+
+/// Subject common name of a DER certificate
+fn common_name(der: &[u8]) -> Option<String> {
+    let (_, cert) = x509_parser::parse_x509_certificate(der).ok()?;
+    let cn = cert.subject().iter_common_name().next()?;
+    cn.as_str().ok().map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_forwarded_proto_and_extension_lookup() {
+        assert_eq!(ConnectionInfo::plaintext().forwarded_proto(), "http");
+        let info = ConnectionInfo::encrypted("HybridMlKem768").with_peer_cert_cn("client-a");
+        assert_eq!(info.forwarded_proto(), "https");
+
+        let mut req = Request::new(());
+        assert!(ConnectionInfo::of(&req).is_none());
+        req.extensions_mut().insert(info.clone());
+        assert_eq!(ConnectionInfo::of(&req), Some(&info));
+    }
+
+    #[test]
+    fn test_common_name_from_certificate() {
+        let mut params = rcgen::CertificateParams::new(vec!["localhost".to_string()]).unwrap();
+        params
+            .distinguished_name
+            .push(rcgen::DnType::CommonName, "client-a");
+        let key = rcgen::KeyPair::generate().unwrap();
+        let cert = params.self_signed(&key).unwrap();
+        assert_eq!(common_name(cert.der()).as_deref(), Some("client-a"));
+        assert_eq!(common_name(b"not a certificate"), None);
+    }
+}
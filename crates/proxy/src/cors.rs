@@ -0,0 +1,432 @@
+//! Cross-Origin Resource Sharing
+//!
+//! Lets browser clients on other origins (dashboards polling `/metrics` or
+//! `/energy`, for example) call the proxy. Preflight requests are answered
+//! directly; actual requests from a permitted origin get the
+//! `Access-Control-Allow-*` headers added to their response, and requests from
+//! any other origin are refused with `403`.
+
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper::{HeaderMap, Response, StatusCode, header::HeaderValue};
+use serde::{Deserialize, Serialize};
+
+use crate::metrics;
+
+/// Response headers produced by a CORS decision
+pub type CorsHeaders = Vec<(&'static str, String)>;
+
+/// CORS policy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CorsConfig {
+    /// Origins permitted to make requests; `"*"` permits any origin
+    #[serde(default)]
+    pub allowed_origins: Vec<String>,
+    /// Methods permitted in cross-origin requests
+    #[serde(default = "default_allowed_methods")]
+    pub allowed_methods: Vec<String>,
+    /// Request headers permitted in cross-origin requests
+    #[serde(default = "default_allowed_headers")]
+    pub allowed_headers: Vec<String>,
+    /// Whether the browser may send credentials
+    #[serde(default)]
+    pub allow_credentials: bool,
+    /// How long browsers may cache a preflight result, in seconds
+    #[serde(default)]
+    pub max_age_secs: Option<u64>,
+}
+
+fn default_allowed_methods() -> Vec<String> {
+    vec!["GET".to_string(), "HEAD".to_string(), "OPTIONS".to_string()]
+}
+
+fn default_allowed_headers() -> Vec<String> {
+    vec!["Content-Type".to_string(), "Authorization".to_string()]
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self {
+            allowed_origins: Vec::new(),
+            allowed_methods: default_allowed_methods(),
+            allowed_headers: default_allowed_headers(),
+            allow_credentials: false,
+            max_age_secs: None,
+        }
+    }
+}
+
+/// What to do with a request under a [`CorsConfig`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CorsDecision {
+    /// No `Origin` header; the request is not subject to CORS
+    NotCors,
+    /// Answer the preflight with these headers
+    Preflight(CorsHeaders),
+    /// Serve the request and add these headers to the response
+    Allow(CorsHeaders),
+    /// The origin or requested method is not permitted
+    Reject,
+}
+
+impl CorsConfig {
+    /// Create a policy permitting the given origins
+    pub fn new(origins: &[&str]) -> Self {
+        Self {
+            allowed_origins: origins.iter().map(|o| o.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Permit the given methods
+    pub fn with_allowed_methods(mut self, methods: &[&str]) -> Self {
+        self.allowed_methods = methods.iter().map(|m| m.to_uppercase()).collect();
+        self
+    }
+
+    /// Permit the given request headers
+    pub fn with_allowed_headers(mut self, headers: &[&str]) -> Self {
+        self.allowed_headers = headers.iter().map(|h| h.to_string()).collect();
+        self
+    }
+
+    /// Let the browser send credentials
+    pub fn with_credentials(mut self, allow: bool) -> Self {
+        self.allow_credentials = allow;
+        self
+    }
+
+    /// Let browsers cache preflight results for `secs` seconds
+    pub fn with_max_age(mut self, secs: u64) -> Self {
+        self.max_age_secs = Some(secs);
+        self
+    }
+
+    /// Whether requests from `origin` are permitted
+    pub fn allows_origin(&self, origin: &str) -> bool {
+        self.allowed_origins
+            .iter()
+            .any(|o| o == "*" || o.eq_ignore_ascii_case(origin))
+    }
+
+    fn allows_method(&self, method: &str) -> bool {
+        self.allowed_methods
+            .iter()
+            .any(|m| m.eq_ignore_ascii_case(method))
+    }
+
+    /// Headers identifying the permitted origin
+    fn origin_headers(&self, origin: &str) -> CorsHeaders {
+        let wildcard = self.allowed_origins.iter().any(|o| o == "*");
+        if wildcard && !self.allow_credentials {
+            return vec![("access-control-allow-origin", "*".to_string())];
+        }
+        let mut headers = vec![
+            ("access-control-allow-origin", origin.to_string()),
+            ("vary", "Origin".to_string()),
+        ];
+        if self.allow_credentials {
+            headers.push(("access-control-allow-credentials", "true".to_string()));
+        }
+        headers
+    }
+
+    /// Decide how to handle a request
+    ///
+    /// `request_method` is the `Access-Control-Request-Method` header, whose
+    /// presence on an `OPTIONS` request marks it as a preflight.
+    pub fn evaluate(
+        &self,
+        method: &str,
+        origin: Option<&str>,
+        request_method: Option<&str>,
+    ) -> CorsDecision {
+        let Some(origin) = origin else {
+            return CorsDecision::NotCors;
+        };
+        if !self.allows_origin(origin) {
+            return CorsDecision::Reject;
+        }
+
+        match request_method {
+            Some(requested) if method.eq_ignore_ascii_case("OPTIONS") => {
+                if !self.allows_method(requested) {
+                    return CorsDecision::Reject;
+                }
+                let mut headers = self.origin_headers(origin);
+                headers.push((
+                    "access-control-allow-methods",
+                    self.allowed_methods.join(", "),
+                ));
+                headers.push((
+                    "access-control-allow-headers",
+                    self.allowed_headers.join(", "),
+                ));
+                if let Some(max_age) = self.max_age_secs {
+                    headers.push(("access-control-max-age", max_age.to_string()));
+                }
+                CorsDecision::Preflight(headers)
+            }
+            _ if self.allows_method(method) => CorsDecision::Allow(self.origin_headers(origin)),
+            _ => CorsDecision::Reject,
+        }
+    }
+
+    fn evaluate_headers(&self, method: &str, headers: &HeaderMap) -> CorsDecision {
+        let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+        self.evaluate(
+            method,
+            header("origin"),
+            header("access-control-request-method"),
+        )
+    }
+
+    /// Build the response for a preflight or rejected hyper request
+    ///
+    /// Returns `None` when the request should be served normally.
+    pub fn check_request(
+        &self,
+        method: &str,
+        headers: &HeaderMap,
+    ) -> Option<Response<Full<Bytes>>> {
+        match self.evaluate_headers(method, headers) {
+            CorsDecision::NotCors | CorsDecision::Allow(_) => None,
+            CorsDecision::Preflight(headers) => {
+                let mut response = Response::builder()
+                    .status(StatusCode::NO_CONTENT)
+                    .body(Full::new(Bytes::new()))
+                    .unwrap();
+                apply_headers(&mut response, headers);
+                Some(response)
+            }
+            CorsDecision::Reject => {
+                metrics::record_error("cors_rejected");
+                Some(forbidden_response())
+            }
+        }
+    }
+
+    /// Headers to add to the response of a request that was served
+    pub fn response_headers(&self, method: &str, headers: &HeaderMap) -> CorsHeaders {
+        match self.evaluate_headers(method, headers) {
+            CorsDecision::Allow(headers) => headers,
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Add CORS headers to a response, replacing any set by the handler
+///
+/// `Vary` is merged instead, so upstream entries such as `Accept-Encoding`
+/// survive for caches.
+pub fn apply_headers<B>(response: &mut Response<B>, headers: CorsHeaders) {
+    for (name, value) in headers {
+        if name == "vary" {
+            merge_vary(response.headers_mut(), &value);
+        } else if let Ok(value) = HeaderValue::from_str(&value) {
+            response.headers_mut().insert(name, value);
+        }
+    }
+}
+
+/// Append `field` to `Vary` unless it, or `*`, is already listed
+fn merge_vary(headers: &mut HeaderMap, field: &str) {
+    let listed = headers
+        .get_all(hyper::header::VARY)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .map(str::trim)
+        .any(|v| v == "*" || v.eq_ignore_ascii_case(field));
+    if !listed && let Ok(value) = HeaderValue::from_str(field) {
+        headers.append(hyper::header::VARY, value);
+    }
+}
+
+/// Build the `403` response for a request from a disallowed origin
+pub fn forbidden_response() -> Response<Full<Bytes>> {
+    Response::builder()
+        .status(StatusCode::FORBIDDEN)
+        .header("content-type", "application/json")
+        .body(Full::new(Bytes::from(
+            "{\"error\":\"cors_rejected\",\"message\":\"Origin not allowed\"}",
+        )))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> CorsConfig {
+        CorsConfig::new(&["https://dashboard.example"])
+            .with_allowed_methods(&["get", "head", "options"])
+            .with_allowed_headers(&["Content-Type", "X-Api-Key"])
+            .with_credentials(true)
+            .with_max_age(600)
+    }
+
+    fn header<'a>(headers: &'a CorsHeaders, name: &str) -> Option<&'a str> {
+        headers
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    #[test]
+    fn test_preflight_returns_configured_headers() {
+        let CorsDecision::Preflight(headers) =
+            policy().evaluate("OPTIONS", Some("https://dashboard.example"), Some("GET"))
+        else {
+            panic!("expected preflight");
+        };
+        assert_eq!(
+            header(&headers, "access-control-allow-origin"),
+            Some("https://dashboard.example")
+        );
+        assert_eq!(
+            header(&headers, "access-control-allow-methods"),
+            Some("GET, HEAD, OPTIONS")
+        );
+        assert_eq!(
+            header(&headers, "access-control-allow-headers"),
+            Some("Content-Type, X-Api-Key")
+        );
+        assert_eq!(
+            header(&headers, "access-control-allow-credentials"),
+            Some("true")
+        );
+        assert_eq!(header(&headers, "access-control-max-age"), Some("600"));
+        assert_eq!(header(&headers, "vary"), Some("Origin"));
+    }
+
+    #[test]
+    fn test_disallowed_origin_and_method_rejected() {
+        let policy = policy();
+        assert_eq!(
+            policy.evaluate("GET", Some("https://evil.example"), None),
+            CorsDecision::Reject
+        );
+        assert_eq!(
+            policy.evaluate("OPTIONS", Some("https://evil.example"), Some("GET")),
+            CorsDecision::Reject
+        );
+        assert_eq!(
+            policy.evaluate("OPTIONS", Some("https://dashboard.example"), Some("DELETE")),
+            CorsDecision::Reject
+        );
+        assert_eq!(
+            policy.evaluate("POST", Some("https://dashboard.example"), None),
+            CorsDecision::Reject
+        );
+    }
+
+    #[test]
+    fn test_actual_request_and_non_cors() {
+        let policy = policy();
+        let CorsDecision::Allow(headers) =
+            policy.evaluate("GET", Some("https://dashboard.example"), None)
+        else {
+            panic!("expected allow");
+        };
+        assert_eq!(
+            header(&headers, "access-control-allow-origin"),
+            Some("https://dashboard.example")
+        );
+        assert!(header(&headers, "access-control-allow-methods").is_none());
+
+        // Same-origin and non-browser requests carry no Origin header
+        assert_eq!(policy.evaluate("POST", None, None), CorsDecision::NotCors);
+    }
+
+    #[test]
+    fn test_wildcard_origin() {
+        let policy = CorsConfig::new(&["*"]);
+        let CorsDecision::Allow(headers) =
+            policy.evaluate("GET", Some("https://any.example"), None)
+        else {
+            panic!("expected allow");
+        };
+        assert_eq!(
+            headers,
+            vec![("access-control-allow-origin", "*".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_check_request_responses() {
+        let policy = policy();
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "origin",
+            HeaderValue::from_static("https://dashboard.example"),
+        );
+        headers.insert(
+            "access-control-request-method",
+            HeaderValue::from_static("GET"),
+        );
+        let response = policy.check_request("OPTIONS", &headers).unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert_eq!(response.headers()["access-control-max-age"], "600");
+        assert!(policy.check_request("GET", &headers).is_none());
+        assert_eq!(policy.response_headers("GET", &headers).len(), 3);
+
+        headers.insert("origin", HeaderValue::from_static("https://evil.example"));
+        let response = policy.check_request("GET", &headers).unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert!(policy.response_headers("GET", &headers).is_empty());
+
+        assert!(policy.check_request("GET", &HeaderMap::new()).is_none());
+        assert!(policy.response_headers("GET", &HeaderMap::new()).is_empty());
+    }
+
+    #[test]
+    fn test_apply_headers_keeps_upstream_vary() {
+        let headers: CorsHeaders = vec![
+            (
+                "access-control-allow-origin",
+                "https://dashboard.example".into(),
+            ),
+            ("vary", "Origin".into()),
+        ];
+        let mut response = Response::new(());
+        response
+            .headers_mut()
+            .insert("vary", HeaderValue::from_static("Accept-Encoding"));
+        apply_headers(&mut response, headers.clone());
+        let vary: Vec<_> = response.headers().get_all("vary").iter().collect();
+        assert_eq!(vary, ["Accept-Encoding", "Origin"]);
+
+        // Applying twice, or to a response already varying on Origin, adds nothing
+        apply_headers(&mut response, headers.clone());
+        assert_eq!(response.headers().get_all("vary").iter().count(), 2);
+        let mut response = Response::new(());
+        response
+            .headers_mut()
+            .insert("vary", HeaderValue::from_static("accept-encoding, origin"));
+        apply_headers(&mut response, headers);
+        assert_eq!(response.headers().get_all("vary").iter().count(), 1);
+        assert_eq!(
+            response.headers()["access-control-allow-origin"],
+            "https://dashboard.example"
+        );
+    }
+
+    #[test]
+    fn test_cors_config_deserialize() {
+        let config: CorsConfig = toml::from_str(
+            r#"
+allowed_origins = ["https://dashboard.example"]
+max_age_secs = 300
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.allowed_methods, vec!["GET", "HEAD", "OPTIONS"]);
+        assert_eq!(
+            config.allowed_headers,
+            vec!["Content-Type", "Authorization"]
+        );
+        assert!(!config.allow_credentials);
+        assert_eq!(config.max_age_secs, Some(300));
+    }
+}
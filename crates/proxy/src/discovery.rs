@@ -1,7 +1,14 @@
 //! Service Discovery Module
 //!
 //! Provides DNS-based service discovery and load balancing.
+//!
+//! Endpoints can be tagged with the region they run in. With a
+//! [`RegionRanker`] such as the [`CarbonRouter`] attached, selection prefers
+//! healthy endpoints in the greenest region and falls back to the next
+//! region, then to any healthy endpoint.
 
+use crate::carbon_router::CarbonRouter;
+use aegis_energy::{BoxFuture, EnergyApiClient};
 use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::sync::Arc;
@@ -22,6 +29,8 @@ pub struct Endpoint {
     pub failures: u32,
     /// Current weight for load balancing
     pub weight: u32,
+    /// Region the endpoint runs in, for region-aware selection
+    pub region: Option<String>,
 }
 
 impl Endpoint {
@@ -33,9 +42,23 @@ impl Endpoint {
             last_check: Instant::now(),
             failures: 0,
             weight: 100,
+            region: None,
         }
     }
 
+    /// Create a healthy endpoint from an address string
+    ///
+    /// Accepts link-local IPv6 with a scope id, e.g. `[fe80::1%eth0]:8080`.
+    pub fn parse(addr: &str) -> std::io::Result<Self> {
+        crate::listener::parse_socket_addr(addr).map(Self::new)
+    }
+
+    /// Tag the endpoint with the region it runs in
+    pub fn with_region(mut self, region: impl Into<String>) -> Self {
+        self.region = Some(region.into());
+        self
+    }
+
     /// Mark endpoint as failed
     pub fn mark_failed(&mut self) {
         self.failures += 1;
@@ -69,6 +92,38 @@ pub enum LoadBalanceStrategy {
     WeightedRoundRobin,
 }
 
+/// Orders regions for region-aware endpoint selection
+pub trait RegionRanker: Send + Sync {
+    /// Region ids in order of preference; unlisted regions are used only
+    /// when no listed region has a healthy endpoint
+    fn ranked_regions(&self) -> BoxFuture<'_, Vec<String>>;
+}
+
+impl<C: EnergyApiClient + Send + Sync> RegionRanker for CarbonRouter<C> {
+    /// The currently selected region first, then other regions under the
+    /// intensity ceiling from greenest to dirtiest
+    ///
+    /// Reads the router's state without making a selection, so ranking is
+    /// neither logged as a decision nor exported as a dry-run
+    /// recommendation. Empty in dry-run mode, so endpoint selection is
+    /// unaffected.
+    fn ranked_regions(&self) -> BoxFuture<'_, Vec<String>> {
+        Box::pin(async move {
+            if self.is_dry_run() {
+                return Vec::new();
+            }
+            let max_intensity = self.max_intensity();
+            let mut ranked: Vec<String> = self.current_region().await.into_iter().collect();
+            for score in self.get_sorted_regions().await {
+                if score.carbon_intensity <= max_intensity && !ranked.contains(&score.region_id) {
+                    ranked.push(score.region_id);
+                }
+            }
+            ranked
+        })
+    }
+}
+
 /// Service registry for discovered services
 pub struct ServiceRegistry {
     /// Map of service name to endpoints
@@ -79,6 +134,8 @@ pub struct ServiceRegistry {
     rr_counters: Arc<RwLock<HashMap<String, usize>>>,
     /// Health check interval
     health_check_interval: Duration,
+    /// Region preference for region-aware selection
+    region_ranker: Option<Arc<dyn RegionRanker>>,
 }
 
 impl ServiceRegistry {
@@ -90,9 +147,19 @@ impl ServiceRegistry {
             strategy,
             rr_counters: Arc::new(RwLock::new(HashMap::with_capacity(16))),
             health_check_interval: Duration::from_secs(10),
+            region_ranker: None,
         }
     }
 
+    /// Prefer endpoints in the regions `ranker` ranks highest
+    ///
+    /// The load balancing strategy then picks among the healthy endpoints of
+    /// the first ranked region that has any.
+    pub fn with_region_ranker(mut self, ranker: Arc<dyn RegionRanker>) -> Self {
+        self.region_ranker = Some(ranker);
+        self
+    }
+
     /// Register a service with endpoints
     pub async fn register(&self, service: &str, endpoints: Vec<SocketAddr>) {
         let mut services = self.services.write().await;
@@ -105,8 +172,28 @@ impl ServiceRegistry {
         services.insert(service.to_string(), eps);
     }
 
+    /// Register a service with endpoints tagged by region
+    pub async fn register_with_region(&self, service: &str, endpoints: Vec<(SocketAddr, String)>) {
+        let mut services = self.services.write().await;
+        let eps: Vec<Endpoint> = endpoints
+            .into_iter()
+            .map(|(addr, region)| Endpoint::new(addr).with_region(region))
+            .collect();
+        info!(
+            "📍 Registered service '{}' with {} regional endpoints",
+            service,
+            eps.len()
+        );
+        services.insert(service.to_string(), eps);
+    }
+
     /// Get next endpoint for a service using load balancing
     pub async fn get_endpoint(&self, service: &str) -> Option<SocketAddr> {
+        let ranking = match &self.region_ranker {
+            Some(ranker) => ranker.ranked_regions().await,
+            None => Vec::new(),
+        };
+
         let services = self.services.read().await;
         let endpoints = services.get(service)?;
 
@@ -115,6 +202,7 @@ impl ServiceRegistry {
             warn!("⚠️ No healthy endpoints for service '{}'", service);
             return None;
         }
+        let healthy = Self::prefer_regions(service, healthy, &ranking);
 
         match self.strategy {
             LoadBalanceStrategy::RoundRobin => {
@@ -154,6 +242,27 @@ impl ServiceRegistry {
         }
     }
 
+    /// Narrow `healthy` to the first ranked region with a healthy endpoint,
+    /// or leave it as is when there is none
+    fn prefer_regions<'a>(
+        service: &str,
+        healthy: Vec<&'a Endpoint>,
+        ranking: &[String],
+    ) -> Vec<&'a Endpoint> {
+        for region in ranking {
+            let in_region: Vec<&Endpoint> = healthy
+                .iter()
+                .copied()
+                .filter(|e| e.region.as_ref() == Some(region))
+                .collect();
+            if !in_region.is_empty() {
+                debug!("🌱 Selecting '{}' endpoints in region {}", service, region);
+                return in_region;
+            }
+        }
+        healthy
+    }
+
     /// Mark an endpoint as failed
     #[allow(clippy::collapsible_if)]
     pub async fn mark_failed(&self, service: &str, addr: SocketAddr) {
@@ -336,6 +445,21 @@ mod tests {
         assert_eq!(endpoint.weight, 100);
     }
 
+    #[tokio::test]
+    async fn test_endpoint_parse_scoped_ipv6() {
+        let ep = Endpoint::parse("[fe80::1%3]:8080").unwrap();
+        let SocketAddr::V6(addr) = ep.addr else {
+            panic!("expected IPv6");
+        };
+        assert_eq!((addr.port(), addr.scope_id()), (8080, 3));
+        assert!(ep.healthy);
+        assert!(Endpoint::parse("fe80::1%3:8080").is_err());
+
+        let registry = ServiceRegistry::new(LoadBalanceStrategy::RoundRobin);
+        registry.register("link-local", vec![ep.addr]).await;
+        assert_eq!(registry.get_endpoint("link-local").await, Some(ep.addr));
+    }
+
     #[test]
     fn test_endpoint_mark_failed() {
         let addr: SocketAddr = "127.0.0.1:8080".parse().unwrap();
@@ -817,4 +941,138 @@ mod tests {
             assert!(result.is_some());
         }
     }
+
+    /// Energy client reporting a fixed intensity per region
+    struct FixedIntensities(HashMap<String, f64>);
+
+    impl EnergyApiClient for FixedIntensities {
+        async fn get_carbon_intensity(
+            &self,
+            region: &aegis_energy::Region,
+        ) -> Result<aegis_energy::CarbonIntensity, aegis_energy::EnergyApiError> {
+            Ok(aegis_energy::CarbonIntensity {
+                region: region.clone(),
+                value: self.0[&region.id],
+                timestamp: chrono::Utc::now(),
+                valid_for_seconds: 300,
+                rating: None,
+                renewable_percentage: None,
+                power_breakdown: None,
+            })
+        }
+
+        async fn get_carbon_intensity_by_location(
+            &self,
+            _latitude: f64,
+            _longitude: f64,
+        ) -> Result<aegis_energy::CarbonIntensity, aegis_energy::EnergyApiError> {
+            unimplemented!()
+        }
+
+        async fn get_region_for_location(
+            &self,
+            _latitude: f64,
+            _longitude: f64,
+        ) -> Result<aegis_energy::Region, aegis_energy::EnergyApiError> {
+            unimplemented!()
+        }
+
+        async fn get_carbon_forecast(
+            &self,
+            _region: &aegis_energy::Region,
+            _hours: u32,
+        ) -> Result<Vec<aegis_energy::ForecastPoint>, aegis_energy::EnergyApiError> {
+            Ok(Vec::new())
+        }
+    }
+
+    async fn carbon_router(
+        config: crate::carbon_router::CarbonRouterConfig,
+    ) -> Arc<CarbonRouter<FixedIntensities>> {
+        let client = FixedIntensities(HashMap::from([
+            ("us-west".to_string(), 50.0),
+            ("us-east".to_string(), 350.0),
+        ]));
+        let router =
+            CarbonRouter::new(config, client, aegis_energy::CarbonIntensityCache::new(300));
+        for region in ["us-west", "us-east"] {
+            router
+                .register_region(aegis_energy::Region::new(region, region))
+                .await;
+        }
+        router.refresh_carbon_data().await.unwrap();
+        Arc::new(router)
+    }
+
+    #[tokio::test]
+    async fn test_region_aware_selection_prefers_greener_region() {
+        let router = carbon_router(Default::default()).await;
+        let registry = ServiceRegistry::new(LoadBalanceStrategy::RoundRobin)
+            .with_region_ranker(router.clone());
+        let west1: SocketAddr = "10.0.1.1:8080".parse().unwrap();
+        let west2: SocketAddr = "10.0.1.2:8080".parse().unwrap();
+        let east: SocketAddr = "10.0.2.1:8080".parse().unwrap();
+        registry
+            .register_with_region(
+                "backend",
+                vec![
+                    (east, "us-east".to_string()),
+                    (west1, "us-west".to_string()),
+                    (west2, "us-west".to_string()),
+                ],
+            )
+            .await;
+
+        // Round-robin stays within the greener region
+        for _ in 0..6 {
+            let addr = registry.get_endpoint("backend").await.unwrap();
+            assert!(addr == west1 || addr == west2, "picked {addr}");
+        }
+
+        // Falls back to the dirtier region once the greener one is unhealthy
+        for addr in [west1, west2] {
+            for _ in 0..3 {
+                registry.mark_failed("backend", addr).await;
+            }
+        }
+        assert_eq!(registry.get_endpoint("backend").await, Some(east));
+
+        registry.mark_healthy("backend", west2).await;
+        assert_eq!(registry.get_endpoint("backend").await, Some(west2));
+
+        // Ranking reads the router's state without logging decisions
+        assert!(router.recent_decisions().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_region_aware_selection_without_preference() {
+        // Dry run leaves selection to the load balancing strategy
+        let router = carbon_router(crate::carbon_router::CarbonRouterConfig {
+            dry_run: true,
+            ..Default::default()
+        })
+        .await;
+        assert!(router.ranked_regions().await.is_empty());
+        assert!(router.recent_decisions().await.is_empty());
+
+        let registry =
+            ServiceRegistry::new(LoadBalanceStrategy::RoundRobin).with_region_ranker(router);
+        let east: SocketAddr = "10.0.2.1:8080".parse().unwrap();
+        let west: SocketAddr = "10.0.1.1:8080".parse().unwrap();
+        registry
+            .register_with_region(
+                "backend",
+                vec![(east, "us-east".to_string()), (west, "us-west".to_string())],
+            )
+            .await;
+        assert_eq!(registry.get_endpoint("backend").await, Some(east));
+        assert_eq!(registry.get_endpoint("backend").await, Some(west));
+
+        // Untagged endpoints are still served when no ranked region is healthy
+        let router = carbon_router(Default::default()).await;
+        let registry =
+            ServiceRegistry::new(LoadBalanceStrategy::RoundRobin).with_region_ranker(router);
+        registry.register("plain", vec![west]).await;
+        assert_eq!(registry.get_endpoint("plain").await, Some(west));
+    }
 }
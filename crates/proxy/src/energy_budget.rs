@@ -0,0 +1,220 @@
+//! Energy Budget Module
+//!
+//! Caps the estimated energy spent serving requests over a rolling window.
+//! Once the ceiling is reached, new requests are shed with `503` until enough
+//! of the recorded energy ages out of the window.
+
+use aegis_common::{SharedClock, SystemClock};
+use aegis_telemetry::EnergyEstimator;
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper::{Response, StatusCode, header::RETRY_AFTER};
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+use crate::metrics;
+
+/// Rolling-window energy budget
+#[derive(Debug)]
+pub struct EnergyBudget {
+    ceiling_joules: f64,
+    window: Duration,
+    /// Energy recorded inside the window, oldest first
+    samples: Mutex<VecDeque<(Instant, f64)>>,
+    estimator: EnergyEstimator,
+    clock: SharedClock,
+}
+
+impl EnergyBudget {
+    /// Create a budget allowing `ceiling_joules` per `window`
+    pub fn new(ceiling_joules: f64, window: Duration) -> Self {
+        Self {
+            ceiling_joules,
+            window,
+            samples: Mutex::new(VecDeque::new()),
+            estimator: EnergyEstimator::new(),
+            clock: SystemClock::shared(),
+        }
+    }
+
+    /// Use a custom time source
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Energy ceiling per window in joules
+    pub fn ceiling_joules(&self) -> f64 {
+        self.ceiling_joules
+    }
+
+    /// Length of the rolling window
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// Record energy spent now
+    pub fn record(&self, joules: f64) {
+        let now = self.clock.instant();
+        let mut samples = self.samples.lock();
+        self.prune(&mut samples, now);
+        samples.push_back((now, joules));
+        self.publish(&samples);
+    }
+
+    /// Record the estimated energy of a served request
+    pub fn record_request(&self, endpoint: &str, method: &str, duration: Duration) {
+        let metrics = self
+            .estimator
+            .estimate_from_duration(endpoint, method, duration, 0);
+        self.record(metrics.total_joules());
+    }
+
+    /// Energy spent inside the current window in joules
+    pub fn used_joules(&self) -> f64 {
+        let mut samples = self.samples.lock();
+        self.prune(&mut samples, self.clock.instant());
+        samples.iter().map(|(_, joules)| joules).sum()
+    }
+
+    /// Energy left before requests are shed, never negative
+    pub fn remaining_joules(&self) -> f64 {
+        (self.ceiling_joules - self.used_joules()).max(0.0)
+    }
+
+    /// Check whether another request may be served
+    ///
+    /// Returns the time until enough energy leaves the window when the
+    /// budget is exhausted.
+    pub fn check(&self) -> Result<(), Duration> {
+        let now = self.clock.instant();
+        let mut samples = self.samples.lock();
+        self.prune(&mut samples, now);
+        self.publish(&samples);
+
+        let mut used: f64 = samples.iter().map(|(_, joules)| joules).sum();
+        if used < self.ceiling_joules {
+            return Ok(());
+        }
+
+        for (recorded_at, joules) in samples.iter() {
+            used -= joules;
+            if used < self.ceiling_joules {
+                return Err((*recorded_at + self.window).saturating_duration_since(now));
+            }
+        }
+        Err(self.window)
+    }
+
+    /// Build the load-shedding response if the budget is exhausted
+    pub fn check_request(&self) -> Option<Response<Full<Bytes>>> {
+        let retry_after = self.check().err()?;
+        warn!(
+            "🔋 Energy budget of {} J per {:?} exhausted, shedding request",
+            self.ceiling_joules, self.window
+        );
+        Some(budget_exceeded_response(retry_after))
+    }
+
+    fn prune(&self, samples: &mut VecDeque<(Instant, f64)>, now: Instant) {
+        while let Some((recorded_at, _)) = samples.front() {
+            if now.saturating_duration_since(*recorded_at) < self.window {
+                break;
+            }
+            samples.pop_front();
+        }
+    }
+
+    fn publish(&self, samples: &VecDeque<(Instant, f64)>) {
+        let used: f64 = samples.iter().map(|(_, joules)| joules).sum();
+        metrics::update_energy_budget_remaining((self.ceiling_joules - used).max(0.0));
+    }
+}
+
+/// Build a `503` response telling the client when the budget frees up
+pub fn budget_exceeded_response(retry_after: Duration) -> Response<Full<Bytes>> {
+    let secs = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+    Response::builder()
+        .status(StatusCode::SERVICE_UNAVAILABLE)
+        .header(RETRY_AFTER, secs)
+        .header("content-type", "application/json")
+        .body(Full::new(Bytes::from(
+            "{\"error\":\"energy_budget_exceeded\",\"message\":\"Energy budget exhausted\"}",
+        )))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aegis_common::MockClock;
+
+    fn budget(clock: &MockClock) -> EnergyBudget {
+        EnergyBudget::new(10.0, Duration::from_secs(60)).with_clock(clock.shared())
+    }
+
+    #[test]
+    fn test_budget_sheds_load_then_recovers() {
+        let clock = MockClock::new();
+        let budget = budget(&clock);
+
+        budget.record(4.0);
+        clock.advance(Duration::from_secs(20));
+        budget.record(4.0);
+        assert!(budget.check().is_ok());
+        assert!((budget.remaining_joules() - 2.0).abs() < 1e-9);
+
+        clock.advance(Duration::from_secs(10));
+        budget.record(4.0);
+        assert_eq!(budget.remaining_joules(), 0.0);
+        // The first sample leaves the window 30s from now
+        assert_eq!(budget.check(), Err(Duration::from_secs(30)));
+
+        let response = budget.check_request().unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.headers()[RETRY_AFTER], "30");
+
+        clock.advance(Duration::from_secs(30));
+        assert!(budget.check().is_ok());
+        assert!(budget.check_request().is_none());
+        assert!((budget.used_joules() - 8.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_budget_retry_after_waits_for_enough_energy() {
+        let clock = MockClock::new();
+        let budget = budget(&clock);
+
+        budget.record(1.0);
+        clock.advance(Duration::from_secs(5));
+        budget.record(15.0);
+
+        // Dropping the 1 J sample is not enough, the 15 J one must expire
+        assert_eq!(budget.check(), Err(Duration::from_secs(60)));
+
+        clock.advance(Duration::from_secs(60));
+        assert!(budget.check().is_ok());
+        assert_eq!(budget.used_joules(), 0.0);
+    }
+
+    #[test]
+    fn test_budget_record_request_uses_estimate() {
+        let clock = MockClock::new();
+        let budget = budget(&clock);
+
+        budget.record_request("/api", "GET", Duration::from_millis(10));
+        assert!(budget.used_joules() > 0.0);
+        assert!(budget.remaining_joules() < budget.ceiling_joules());
+    }
+
+    #[test]
+    fn test_budget_exceeded_response_rounds_up() {
+        let response = budget_exceeded_response(Duration::from_millis(1500));
+        assert_eq!(response.headers()[RETRY_AFTER], "2");
+
+        let response = budget_exceeded_response(Duration::ZERO);
+        assert_eq!(response.headers()[RETRY_AFTER], "1");
+    }
+}
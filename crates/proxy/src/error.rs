@@ -0,0 +1,76 @@
+//! Typed errors for the proxy server run paths
+//!
+//! Library embedders can match on [`ProxyError`] to distinguish failure
+//! kinds; the binary converts it to `anyhow::Error` at the boundary.
+
+use crate::config::ConfigError;
+use thiserror::Error;
+
+/// Errors returned from the proxy server run paths
+#[derive(Debug, Error)]
+pub enum ProxyError {
+    /// Failed to bind the listening socket
+    #[error("Failed to bind {addr}: {source}")]
+    Bind {
+        addr: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// TLS / PQC handshake stack could not be initialized
+    #[error("TLS initialization failed: {0}")]
+    TlsInit(String),
+
+    /// Configuration was rejected before the server started
+    #[error("Invalid configuration: {0}")]
+    ConfigInvalid(String),
+
+    /// Upstream could not be reached
+    #[error("Upstream unreachable: {0}")]
+    UpstreamUnreachable(String),
+}
+
+impl ProxyError {
+    /// Build a [`ProxyError::Bind`] for the given address
+    pub fn bind(addr: impl Into<String>, source: std::io::Error) -> Self {
+        Self::Bind {
+            addr: addr.into(),
+            source,
+        }
+    }
+}
+
+impl From<ConfigError> for ProxyError {
+    fn from(err: ConfigError) -> Self {
+        Self::ConfigInvalid(err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bind_error_display_and_source() {
+        use std::error::Error;
+        let err = ProxyError::bind(
+            "127.0.0.1:1",
+            std::io::Error::new(std::io::ErrorKind::PermissionDenied, "denied"),
+        );
+        assert!(err.to_string().contains("127.0.0.1:1"));
+        assert!(err.source().is_some());
+    }
+
+    #[test]
+    fn test_from_config_error() {
+        let err: ProxyError = ConfigError::ValidationError("bad port".to_string()).into();
+        assert!(matches!(err, ProxyError::ConfigInvalid(ref msg) if msg.contains("bad port")));
+    }
+
+    #[test]
+    fn test_into_anyhow() {
+        let err = ProxyError::UpstreamUnreachable("10.0.0.1:9000".to_string());
+        let any: anyhow::Error = err.into();
+        assert!(any.downcast_ref::<ProxyError>().is_some());
+    }
+}
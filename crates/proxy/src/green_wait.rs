@@ -3,10 +3,15 @@
 //! Defers non-urgent jobs to time periods with lower carbon intensity.
 //! Uses energy forecasts to schedule jobs during "green" windows.
 
+use crate::lifecycle::ShutdownReceiver;
 use crate::metrics;
+use aegis_common::{SharedClock, SystemClock};
 use aegis_energy::{CarbonIntensityCache, EnergyApiClient, Region};
+use futures_util::{Stream, StreamExt, stream};
 use std::sync::Arc;
 use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
 use tracing::{debug, info, warn};
 
 /// Priority level for deferred jobs
@@ -26,7 +31,19 @@ pub enum JobPriority {
 }
 
 impl JobPriority {
-    /// Maximum wait time for this priority level
+    /// Every priority, most urgent first; `ALL[p as usize] == p`
+    pub const ALL: [JobPriority; 5] = [
+        JobPriority::Critical,
+        JobPriority::High,
+        JobPriority::Normal,
+        JobPriority::Low,
+        JobPriority::Background,
+    ];
+
+    /// Default maximum wait time for this priority level
+    ///
+    /// The scheduler uses [`GreenWaitConfig::wait_budgets`], which start out
+    /// with these values.
     pub fn max_wait_duration(&self) -> Duration {
         match self {
             JobPriority::Critical => Duration::ZERO,
@@ -36,9 +53,74 @@ impl JobPriority {
             JobPriority::Background => Duration::from_secs(24 * 60 * 60), // 24 hours
         }
     }
+
+    /// Priority for a numeric level, clamped to `Critical..=Background`
+    fn from_level(level: u128) -> Self {
+        match level {
+            0 => JobPriority::Critical,
+            1 => JobPriority::High,
+            2 => JobPriority::Normal,
+            3 => JobPriority::Low,
+            _ => JobPriority::Background,
+        }
+    }
+
+    /// Parse a priority name such as `"critical"` or `"Low"` (case-insensitive)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "critical" => Some(JobPriority::Critical),
+            "high" => Some(JobPriority::High),
+            "normal" => Some(JobPriority::Normal),
+            "low" => Some(JobPriority::Low),
+            "background" => Some(JobPriority::Background),
+            _ => None,
+        }
+    }
 }
 
 use serde::{Serialize, Deserialize};
+use serde::de::DeserializeOwned;
+
+/// Encoding for structured job payloads
+///
+/// The codec is not stored with the job, so decode with the one used to encode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PayloadCodec {
+    /// Self-describing JSON, readable by non-Rust consumers
+    #[default]
+    Json,
+    /// Compact bincode
+    Bincode,
+}
+
+impl PayloadCodec {
+    /// Serialize `value` into payload bytes
+    pub fn encode<T: Serialize>(&self, value: &T) -> Result<Vec<u8>, PayloadError> {
+        Ok(match self {
+            PayloadCodec::Json => serde_json::to_vec(value)?,
+            PayloadCodec::Bincode => bincode::serialize(value)?,
+        })
+    }
+
+    /// Deserialize payload bytes produced by [`Self::encode`]
+    pub fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, PayloadError> {
+        Ok(match self {
+            PayloadCodec::Json => serde_json::from_slice(bytes)?,
+            PayloadCodec::Bincode => bincode::deserialize(bytes)?,
+        })
+    }
+}
+
+/// Error encoding or decoding a structured job payload
+#[derive(Debug, thiserror::Error)]
+pub enum PayloadError {
+    /// JSON (de)serialization failed
+    #[error("JSON payload error: {0}")]
+    Json(#[from] serde_json::Error),
+    /// bincode (de)serialization failed
+    #[error("bincode payload error: {0}")]
+    Bincode(#[from] bincode::Error),
+}
 
 /// A deferred job waiting for a green window
 #[derive(Debug, Serialize, Deserialize)]
@@ -54,7 +136,7 @@ pub struct DeferredJob {
     pub submitted_at: chrono::DateTime<chrono::Utc>,
     /// Maximum carbon intensity threshold for execution
     pub carbon_threshold: f64,
-    /// Job payload (opaque bytes)
+    /// Job payload (opaque bytes, see [`DeferredJob::new_typed`] for structured payloads)
     pub payload: Vec<u8>,
 }
 
@@ -77,25 +159,77 @@ impl DeferredJob {
         }
     }
 
-    /// Check if this job has exceeded its maximum wait time
-    pub fn is_expired(&self) -> bool {
-        let elapsed = chrono::Utc::now().signed_duration_since(self.submitted_at);
-        let max_wait = chrono::Duration::from_std(self.priority.max_wait_duration()).unwrap_or(chrono::Duration::zero());
+    /// Create a deferred job with a structured payload encoded by `codec`
+    pub fn new_typed<T: Serialize>(
+        id: impl Into<String>,
+        priority: JobPriority,
+        region: Region,
+        carbon_threshold: f64,
+        payload: &T,
+        codec: PayloadCodec,
+    ) -> Result<Self, PayloadError> {
+        let payload = codec.encode(payload)?;
+        Ok(Self::new(id, priority, region, carbon_threshold, payload))
+    }
+
+    /// Decode a payload created by [`Self::new_typed`] with the same `codec`
+    pub fn decode_payload<T: DeserializeOwned>(&self, codec: PayloadCodec) -> Result<T, PayloadError> {
+        codec.decode(&self.payload)
+    }
+
+    /// Check if this job has waited longer than `max_wait`
+    ///
+    /// Use [`GreenWaitConfig::max_wait`] for the budget of the job's priority.
+    pub fn is_expired(&self, max_wait: Duration) -> bool {
+        self.is_expired_at(chrono::Utc::now(), max_wait)
+    }
+
+    /// Check if this job has waited longer than `max_wait` as of `now`
+    pub fn is_expired_at(&self, now: chrono::DateTime<chrono::Utc>, max_wait: Duration) -> bool {
+        let elapsed = now.signed_duration_since(self.submitted_at);
+        let max_wait = chrono::Duration::from_std(max_wait).unwrap_or(chrono::Duration::MAX);
         elapsed > max_wait
     }
 
-    /// Time remaining before expiration
-    pub fn time_remaining(&self) -> Duration {
-        let elapsed = chrono::Utc::now().signed_duration_since(self.submitted_at);
-        let max_wait = chrono::Duration::from_std(self.priority.max_wait_duration()).unwrap_or(chrono::Duration::zero());
+    /// Priority as of `now`, raised one level for every `aging_interval` waited
+    ///
+    /// A zero interval disables aging.
+    pub fn effective_priority_at(&self, now: chrono::DateTime<chrono::Utc>, aging_interval: Duration) -> JobPriority {
+        if aging_interval.is_zero() {
+            return self.priority;
+        }
+        let waited = now.signed_duration_since(self.submitted_at).to_std().unwrap_or(Duration::ZERO);
+        let boost = waited.as_nanos() / aging_interval.as_nanos();
+        JobPriority::from_level((self.priority as u128).saturating_sub(boost))
+    }
+
+    /// Time remaining before the job has waited `max_wait`
+    pub fn time_remaining(&self, max_wait: Duration) -> Duration {
+        self.time_remaining_at(chrono::Utc::now(), max_wait)
+    }
+
+    /// Time remaining before the job has waited `max_wait`, as of `now`
+    pub fn time_remaining_at(&self, now: chrono::DateTime<chrono::Utc>, max_wait: Duration) -> Duration {
+        let elapsed = now.signed_duration_since(self.submitted_at);
+        let max_wait = chrono::Duration::from_std(max_wait).unwrap_or(chrono::Duration::MAX);
         if elapsed >= max_wait {
             Duration::ZERO
         } else {
-            (max_wait - elapsed).to_std().unwrap_or(Duration::ZERO)
+            max_wait.checked_sub(&elapsed).and_then(|left| left.to_std().ok()).unwrap_or(Duration::MAX)
         }
     }
 }
 
+/// What the green-wait driver does with still-queued jobs on shutdown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ShutdownMode {
+    /// Leave them in the persistent queue; they are reloaded on the next start
+    #[default]
+    Persist,
+    /// Release every queued job immediately, ready or not
+    Flush,
+}
+
 /// Configuration for the Green-Wait scheduler
 #[derive(Debug, Clone)]
 pub struct GreenWaitConfig {
@@ -107,6 +241,41 @@ pub struct GreenWaitConfig {
     pub check_interval_secs: u64,
     /// Maximum queue size
     pub max_queue_size: usize,
+    /// Seconds of waiting that raise a queued job's priority by one level (0 disables aging)
+    ///
+    /// A job aged all the way to Critical is released even while its region
+    /// is above its carbon threshold, so it cannot starve before its expiry.
+    pub priority_aging_secs: u64,
+    /// Up to this fraction is added to each job's aging interval, derived from
+    /// its ID, so jobs submitted together do not all age out in the same scan
+    pub priority_aging_jitter: f64,
+    /// What [`GreenWaitScheduler::spawn_driver`] does with queued jobs on shutdown
+    pub shutdown_mode: ShutdownMode,
+    /// Longest a job may wait for a green window, indexed by
+    /// `JobPriority as usize`; expired jobs are released regardless of carbon
+    pub wait_budgets: [Duration; 5],
+}
+
+impl GreenWaitConfig {
+    /// Wait budget for jobs of `priority`
+    pub fn max_wait(&self, priority: JobPriority) -> Duration {
+        self.wait_budgets[priority as usize]
+    }
+
+    /// Aging interval for `job`, including its jitter
+    pub fn aging_interval_for(&self, job: &DeferredJob) -> Duration {
+        use std::hash::{Hash, Hasher};
+
+        let base = Duration::from_secs(self.priority_aging_secs);
+        let jitter = self.priority_aging_jitter.clamp(0.0, 1.0);
+        if base.is_zero() || jitter == 0.0 {
+            return base;
+        }
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        job.id.hash(&mut hasher);
+        let unit = (hasher.finish() % 1000) as f64 / 1000.0;
+        base.mul_f64(1.0 + jitter * unit)
+    }
 }
 
 impl Default for GreenWaitConfig {
@@ -116,6 +285,10 @@ impl Default for GreenWaitConfig {
             default_threshold: 150.0,
             check_interval_secs: 60,
             max_queue_size: 1000,
+            priority_aging_secs: 2 * 60 * 60,
+            priority_aging_jitter: 0.1,
+            shutdown_mode: ShutdownMode::default(),
+            wait_budgets: JobPriority::ALL.map(|priority| priority.max_wait_duration()),
         }
     }
 }
@@ -133,6 +306,53 @@ pub enum ScheduleResult {
     Disabled,
 }
 
+/// State of an in-progress [`GreenWaitScheduler::drain_ready`] scan
+struct ReadyScan {
+    intensities: std::collections::HashMap<String, f64>,
+    now: chrono::DateTime<chrono::Utc>,
+    ids: std::vec::IntoIter<u64>,
+}
+
+/// Whether `job` should run now: it has waited too long, aged up to
+/// Critical, or its region is at or below its carbon threshold
+fn job_is_ready(
+    job: &DeferredJob,
+    intensities: &std::collections::HashMap<String, f64>,
+    now: chrono::DateTime<chrono::Utc>,
+    config: &GreenWaitConfig,
+) -> bool {
+    if job.is_expired_at(now, config.max_wait(job.priority)) {
+        info!(
+            job_id = %job.id,
+            "Job expired, executing regardless of carbon intensity"
+        );
+        return true;
+    }
+
+    if job.effective_priority_at(now, config.aging_interval_for(job)) == JobPriority::Critical {
+        info!(
+            job_id = %job.id,
+            priority = ?job.priority,
+            "Job aged to critical, executing regardless of carbon intensity"
+        );
+        return true;
+    }
+
+    if let Some(&intensity) = intensities.get(&job.region.id)
+        && intensity <= job.carbon_threshold
+    {
+        info!(
+            job_id = %job.id,
+            intensity = intensity,
+            threshold = job.carbon_threshold,
+            "Green window detected, executing job"
+        );
+        return true;
+    }
+
+    false
+}
+
 /// Green-Wait Scheduler for temporal shifting
 pub struct GreenWaitScheduler<C: EnergyApiClient> {
     config: GreenWaitConfig,
@@ -142,6 +362,8 @@ pub struct GreenWaitScheduler<C: EnergyApiClient> {
     queue: Arc<crate::persistent_queue::PersistentQueue>,
     /// Current carbon intensity per region
     region_intensity: Arc<tokio::sync::RwLock<std::collections::HashMap<String, f64>>>,
+    /// Time source for job expiry
+    clock: SharedClock,
 }
 
 impl<C: EnergyApiClient + Send + Sync + 'static> GreenWaitScheduler<C> {
@@ -154,9 +376,16 @@ impl<C: EnergyApiClient + Send + Sync + 'static> GreenWaitScheduler<C> {
             cache: Arc::new(cache),
             queue: Arc::new(queue),
             region_intensity: Arc::new(tokio::sync::RwLock::new(std::collections::HashMap::new())),
+            clock: SystemClock::shared(),
         })
     }
 
+    /// Use a custom time source for job expiry
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
     /// Check if the scheduler is enabled
     pub fn is_enabled(&self) -> bool {
         self.config.enabled
@@ -223,8 +452,8 @@ impl<C: EnergyApiClient + Send + Sync + 'static> GreenWaitScheduler<C> {
         ScheduleResult::Queued { position }
     }
 
-    /// Get carbon intensity for a region
-    async fn get_region_intensity(&self, region_id: &str) -> Option<f64> {
+    /// Get the last known carbon intensity for a region
+    pub async fn get_region_intensity(&self, region_id: &str) -> Option<f64> {
         let intensities = self.region_intensity.read().await;
         intensities.get(region_id).copied()
     }
@@ -237,47 +466,111 @@ impl<C: EnergyApiClient + Send + Sync + 'static> GreenWaitScheduler<C> {
 
     /// Process ready jobs from the queue
     pub async fn process_ready_jobs(&self) -> Vec<DeferredJob> {
-        let intensities = self.region_intensity.read().await;
+        self.drain_ready().collect().await
+    }
 
-        let mut ready_jobs = Vec::new();
-        let mut remaining_jobs = Vec::new();
-
-        while let Ok(Some((_id, job))) = self.queue.pop().await {
-            // Check if job is expired (must execute now)
-            if job.is_expired() {
-                info!(
-                    job_id = %job.id,
-                    "Job expired, executing regardless of carbon intensity"
-                );
-                ready_jobs.push(job);
-                continue;
-            }
+    /// Remove and yield ready jobs one at a time, in queue order
+    ///
+    /// The queue lock is only held while a single job is checked, so callers
+    /// can start on the first ready job before the scan finishes. Jobs that
+    /// are not ready stay where they are, even if the stream is dropped early.
+    pub fn drain_ready(&self) -> impl Stream<Item = DeferredJob> {
+        stream::unfold(None, move |scan: Option<ReadyScan>| async move {
+            let mut scan = match scan {
+                Some(scan) => scan,
+                // Snapshot so intensity updates are not blocked while the queue drains
+                None => ReadyScan {
+                    intensities: self.region_intensity.read().await.clone(),
+                    now: self.clock.now().into(),
+                    ids: self.queue.ids().await.into_iter(),
+                },
+            };
 
-            // Check if carbon intensity is acceptable
-            if let Some(&intensity) = intensities.get(&job.region.id) {
-                if intensity <= job.carbon_threshold {
-                    info!(
-                        job_id = %job.id,
-                        intensity = intensity,
-                        threshold = job.carbon_threshold,
-                        "Green window detected, executing job"
-                    );
-                    ready_jobs.push(job);
-                    continue;
+            while let Some(id) = scan.ids.next() {
+                let ready = |job: &DeferredJob| {
+                    job_is_ready(job, &scan.intensities, scan.now, &self.config)
+                };
+                match self.queue.take_if(id, ready).await {
+                    Ok(Some(job)) => return Some((job, Some(scan))),
+                    Ok(None) => {}
+                    Err(e) => warn!(error = %e, "Failed to check deferred job"),
                 }
             }
 
-            // Job not ready, keep in queue
-            remaining_jobs.push(job);
-        }
+            metrics::update_deferred_jobs(self.queue.len().await);
+            None
+        })
+    }
 
-        // Re-queue remaining jobs
-        for job in remaining_jobs {
-            let _ = self.queue.push(&job).await;
+    /// Remove and return every queued job, ready or not
+    pub async fn flush(&self) -> Vec<DeferredJob> {
+        let mut jobs = Vec::new();
+        for id in self.queue.ids().await {
+            match self.queue.take_if(id, |_| true).await {
+                Ok(Some(job)) => jobs.push(job),
+                Ok(None) => {}
+                Err(e) => warn!(error = %e, "Failed to flush deferred job"),
+            }
         }
-
         metrics::update_deferred_jobs(self.queue.len().await);
-        ready_jobs
+        jobs
+    }
+
+    /// Run the scheduler in the background until `shutdown` fires
+    ///
+    /// Every `check_interval_secs` the driver refreshes intensities and sends
+    /// ready jobs to the returned channel. On shutdown, queued jobs are handled
+    /// according to [`GreenWaitConfig::shutdown_mode`] before the task exits,
+    /// so the channel closes only once every released job has been sent.
+    pub fn spawn_driver(self: &Arc<Self>, mut shutdown: ShutdownReceiver) -> (mpsc::Receiver<DeferredJob>, JoinHandle<()>) {
+        let (tx, rx) = mpsc::channel(self.config.max_queue_size.max(1));
+        let scheduler = Arc::clone(self);
+        let handle = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(Duration::from_secs(scheduler.config.check_interval_secs.max(1)));
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {}
+                    _ = shutdown.recv() => break,
+                }
+                scheduler.refresh_intensities().await;
+                let mut ready = std::pin::pin!(scheduler.drain_ready());
+                while let Some(job) = ready.next().await {
+                    if !scheduler.send_or_requeue(&tx, job).await {
+                        return;
+                    }
+                }
+            }
+
+            match scheduler.config.shutdown_mode {
+                ShutdownMode::Persist => {
+                    let queued = scheduler.queue_length().await;
+                    info!(queued, "Green-wait driver stopped, queued jobs kept for next start");
+                }
+                ShutdownMode::Flush => {
+                    let jobs = scheduler.flush().await;
+                    info!(flushed = jobs.len(), "Green-wait driver stopped, releasing queued jobs");
+                    for job in jobs {
+                        if !scheduler.send_or_requeue(&tx, job).await {
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+        (rx, handle)
+    }
+
+    /// Send `job` to the driver's consumer, putting it back in the queue if
+    /// the consumer has gone away
+    async fn send_or_requeue(&self, tx: &mpsc::Sender<DeferredJob>, job: DeferredJob) -> bool {
+        let Err(mpsc::error::SendError(job)) = tx.send(job).await else {
+            return true;
+        };
+        warn!(job_id = %job.id, "Green-wait driver receiver dropped, requeueing job");
+        if let Err(e) = self.queue.push(&job).await {
+            warn!(job_id = %job.id, error = %e, "Failed to requeue deferred job");
+        }
+        false
     }
 
     /// Refresh carbon intensity data for all queued regions
@@ -314,7 +607,7 @@ impl<C: EnergyApiClient + Send + Sync + 'static> GreenWaitScheduler<C> {
 
     /// Get queue statistics
     pub async fn stats(&self) -> GreenWaitStats {
-        let (total, expired, critical, high, normal, low, background) = self.queue.get_stats().await;
+        let (total, expired, critical, high, normal, low, background) = self.queue.get_stats(self.clock.now().into(), &self.config.wait_budgets).await;
 
         let by_priority = [critical, high, normal, low, background];
 
@@ -327,7 +620,7 @@ impl<C: EnergyApiClient + Send + Sync + 'static> GreenWaitScheduler<C> {
 
     /// Estimate the greenest point in time within the job's max wait duration
     pub async fn estimate_green_window(&self, job: &DeferredJob) -> Option<chrono::DateTime<chrono::Utc>> {
-        let max_wait = job.priority.max_wait_duration();
+        let max_wait = self.config.max_wait(job.priority);
         let deadline = job.submitted_at + chrono::Duration::from_std(max_wait).unwrap_or(chrono::Duration::seconds(0));
         
         // Request based on max wait
@@ -363,6 +656,7 @@ pub struct GreenWaitStats {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aegis_common::{Clock, MockClock};
     use aegis_energy::{CarbonIntensity, EnergyApiError};
 
     struct MockClient {
@@ -380,6 +674,8 @@ mod tests {
                 timestamp: chrono::Utc::now(),
                 valid_for_seconds: 300,
                 rating: None,
+                renewable_percentage: None,
+                power_breakdown: None,
             })
         }
 
@@ -505,6 +801,43 @@ mod tests {
         assert!(matches!(result, ScheduleResult::ExecutedImmediately));
     }
 
+    #[tokio::test]
+    async fn test_queued_job_expires_with_clock() {
+        let clock = MockClock::new();
+        let client = MockClient { intensity: 500.0 };
+        let cache = CarbonIntensityCache::new(300);
+        let scheduler = GreenWaitScheduler::new(
+            GreenWaitConfig::default(),
+            client,
+            cache,
+            tempfile::NamedTempFile::new().unwrap().path(),
+        )
+        .unwrap()
+        .with_clock(clock.shared());
+
+        let mut job = DeferredJob::new(
+            "normal-1",
+            JobPriority::Normal,
+            Region::new("us-west", "US West"),
+            100.0,
+            vec![],
+        );
+        job.submitted_at = clock.now().into();
+        scheduler.update_region_intensity("us-west", 500.0).await;
+        assert!(matches!(scheduler.submit(job).await, ScheduleResult::Queued { .. }));
+
+        // Still inside the 30 minute window for normal jobs
+        clock.advance(Duration::from_secs(29 * 60));
+        assert!(scheduler.process_ready_jobs().await.is_empty());
+        assert_eq!(scheduler.stats().await.expired_count, 0);
+
+        clock.advance(Duration::from_secs(2 * 60));
+        assert_eq!(scheduler.stats().await.expired_count, 1);
+        let ready = scheduler.process_ready_jobs().await;
+        assert_eq!(ready.len(), 1);
+        assert_eq!(ready[0].id, "normal-1");
+    }
+
     #[tokio::test]
     async fn test_queue_stats() {
         let client = MockClient { intensity: 500.0 };
@@ -557,6 +890,316 @@ mod tests {
         assert_eq!(scheduler.queue_length().await, 0);
     }
 
+    #[tokio::test]
+    async fn test_drain_ready_streams_ready_jobs() {
+        use futures_util::StreamExt;
+
+        let client = MockClient { intensity: 500.0 };
+        let cache = CarbonIntensityCache::new(300);
+        let scheduler = GreenWaitScheduler::new(GreenWaitConfig::default(), client, cache, tempfile::NamedTempFile::new().unwrap().path()).unwrap();
+
+        scheduler.update_region_intensity("green", 500.0).await;
+        scheduler.update_region_intensity("dirty", 500.0).await;
+        for i in 0..4 {
+            let region = if i % 2 == 0 { "green" } else { "dirty" };
+            let job = DeferredJob::new(
+                format!("{region}-{i}"),
+                JobPriority::Normal,
+                Region::new(region, region),
+                100.0,
+                vec![],
+            );
+            scheduler.submit(job).await;
+        }
+        scheduler.update_region_intensity("green", 50.0).await;
+
+        let mut ready = std::pin::pin!(scheduler.drain_ready());
+        let first = ready.next().await.unwrap();
+        assert_eq!(first.id, "green-0");
+        // Only the yielded job has left the queue so far
+        assert_eq!(scheduler.queue_length().await, 3);
+
+        let rest: Vec<_> = ready.map(|job| job.id).collect().await;
+        assert_eq!(rest, vec!["green-2"]);
+        assert_eq!(scheduler.queue_length().await, 2);
+
+        // Non-ready jobs stay queued in their original order
+        scheduler.update_region_intensity("dirty", 50.0).await;
+        let ids: Vec<_> = scheduler.drain_ready().map(|job| job.id).collect().await;
+        assert_eq!(ids, vec!["dirty-1", "dirty-3"]);
+        assert_eq!(scheduler.queue_length().await, 0);
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct ReportRequest {
+        tenant: String,
+        rows: Vec<u32>,
+        include_summary: bool,
+    }
+
+    #[tokio::test]
+    async fn test_typed_payload_roundtrip_through_queue() {
+        let client = MockClient { intensity: 500.0 };
+        let cache = CarbonIntensityCache::new(300);
+        let scheduler = GreenWaitScheduler::new(GreenWaitConfig::default(), client, cache, tempfile::NamedTempFile::new().unwrap().path()).unwrap();
+        scheduler.update_region_intensity("us-west", 500.0).await;
+
+        let request = ReportRequest {
+            tenant: "acme".to_string(),
+            rows: vec![3, 1, 4],
+            include_summary: true,
+        };
+        for (id, codec) in [("json", PayloadCodec::Json), ("bincode", PayloadCodec::Bincode)] {
+            let job = DeferredJob::new_typed(id, JobPriority::Normal, Region::new("us-west", "US West"), 100.0, &request, codec).unwrap();
+            assert!(matches!(scheduler.submit(job).await, ScheduleResult::Queued { .. }));
+        }
+
+        scheduler.update_region_intensity("us-west", 50.0).await;
+        let ready = scheduler.process_ready_jobs().await;
+        assert_eq!(ready.len(), 2);
+        assert_eq!(ready[0].decode_payload::<ReportRequest>(PayloadCodec::Json).unwrap(), request);
+        assert_eq!(ready[1].decode_payload::<ReportRequest>(PayloadCodec::Bincode).unwrap(), request);
+
+        // JSON payloads are plain bytes for consumers that skip the typed API
+        let json: serde_json::Value = serde_json::from_slice(&ready[0].payload).unwrap();
+        assert_eq!(json["tenant"], "acme");
+    }
+
+    #[test]
+    fn test_typed_payload_codec_mismatch() {
+        let job = DeferredJob::new_typed("job", JobPriority::Normal, Region::new("us-west", "US West"), 100.0, &vec![1u32, 2], PayloadCodec::Bincode).unwrap();
+        assert!(matches!(
+            job.decode_payload::<Vec<u32>>(PayloadCodec::Json),
+            Err(PayloadError::Json(_))
+        ));
+
+        let raw = DeferredJob::new("raw", JobPriority::Normal, Region::new("us-west", "US West"), 100.0, vec![0xFF]);
+        assert!(matches!(
+            raw.decode_payload::<String>(PayloadCodec::Bincode),
+            Err(PayloadError::Bincode(_))
+        ));
+    }
+
+    #[test]
+    fn test_effective_priority_aging() {
+        let job = DeferredJob::new("bg", JobPriority::Background, Region::new("us-west", "US West"), 100.0, vec![]);
+        let hour = Duration::from_secs(60 * 60);
+        let at = |hours: i64| job.submitted_at + chrono::Duration::hours(hours);
+
+        assert_eq!(job.effective_priority_at(at(0), hour), JobPriority::Background);
+        assert_eq!(job.effective_priority_at(at(1), hour), JobPriority::Low);
+        assert_eq!(job.effective_priority_at(at(3), hour), JobPriority::High);
+        assert_eq!(job.effective_priority_at(at(10), hour), JobPriority::Critical);
+        // Aging disabled
+        assert_eq!(job.effective_priority_at(at(10), Duration::ZERO), JobPriority::Background);
+    }
+
+    #[test]
+    fn test_aging_interval_jitter_is_bounded_and_stable() {
+        let config = GreenWaitConfig {
+            priority_aging_secs: 1000,
+            priority_aging_jitter: 0.5,
+            ..Default::default()
+        };
+        for i in 0..20 {
+            let job = DeferredJob::new(format!("job-{i}"), JobPriority::Low, Region::new("us-west", "US West"), 100.0, vec![]);
+            let interval = config.aging_interval_for(&job);
+            assert!(interval >= Duration::from_secs(1000) && interval < Duration::from_secs(1500), "{interval:?}");
+            assert_eq!(config.aging_interval_for(&job), interval);
+        }
+
+        let no_jitter = GreenWaitConfig { priority_aging_jitter: 0.0, ..config };
+        let job = DeferredJob::new("job", JobPriority::Low, Region::new("us-west", "US West"), 100.0, vec![]);
+        assert_eq!(no_jitter.aging_interval_for(&job), Duration::from_secs(1000));
+    }
+
+    #[tokio::test]
+    async fn test_aged_background_job_released_before_newer_jobs() {
+        let clock = MockClock::new();
+        let config = GreenWaitConfig {
+            priority_aging_secs: 10 * 60,
+            priority_aging_jitter: 0.0,
+            ..Default::default()
+        };
+        let client = MockClient { intensity: 500.0 };
+        let cache = CarbonIntensityCache::new(300);
+        let scheduler = GreenWaitScheduler::new(config, client, cache, tempfile::NamedTempFile::new().unwrap().path())
+            .unwrap()
+            .with_clock(clock.shared());
+        // The region never gets green
+        scheduler.update_region_intensity("us-west", 500.0).await;
+
+        let submit = |id: &str, priority: JobPriority| {
+            let mut job = DeferredJob::new(id, priority, Region::new("us-west", "US West"), 100.0, vec![]);
+            job.submitted_at = clock.now().into();
+            job
+        };
+        assert!(matches!(scheduler.submit(submit("old-background", JobPriority::Background)).await, ScheduleResult::Queued { .. }));
+
+        // Three levels up after 30 minutes: High, not yet released
+        clock.advance(Duration::from_secs(30 * 60));
+        assert!(scheduler.process_ready_jobs().await.is_empty());
+        scheduler.submit(submit("new-low", JobPriority::Low)).await;
+        scheduler.submit(submit("new-background", JobPriority::Background)).await;
+
+        // 40 minutes in, the old job has aged to Critical while the newer ones,
+        // including a higher base priority, keep waiting
+        clock.advance(Duration::from_secs(10 * 60));
+        let ready = scheduler.process_ready_jobs().await;
+        assert_eq!(ready.len(), 1);
+        assert_eq!(ready[0].id, "old-background");
+        assert!(!ready[0].is_expired_at(clock.now().into(), JobPriority::Background.max_wait_duration()));
+        assert_eq!(scheduler.queue_length().await, 2);
+    }
+
+    async fn queued_scheduler(mode: ShutdownMode, jobs: usize) -> Arc<GreenWaitScheduler<MockClient>> {
+        let config = GreenWaitConfig {
+            check_interval_secs: 3600,
+            shutdown_mode: mode,
+            ..Default::default()
+        };
+        let scheduler = GreenWaitScheduler::new(
+            config,
+            MockClient { intensity: 500.0 },
+            CarbonIntensityCache::new(300),
+            tempfile::NamedTempFile::new().unwrap().path(),
+        )
+        .unwrap();
+        for i in 0..jobs {
+            let job = DeferredJob::new(format!("job-{i}"), JobPriority::Low, Region::new("us-west", "US West"), 100.0, vec![]);
+            assert!(matches!(scheduler.submit(job).await, ScheduleResult::Queued { .. }));
+        }
+        Arc::new(scheduler)
+    }
+
+    #[tokio::test]
+    async fn test_driver_flushes_queued_jobs_on_shutdown() {
+        let scheduler = queued_scheduler(ShutdownMode::Flush, 3).await;
+        let lifecycle = crate::lifecycle::LifecycleManager::new();
+        let (mut rx, handle) = scheduler.spawn_driver(lifecycle.shutdown_receiver());
+
+        lifecycle.initiate_shutdown().await;
+        let mut released = Vec::new();
+        while let Some(job) = rx.recv().await {
+            released.push(job.id);
+        }
+        handle.await.unwrap();
+
+        assert_eq!(released, ["job-0", "job-1", "job-2"]);
+        assert_eq!(scheduler.queue_length().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_driver_delivers_job_once_region_turns_green() {
+        let config = GreenWaitConfig {
+            check_interval_secs: 1,
+            ..Default::default()
+        };
+        let scheduler = Arc::new(
+            GreenWaitScheduler::new(
+                config,
+                MockClient { intensity: 500.0 },
+                CarbonIntensityCache::new(300),
+                tempfile::NamedTempFile::new().unwrap().path(),
+            )
+            .unwrap(),
+        );
+        let region = Region::new("us-west", "US West");
+        let job = DeferredJob::new("dirty", JobPriority::Low, region.clone(), 100.0, vec![]);
+        assert!(matches!(scheduler.submit(job).await, ScheduleResult::Queued { .. }));
+
+        let lifecycle = crate::lifecycle::LifecycleManager::new();
+        let (mut rx, handle) = scheduler.spawn_driver(lifecycle.shutdown_receiver());
+        scheduler
+            .cache
+            .put(CarbonIntensity {
+                region,
+                value: 50.0,
+                timestamp: chrono::Utc::now(),
+                valid_for_seconds: 300,
+                rating: None,
+                renewable_percentage: None,
+                power_breakdown: None,
+            })
+            .await;
+
+        let job = tokio::time::timeout(Duration::from_secs(3), rx.recv())
+            .await
+            .expect("job not delivered within two intervals")
+            .unwrap();
+        assert_eq!(job.id, "dirty");
+        assert_eq!(scheduler.queue_length().await, 0);
+
+        lifecycle.initiate_shutdown().await;
+        handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_driver_persists_queued_jobs_on_shutdown() {
+        let scheduler = queued_scheduler(ShutdownMode::Persist, 2).await;
+        let lifecycle = crate::lifecycle::LifecycleManager::new();
+        let (mut rx, handle) = scheduler.spawn_driver(lifecycle.shutdown_receiver());
+
+        lifecycle.initiate_shutdown().await;
+        assert!(rx.recv().await.is_none());
+        handle.await.unwrap();
+        assert_eq!(scheduler.queue_length().await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_custom_wait_budgets_drive_expiry() {
+        let clock = MockClock::new();
+        let mut wait_budgets = GreenWaitConfig::default().wait_budgets;
+        wait_budgets[JobPriority::Normal as usize] = Duration::from_secs(60);
+        wait_budgets[JobPriority::Low as usize] = Duration::from_secs(10 * 60 * 60);
+        let config = GreenWaitConfig {
+            wait_budgets,
+            priority_aging_secs: 0,
+            ..Default::default()
+        };
+        assert_eq!(config.max_wait(JobPriority::Normal), Duration::from_secs(60));
+
+        let scheduler = GreenWaitScheduler::new(
+            config,
+            MockClient { intensity: 500.0 },
+            CarbonIntensityCache::new(300),
+            tempfile::NamedTempFile::new().unwrap().path(),
+        )
+        .unwrap()
+        .with_clock(clock.shared());
+        scheduler.update_region_intensity("us-west", 500.0).await;
+        let submit = |id: &str, priority| {
+            let mut job = DeferredJob::new(id, priority, Region::new("us-west", "US West"), 100.0, vec![]);
+            job.submitted_at = clock.now().into();
+            job
+        };
+        scheduler.submit(submit("normal", JobPriority::Normal)).await;
+        scheduler.submit(submit("low", JobPriority::Low)).await;
+
+        // Past the custom Normal budget but well inside its 30 minute default
+        clock.advance(Duration::from_secs(90));
+        assert_eq!(scheduler.stats().await.expired_count, 1);
+        let ready = scheduler.process_ready_jobs().await;
+        assert_eq!(ready.len(), 1);
+        assert_eq!(ready[0].id, "normal");
+
+        // Past the 2 hour Low default but inside the custom 10 hours
+        clock.advance(Duration::from_secs(3 * 60 * 60));
+        assert!(scheduler.process_ready_jobs().await.is_empty());
+        assert_eq!(scheduler.queue_length().await, 1);
+    }
+
+    #[test]
+    fn test_time_remaining_uses_given_budget() {
+        let job = DeferredJob::new("job", JobPriority::High, Region::new("us-west", "US West"), 100.0, vec![]);
+        let now = job.submitted_at + chrono::Duration::seconds(30);
+        assert_eq!(job.time_remaining_at(now, Duration::from_secs(60)), Duration::from_secs(30));
+        assert!(job.is_expired_at(now, Duration::from_secs(10)));
+        // Budgets too large for chrono never expire
+        assert!(!job.is_expired_at(now, Duration::MAX));
+        assert!(job.time_remaining_at(now, Duration::MAX) > Duration::from_secs(u32::MAX.into()));
+    }
+
     #[tokio::test]
     async fn test_disabled_scheduler() {
         let client = MockClient { intensity: 50.0 };
@@ -623,10 +1266,11 @@ mod tests {
             100.0,
             vec![1, 2, 3],
         );
-        let remaining = job.time_remaining();
+        let max_wait = GreenWaitConfig::default().max_wait(job.priority);
+        let remaining = job.time_remaining(max_wait);
         assert!(remaining > Duration::ZERO);
         assert!(remaining <= Duration::from_secs(300));
-        assert!(!job.is_expired());
+        assert!(!job.is_expired(max_wait));
     }
 
     #[test]
@@ -703,8 +1347,9 @@ mod tests {
             100.0,
             vec![],
         );
-        assert!(!job.is_expired());
-        assert!(job.time_remaining() > Duration::ZERO);
+        let max_wait = GreenWaitConfig::default().max_wait(job.priority);
+        assert!(!job.is_expired(max_wait));
+        assert!(job.time_remaining(max_wait) > Duration::ZERO);
     }
 
     #[test]
@@ -905,6 +1550,8 @@ mod tests {
                 timestamp: chrono::Utc::now(),
                 valid_for_seconds: 300,
                 rating: None,
+                renewable_percentage: None,
+                power_breakdown: None,
             })
         }
 
@@ -1053,14 +1700,22 @@ mod tests {
         assert!(JobPriority::Low < JobPriority::Background);
     }
 
+    #[test]
+    fn test_job_priority_from_name() {
+        assert_eq!(JobPriority::from_name("critical"), Some(JobPriority::Critical));
+        assert_eq!(JobPriority::from_name(" Low "), Some(JobPriority::Low));
+        assert_eq!(JobPriority::from_name("BACKGROUND"), Some(JobPriority::Background));
+        assert_eq!(JobPriority::from_name("urgent"), None);
+    }
+
     #[test]
     fn test_job_priority_default() {
         let priority: JobPriority = Default::default();
         assert_eq!(priority, JobPriority::Normal);
     }
 
-    #[tokio::test]
-    async fn test_deferred_job_is_expired_critical() {
+    #[test]
+    fn test_deferred_job_is_expired_critical() {
         let job = DeferredJob::new(
             "critical-job",
             JobPriority::Critical,
@@ -1070,8 +1725,11 @@ mod tests {
         );
 
         // Critical jobs have zero wait duration, so they expire after any time passes
-        tokio::time::sleep(std::time::Duration::from_millis(1)).await;
-        assert!(job.is_expired());
+        let clock = MockClock::starting_at(job.submitted_at.into());
+        let max_wait = GreenWaitConfig::default().max_wait(job.priority);
+        assert!(!job.is_expired_at(clock.now().into(), max_wait));
+        clock.advance(Duration::from_millis(1));
+        assert!(job.is_expired_at(clock.now().into(), max_wait));
     }
 
     #[test]
@@ -1137,7 +1795,7 @@ mod tests {
             vec![],
         );
         // Job just created, should not be expired
-        assert!(!job.is_expired());
+        assert!(!job.is_expired(GreenWaitConfig::default().max_wait(job.priority)));
     }
 
     #[test]
@@ -1163,7 +1821,7 @@ mod tests {
             vec![],
         );
         // For Critical priority, max_wait is ZERO, so time_remaining is ZERO
-        assert_eq!(job.time_remaining(), Duration::ZERO);
+        assert_eq!(job.time_remaining(GreenWaitConfig::default().max_wait(job.priority)), Duration::ZERO);
     }
 
     #[tokio::test]
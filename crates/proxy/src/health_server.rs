@@ -2,7 +2,9 @@ use crate::config::HealthConfig;
 use crate::lifecycle::LifecycleManager;
 use anyhow::Result;
 use bytes::Bytes;
-use http_body_util::Full;
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::Body;
+use hyper::header::{ALLOW, AUTHORIZATION, CONTENT_LENGTH, CONTENT_TYPE, WWW_AUTHENTICATE};
 use hyper::server::conn::http1;
 use hyper::service::service_fn;
 use hyper::{Method, Request, Response, StatusCode};
@@ -25,16 +27,36 @@ pub async fn run_health_server(
 
     info!("🏥 Health server listening on http://{}", addr);
 
-    run_health_server_with_listener(listener, lifecycle, metrics_handle, std::future::pending())
-        .await
+    run_health_server_with_admin(
+        listener,
+        lifecycle,
+        metrics_handle,
+        config.admin_token,
+        std::future::pending(),
+    )
+    .await
 }
 
+/// Serve health endpoints on `listener` with the admin API disabled
 pub async fn run_health_server_with_listener(
     listener: TcpListener,
     lifecycle: Arc<LifecycleManager>,
     metrics_handle: Option<PrometheusHandle>,
     shutdown: impl std::future::Future<Output = ()>,
 ) -> Result<()> {
+    run_health_server_with_admin(listener, lifecycle, metrics_handle, None, shutdown).await
+}
+
+/// Serve health endpoints on `listener`, accepting admin requests that
+/// carry `admin_token` as a bearer token
+pub async fn run_health_server_with_admin(
+    listener: TcpListener,
+    lifecycle: Arc<LifecycleManager>,
+    metrics_handle: Option<PrometheusHandle>,
+    admin_token: Option<String>,
+    shutdown: impl std::future::Future<Output = ()>,
+) -> Result<()> {
+    let admin_token: Option<Arc<str>> = admin_token.map(Arc::from);
     tokio::pin!(shutdown);
 
     loop {
@@ -45,13 +67,19 @@ pub async fn run_health_server_with_listener(
                         let io = TokioIo::new(stream);
                         let lifecycle = lifecycle.clone();
                         let metrics_handle = metrics_handle.clone();
+                        let admin_token = admin_token.clone();
 
                         tokio::task::spawn(async move {
                             if let Err(err) = http1::Builder::new()
                                 .serve_connection(
                                     io,
                                     service_fn(move |req| {
-                                        handle_request(req, lifecycle.clone(), metrics_handle.clone())
+                                        handle_request(
+                                            req,
+                                            lifecycle.clone(),
+                                            metrics_handle.clone(),
+                                            admin_token.clone(),
+                                        )
                                     }),
                                 )
                                 .await
@@ -77,55 +105,227 @@ pub async fn run_health_server_with_listener(
     Ok(())
 }
 
+/// Methods accepted on every health server endpoint
+const ALLOWED_METHODS: &str = "GET, HEAD, OPTIONS";
+
+/// Toggles maintenance mode on `POST`, reports it on `GET`
+const MAINTENANCE_PATH: &str = "/admin/maintenance";
+
+/// Largest accepted maintenance request body
+const MAX_ADMIN_BODY_BYTES: usize = 1024;
+
+/// Body of `POST /admin/maintenance`
+#[derive(serde::Deserialize)]
+struct MaintenanceRequest {
+    enabled: bool,
+}
+
 async fn handle_request<B>(
     req: Request<B>,
     lifecycle: Arc<LifecycleManager>,
     metrics_handle: Option<PrometheusHandle>,
-) -> Result<Response<Full<Bytes>>, Infallible> {
-    match (req.method(), req.uri().path()) {
-        (&Method::GET, "/health") => {
+    admin_token: Option<Arc<str>>,
+) -> Result<Response<Full<Bytes>>, Infallible>
+where
+    B: Body,
+    B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    let path = req.uri().path();
+    match *req.method() {
+        Method::POST if path == MAINTENANCE_PATH => {
+            if let Some(rejection) = check_admin_request(&req, admin_token.as_deref()) {
+                return Ok(rejection);
+            }
+            Ok(set_maintenance(req, &lifecycle).await)
+        }
+        Method::GET => Ok(get_response(path, lifecycle, metrics_handle).await),
+        Method::HEAD => {
+            let (mut parts, body) = get_response(path, lifecycle, metrics_handle)
+                .await
+                .into_parts();
+            parts
+                .headers
+                .insert(CONTENT_LENGTH, body.size_hint().lower().into());
+            Ok(Response::from_parts(parts, Full::new(Bytes::new())))
+        }
+        Method::OPTIONS if matches!(path, "/health" | "/ready" | "/metrics") => {
+            Ok(Response::builder()
+                .status(StatusCode::OK)
+                .header(ALLOW, ALLOWED_METHODS)
+                .header("Access-Control-Allow-Origin", "*")
+                .header("Access-Control-Allow-Methods", ALLOWED_METHODS)
+                .body(Full::new(Bytes::new()))
+                .unwrap())
+        }
+        _ => Ok(not_found()),
+    }
+}
+
+/// Reject admin requests without the configured bearer token or a JSON body
+fn check_admin_request<B>(
+    req: &Request<B>,
+    admin_token: Option<&str>,
+) -> Option<Response<Full<Bytes>>> {
+    let Some(expected) = admin_token else {
+        return Some(plain_response(
+            StatusCode::FORBIDDEN,
+            "Admin API disabled: no admin token configured",
+        ));
+    };
+    let authorized = req
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .is_some_and(|token| tokens_match(token.as_bytes(), expected.as_bytes()));
+    if !authorized {
+        warn!("Rejected unauthenticated admin request");
+        let mut response =
+            plain_response(StatusCode::UNAUTHORIZED, "Missing or invalid admin token");
+        response.headers_mut().insert(
+            WWW_AUTHENTICATE,
+            hyper::header::HeaderValue::from_static("Bearer"),
+        );
+        return Some(response);
+    }
+
+    let is_json = req
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split(';').next())
+        .is_some_and(|media_type| media_type.trim().eq_ignore_ascii_case("application/json"));
+    if !is_json {
+        return Some(plain_response(
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            "Expected Content-Type: application/json",
+        ));
+    }
+    None
+}
+
+/// Compare tokens without stopping at the first differing byte
+fn tokens_match(presented: &[u8], expected: &[u8]) -> bool {
+    presented.len() == expected.len()
+        && presented
+            .iter()
+            .zip(expected)
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+async fn set_maintenance<B>(req: Request<B>, lifecycle: &LifecycleManager) -> Response<Full<Bytes>>
+where
+    B: Body,
+    B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    let body = match Limited::new(req.into_body(), MAX_ADMIN_BODY_BYTES)
+        .collect()
+        .await
+    {
+        Ok(collected) => collected.to_bytes(),
+        Err(_) => return bad_request("Unreadable request body"),
+    };
+    let Ok(request) = serde_json::from_slice::<MaintenanceRequest>(&body) else {
+        return bad_request(r#"Expected {"enabled": true|false}"#);
+    };
+
+    if lifecycle.set_maintenance(request.enabled).await {
+        info!(
+            "🔧 Maintenance mode {} via admin API",
+            if request.enabled {
+                "enabled"
+            } else {
+                "disabled"
+            }
+        );
+        maintenance_response(lifecycle, StatusCode::OK).await
+    } else {
+        maintenance_response(lifecycle, StatusCode::CONFLICT).await
+    }
+}
+
+async fn maintenance_response(
+    lifecycle: &LifecycleManager,
+    status: StatusCode,
+) -> Response<Full<Bytes>> {
+    let health = lifecycle.health_status().await;
+    let json = serde_json::json!({
+        "maintenance": health == crate::lifecycle::HealthStatus::Maintenance,
+        "status": health.to_string(),
+    });
+    Response::builder()
+        .status(status)
+        .header("Content-Type", "application/json")
+        .body(Full::new(Bytes::from(json.to_string())))
+        .unwrap()
+}
+
+fn bad_request(message: &'static str) -> Response<Full<Bytes>> {
+    plain_response(StatusCode::BAD_REQUEST, message)
+}
+
+fn plain_response(status: StatusCode, message: &'static str) -> Response<Full<Bytes>> {
+    Response::builder()
+        .status(status)
+        .body(Full::new(Bytes::from(message)))
+        .unwrap()
+}
+
+async fn get_response(
+    path: &str,
+    lifecycle: Arc<LifecycleManager>,
+    metrics_handle: Option<PrometheusHandle>,
+) -> Response<Full<Bytes>> {
+    match path {
+        MAINTENANCE_PATH => maintenance_response(&lifecycle, StatusCode::OK).await,
+        "/health" => {
             let response = lifecycle.health_response().await;
             let json = serde_json::to_string(&response).unwrap_or_default();
-            Ok(Response::builder()
+            Response::builder()
                 .header("Content-Type", "application/json")
                 .body(Full::new(Bytes::from(json)))
-                .unwrap())
+                .unwrap()
         }
-        (&Method::GET, "/ready") => {
+        "/ready" => {
             let status = lifecycle.health_status().await;
             if status.is_ready() {
-                Ok(Response::builder()
+                Response::builder()
                     .status(StatusCode::OK)
                     .body(Full::new(Bytes::from("OK")))
-                    .unwrap())
+                    .unwrap()
             } else {
-                Ok(Response::builder()
+                Response::builder()
                     .status(StatusCode::SERVICE_UNAVAILABLE)
                     .body(Full::new(Bytes::from("Not Ready")))
-                    .unwrap())
+                    .unwrap()
             }
         }
-        (&Method::GET, "/metrics") => {
+        "/metrics" => {
             if let Some(handle) = metrics_handle {
                 let metrics = handle.render();
-                Ok(Response::builder()
+                Response::builder()
                     .header("Content-Type", "text/plain")
                     .body(Full::new(Bytes::from(metrics)))
-                    .unwrap())
+                    .unwrap()
             } else {
-                Ok(Response::builder()
+                Response::builder()
                     .status(StatusCode::NOT_IMPLEMENTED)
                     .body(Full::new(Bytes::from("Metrics not enabled")))
-                    .unwrap())
+                    .unwrap()
             }
         }
-        _ => Ok(Response::builder()
-            .status(StatusCode::NOT_FOUND)
-            .body(Full::new(Bytes::from("Not Found")))
-            .unwrap()),
+        _ => not_found(),
     }
 }
 
+fn not_found() -> Response<Full<Bytes>> {
+    Response::builder()
+        .status(StatusCode::NOT_FOUND)
+        .body(Full::new(Bytes::from("Not Found")))
+        .unwrap()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -236,7 +436,7 @@ mod tests {
             .body(http_body_util::Empty::<Bytes>::new())
             .unwrap();
 
-        let resp = handle_request(req, lifecycle, None).await.unwrap();
+        let resp = handle_request(req, lifecycle, None, None).await.unwrap();
         assert_eq!(resp.status(), StatusCode::OK);
         assert_eq!(
             resp.headers().get("Content-Type").unwrap(),
@@ -254,7 +454,9 @@ mod tests {
             .method(Method::GET)
             .body(http_body_util::Empty::<Bytes>::new())
             .unwrap();
-        let resp = handle_request(req, lifecycle.clone(), None).await.unwrap();
+        let resp = handle_request(req, lifecycle.clone(), None, None)
+            .await
+            .unwrap();
         assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
 
         // Mark ready
@@ -265,10 +467,199 @@ mod tests {
             .method(Method::GET)
             .body(http_body_util::Empty::<Bytes>::new())
             .unwrap();
-        let resp = handle_request(req, lifecycle, None).await.unwrap();
+        let resp = handle_request(req, lifecycle, None, None).await.unwrap();
         assert_eq!(resp.status(), StatusCode::OK);
     }
 
+    async fn send(
+        lifecycle: &Arc<LifecycleManager>,
+        method: Method,
+        uri: &str,
+        body: &'static str,
+    ) -> (StatusCode, String) {
+        let req = Request::builder()
+            .uri(uri)
+            .method(method)
+            .header("Authorization", "Bearer s3cret")
+            .header("Content-Type", "application/json")
+            .body(Full::new(Bytes::from(body)))
+            .unwrap();
+        let resp = handle_request(req, lifecycle.clone(), None, Some(Arc::from("s3cret")))
+            .await
+            .unwrap();
+        let status = resp.status();
+        let body = resp.into_body().collect().await.unwrap().to_bytes();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_maintenance_toggle_fails_readiness_not_liveness() {
+        let lifecycle = create_test_lifecycle();
+        lifecycle.mark_ready().await;
+
+        let (status, body) = send(
+            &lifecycle,
+            Method::POST,
+            "/admin/maintenance",
+            r#"{"enabled":true}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["maintenance"], true);
+
+        let (ready, _) = send(&lifecycle, Method::GET, "/ready", "").await;
+        assert_eq!(ready, StatusCode::SERVICE_UNAVAILABLE);
+        let (live, body) = send(&lifecycle, Method::GET, "/health", "").await;
+        assert_eq!(live, StatusCode::OK);
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["status"], "maintenance");
+        assert_eq!(json["alive"], true);
+        assert_eq!(json["ready"], false);
+
+        let (_, body) = send(&lifecycle, Method::GET, "/admin/maintenance", "").await;
+        assert!(body.contains(r#""maintenance":true"#));
+
+        let (status, _) = send(
+            &lifecycle,
+            Method::POST,
+            "/admin/maintenance",
+            r#"{"enabled":false}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let (ready, _) = send(&lifecycle, Method::GET, "/ready", "").await;
+        assert_eq!(ready, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_maintenance_requires_admin_token_and_json() {
+        let lifecycle = create_test_lifecycle();
+        lifecycle.mark_ready().await;
+        let post = |authorization: Option<&'static str>, content_type: &'static str| {
+            let mut builder = Request::builder()
+                .uri("/admin/maintenance")
+                .method(Method::POST)
+                .header("Content-Type", content_type);
+            if let Some(authorization) = authorization {
+                builder = builder.header("Authorization", authorization);
+            }
+            builder
+                .body(Full::new(Bytes::from(r#"{"enabled":true}"#)))
+                .unwrap()
+        };
+        let token = || Some(Arc::<str>::from("s3cret"));
+
+        // Disabled unless a token is configured
+        let resp = handle_request(
+            post(Some("Bearer s3cret"), "application/json"),
+            lifecycle.clone(),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+        for authorization in [None, Some("Bearer wrong"), Some("Basic s3cret")] {
+            let resp = handle_request(
+                post(authorization, "application/json"),
+                lifecycle.clone(),
+                None,
+                token(),
+            )
+            .await
+            .unwrap();
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED, "{authorization:?}");
+            assert_eq!(resp.headers()[WWW_AUTHENTICATE], "Bearer");
+        }
+
+        let resp = handle_request(
+            post(Some("Bearer s3cret"), "text/plain"),
+            lifecycle.clone(),
+            None,
+            token(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(resp.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert!(!lifecycle.is_in_maintenance().await);
+
+        let resp = handle_request(
+            post(Some("Bearer s3cret"), "application/json; charset=utf-8"),
+            lifecycle.clone(),
+            None,
+            token(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert!(lifecycle.is_in_maintenance().await);
+    }
+
+    #[tokio::test]
+    async fn test_maintenance_toggle_rejections() {
+        let lifecycle = create_test_lifecycle();
+
+        // Still starting up
+        let (status, body) = send(
+            &lifecycle,
+            Method::POST,
+            "/admin/maintenance",
+            r#"{"enabled":true}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert!(body.contains(r#""status":"starting""#));
+
+        lifecycle.mark_ready().await;
+        for bad in ["", "{}", r#"{"enabled":"yes"}"#] {
+            let (status, _) = send(&lifecycle, Method::POST, "/admin/maintenance", bad).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{bad}");
+        }
+        let (status, _) = send(&lifecycle, Method::POST, "/ready", "").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert!(!lifecycle.is_in_maintenance().await);
+    }
+
+    #[tokio::test]
+    async fn test_handle_request_head_health() {
+        let lifecycle = create_test_lifecycle();
+        let req = Request::builder()
+            .uri("/health")
+            .method(Method::HEAD)
+            .body(http_body_util::Empty::<Bytes>::new())
+            .unwrap();
+
+        let resp = handle_request(req, lifecycle, None, None).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers().get("Content-Type").unwrap(),
+            "application/json"
+        );
+        assert_ne!(resp.headers()[CONTENT_LENGTH], "0");
+        let body = http_body_util::BodyExt::collect(resp.into_body())
+            .await
+            .unwrap()
+            .to_bytes();
+        assert!(body.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_handle_request_options_metrics() {
+        let lifecycle = create_test_lifecycle();
+        let req = Request::builder()
+            .uri("/metrics")
+            .method(Method::OPTIONS)
+            .body(http_body_util::Empty::<Bytes>::new())
+            .unwrap();
+
+        let resp = handle_request(req, lifecycle, None, None).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers()[ALLOW], "GET, HEAD, OPTIONS");
+        assert_eq!(resp.headers()["Access-Control-Allow-Origin"], "*");
+    }
+
     #[tokio::test]
     async fn test_handle_request_404() {
         let lifecycle = create_test_lifecycle();
@@ -278,7 +669,7 @@ mod tests {
             .body(http_body_util::Empty::<Bytes>::new())
             .unwrap();
 
-        let resp = handle_request(req, lifecycle, None).await.unwrap();
+        let resp = handle_request(req, lifecycle, None, None).await.unwrap();
         assert_eq!(resp.status(), StatusCode::NOT_FOUND);
     }
 
@@ -291,7 +682,7 @@ mod tests {
             .body(http_body_util::Empty::<Bytes>::new())
             .unwrap();
 
-        let resp = handle_request(req, lifecycle, None).await.unwrap();
+        let resp = handle_request(req, lifecycle, None, None).await.unwrap();
         assert_eq!(resp.status(), StatusCode::NOT_IMPLEMENTED);
     }
 
@@ -403,7 +794,9 @@ mod tests {
             .body(http_body_util::Empty::<Bytes>::new())
             .unwrap();
 
-        let resp = handle_request(req, lifecycle, Some(handle)).await.unwrap();
+        let resp = handle_request(req, lifecycle, Some(handle), None)
+            .await
+            .unwrap();
         assert_eq!(resp.status(), StatusCode::OK);
         assert_eq!(resp.headers().get("Content-Type").unwrap(), "text/plain");
     }
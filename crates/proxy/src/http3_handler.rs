@@ -2,7 +2,9 @@
 //!
 //! HTTP/3 request and response handling over QUIC streams.
 
+use crate::cors::CorsDecision;
 use bytes::Bytes;
+use thiserror::Error;
 use tracing::{debug, error, info, warn};
 
 pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
@@ -52,6 +54,27 @@ impl HttpBodyType {
     }
 }
 
+/// Reasons a raw request head is rejected with `400`
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum RequestParseError {
+    /// The request line or headers are not valid UTF-8
+    #[error("Request head is not valid UTF-8")]
+    InvalidUtf8,
+    /// The request line is not `METHOD target HTTP/version`
+    #[error("Malformed request line: {0:?}")]
+    MalformedRequestLine(String),
+    /// A header line is not `Name: value`
+    #[error("Malformed header line: {0:?}")]
+    MalformedHeader(String),
+}
+
+/// Characters allowed in methods and header names (RFC 9110 `tchar`)
+fn is_token(s: &str) -> bool {
+    !s.is_empty()
+        && s.bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b))
+}
+
 /// HTTP/3 request representation
 #[derive(Debug)]
 pub struct Http3Request {
@@ -76,6 +99,50 @@ impl Http3Request {
         }
     }
 
+    /// Parse a raw `METHOD target HTTP/version` request with headers and body
+    ///
+    /// Only the head has to be UTF-8; everything after the first blank line
+    /// becomes the body untouched.
+    pub fn parse(data: &[u8]) -> Result<Self, RequestParseError> {
+        let (head, body) = match data.windows(4).position(|w| w == b"\r\n\r\n") {
+            Some(end) => (&data[..end], &data[end + 4..]),
+            None => (data, &[][..]),
+        };
+        let head = std::str::from_utf8(head).map_err(|_| RequestParseError::InvalidUtf8)?;
+        let mut lines = head.split("\r\n");
+
+        let request_line = lines.next().unwrap_or_default();
+        let malformed = || RequestParseError::MalformedRequestLine(request_line.to_string());
+        let mut parts = request_line.split(' ');
+        let (Some(method), Some(path), Some(version), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(malformed());
+        };
+        if !is_token(method)
+            || !(path.starts_with('/') || path == "*")
+            || path.chars().any(|c| c.is_control())
+            || !version.starts_with("HTTP/")
+        {
+            return Err(malformed());
+        }
+
+        let mut request = Self::new(method, path);
+        for line in lines {
+            let header = line
+                .split_once(':')
+                .filter(|(name, value)| {
+                    is_token(name) && !value.chars().any(|c| c.is_control() && c != '\t')
+                })
+                .ok_or_else(|| RequestParseError::MalformedHeader(line.to_string()))?;
+            request = request.with_header(header.0, header.1.trim());
+        }
+        if !body.is_empty() {
+            request = request.with_body(Bytes::copy_from_slice(body));
+        }
+        Ok(request)
+    }
+
     /// Add a header to the request
     pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
         self.headers.push((name.into(), value.into()));
@@ -166,6 +233,35 @@ impl Http3Response {
         self.body = HttpBodyType::Stream(rx);
         self
     }
+
+    /// Body length to advertise, or `None` for streamed bodies and statuses
+    /// that must not carry a `content-length` (1xx, 204, 304)
+    pub fn content_length(&self) -> Option<usize> {
+        if matches!(self.status, 100..=199 | 204 | 304) {
+            return None;
+        }
+        match &self.body {
+            HttpBodyType::Bytes(b) => Some(b.len()),
+            HttpBodyType::Empty => Some(0),
+            HttpBodyType::Stream(_) => None,
+        }
+    }
+
+    /// Headers to send, with `content-length` set from the body so a client
+    /// of an empty response does not wait for data that never comes
+    pub fn wire_headers(&self) -> Vec<(String, String)> {
+        let length = self.content_length();
+        let mut headers: Vec<(String, String)> = self
+            .headers
+            .iter()
+            .filter(|(name, _)| length.is_none() || !name.eq_ignore_ascii_case("content-length"))
+            .cloned()
+            .collect();
+        if let Some(length) = length {
+            headers.push(("content-length".to_string(), length.to_string()));
+        }
+        headers
+    }
 }
 
 /// HTTP/3 connection handler configuration
@@ -175,6 +271,8 @@ pub struct Http3Config {
     pub max_concurrent_streams: u32,
     /// Request body size limit
     pub max_body_size: usize,
+    /// Request header size limit (names plus values)
+    pub max_header_bytes: usize,
     /// Enable request logging
     pub log_requests: bool,
 }
@@ -184,16 +282,32 @@ impl Default for Http3Config {
         Self {
             max_concurrent_streams: 100,
             max_body_size: 16 * 1024 * 1024, // 16MB
+            max_header_bytes: crate::config::DEFAULT_MAX_HEADER_BYTES,
             log_requests: true,
         }
     }
 }
 
+/// Paths served by the handler itself rather than the upstream
+const BUILTIN_PATHS: [&str; 6] = [
+    "/health",
+    "/healthz",
+    "/ready",
+    "/readiness",
+    "/metrics",
+    "/energy",
+];
+
+/// Methods accepted on the built-in endpoints
+const BUILTIN_METHODS: &str = "GET, HEAD, OPTIONS";
+
 /// HTTP/3 request handler
 pub struct Http3Handler {
     config: Http3Config,
     upstream_addr: String,
     client: reqwest::Client,
+    routes: crate::route::RouteTable,
+    cors: Option<crate::cors::CorsConfig>,
 }
 
 impl Http3Handler {
@@ -215,9 +329,23 @@ impl Http3Handler {
             config,
             upstream_addr,
             client,
+            routes: crate::route::RouteTable::default(),
+            cors: None,
         }
     }
 
+    /// Enforce per-route method allowlists and timeouts
+    pub fn with_routes(mut self, routes: crate::route::RouteTable) -> Self {
+        self.routes = routes;
+        self
+    }
+
+    /// Answer preflights and check origins against a CORS policy
+    pub fn with_cors(mut self, cors: Option<crate::cors::CorsConfig>) -> Self {
+        self.cors = cors;
+        self
+    }
+
     /// Handle an HTTP/3 request and produce a response
     pub async fn handle_request(&self, mut request: Http3Request) -> Http3Response {
         use aegis_telemetry::EnergyEstimator;
@@ -229,6 +357,19 @@ impl Http3Handler {
             info!("📥 HTTP/3 {} {}", request.method, request.path);
         }
 
+        let header_bytes: usize = request
+            .headers
+            .iter()
+            .map(|(name, value)| name.len() + value.len())
+            .sum();
+        if header_bytes > self.config.max_header_bytes {
+            warn!(
+                "⚠️ Rejecting HTTP/3 request with {} header bytes (limit {})",
+                header_bytes, self.config.max_header_bytes
+            );
+            return Http3Response::new(431).with_body("Request Header Fields Too Large");
+        }
+
         // 0-RTT Replay Protection
         let is_early_data = request
             .headers
@@ -246,8 +387,59 @@ impl Http3Handler {
             }
         }
 
+        let cors_headers = match &self.cors {
+            Some(cors) => {
+                let header = |name: &str| {
+                    request
+                        .headers
+                        .iter()
+                        .find(|(k, _)| k.eq_ignore_ascii_case(name))
+                        .map(|(_, v)| v.as_str())
+                };
+                match cors.evaluate(
+                    &request.method,
+                    header("origin"),
+                    header("access-control-request-method"),
+                ) {
+                    CorsDecision::NotCors => Vec::new(),
+                    CorsDecision::Allow(headers) => headers,
+                    CorsDecision::Preflight(headers) => {
+                        return headers
+                            .into_iter()
+                            .fold(Http3Response::new(204), |resp, (k, v)| {
+                                resp.with_header(k, v)
+                            });
+                    }
+                    CorsDecision::Reject => {
+                        crate::metrics::record_error("cors_rejected");
+                        return Http3Response::new(403).with_body("Origin not allowed");
+                    }
+                }
+            }
+            None => Vec::new(),
+        };
+
+        if let Err(allow) = self.routes.check_method(&request.method, &request.path) {
+            crate::metrics::record_error("method_not_allowed");
+            return Http3Response::new(405)
+                .with_header("allow", allow)
+                .with_body("Method Not Allowed");
+        }
+        let timeout = self.routes.timeout_for(&request.path);
+
+        let builtin = BUILTIN_PATHS.contains(&request.path.as_str());
+        if builtin && request.method == "OPTIONS" {
+            return Http3Response::new(200)
+                .with_header("allow", BUILTIN_METHODS)
+                .with_header("access-control-allow-origin", "*")
+                .with_header("access-control-allow-methods", BUILTIN_METHODS);
+        }
+        // Built-in endpoints answer HEAD with the GET headers and no body
+        let head = builtin && request.method == "HEAD";
+        let method = if head { "GET" } else { request.method.as_str() };
+
         // Route to appropriate handler
-        let response = match (request.method.as_str(), request.path.as_str()) {
+        let response = match (method, request.path.as_str()) {
             ("GET", "/healthz") | ("GET", "/health") => {
                 Http3Response::ok(r#"{"status":"healthy"}"#)
             }
@@ -268,13 +460,27 @@ impl Http3Handler {
                     "total_requests": estimator.request_count(),
                     "total_energy_joules": estimator.total_energy_joules(),
                     "average_energy_joules": estimator.average_energy_joules(),
+                    "breakdown": estimator.total_breakdown(),
                     "source": "software"
                 });
                 Http3Response::ok(info.to_string()).with_header("content-type", "application/json")
             }
             _ => {
                 // Forward to upstream
-                match self.forward_to_upstream(request).await {
+                let path = request.path.clone();
+                let forwarded = self.forward_to_upstream(request);
+                let result = match timeout {
+                    Some(timeout) => match tokio::time::timeout(timeout, forwarded).await {
+                        Ok(result) => result,
+                        Err(_) => {
+                            warn!("⏱️ HTTP/3 {} timed out after {:?}", path, timeout);
+                            crate::metrics::record_error("request_timeout");
+                            return Http3Response::new(504).with_body("Gateway Timeout");
+                        }
+                    },
+                    None => forwarded.await,
+                };
+                match result {
                     Ok(resp) => resp,
                     Err(e) => {
                         error!("❌ HTTP/3 Upstream error: {}", e);
@@ -287,7 +493,21 @@ impl Http3Handler {
         let duration = start.elapsed();
         debug!("⚡ Request handled in {:?}", duration);
 
-        response
+        let mut response = response;
+        for (name, value) in cors_headers {
+            response
+                .headers
+                .retain(|(k, _)| !k.eq_ignore_ascii_case(name));
+            response.headers.push((name.to_string(), value));
+        }
+        if head {
+            let len = response.body.as_bytes().map_or(0, |b| b.len());
+            let mut response = response.with_header("content-length", len.to_string());
+            response.body = HttpBodyType::Empty;
+            response
+        } else {
+            response
+        }
     }
 
     /// Forward request to upstream address
@@ -338,7 +558,12 @@ impl Http3Handler {
             HttpBodyType::Empty => {}
         }
 
+        let sent_at = std::time::Instant::now();
         let upstream_resp = upstream_req.send().await?;
+        crate::metrics::record_upstream_duration(
+            &self.upstream_addr,
+            sent_at.elapsed().as_secs_f64(),
+        );
         let status = upstream_resp.status().as_u16();
 
         let mut h3_resp = Http3Response::new(status);
@@ -376,6 +601,11 @@ impl Http3Handler {
         Ok(h3_resp)
     }
 
+    /// Get the request header size limit
+    pub fn max_header_bytes(&self) -> usize {
+        self.config.max_header_bytes
+    }
+
     /// Get the upstream address
     pub fn upstream_addr(&self) -> &str {
         &self.upstream_addr
@@ -555,6 +785,7 @@ mod tests {
         let config = Http3Config {
             max_concurrent_streams: 50,
             max_body_size: 1024,
+            max_header_bytes: 4096,
             log_requests: false,
         };
         assert_eq!(config.max_concurrent_streams, 50);
@@ -578,6 +809,64 @@ mod tests {
         assert!(handler.is_logging_enabled());
     }
 
+    #[test]
+    fn test_parse_request() {
+        let request = Http3Request::parse(
+            b"POST /api?x=1 HTTP/3\r\nContent-Type:  text/plain \r\n\r\n\xff\x00",
+        )
+        .unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/api?x=1");
+        assert_eq!(
+            request.headers,
+            vec![("Content-Type".to_string(), "text/plain".to_string())]
+        );
+        // The body may be binary
+        assert_eq!(request.body.as_bytes().unwrap(), b"\xff\x00");
+
+        let request = Http3Request::parse(b"GET / HTTP/3").unwrap();
+        assert!(request.headers.is_empty());
+        assert!(request.body.is_empty());
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed_heads() {
+        assert_eq!(
+            Http3Request::parse(b"GET /\xff HTTP/3\r\n\r\n").unwrap_err(),
+            RequestParseError::InvalidUtf8
+        );
+        for line in [
+            &b""[..],
+            b"GET",
+            b"GET /",
+            b"GET  / HTTP/3",
+            b"GET / HTTP/3 extra",
+            b"G(T / HTTP/3",
+            b"GET relative HTTP/3",
+            b"GET / SPDY/3",
+        ] {
+            assert!(
+                matches!(
+                    Http3Request::parse(line),
+                    Err(RequestParseError::MalformedRequestLine(_))
+                ),
+                "{:?}",
+                String::from_utf8_lossy(line)
+            );
+        }
+        for header in ["no-colon", ": empty-name", "Bad Name: x", "X: a\u{1}b"] {
+            let raw = format!("GET / HTTP/3\r\n{}\r\n\r\n", header);
+            assert!(
+                matches!(
+                    Http3Request::parse(raw.as_bytes()),
+                    Err(RequestParseError::MalformedHeader(_))
+                ),
+                "{:?}",
+                header
+            );
+        }
+    }
+
     #[tokio::test]
     async fn test_http3_handler_health_endpoint() {
         let handler = Http3Handler::new(Http3Config::default(), "127.0.0.1:8080".to_string());
@@ -594,12 +883,125 @@ mod tests {
         assert_eq!(resp.status, 200);
     }
 
+    #[tokio::test]
+    async fn test_head_health_returns_headers_without_body() {
+        let handler = Http3Handler::new(Http3Config::default(), "127.0.0.1:8080".to_string());
+        let resp = handler
+            .handle_request(Http3Request::new("HEAD", "/health"))
+            .await;
+        assert_eq!(resp.status, 200);
+        assert!(resp.body.is_empty());
+        assert!(
+            resp.headers
+                .contains(&("content-type".to_string(), "application/json".to_string()))
+        );
+        assert!(resp.headers.contains(&(
+            "content-length".to_string(),
+            r#"{"status":"healthy"}"#.len().to_string()
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_options_metrics_lists_allowed_methods() {
+        let handler = Http3Handler::new(Http3Config::default(), "127.0.0.1:8080".to_string());
+        let resp = handler
+            .handle_request(Http3Request::new("OPTIONS", "/metrics"))
+            .await;
+        assert_eq!(resp.status, 200);
+        assert!(resp.body.is_empty());
+        assert!(
+            resp.headers
+                .contains(&("allow".to_string(), "GET, HEAD, OPTIONS".to_string()))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cors_preflight_and_disallowed_origin() {
+        let handler = Http3Handler::new(Http3Config::default(), "127.0.0.1:8080".to_string())
+            .with_cors(Some(
+                crate::cors::CorsConfig::new(&["https://dashboard.example"]).with_max_age(600),
+            ));
+
+        let resp = handler
+            .handle_request(
+                Http3Request::new("OPTIONS", "/energy")
+                    .with_header("Origin", "https://dashboard.example")
+                    .with_header("Access-Control-Request-Method", "GET"),
+            )
+            .await;
+        assert_eq!(resp.status, 204);
+        for header in [
+            ("access-control-allow-origin", "https://dashboard.example"),
+            ("access-control-allow-methods", "GET, HEAD, OPTIONS"),
+            ("access-control-max-age", "600"),
+        ] {
+            assert!(
+                resp.headers
+                    .contains(&(header.0.to_string(), header.1.to_string())),
+                "missing {:?}",
+                header
+            );
+        }
+
+        let resp = handler
+            .handle_request(
+                Http3Request::new("GET", "/energy")
+                    .with_header("origin", "https://dashboard.example"),
+            )
+            .await;
+        assert_eq!(resp.status, 200);
+        assert!(resp.headers.contains(&(
+            "access-control-allow-origin".to_string(),
+            "https://dashboard.example".to_string()
+        )));
+
+        let resp = handler
+            .handle_request(
+                Http3Request::new("GET", "/energy").with_header("origin", "https://evil.example"),
+            )
+            .await;
+        assert_eq!(resp.status, 403);
+    }
+
+    #[tokio::test]
+    async fn test_oversized_headers_rejected() {
+        let config = Http3Config {
+            max_header_bytes: 1024,
+            ..Default::default()
+        };
+        let handler = Http3Handler::new(config, "127.0.0.1:8080".to_string());
+        assert_eq!(handler.max_header_bytes(), 1024);
+
+        let req = Http3Request::new("GET", "/health").with_header("x-big", "a".repeat(1020));
+        let resp = handler.handle_request(req).await;
+        assert_eq!(resp.status, 431);
+
+        let req = Http3Request::new("GET", "/health").with_header("x-big", "a".repeat(1019));
+        let resp = handler.handle_request(req).await;
+        assert_eq!(resp.status, 200);
+    }
+
     #[tokio::test]
     async fn test_http3_handler_energy_endpoint() {
         let handler = Http3Handler::new(Http3Config::default(), "127.0.0.1:8080".to_string());
         let req = Http3Request::new("GET", "/energy");
         let resp = handler.handle_request(req).await;
         assert_eq!(resp.status, 200);
+
+        let body: serde_json::Value =
+            serde_json::from_slice(resp.body.as_bytes().unwrap()).unwrap();
+        for component in [
+            "cpu_joules",
+            "memory_joules",
+            "network_joules",
+            "storage_joules",
+        ] {
+            assert!(
+                body["breakdown"][component].is_number(),
+                "missing {}",
+                component
+            );
+        }
     }
 
     #[tokio::test]
@@ -654,6 +1056,7 @@ mod tests {
         let config = Http3Config {
             max_concurrent_streams: 200,
             max_body_size: 2048,
+            max_header_bytes: 8192,
             log_requests: false,
         };
         let cloned = config.clone();
@@ -789,4 +1192,90 @@ mod tests {
             resp.status
         );
     }
+
+    #[tokio::test]
+    async fn test_route_methods_and_timeouts() {
+        use crate::route::{RouteConfig, RouteTable};
+        use std::time::Duration;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // Upstream answering every request after 100ms
+        let upstream = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let upstream_addr = upstream.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = upstream.accept().await {
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 4096];
+                    let _ = stream.read(&mut buf).await;
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    let _ = stream
+                        .write_all(
+                            b"HTTP/1.1 200 OK\r\ncontent-length: 2\r\nconnection: close\r\n\r\nok",
+                        )
+                        .await;
+                });
+            }
+        });
+
+        let routes = RouteTable::new(vec![
+            RouteConfig::new("/reports")
+                .with_allowed_methods(&["GET", "HEAD"])
+                .with_timeout(Duration::from_millis(20)),
+        ])
+        .with_default_timeout(Some(Duration::from_secs(5)));
+        let handler = Http3Handler::new(Http3Config::default(), upstream_addr.to_string())
+            .with_routes(routes);
+
+        let resp = handler
+            .handle_request(Http3Request::new("DELETE", "/reports/1"))
+            .await;
+        assert_eq!(resp.status, 405);
+        assert!(
+            resp.headers
+                .iter()
+                .any(|(k, v)| k == "allow" && v == "GET, HEAD")
+        );
+
+        // The route's 20ms timeout fires before the upstream answers
+        let resp = handler
+            .handle_request(Http3Request::new("GET", "/reports/1"))
+            .await;
+        assert_eq!(resp.status, 504);
+
+        // Other paths only see the global timeout
+        let resp = handler
+            .handle_request(Http3Request::new("GET", "/other"))
+            .await;
+        assert_eq!(resp.status, 200);
+    }
+
+    #[test]
+    fn test_wire_headers_set_content_length() {
+        let response = Http3Response::new(200);
+        assert_eq!(response.content_length(), Some(0));
+        assert_eq!(
+            response.wire_headers(),
+            vec![("content-length".to_string(), "0".to_string())]
+        );
+
+        // A stale length from upstream is replaced by the real one
+        let response = Http3Response::ok("abc").with_header("Content-Length", "99");
+        let headers = response.wire_headers();
+        assert_eq!(
+            headers
+                .iter()
+                .filter(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+                .collect::<Vec<_>>(),
+            vec![&("content-length".to_string(), "3".to_string())]
+        );
+
+        assert_eq!(Http3Response::new(204).content_length(), None);
+        let (_tx, rx) = tokio::sync::mpsc::channel(1);
+        assert_eq!(
+            Http3Response::new(200)
+                .with_stream_body(rx)
+                .content_length(),
+            None
+        );
+    }
 }
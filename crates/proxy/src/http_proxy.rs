@@ -5,9 +5,9 @@
 use anyhow::Result;
 use bytes::Bytes;
 use http_body_util::{BodyExt, Full};
-use hyper::{Method, Request, Response, StatusCode, server::conn::http1, service::service_fn};
+use hyper::{Method, Request, Response, StatusCode, service::service_fn};
 use hyper_util::rt::TokioIo;
-use reqwest::ClientBuilder;
+use hyper_util::server::conn::auto;
 
 use crate::scgi::ScgiClient;
 use opentelemetry::propagation::{Extractor, Injector};
@@ -17,6 +17,7 @@ use tokio::net::{TcpListener, TcpStream};
 use tracing::{debug, error, info, instrument, warn};
 use tracing_opentelemetry::OpenTelemetrySpanExt;
 
+use crate::connection_info::ConnectionInfo;
 use crate::metrics;
 
 struct HeaderExtractor<'a>(&'a hyper::HeaderMap);
@@ -43,6 +44,10 @@ pub struct HttpProxyConfig {
     pub listen_addr: SocketAddr,
     /// Upstream server address
     pub upstream_addr: String,
+    /// Protocol used to connect to the upstream
+    pub upstream_protocol: crate::upstream_client::UpstreamProtocol,
+    /// Circuit breaker around upstream forwarding
+    pub circuit_breaker: Option<crate::upstream::CircuitBreakerConfig>,
     /// Max concurrent streams
     pub max_concurrent_streams: u32,
     /// Initial window size
@@ -67,6 +72,18 @@ pub struct HttpProxyConfig {
     pub locations: Vec<crate::location::LocationBlock>,
     /// Whether QUIC/HTTP3 listener is active (controls Alt-Svc injection)
     pub quic_enabled: bool,
+    /// Listening socket options (reuse flags, bind retry)
+    pub listener: crate::config::ListenerConfig,
+    /// Energy budget; requests are shed with 503 once it is exhausted
+    pub energy_budget: Option<std::sync::Arc<crate::energy_budget::EnergyBudget>>,
+    /// Maximum request header size; larger requests get 431
+    pub max_header_bytes: usize,
+    /// Global request timeout; requests exceeding it get 504
+    pub request_timeout: Option<std::time::Duration>,
+    /// Per-route method allowlists and timeouts
+    pub routes: Vec<crate::route::RouteConfig>,
+    /// CORS policy; without one the built-in endpoints allow any origin
+    pub cors: Option<crate::cors::CorsConfig>,
 }
 
 impl Default for HttpProxyConfig {
@@ -74,6 +91,8 @@ impl Default for HttpProxyConfig {
         Self {
             listen_addr: "0.0.0.0:8080".parse().unwrap(),
             upstream_addr: "127.0.0.1:9000".to_string(),
+            upstream_protocol: Default::default(),
+            circuit_breaker: None,
             max_concurrent_streams: 100,
             initial_window_size: 65535,
             static_files: None,
@@ -86,10 +105,21 @@ impl Default for HttpProxyConfig {
             tls_server_config: None,
             locations: Vec::new(),
             quic_enabled: false,
+            listener: crate::config::ListenerConfig::default(),
+            energy_budget: None,
+            max_header_bytes: crate::config::DEFAULT_MAX_HEADER_BYTES,
+            request_timeout: None,
+            routes: Vec::new(),
+            cors: None,
         }
     }
 }
 
+/// Carbon router shared with the proxy, over a runtime-chosen energy provider
+pub type SharedCarbonRouter = std::sync::Arc<
+    crate::carbon_router::CarbonRouter<std::sync::Arc<dyn aegis_energy::DynEnergyApiClient>>,
+>;
+
 /// HTTP/2 Reverse Proxy Server
 pub struct HttpProxy {
     pub config: HttpProxyConfig,
@@ -98,6 +128,11 @@ pub struct HttpProxy {
     ttl_config: std::sync::Arc<crate::proxy_cache::TtlConfig>,
     bypass_check: std::sync::Arc<crate::proxy_cache::BypassCheck>,
     locations: std::sync::Arc<Vec<crate::location::ParsedLocationBlock>>,
+    routes: std::sync::Arc<crate::route::RouteTable>,
+    cors: Option<std::sync::Arc<crate::cors::CorsConfig>>,
+    carbon_router: Option<SharedCarbonRouter>,
+    breakers: std::sync::Arc<crate::circuit_breaker::UpstreamBreakers>,
+    lifecycle: Option<std::sync::Arc<crate::lifecycle::LifecycleManager>>,
 }
 
 impl HttpProxy {
@@ -134,6 +169,18 @@ impl HttpProxy {
             }
         }
         let locations = std::sync::Arc::new(parsed_locations);
+        let routes = std::sync::Arc::new(
+            crate::route::RouteTable::new(config.routes.clone())
+                .with_default_timeout(config.request_timeout),
+        );
+        let breakers = std::sync::Arc::new(crate::circuit_breaker::UpstreamBreakers::default());
+        if let Some(breaker) = &config.circuit_breaker {
+            breakers.register(
+                &config.upstream_protocol.target(&config.upstream_addr),
+                breaker,
+            );
+        }
+        let cors = config.cors.clone().map(std::sync::Arc::new);
 
         Self {
             config,
@@ -142,9 +189,38 @@ impl HttpProxy {
             ttl_config,
             bypass_check,
             locations,
+            routes,
+            cors,
+            carbon_router: None,
+            breakers,
+            lifecycle: None,
         }
     }
 
+    /// Count each accepted connection as active until it closes, so a
+    /// graceful shutdown waits for it to drain
+    pub fn with_lifecycle(
+        mut self,
+        lifecycle: std::sync::Arc<crate::lifecycle::LifecycleManager>,
+    ) -> Self {
+        self.lifecycle = Some(lifecycle);
+        self
+    }
+
+    /// Circuit breakers guarding this proxy's upstreams
+    pub fn breakers(&self) -> &std::sync::Arc<crate::circuit_breaker::UpstreamBreakers> {
+        &self.breakers
+    }
+
+    /// Pick each request's upstream through a carbon router
+    ///
+    /// In dry-run mode the router only records its recommendation and
+    /// requests keep going to `upstream_addr`.
+    pub fn with_carbon_router(mut self, router: SharedCarbonRouter) -> Self {
+        self.carbon_router = Some(router);
+        self
+    }
+
     /// Run the proxy server
     /// Run the proxy server
     #[instrument(skip(self))]
@@ -157,7 +233,11 @@ impl HttpProxy {
         &self,
         shutdown: impl std::future::Future<Output = ()>,
     ) -> Result<()> {
-        let listener = TcpListener::bind(self.config.listen_addr).await?;
+        let listener = crate::listener::bind_tcp(
+            &self.config.listen_addr.to_string(),
+            &self.config.listener,
+        )
+        .await?;
         self.run_with_listener(listener, shutdown).await
     }
 
@@ -171,6 +251,7 @@ impl HttpProxy {
         info!("🌐 HTTP/2 Proxy listening on {}", local_addr);
         info!("🔄 Forwarding to {}", self.config.upstream_addr);
 
+        let conn_builder = self.connection_builder();
         tokio::pin!(shutdown);
 
         loop {
@@ -178,7 +259,15 @@ impl HttpProxy {
                 accept_result = listener.accept() => {
                     match accept_result {
                         Ok((stream, peer_addr)) => {
-                            let upstream = self.config.upstream_addr.clone();
+                            if let Err(e) =
+                                crate::listener::configure_accepted(&stream, &self.config.listener)
+                            {
+                                warn!("⚠️ Failed to set socket options for {}: {}", peer_addr, e);
+                            }
+                            let upstream = self
+                                .config
+                                .upstream_protocol
+                                .target(&self.config.upstream_addr);
                             let static_server = self.static_server.clone();
                             let memory_cache = self.memory_cache.clone();
                             let ttl_config = self.ttl_config.clone();
@@ -187,21 +276,83 @@ impl HttpProxy {
                             let tls_cfg = self.config.tls_server_config.clone();
                             let locations = self.locations.clone();
                             let quic_enabled = self.config.quic_enabled;
+                            let upstream_addr = self.config.upstream_addr.clone();
+                            let upstream_protocol = self.config.upstream_protocol;
+                            let carbon_router = self.carbon_router.clone();
+                            let breakers = self.breakers.clone();
+                            let energy_budget = self.config.energy_budget.clone();
+                            let routes = self.routes.clone();
+                            let cors = self.cors.clone();
+                            let conn_builder = conn_builder.clone();
+                            let guard = self.lifecycle.clone().map(crate::lifecycle::ConnectionGuard::new);
 
                             tokio::spawn(async move {
-                                debug!("📥 HTTP/2 connection from {}", peer_addr);
+                                let _guard = guard;
+                                debug!("📥 HTTP connection from {}", peer_addr);
 
                                 let acme_manager_svc = acme_manager.clone();
                                 let locations_svc = locations.clone();
-                                let service = service_fn(move |req| {
+                                // Built once the transport is known, so every request
+                                // carries the connection's security details
+                                let service = move |connection: ConnectionInfo| service_fn(move |mut req: Request<hyper::body::Incoming>| {
+                                    req.extensions_mut().insert(connection.clone());
+                                    req.extensions_mut().insert(breakers.clone());
                                     let upstream = upstream.clone();
+                                    let upstream_addr = upstream_addr.clone();
+                                    let carbon_router = carbon_router.clone();
                                     let static_server = static_server.clone();
                                     let memory_cache = memory_cache.clone();
                                     let ttl_config = ttl_config.clone();
                                     let bypass_check = bypass_check.clone();
                                     let acme_manager_req = acme_manager_svc.clone();
                                     let locations_req = locations_svc.clone();
-                                    async move { handle_request(req, &upstream, static_server, memory_cache, ttl_config, bypass_check, acme_manager_req, locations_req, quic_enabled).await }
+                                    let energy_budget = energy_budget.clone();
+                                    let routes = routes.clone();
+                                    let cors = cors.clone();
+                                    async move {
+                                        // Energy budget load shedding
+                                        if let Some(response) = energy_budget.as_ref().and_then(|b| b.check_request()) {
+                                            return Ok(response.map(|b| b.map_err(|never| match never {}).boxed()));
+                                        }
+                                        let start = Instant::now();
+                                        let method = req.method().clone();
+                                        let path = req.uri().path().to_string();
+                                        // CORS preflight and origin check
+                                        if let Some(response) = cors.as_ref().and_then(|c| c.check_request(method.as_str(), req.headers())) {
+                                            return Ok(response.map(|b| b.map_err(|never| match never {}).boxed()));
+                                        }
+                                        let cors_headers = cors.as_ref().map(|c| c.response_headers(method.as_str(), req.headers())).unwrap_or_default();
+                                        // Per-route method allowlist and timeout
+                                        if let Some(response) = routes.check_request(method.as_str(), &path) {
+                                            return Ok(response.map(|b| b.map_err(|never| match never {}).boxed()));
+                                        }
+                                        if let Some(response) = routes.check_connection(&path, ConnectionInfo::of(&req)) {
+                                            return Ok(response.map(|b| b.map_err(|never| match never {}).boxed()));
+                                        }
+                                        let upstream = match &carbon_router {
+                                            Some(router) => upstream_protocol.target(&router.select_upstream(&upstream_addr).await),
+                                            None => upstream,
+                                        };
+                                        let handled = handle_request(req, &upstream, static_server, memory_cache, ttl_config, bypass_check, acme_manager_req, locations_req, quic_enabled);
+                                        let result = match routes.timeout_for(&path) {
+                                            Some(timeout) => match tokio::time::timeout(timeout, handled).await {
+                                                Ok(result) => result,
+                                                Err(_) => {
+                                                    warn!("⏱️ {} {} timed out after {:?}", method, path, timeout);
+                                                    metrics::record_error("request_timeout");
+                                                    Ok(crate::route::gateway_timeout_response().map(|b| b.map_err(|never| match never {}).boxed()))
+                                                }
+                                            },
+                                            None => handled.await,
+                                        };
+                                        if let Some(budget) = &energy_budget {
+                                            budget.record_request(&path, method.as_str(), start.elapsed());
+                                        }
+                                        result.map(|mut response| {
+                                            crate::cors::apply_headers(&mut response, cors_headers);
+                                            response
+                                        })
+                                    }
                                 });
 
                                 if let Some(config) = tls_cfg {
@@ -223,12 +374,10 @@ impl HttpProxy {
                                             // Proceed with TLS handshake using the populated cert cache
                                             match start_handshake.into_stream(config).await {
                                                 Ok(tls_stream) => {
+                                                    let connection = ConnectionInfo::from_tls(tls_stream.get_ref().1);
                                                     let io = TokioIo::new(tls_stream);
-                                                    if let Err(e) = http1::Builder::new()
-                                                        .serve_connection(io, service)
-                                                        .await
-                                                    {
-                                                        error!("❌ HTTP/1.1 TLS connection error: {}", e);
+                                                    if let Err(e) = conn_builder.serve_connection(io, service(connection)).await {
+                                                        error!("❌ HTTP TLS connection error: {}", e);
                                                     }
                                                 }
                                                 Err(e) => {
@@ -242,11 +391,8 @@ impl HttpProxy {
                                     }
                                 } else {
                                     let io = TokioIo::new(stream);
-                                    if let Err(e) = http1::Builder::new()
-                                        .serve_connection(io, service)
-                                        .await
-                                    {
-                                        error!("❌ HTTP/1.1 connection error: {}", e);
+                                    if let Err(e) = conn_builder.serve_connection(io, service(ConnectionInfo::plaintext())).await {
+                                        error!("❌ HTTP connection error: {}", e);
                                     }
                                 }
                             });
@@ -264,6 +410,26 @@ impl HttpProxy {
         }
         Ok(())
     }
+
+    /// Connection builder serving HTTP/1.1 and HTTP/2 on the same listener
+    ///
+    /// The protocol is picked per connection from the HTTP/2 preface, so
+    /// HTTP/1.1 clients and HTTP/2 clients (prior knowledge or ALPN `h2`)
+    /// can share a port. Both protocols answer oversized headers with 431;
+    /// HTTP/1.1 cannot buffer less than 8 KiB, so smaller limits round up.
+    fn connection_builder(&self) -> auto::Builder<TokioExecutor> {
+        let max_header_bytes = self.config.max_header_bytes;
+        let mut builder = auto::Builder::new(TokioExecutor);
+        builder
+            .http1()
+            .max_buf_size(max_header_bytes.max(8192));
+        builder
+            .http2()
+            .max_concurrent_streams(self.config.max_concurrent_streams)
+            .initial_stream_window_size(self.config.initial_window_size)
+            .max_header_list_size(u32::try_from(max_header_bytes).unwrap_or(u32::MAX));
+        builder
+    }
 }
 
 use http_body_util::combinators::BoxBody;
@@ -297,13 +463,32 @@ where
     let start = std::time::Instant::now();
     let method = req.method().clone();
     let uri = req.uri().clone();
-    let headers = req.headers().clone();
+    let mut headers = req.headers().clone();
+    let encrypted = ConnectionInfo::of(&req).map(|c| c.encrypted);
+    // Attached by the accepting server; requests without it are unguarded
+    let breakers = req
+        .extensions()
+        .get::<std::sync::Arc<crate::circuit_breaker::UpstreamBreakers>>()
+        .cloned();
+
+    // The listener knows the transport; a client-sent value is not trusted
+    if let Some(connection) = ConnectionInfo::of(&req) {
+        headers.insert(
+            "x-forwarded-proto",
+            hyper::header::HeaderValue::from_static(connection.forwarded_proto()),
+        );
+    }
 
     // Extract OpenTelemetry context (Trace Context + Baggage)
     let parent_cx = opentelemetry::global::get_text_map_propagator(|propagator| {
         propagator.extract(&HeaderExtractor(&headers))
     });
-    let span = tracing::info_span!("http_request", method = %method, path = %uri.path());
+    let span = tracing::info_span!(
+        "http_request",
+        method = %method,
+        path = %uri.path(),
+        encrypted = ?encrypted
+    );
     span.set_parent(parent_cx);
     let _enter = span.enter();
 
@@ -430,26 +615,31 @@ where
         }
     }
 
+    let builtin = is_builtin_path(uri.path());
     if method == Method::OPTIONS {
-        return Ok(build_cors_preflight().map(|b| b.map_err(|never| match never {}).boxed()));
+        let preflight = if builtin {
+            build_builtin_preflight()
+        } else {
+            build_cors_preflight()
+        };
+        return Ok(preflight.map(|b| b.map_err(|never| match never {}).boxed()));
     }
+    let builtin = builtin && (method == Method::GET || method == Method::HEAD);
 
     // Handle built-in endpoints
-    let response: Response<BoxBody<Bytes, BoxError>> = if uri.path() == "/health"
-        && method == Method::GET
-    {
+    let response: Response<BoxBody<Bytes, BoxError>> = if builtin && uri.path() == "/health" {
         Response::builder()
             .status(StatusCode::OK)
             .header("Access-Control-Allow-Origin", "*")
             .body(full(Bytes::from("OK")))
             .unwrap()
-    } else if uri.path() == "/ready" && method == Method::GET {
+    } else if builtin && uri.path() == "/ready" {
         Response::builder()
             .status(StatusCode::OK)
             .header("Access-Control-Allow-Origin", "*")
             .body(full(Bytes::from("{\"status\":\"ready\"}")))
             .unwrap()
-    } else if uri.path() == "/metrics" && method == Method::GET {
+    } else if builtin && uri.path() == "/metrics" {
         let body = if let Some(handle) = metrics::get_metrics_handle() {
             handle.render()
         } else {
@@ -513,7 +703,15 @@ where
         }
 
         // --- Forward request to upstream ---
-        let res = forward_to_upstream(upstream, &method, &uri, &headers, body_bytes).await;
+        let res = forward_to_upstream(
+            upstream,
+            breakers.as_deref(),
+            &method,
+            &uri,
+            &headers,
+            body_bytes,
+        )
+        .await;
 
         let is_sse = res.headers().get("content-type").map_or(false, |v| {
             v.to_str().unwrap_or("").contains("text/event-stream")
@@ -590,6 +788,11 @@ where
         );
         Response::from_parts(parts, full(body_bytes_resp))
     };
+    let response = if builtin && method == Method::HEAD {
+        head_response(response)
+    } else {
+        response
+    };
 
     // Record metrics
     let status_code = response.status().as_u16();
@@ -600,7 +803,7 @@ where
     // Energy estimation (simplified model)
     let estimated_bytes = 1024.0;
     let energy_j = (estimated_bytes * 0.5e-9) + 0.01;
-    let carbon_g = energy_j / 3.6e6 * 150.0;
+    let carbon_g = aegis_energy::grams_for_joules(energy_j, 150.0);
 
     metrics::record_energy_impact(energy_j, carbon_g, "unknown");
 
@@ -636,9 +839,42 @@ fn build_cors_preflight() -> Response<Full<Bytes>> {
         .unwrap()
 }
 
+/// Paths answered by the proxy itself
+const BUILTIN_PATHS: [&str; 3] = ["/health", "/ready", "/metrics"];
+
+/// Methods accepted on the built-in endpoints
+const BUILTIN_METHODS: &str = "GET, HEAD, OPTIONS";
+
+fn is_builtin_path(path: &str) -> bool {
+    BUILTIN_PATHS.contains(&path)
+}
+
+/// Build the preflight response for a built-in endpoint
+fn build_builtin_preflight() -> Response<Full<Bytes>> {
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(hyper::header::ALLOW, BUILTIN_METHODS)
+        .header("Access-Control-Allow-Origin", "*")
+        .header("Access-Control-Allow-Methods", BUILTIN_METHODS)
+        .body(Full::new(Bytes::new()))
+        .unwrap()
+}
+
+/// Turn a `GET` response into its `HEAD` counterpart: same headers, no body
+fn head_response(
+    response: Response<BoxBody<Bytes, BoxError>>,
+) -> Response<BoxBody<Bytes, BoxError>> {
+    let (mut parts, body) = response.into_parts();
+    if let Some(len) = hyper::body::Body::size_hint(&body).exact() {
+        parts.headers.insert(hyper::header::CONTENT_LENGTH, len.into());
+    }
+    Response::from_parts(parts, full(Bytes::new()))
+}
+
 /// Forward request to upstream server
 async fn forward_to_upstream(
     upstream: &str,
+    breakers: Option<&crate::circuit_breaker::UpstreamBreakers>,
     method: &Method,
     uri: &hyper::Uri,
     headers: &hyper::HeaderMap,
@@ -700,33 +936,56 @@ async fn forward_to_upstream(
     }
 
     // --- HTTP / gRPC Forwarding ---
-    let mut is_grpc = false;
-    let url_scheme = if upstream.starts_with("grpc://") {
-        is_grpc = true;
-        "http://" // Reqwest handles grpc over http2
+    use crate::upstream_client::{UpstreamClient, UpstreamProtocol};
+
+    // Fail fast while the upstream's circuit breaker is open
+    let permit = match breakers.map(|b| b.acquire(upstream)) {
+        Some(None) => {
+            debug!("⚡ Circuit open for {}, rejecting request", upstream);
+            crate::metrics::record_error("circuit_open");
+            return build_error_response(StatusCode::SERVICE_UNAVAILABLE, "Upstream circuit open")
+                .map(|b| b.map_err(|never| match never {}).boxed());
+        }
+        Some(permit) => permit,
+        None => None,
+    };
+
+    let is_grpc = upstream.starts_with("grpc://");
+    let (protocol, host_addr) = if let Some((protocol, addr)) =
+        UpstreamProtocol::parse_target(upstream)
+    {
+        // Protocol selected through `upstream_protocol`
+        (Some(protocol), addr)
+    } else if let Some(addr) = upstream.strip_prefix("grpc://") {
+        (Some(UpstreamProtocol::H2c), addr) // Reqwest handles grpc over http2
     } else if upstream.starts_with("https://") {
-        "https://"
+        (None, upstream)
     } else {
-        "http://"
+        (
+            Some(UpstreamProtocol::H1),
+            upstream.trim_start_matches("http://"),
+        )
     };
 
-    let host_addr = upstream
-        .trim_start_matches("http://")
-        .trim_start_matches("https://")
-        .trim_start_matches("grpc://");
-    let upstream_url = format!("{}{}{}", url_scheme, host_addr, path_and_query);
+    // Plaintext and explicitly configured upstreams share pooled clients
+    let (client, upstream_url) = match protocol.map(UpstreamClient::shared) {
+        Some(Ok(client)) => {
+            let url = client.url(host_addr, path_and_query);
+            (client.client().clone(), url)
+        }
+        Some(Err(e)) => {
+            error!("❌ Upstream error: {}", e);
+            return build_error_response(StatusCode::BAD_GATEWAY, &e.to_string())
+                .map(|b| b.map_err(|never| match never {}).boxed());
+        }
+        None => (
+            reqwest::Client::new(),
+            format!("{}{}", host_addr, path_and_query),
+        ),
+    };
 
     debug!("🔄 Forwarding to: {}", upstream_url);
 
-    let client = if is_grpc {
-        ClientBuilder::new()
-            .http2_prior_knowledge()
-            .build()
-            .unwrap_or_else(|_| reqwest::Client::new())
-    } else {
-        reqwest::Client::new()
-    };
-
     // Build upstream request
     let reqwest_method =
         reqwest::Method::from_bytes(method.as_str().as_bytes()).unwrap_or(reqwest::Method::GET);
@@ -763,8 +1022,38 @@ async fn forward_to_upstream(
         upstream_req = upstream_req.header(name, value);
     }
 
+    // A pooled connection may have been closed by the upstream since its last
+    // use; idempotent requests get one more attempt on a fresh connection
+    let retry = if method.is_idempotent() {
+        upstream_req.try_clone()
+    } else {
+        None
+    };
+
     // Send request and get response
-    let result: Result<reqwest::Response, reqwest::Error> = upstream_req.send().await;
+    let sent_at = Instant::now();
+    let mut result: Result<reqwest::Response, reqwest::Error> = upstream_req.send().await;
+    if let Err(e) = &result
+        && crate::upstream_client::is_stale_connection_error(e)
+        && let Some(retry) = retry
+    {
+        warn!(
+            "♻️ Upstream connection for {} went away ({}), retrying on a fresh connection",
+            upstream_url, e
+        );
+        crate::metrics::record_error("upstream_stale_connection");
+        result = retry.send().await;
+    }
+    if result.is_ok() {
+        crate::metrics::record_upstream_duration(upstream, sent_at.elapsed().as_secs_f64());
+    }
+
+    if let Some(permit) = permit {
+        match &result {
+            Ok(resp) if !resp.status().is_server_error() => permit.success(),
+            _ => permit.failure(),
+        }
+    }
 
     match result {
         Ok(resp) => {
@@ -791,6 +1080,13 @@ async fn forward_to_upstream(
             info!("✅ Forwarded {} {} -> {}", method, uri.path(), resp_status);
             builder.body(box_body).unwrap()
         }
+        Err(e) if e.is_connect() => {
+            let err = crate::error::ProxyError::UpstreamUnreachable(format!("{}: {}", upstream, e));
+            error!("❌ {}", err);
+            crate::metrics::record_error("upstream_unreachable");
+            build_error_response(StatusCode::BAD_GATEWAY, &err.to_string())
+                .map(|b| b.map_err(|never| match never {}).boxed())
+        }
         Err(e) => {
             error!("❌ Upstream error: {}", e);
             build_error_response(StatusCode::BAD_GATEWAY, &format!("Upstream error: {}", e))
@@ -1268,6 +1564,65 @@ mod tests {
         assert_eq!(resp.status(), StatusCode::BAD_GATEWAY);
     }
 
+    #[tokio::test]
+    async fn test_head_health_has_headers_but_no_body() {
+        use http_body_util::Empty;
+        let req = Request::builder()
+            .method(Method::HEAD)
+            .uri("/health")
+            .body(Empty::<Bytes>::new())
+            .unwrap();
+
+        let resp = handle_request(
+            req,
+            "upstream",
+            None,
+            None,
+            std::sync::Arc::new(crate::proxy_cache::TtlConfig::new(60)),
+            std::sync::Arc::new(crate::proxy_cache::BypassCheck::default()),
+            None,
+            std::sync::Arc::new(vec![]),
+            false,
+        )
+        .await
+        .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers()["Access-Control-Allow-Origin"], "*");
+        assert_eq!(resp.headers()[hyper::header::CONTENT_LENGTH], "2");
+        let body = resp.into_body().collect().await.unwrap().to_bytes();
+        assert!(body.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_options_metrics_lists_allowed_methods() {
+        use http_body_util::Empty;
+        let req = Request::builder()
+            .method(Method::OPTIONS)
+            .uri("/metrics")
+            .body(Empty::<Bytes>::new())
+            .unwrap();
+
+        let resp = handle_request(
+            req,
+            "upstream",
+            None,
+            None,
+            std::sync::Arc::new(crate::proxy_cache::TtlConfig::new(60)),
+            std::sync::Arc::new(crate::proxy_cache::BypassCheck::default()),
+            None,
+            std::sync::Arc::new(vec![]),
+            false,
+        )
+        .await
+        .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers()[hyper::header::ALLOW], "GET, HEAD, OPTIONS");
+        assert_eq!(
+            resp.headers()["Access-Control-Allow-Methods"],
+            "GET, HEAD, OPTIONS"
+        );
+    }
+
     #[test]
     fn test_proxy_config_debug() {
         let config = HttpProxyConfig::default();
@@ -1456,6 +1811,37 @@ mod tests {
         assert_eq!(json["error"], "proxy_error");
     }
 
+    #[tokio::test]
+    async fn test_refused_upstream_reports_unreachable() {
+        let closed = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let upstream = closed.local_addr().unwrap().to_string();
+        drop(closed);
+
+        let req = Request::builder()
+            .uri("/some/api")
+            .body(Full::new(Bytes::new()))
+            .unwrap();
+        let resp = handle_request(
+            req,
+            &upstream,
+            None,
+            None,
+            std::sync::Arc::new(crate::proxy_cache::TtlConfig::new(60)),
+            std::sync::Arc::new(crate::proxy_cache::BypassCheck::default()),
+            None,
+            std::sync::Arc::new(vec![]),
+            false,
+        )
+        .await
+        .unwrap();
+        assert_eq!(resp.status(), StatusCode::BAD_GATEWAY);
+        let body = resp.into_body().collect().await.unwrap().to_bytes();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let message = json["message"].as_str().unwrap();
+        assert!(message.starts_with("Upstream unreachable: "), "{message}");
+        assert!(message.contains(&upstream), "{message}");
+    }
+
     #[tokio::test]
     async fn test_handle_request_exhaustive_methods() {
         use http_body_util::Empty;
@@ -1589,6 +1975,661 @@ mod tests {
         tx.send(()).unwrap();
     }
 
+    #[tokio::test]
+    async fn test_proxy_serves_http1_and_http2_on_same_port() {
+        use http_body_util::Empty;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let proxy = HttpProxy::new(HttpProxyConfig {
+            listen_addr: addr,
+            ..Default::default()
+        });
+
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        tokio::spawn(async move {
+            proxy
+                .run_with_listener(listener, async {
+                    rx.await.ok();
+                })
+                .await
+                .ok();
+        });
+
+        let uri: hyper::Uri = format!("http://{}/health", addr).parse().unwrap();
+
+        let http1_client =
+            hyper_util::client::legacy::Client::builder(hyper_util::rt::TokioExecutor::new())
+                .build_http::<Empty<Bytes>>();
+        let res = http1_client.get(uri.clone()).await.unwrap();
+        assert_eq!(res.version(), hyper::Version::HTTP_11);
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(&res.into_body().collect().await.unwrap().to_bytes()[..], b"OK");
+
+        // HTTP/2 with prior knowledge, as `curl --http2-prior-knowledge` does
+        let http2_client =
+            hyper_util::client::legacy::Client::builder(hyper_util::rt::TokioExecutor::new())
+                .http2_only(true)
+                .build_http::<Empty<Bytes>>();
+        let res = http2_client.get(uri).await.unwrap();
+        assert_eq!(res.version(), hyper::Version::HTTP_2);
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(&res.into_body().collect().await.unwrap().to_bytes()[..], b"OK");
+
+        tx.send(()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_proxy_rejects_oversized_headers() {
+        use http_body_util::Empty;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let proxy = HttpProxy::new(HttpProxyConfig {
+            listen_addr: addr,
+            max_header_bytes: 16 * 1024,
+            ..Default::default()
+        });
+
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        tokio::spawn(async move {
+            proxy
+                .run_with_listener(listener, async {
+                    rx.await.ok();
+                })
+                .await
+                .ok();
+        });
+
+        let oversized = "a".repeat(32 * 1024);
+        for http2_only in [false, true] {
+            let client =
+                hyper_util::client::legacy::Client::builder(hyper_util::rt::TokioExecutor::new())
+                    .http2_only(http2_only)
+                    .build_http::<Empty<Bytes>>();
+            let request = |value: &str| {
+                Request::get(format!("http://{}/health", addr))
+                    .header("x-big", value)
+                    .body(Empty::new())
+                    .unwrap()
+            };
+
+            let res = client.request(request(&oversized)).await.unwrap();
+            assert_eq!(
+                res.status(),
+                StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE,
+                "http2_only={}",
+                http2_only
+            );
+
+            let res = client.request(request("small")).await.unwrap();
+            assert_eq!(res.status(), StatusCode::OK, "http2_only={}", http2_only);
+        }
+
+        tx.send(()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_proxy_applies_cors_policy() {
+        use http_body_util::Empty;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let proxy = HttpProxy::new(HttpProxyConfig {
+            listen_addr: addr,
+            cors: Some(
+                crate::cors::CorsConfig::new(&["https://dashboard.example"])
+                    .with_credentials(true)
+                    .with_max_age(600),
+            ),
+            ..Default::default()
+        });
+
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        tokio::spawn(async move {
+            proxy
+                .run_with_listener(listener, async {
+                    rx.await.ok();
+                })
+                .await
+                .ok();
+        });
+
+        let client =
+            hyper_util::client::legacy::Client::builder(hyper_util::rt::TokioExecutor::new())
+                .build_http::<Empty<Bytes>>();
+
+        let preflight = Request::builder()
+            .method(Method::OPTIONS)
+            .uri(format!("http://{}/metrics", addr))
+            .header("origin", "https://dashboard.example")
+            .header("access-control-request-method", "GET")
+            .body(Empty::new())
+            .unwrap();
+        let res = client.request(preflight).await.unwrap();
+        assert_eq!(res.status(), StatusCode::NO_CONTENT);
+        let headers = res.headers();
+        assert_eq!(
+            headers["access-control-allow-origin"],
+            "https://dashboard.example"
+        );
+        assert_eq!(
+            headers["access-control-allow-methods"],
+            "GET, HEAD, OPTIONS"
+        );
+        assert_eq!(headers["access-control-allow-credentials"], "true");
+        assert_eq!(headers["access-control-max-age"], "600");
+
+        let request = |origin: &str| {
+            Request::get(format!("http://{}/health", addr))
+                .header("origin", origin)
+                .body(Empty::new())
+                .unwrap()
+        };
+        let res = client
+            .request(request("https://dashboard.example"))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(
+            res.headers()["access-control-allow-origin"],
+            "https://dashboard.example"
+        );
+        assert_eq!(res.headers()["vary"], "Origin");
+
+        let res = client.request(request("https://evil.example")).await.unwrap();
+        assert_eq!(res.status(), StatusCode::FORBIDDEN);
+
+        tx.send(()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_proxy_enforces_route_methods_and_timeouts() {
+        use http_body_util::Empty;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // Upstream answering every request after 300ms
+        let upstream = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let upstream_addr = upstream.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = upstream.accept().await {
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 4096];
+                    let _ = stream.read(&mut buf).await;
+                    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+                    let _ = stream
+                        .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 2\r\nconnection: close\r\n\r\nok")
+                        .await;
+                });
+            }
+        });
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let proxy = HttpProxy::new(HttpProxyConfig {
+            listen_addr: addr,
+            upstream_addr: upstream_addr.to_string(),
+            request_timeout: Some(std::time::Duration::from_secs(5)),
+            routes: vec![
+                crate::route::RouteConfig::new("/reports")
+                    .with_allowed_methods(&["GET", "HEAD"])
+                    .with_timeout(std::time::Duration::from_millis(50)),
+            ],
+            ..Default::default()
+        });
+
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        tokio::spawn(async move {
+            proxy
+                .run_with_listener(listener, async {
+                    rx.await.ok();
+                })
+                .await
+                .ok();
+        });
+
+        let client =
+            hyper_util::client::legacy::Client::builder(hyper_util::rt::TokioExecutor::new())
+                .build_http::<Empty<Bytes>>();
+        let request = |method: Method, path: &str| {
+            Request::builder()
+                .method(method)
+                .uri(format!("http://{}{}", addr, path))
+                .body(Empty::new())
+                .unwrap()
+        };
+
+        let res = client
+            .request(request(Method::POST, "/reports/daily"))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(res.headers()[hyper::header::ALLOW], "GET, HEAD");
+
+        // The route's 50ms timeout fires well before the global 5s one
+        let started = std::time::Instant::now();
+        let res = client
+            .request(request(Method::GET, "/reports/daily"))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::GATEWAY_TIMEOUT);
+        assert!(started.elapsed() < std::time::Duration::from_millis(300));
+
+        // Other paths only see the global timeout and reach the upstream
+        let res = client.request(request(Method::GET, "/other")).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        tx.send(()).unwrap();
+    }
+
+    /// Upstream echoing the HTTP version, path and body it received
+    async fn spawn_echo_upstream(http2: bool) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let service = hyper::service::service_fn(|req: Request<hyper::body::Incoming>| async move {
+                    let version = format!("{:?}", req.version());
+                    let path = req.uri().path().to_string();
+                    let body = req.into_body().collect().await.unwrap().to_bytes();
+                    let reply = format!("{} {} {}", version, path, String::from_utf8_lossy(&body));
+                    Ok::<_, hyper::Error>(Response::new(Full::new(Bytes::from(reply))))
+                });
+                let io = TokioIo::new(stream);
+                tokio::spawn(async move {
+                    if http2 {
+                        let _ = hyper::server::conn::http2::Builder::new(TokioExecutor)
+                            .serve_connection(io, service)
+                            .await;
+                    } else {
+                        let _ = hyper::server::conn::http1::Builder::new()
+                            .serve_connection(io, service)
+                            .await;
+                    }
+                });
+            }
+        });
+        addr
+    }
+
+    #[tokio::test]
+    async fn test_proxy_forwards_with_configured_upstream_protocol() {
+        use crate::upstream_client::UpstreamProtocol;
+
+        for (protocol, http2, version) in [
+            (UpstreamProtocol::H2c, true, "HTTP/2.0"),
+            (UpstreamProtocol::H1, false, "HTTP/1.1"),
+        ] {
+            let upstream_addr = spawn_echo_upstream(http2).await;
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = listener.local_addr().unwrap();
+            let proxy = HttpProxy::new(HttpProxyConfig {
+                listen_addr: addr,
+                upstream_addr: upstream_addr.to_string(),
+                upstream_protocol: protocol,
+                ..Default::default()
+            });
+
+            let (tx, rx) = tokio::sync::oneshot::channel();
+            tokio::spawn(async move {
+                proxy
+                    .run_with_listener(listener, async {
+                        rx.await.ok();
+                    })
+                    .await
+                    .ok();
+            });
+
+            let client =
+                hyper_util::client::legacy::Client::builder(hyper_util::rt::TokioExecutor::new())
+                    .build_http::<Full<Bytes>>();
+            let req = Request::builder()
+                .method(Method::POST)
+                .uri(format!("http://{}/echo", addr))
+                .body(Full::new(Bytes::from("ping")))
+                .unwrap();
+            let res = client.request(req).await.unwrap();
+            assert_eq!(res.status(), StatusCode::OK, "{:?}", protocol);
+            let body = res.into_body().collect().await.unwrap().to_bytes();
+            assert_eq!(body, format!("{} /echo ping", version));
+
+            tx.send(()).unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stale_upstream_connection_is_retried() {
+        use http_body_util::Empty;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // Upstream answering the first request on each connection with
+        // keep-alive, then closing it when the next request arrives
+        let upstream = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let upstream_addr = upstream.local_addr().unwrap();
+        let connections = std::sync::Arc::new(AtomicUsize::new(0));
+        let accepted = connections.clone();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = upstream.accept().await {
+                accepted.fetch_add(1, Ordering::SeqCst);
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 4096];
+                    if stream.read(&mut buf).await.unwrap_or(0) == 0 {
+                        return;
+                    }
+                    let _ = stream
+                        .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 2\r\n\r\nok")
+                        .await;
+                    let _ = stream.read(&mut buf).await;
+                });
+            }
+        });
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let proxy = HttpProxy::new(HttpProxyConfig {
+            listen_addr: addr,
+            upstream_addr: upstream_addr.to_string(),
+            ..Default::default()
+        });
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        tokio::spawn(async move {
+            proxy
+                .run_with_listener(listener, async {
+                    rx.await.ok();
+                })
+                .await
+                .ok();
+        });
+
+        let client =
+            hyper_util::client::legacy::Client::builder(hyper_util::rt::TokioExecutor::new())
+                .build_http::<Empty<Bytes>>();
+        let request = |method: Method, path: &str| {
+            Request::builder()
+                .method(method)
+                .uri(format!("http://{}{}", addr, path))
+                .body(Empty::new())
+                .unwrap()
+        };
+
+        let res = client.request(request(Method::GET, "/a")).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(connections.load(Ordering::SeqCst), 1);
+
+        // The pooled connection dies under this request; it is retried on a new one
+        let res = client.request(request(Method::GET, "/b")).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let body = res.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(body, "ok");
+        assert_eq!(connections.load(Ordering::SeqCst), 2);
+
+        // Non-idempotent requests are not replayed
+        let res = client.request(request(Method::POST, "/c")).await.unwrap();
+        assert_eq!(res.status(), StatusCode::BAD_GATEWAY);
+        assert_eq!(connections.load(Ordering::SeqCst), 2);
+
+        tx.send(()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_fails_fast_and_recovers() {
+        use crate::circuit_breaker::CircuitState;
+        use http_body_util::Empty;
+        use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        crate::metrics::init_metrics();
+
+        // Upstream failing with 500 until it is marked healthy
+        let upstream = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let upstream_addr = upstream.local_addr().unwrap().to_string();
+        let healthy = std::sync::Arc::new(AtomicBool::new(false));
+        let hits = std::sync::Arc::new(AtomicUsize::new(0));
+        let (upstream_healthy, upstream_hits) = (healthy.clone(), hits.clone());
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = upstream.accept().await {
+                let healthy = upstream_healthy.clone();
+                let hits = upstream_hits.clone();
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 4096];
+                    while stream.read(&mut buf).await.unwrap_or(0) > 0 {
+                        hits.fetch_add(1, Ordering::SeqCst);
+                        let reply: &[u8] = if healthy.load(Ordering::SeqCst) {
+                            b"HTTP/1.1 200 OK\r\ncontent-length: 2\r\n\r\nok"
+                        } else {
+                            b"HTTP/1.1 500 Internal Server Error\r\ncontent-length: 0\r\n\r\n"
+                        };
+                        if stream.write_all(reply).await.is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let proxy = HttpProxy::new(HttpProxyConfig {
+            listen_addr: addr,
+            upstream_addr: upstream_addr.clone(),
+            circuit_breaker: Some(crate::upstream::CircuitBreakerConfig {
+                error_threshold_percent: 50,
+                window_size_ms: 10_000,
+                open_time_ms: 200,
+            }),
+            ..Default::default()
+        });
+        let breakers = proxy.breakers().clone();
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        tokio::spawn(async move {
+            proxy
+                .run_with_listener(listener, async {
+                    rx.await.ok();
+                })
+                .await
+                .ok();
+        });
+
+        let client =
+            hyper_util::client::legacy::Client::builder(hyper_util::rt::TokioExecutor::new())
+                .build_http::<Empty<Bytes>>();
+        let get = || {
+            client.request(
+                Request::builder()
+                    .uri(format!("http://{}/api", addr))
+                    .body(Empty::new())
+                    .unwrap(),
+            )
+        };
+
+        // Enough failures to trip the breaker
+        for _ in 0..6 {
+            assert_eq!(
+                get().await.unwrap().status(),
+                StatusCode::INTERNAL_SERVER_ERROR
+            );
+        }
+        assert_eq!(breakers.state(&upstream_addr), Some(CircuitState::Open));
+        let rendered = crate::metrics::get_metrics_handle().unwrap().render();
+        assert!(rendered.contains(&format!(
+            "aegis_upstream_circuit_state{{upstream=\"{}\"}} 2",
+            upstream_addr
+        )));
+
+        // While open, requests fail fast without reaching the upstream
+        let before = hits.load(Ordering::SeqCst);
+        assert_eq!(get().await.unwrap().status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(hits.load(Ordering::SeqCst), before);
+
+        // After the open window a successful probe closes the breaker
+        healthy.store(true, Ordering::SeqCst);
+        tokio::time::sleep(std::time::Duration::from_millis(250)).await;
+        assert_eq!(get().await.unwrap().status(), StatusCode::OK);
+        assert_eq!(breakers.state(&upstream_addr), Some(CircuitState::Closed));
+        assert_eq!(get().await.unwrap().status(), StatusCode::OK);
+
+        tx.send(()).unwrap();
+    }
+
+    /// Upstream answering every request with its `x-forwarded-proto` header
+    async fn forwarded_proto_upstream() -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let upstream = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = upstream.local_addr().unwrap().to_string();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = upstream.accept().await {
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 4096];
+                    while let Ok(n) = stream.read(&mut buf).await {
+                        if n == 0 {
+                            break;
+                        }
+                        let head = String::from_utf8_lossy(&buf[..n]).to_lowercase();
+                        let proto = head
+                            .lines()
+                            .find_map(|line| line.strip_prefix("x-forwarded-proto: "))
+                            .unwrap_or("none")
+                            .trim()
+                            .to_string();
+                        let reply = format!(
+                            "HTTP/1.1 200 OK\r\ncontent-length: {}\r\n\r\n{}",
+                            proto.len(),
+                            proto
+                        );
+                        if stream.write_all(reply.as_bytes()).await.is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+        addr
+    }
+
+    #[tokio::test]
+    async fn test_plaintext_connection_info_enforced_and_forwarded() {
+        use http_body_util::Empty;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let proxy = HttpProxy::new(HttpProxyConfig {
+            listen_addr: addr,
+            upstream_addr: forwarded_proto_upstream().await,
+            routes: vec![crate::route::RouteConfig::new("/admin").with_require_encryption()],
+            ..Default::default()
+        });
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        tokio::spawn(async move {
+            proxy
+                .run_with_listener(listener, async {
+                    rx.await.ok();
+                })
+                .await
+                .ok();
+        });
+
+        let client =
+            hyper_util::client::legacy::Client::builder(hyper_util::rt::TokioExecutor::new())
+                .build_http::<Empty<Bytes>>();
+        let request = |path: &str| {
+            Request::builder()
+                .uri(format!("http://{}{}", addr, path))
+                .header("x-forwarded-proto", "https")
+                .body(Empty::new())
+                .unwrap()
+        };
+
+        let res = client.request(request("/admin/users")).await.unwrap();
+        assert_eq!(res.status(), StatusCode::FORBIDDEN);
+
+        // Other routes are proxied, with the spoofed protocol replaced
+        let res = client.request(request("/public")).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let body = res.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(&body[..], b"http");
+
+        tx.send(()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_upstream_duration_excludes_response_body() {
+        use http_body_util::{BodyExt, Empty};
+        use std::time::Duration;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        crate::metrics::init_metrics();
+
+        // Headers arrive after 200ms, the body another 300ms later
+        let upstream = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let upstream_addr = upstream.local_addr().unwrap().to_string();
+        tokio::spawn(async move {
+            let (mut stream, _) = upstream.accept().await.unwrap();
+            let mut buf = [0u8; 4096];
+            assert!(stream.read(&mut buf).await.unwrap() > 0);
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 4\r\n\r\n")
+                .await
+                .unwrap();
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            stream.write_all(b"done").await.unwrap();
+        });
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let proxy = HttpProxy::new(HttpProxyConfig {
+            listen_addr: addr,
+            upstream_addr: upstream_addr.clone(),
+            ..Default::default()
+        });
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        tokio::spawn(async move {
+            proxy
+                .run_with_listener(listener, async {
+                    rx.await.ok();
+                })
+                .await
+                .ok();
+        });
+
+        let client =
+            hyper_util::client::legacy::Client::builder(hyper_util::rt::TokioExecutor::new())
+                .build_http::<Empty<Bytes>>();
+        let started = Instant::now();
+        let res = client
+            .request(
+                Request::builder()
+                    .uri(format!("http://{}/slow", addr))
+                    .body(Empty::new())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let body = res.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(&body[..], b"done");
+        let total = started.elapsed().as_secs_f64();
+
+        let rendered = crate::metrics::get_metrics_handle().unwrap().render();
+        let prefix = format!(
+            "aegis_upstream_duration_seconds_sum{{upstream=\"{}\"}} ",
+            upstream_addr
+        );
+        let upstream_secs: f64 = rendered
+            .lines()
+            .find_map(|line| line.strip_prefix(prefix.as_str()))
+            .expect("upstream duration recorded")
+            .parse()
+            .unwrap();
+        assert!(upstream_secs >= 0.2, "upstream took {upstream_secs}s");
+        assert!(upstream_secs < 0.45, "upstream took {upstream_secs}s");
+        assert!(total >= 0.5, "request took {total}s");
+
+        tx.send(()).unwrap();
+    }
+
     #[tokio::test]
     async fn test_handle_request_force_https() {
         let req = Request::builder()
@@ -1622,6 +2663,219 @@ mod tests {
             panic!("Should have redirected");
         }
     }
+
+    #[tokio::test]
+    async fn test_shutdown_waits_for_proxied_connections() {
+        use crate::lifecycle::LifecycleManager;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // Upstream answering after a delay, so the request spans the shutdown
+        let upstream = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let upstream_addr = upstream.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = upstream.accept().await {
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 4096];
+                    if stream.read(&mut buf).await.unwrap_or(0) == 0 {
+                        return;
+                    }
+                    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+                    let _ = stream
+                        .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 4\r\n\r\nslow")
+                        .await;
+                });
+            }
+        });
+
+        let lifecycle = std::sync::Arc::new(
+            LifecycleManager::new().with_drain_timeout(std::time::Duration::from_secs(1)),
+        );
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let proxy = HttpProxy::new(HttpProxyConfig {
+            listen_addr: addr,
+            upstream_addr: upstream_addr.to_string(),
+            ..Default::default()
+        })
+        .with_lifecycle(lifecycle.clone());
+        let mut shutdown = lifecycle.shutdown_receiver();
+        tokio::spawn(async move {
+            proxy
+                .run_with_listener(listener, async move {
+                    shutdown.recv().await.ok();
+                })
+                .await
+                .ok();
+        });
+
+        // One connection finishes its request while draining, one stays idle
+        let mut busy = TcpStream::connect(addr).await.unwrap();
+        busy.write_all(b"GET /slow HTTP/1.1\r\nhost: proxy\r\nconnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let _idle = TcpStream::connect(addr).await.unwrap();
+        for _ in 0..100 {
+            if lifecycle.active_connections() == 2 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(lifecycle.active_connections(), 2);
+
+        let response = tokio::spawn(async move {
+            let mut response = Vec::new();
+            busy.read_to_end(&mut response).await.unwrap();
+            response
+        });
+        let report = lifecycle.initiate_shutdown().await.unwrap();
+        assert_eq!(report.drained, 1);
+        assert!(report.forced);
+        assert_eq!(report.remaining_connections, 1);
+
+        let response = String::from_utf8(response.await.unwrap()).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+        assert!(response.ends_with("slow"), "{response}");
+    }
+
+    /// Energy client reporting a fixed intensity per region
+    struct FixedIntensities(std::collections::HashMap<String, f64>);
+
+    impl aegis_energy::EnergyApiClient for FixedIntensities {
+        async fn get_carbon_intensity(
+            &self,
+            region: &aegis_energy::Region,
+        ) -> std::result::Result<aegis_energy::CarbonIntensity, aegis_energy::EnergyApiError>
+        {
+            Ok(aegis_energy::CarbonIntensity {
+                region: region.clone(),
+                value: self.0.get(&region.id).copied().unwrap_or(200.0),
+                timestamp: chrono::Utc::now(),
+                valid_for_seconds: 300,
+                rating: None,
+                renewable_percentage: None,
+                power_breakdown: None,
+            })
+        }
+
+        async fn get_carbon_intensity_by_location(
+            &self,
+            latitude: f64,
+            longitude: f64,
+        ) -> std::result::Result<aegis_energy::CarbonIntensity, aegis_energy::EnergyApiError>
+        {
+            let region = self.get_region_for_location(latitude, longitude).await?;
+            self.get_carbon_intensity(&region).await
+        }
+
+        async fn get_region_for_location(
+            &self,
+            _latitude: f64,
+            _longitude: f64,
+        ) -> std::result::Result<aegis_energy::Region, aegis_energy::EnergyApiError> {
+            Ok(aegis_energy::Region::new("unknown", "Unknown"))
+        }
+
+        async fn get_carbon_forecast(
+            &self,
+            _region: &aegis_energy::Region,
+            _hours: u32,
+        ) -> std::result::Result<Vec<aegis_energy::ForecastPoint>, aegis_energy::EnergyApiError>
+        {
+            Ok(Vec::new())
+        }
+    }
+
+    /// Upstream answering every request with `name`
+    async fn spawn_named_upstream(name: &'static str) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let service = hyper::service::service_fn(move |_req: Request<hyper::body::Incoming>| async move {
+                    Ok::<_, hyper::Error>(Response::new(Full::new(Bytes::from(name))))
+                });
+                tokio::spawn(async move {
+                    let _ = hyper::server::conn::http1::Builder::new()
+                        .serve_connection(TokioIo::new(stream), service)
+                        .await;
+                });
+            }
+        });
+        addr
+    }
+
+    #[tokio::test]
+    async fn test_carbon_router_picks_request_upstream() {
+        use crate::carbon_router::{CarbonRouter, CarbonRouterConfig};
+        use http_body_util::Empty;
+
+        crate::metrics::init_metrics();
+        let default_upstream = spawn_named_upstream("default").await;
+        let green_upstream = spawn_named_upstream("green").await;
+
+        for (dry_run, expected) in [(true, "default"), (false, "green")] {
+            let client: std::sync::Arc<dyn aegis_energy::DynEnergyApiClient> =
+                std::sync::Arc::new(FixedIntensities(std::collections::HashMap::from([
+                    ("us-west".to_string(), 50.0),
+                    ("us-east".to_string(), 350.0),
+                ])));
+            let router = CarbonRouter::new(
+                CarbonRouterConfig {
+                    enabled: true,
+                    dry_run,
+                    region_upstreams: std::collections::HashMap::from([(
+                        "us-west".to_string(),
+                        green_upstream.to_string(),
+                    )]),
+                    ..Default::default()
+                },
+                client,
+                aegis_energy::CarbonIntensityCache::new(300),
+            );
+            router
+                .register_region(aegis_energy::Region::new("us-west", "US West"))
+                .await;
+            router
+                .register_region(aegis_energy::Region::new("us-east", "US East"))
+                .await;
+            router.refresh_carbon_data().await.unwrap();
+
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = listener.local_addr().unwrap();
+            let proxy = HttpProxy::new(HttpProxyConfig {
+                listen_addr: addr,
+                upstream_addr: default_upstream.to_string(),
+                ..Default::default()
+            })
+            .with_carbon_router(std::sync::Arc::new(router));
+            let (tx, rx) = tokio::sync::oneshot::channel();
+            tokio::spawn(async move {
+                proxy
+                    .run_with_listener(listener, async {
+                        rx.await.ok();
+                    })
+                    .await
+                    .ok();
+            });
+
+            let client =
+                hyper_util::client::legacy::Client::builder(hyper_util::rt::TokioExecutor::new())
+                    .build_http::<Empty<Bytes>>();
+            let res = client
+                .request(
+                    Request::builder()
+                        .uri(format!("http://{}/where", addr))
+                        .body(Empty::new())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(res.status(), StatusCode::OK);
+            let body = res.into_body().collect().await.unwrap().to_bytes();
+            assert_eq!(&body[..], expected.as_bytes(), "dry_run={dry_run}");
+            tx.send(()).unwrap();
+        }
+    }
 }
 
 /// Runs a standalone HTTP server on port 80 that serves ACME challenges
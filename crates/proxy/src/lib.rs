@@ -7,6 +7,7 @@ pub mod access_log;
 pub mod acl;
 pub mod acme;
 pub mod admin_api;
+pub mod admission;
 pub mod auth;
 pub mod auth_request;
 pub mod autoindex;
@@ -17,9 +18,13 @@ pub mod circuit_breaker;
 pub mod compression;
 pub mod config;
 pub mod conn_limit;
+pub mod connection_info;
+pub mod cors;
 pub mod discovery;
 pub mod dns;
 pub mod dual_stack_server;
+pub mod energy_budget;
+pub mod error;
 pub mod fastcgi;
 pub mod geoip;
 pub mod green_wait;
@@ -35,6 +40,7 @@ pub mod lb;
 pub mod lifecycle;
 pub mod limit_except;
 pub mod limit_rate;
+pub mod listener;
 pub mod location;
 pub mod map_directive;
 pub mod master;
@@ -49,6 +55,7 @@ pub mod quic_server;
 pub mod ranges;
 pub mod rate_limit;
 pub mod rewrite;
+pub mod route;
 pub mod scgi;
 pub mod server;
 pub mod sni;
@@ -61,6 +68,8 @@ pub mod stream_proxy;
 pub mod stub_status;
 pub mod sub_filter;
 pub mod syslog;
+#[cfg(any(test, feature = "test-harness"))]
+pub mod test_harness;
 pub mod tracing_otel;
 pub mod udp_proxy;
 pub mod upstream;
@@ -74,19 +83,33 @@ pub mod websocket;
 pub mod xds;
 pub mod xslt;
 pub mod zero_copy;
-pub use carbon_router::{CarbonRouter, CarbonRouterConfig, RegionScore};
+pub use admission::{AdmissionConfig, AdmissionController, AdmissionDecision, DeferredRequest};
+pub use carbon_router::{
+    CarbonRouter, CarbonRouterConfig, CarbonRouterConfigBuilder, RegionScore, RegionSnapshot,
+    RouterState,
+};
 pub use config::{
-    ConfigError, ConfigFormat, ConfigManager, HealthConfig, LogConfig, ProxyConfig, TlsConfig,
+    ArrayMerge, ConfigError, ConfigFormat, ConfigManager, ConfigWatcher, EnergyBudgetConfig,
+    HealthConfig, ListenerConfig, LogConfig, ProxyConfig, TcpKeepaliveConfig, TlsConfig,
 };
-pub use discovery::{LoadBalanceStrategy, ServiceRegistry};
+pub use cors::CorsConfig;
+pub use discovery::{LoadBalanceStrategy, RegionRanker, ServiceRegistry};
 pub use dual_stack_server::{DualStackConfig, DualStackServer, DualStackStats};
+pub use energy_budget::EnergyBudget;
+pub use error::ProxyError;
 pub use green_wait::{
-    DeferredJob, GreenWaitConfig, GreenWaitScheduler, JobPriority, ScheduleResult,
+    DeferredJob, GreenWaitConfig, GreenWaitScheduler, JobPriority, PayloadCodec, PayloadError,
+    ScheduleResult, ShutdownMode,
 };
 pub use http_proxy::{HttpProxy, HttpProxyConfig};
-pub use http3_handler::{Http3Config, Http3Handler, Http3Request, Http3Response};
+pub use http3_handler::{
+    Http3Config, Http3Handler, Http3Request, Http3Response, RequestParseError,
+};
 pub use lifecycle::{
     ConnectionGuard, HealthResponse, HealthStatus, LifecycleManager, ShutdownReceiver,
+    ShutdownReport,
 };
 pub use pqc_server::PqcProxyServer;
 pub use quic_server::{QuicConfig, QuicServer, QuicStats};
+pub use route::{RouteConfig, RouteTable};
+pub use upstream_client::{UpstreamClient, UpstreamProtocol};
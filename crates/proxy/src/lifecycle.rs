@@ -5,8 +5,10 @@
 //! - SIGTERM/SIGINT signal handling
 //! - Connection draining
 //! - Readiness and liveness probes
+//! - Maintenance mode (not ready, still alive)
 //! - Startup probes
 
+use aegis_common::{SharedClock, SystemClock};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::time::{Duration, Instant};
@@ -27,6 +29,8 @@ pub enum HealthStatus {
     Draining,
     /// Service is unhealthy
     Unhealthy,
+    /// Taken out of rotation by an operator; keeps serving open connections
+    Maintenance,
 }
 
 impl HealthStatus {
@@ -37,6 +41,7 @@ impl HealthStatus {
             Self::Starting => 503,
             Self::Draining => 503,
             Self::Unhealthy => 503,
+            Self::Maintenance => 503,
         }
     }
 
@@ -58,6 +63,7 @@ impl std::fmt::Display for HealthStatus {
             Self::Starting => write!(f, "starting"),
             Self::Draining => write!(f, "draining"),
             Self::Unhealthy => write!(f, "unhealthy"),
+            Self::Maintenance => write!(f, "maintenance"),
         }
     }
 }
@@ -113,6 +119,19 @@ impl HealthResponse {
     }
 }
 
+/// Outcome of a graceful shutdown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShutdownReport {
+    /// Connections that closed on their own while draining
+    pub drained: u64,
+    /// Whether the drain timeout expired with connections still open
+    pub forced: bool,
+    /// Connections still open when shutdown finished
+    pub remaining_connections: u64,
+    /// Time spent waiting for connections to drain
+    pub drain_duration: Duration,
+}
+
 /// Lifecycle manager for graceful shutdown and health monitoring
 pub struct LifecycleManager {
     /// Current health status
@@ -127,23 +146,34 @@ pub struct LifecycleManager {
     shutting_down: Arc<AtomicBool>,
     /// Drain timeout
     drain_timeout: Duration,
+    /// Time source for uptime
+    clock: SharedClock,
 }
 
 impl LifecycleManager {
     /// Create a new lifecycle manager
     pub fn new() -> Self {
         let (shutdown_tx, _) = broadcast::channel(1);
+        let clock = SystemClock::shared();
 
         Self {
             status: Arc::new(tokio::sync::RwLock::new(HealthStatus::Starting)),
             shutdown_tx,
             active_connections: Arc::new(AtomicU64::new(0)),
-            start_time: Instant::now(),
+            start_time: clock.instant(),
             shutting_down: Arc::new(AtomicBool::new(false)),
             drain_timeout: Duration::from_secs(30),
+            clock,
         }
     }
 
+    /// Use a custom time source; uptime is measured from this call
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.start_time = clock.instant();
+        self.clock = clock;
+        self
+    }
+
     /// Create with custom drain timeout
     pub fn with_drain_timeout(mut self, timeout: Duration) -> Self {
         self.drain_timeout = timeout;
@@ -172,6 +202,36 @@ impl LifecycleManager {
         self.set_status(HealthStatus::Unhealthy).await;
     }
 
+    /// Enter or leave maintenance mode
+    ///
+    /// Maintenance fails readiness but not liveness, so orchestrators stop
+    /// routing new traffic without restarting the process. Only a ready
+    /// service can enter or leave it; returns `false` and leaves the status
+    /// alone while starting, unhealthy or draining.
+    pub async fn set_maintenance(&self, enabled: bool) -> bool {
+        let mut status = self.status.write().await;
+        let next = match *status {
+            HealthStatus::Healthy | HealthStatus::Maintenance if enabled => {
+                HealthStatus::Maintenance
+            }
+            HealthStatus::Healthy | HealthStatus::Maintenance => HealthStatus::Healthy,
+            current => {
+                warn!("Cannot change maintenance mode while {}", current);
+                return false;
+            }
+        };
+        if *status != next {
+            *status = next;
+            info!("Health status changed to: {}", next);
+        }
+        true
+    }
+
+    /// Whether the service is in maintenance mode
+    pub async fn is_in_maintenance(&self) -> bool {
+        self.health_status().await == HealthStatus::Maintenance
+    }
+
     /// Get a shutdown signal receiver
     pub fn shutdown_receiver(&self) -> ShutdownReceiver {
         self.shutdown_tx.subscribe()
@@ -196,7 +256,9 @@ impl LifecycleManager {
 
     /// Get service uptime
     pub fn uptime(&self) -> Duration {
-        self.start_time.elapsed()
+        self.clock
+            .instant()
+            .saturating_duration_since(self.start_time)
     }
 
     /// Check if shutdown has been initiated
@@ -213,10 +275,12 @@ impl LifecycleManager {
     }
 
     /// Initiate graceful shutdown
-    pub async fn initiate_shutdown(&self) {
+    ///
+    /// Returns `None` if a shutdown was already in progress.
+    pub async fn initiate_shutdown(&self) -> Option<ShutdownReport> {
         if self.shutting_down.swap(true, Ordering::SeqCst) {
             warn!("Shutdown already in progress");
-            return;
+            return None;
         }
 
         info!("🛑 Initiating graceful shutdown...");
@@ -226,24 +290,42 @@ impl LifecycleManager {
         let _ = self.shutdown_tx.send(());
 
         // Wait for connections to drain
+        let initial_connections = self.active_connections();
         let drain_start = Instant::now();
+        let mut forced = false;
         while self.active_connections() > 0 {
             if drain_start.elapsed() > self.drain_timeout {
                 warn!(
                     "Drain timeout reached, {} connections still active",
                     self.active_connections()
                 );
+                forced = true;
                 break;
             }
             tokio::time::sleep(Duration::from_millis(100)).await;
         }
 
-        info!("✅ Graceful shutdown complete");
+        let remaining_connections = self.active_connections();
+        let report = ShutdownReport {
+            drained: initial_connections.saturating_sub(remaining_connections),
+            forced,
+            remaining_connections,
+            drain_duration: drain_start.elapsed(),
+        };
+
+        info!(
+            drained = report.drained,
+            forced = report.forced,
+            remaining_connections = report.remaining_connections,
+            drain_ms = report.drain_duration.as_millis() as u64,
+            "✅ Graceful shutdown complete"
+        );
+        Some(report)
     }
 
     /// Setup signal handlers for Unix systems
     #[cfg(unix)]
-    pub async fn wait_for_shutdown_signal(&self) {
+    pub async fn wait_for_shutdown_signal(&self) -> Option<ShutdownReport> {
         use tokio::signal::unix::{SignalKind, signal};
 
         let mut sigterm =
@@ -259,17 +341,17 @@ impl LifecycleManager {
             }
         }
 
-        self.initiate_shutdown().await;
+        self.initiate_shutdown().await
     }
 
     /// Setup signal handlers for non-Unix systems
     #[cfg(not(unix))]
-    pub async fn wait_for_shutdown_signal(&self) {
+    pub async fn wait_for_shutdown_signal(&self) -> Option<ShutdownReport> {
         tokio::signal::ctrl_c()
             .await
             .expect("Failed to install Ctrl+C handler");
         info!("Received Ctrl+C");
-        self.initiate_shutdown().await;
+        self.initiate_shutdown().await
     }
 }
 
@@ -417,6 +499,17 @@ mod tests {
         let _ = shutdown_handle.await;
     }
 
+    #[tokio::test]
+    async fn test_uptime_uses_clock() {
+        let clock = aegis_common::MockClock::new();
+        let manager = LifecycleManager::new().with_clock(clock.shared());
+        assert_eq!(manager.uptime(), Duration::ZERO);
+
+        clock.advance(Duration::from_secs(3600));
+        assert_eq!(manager.uptime(), Duration::from_secs(3600));
+        assert_eq!(manager.health_response().await.uptime_seconds, Some(3600));
+    }
+
     #[test]
     fn test_with_drain_timeout() {
         let manager = LifecycleManager::new().with_drain_timeout(Duration::from_secs(60));
@@ -475,7 +568,7 @@ mod tests {
         manager.connection_started();
 
         let start = Instant::now();
-        manager.initiate_shutdown().await;
+        let report = manager.initiate_shutdown().await.unwrap();
         let elapsed = start.elapsed();
 
         // Should have waited at least 100ms
@@ -485,6 +578,44 @@ mod tests {
 
         // Connections still active
         assert_eq!(manager.active_connections(), 1);
+        assert!(report.forced);
+        assert_eq!(report.remaining_connections, 1);
+        assert_eq!(report.drained, 0);
+        assert!(report.drain_duration >= Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_report_counts_drained_connections() {
+        let manager =
+            Arc::new(LifecycleManager::new().with_drain_timeout(Duration::from_millis(300)));
+        manager.connection_started();
+        manager.connection_started();
+        manager.connection_started();
+
+        // Two connections finish during the drain, one lingers past the timeout
+        let closer = Arc::clone(&manager);
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            closer.connection_finished();
+            closer.connection_finished();
+        });
+
+        let report = manager.initiate_shutdown().await.unwrap();
+        assert!(report.forced);
+        assert_eq!(report.drained, 2);
+        assert_eq!(report.remaining_connections, 1);
+
+        // A second shutdown does not produce another report
+        assert!(manager.initiate_shutdown().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_report_clean_drain() {
+        let manager = LifecycleManager::new();
+        let report = manager.initiate_shutdown().await.unwrap();
+        assert!(!report.forced);
+        assert_eq!(report.drained, 0);
+        assert_eq!(report.remaining_connections, 0);
     }
 
     #[test]
@@ -566,6 +697,36 @@ mod tests {
         assert!(!manager.health_status().await.is_ready());
     }
 
+    #[tokio::test]
+    async fn test_maintenance_mode_transitions() {
+        let manager = LifecycleManager::new();
+
+        // Not before startup completes
+        assert!(!manager.set_maintenance(true).await);
+        assert_eq!(manager.health_status().await, HealthStatus::Starting);
+
+        manager.mark_ready().await;
+        assert!(manager.set_maintenance(true).await);
+        let status = manager.health_status().await;
+        assert!(manager.is_in_maintenance().await);
+        assert!(!status.is_ready());
+        assert!(status.is_alive());
+        assert_eq!(status.to_status_code(), 503);
+        assert_eq!(status.to_string(), "maintenance");
+
+        // Idempotent both ways
+        assert!(manager.set_maintenance(true).await);
+        assert!(manager.set_maintenance(false).await);
+        assert!(manager.set_maintenance(false).await);
+        assert_eq!(manager.health_status().await, HealthStatus::Healthy);
+
+        // Shutdown wins over maintenance
+        manager.set_maintenance(true).await;
+        manager.initiate_shutdown().await;
+        assert!(!manager.set_maintenance(false).await);
+        assert_eq!(manager.health_status().await, HealthStatus::Draining);
+    }
+
     #[tokio::test]
     async fn test_lifecycle_initial_state_healthy() {
         let manager = LifecycleManager::new();
@@ -0,0 +1,402 @@
+//! Listening socket setup
+//!
+//! Binds TCP and UDP listeners with `SO_REUSEADDR` / `SO_REUSEPORT` and
+//! retries with a short backoff while the port is still held, e.g. by
+//! `TIME_WAIT` sockets after a fast restart. Accepted TCP connections get
+//! `TCP_NODELAY` and keepalive probing applied from the same configuration.
+//!
+//! Addresses may be link-local IPv6 with a scope id, either numeric
+//! (`[fe80::1%2]:8080`) or an interface name (`[fe80::1%eth0]:8080`).
+
+use crate::config::ListenerConfig;
+use socket2::{Domain, Protocol, SockRef, Socket, TcpKeepalive, Type};
+use std::io;
+use std::net::{Ipv6Addr, SocketAddr, SocketAddrV6};
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tracing::warn;
+
+/// Maximum delay between two bind attempts
+const MAX_BIND_BACKOFF: Duration = Duration::from_secs(5);
+
+/// Resolve `addr` and bind a TCP listener, retrying while the port is in use
+pub async fn bind_tcp(addr: &str, config: &ListenerConfig) -> io::Result<TcpListener> {
+    let socket_addr = resolve(addr).await?;
+    let std_listener = with_retry(addr, config, || tcp_socket(socket_addr, config)).await?;
+    TcpListener::from_std(std_listener)
+}
+
+/// Resolve `addr` and bind a non-blocking UDP socket, retrying while the port is in use
+pub async fn bind_udp(addr: &str, config: &ListenerConfig) -> io::Result<std::net::UdpSocket> {
+    let socket_addr = resolve(addr).await?;
+    with_retry(addr, config, || udp_socket(socket_addr, config)).await
+}
+
+/// Apply `TCP_NODELAY` and keepalive settings to an accepted connection
+pub fn configure_accepted(stream: &TcpStream, config: &ListenerConfig) -> io::Result<()> {
+    let socket = SockRef::from(stream);
+    socket.set_tcp_nodelay(config.tcp_nodelay)?;
+
+    let keepalive = &config.keepalive;
+    if keepalive.enabled {
+        let params = TcpKeepalive::new().with_time(Duration::from_secs(keepalive.idle_secs));
+        #[cfg(any(target_os = "linux", target_os = "macos", target_os = "freebsd"))]
+        let params = params
+            .with_interval(Duration::from_secs(keepalive.interval_secs))
+            .with_retries(keepalive.retries);
+        socket.set_tcp_keepalive(&params)?;
+    } else {
+        socket.set_keepalive(false)?;
+    }
+    Ok(())
+}
+
+/// Join a host and port, bracketing IPv6 hosts (`fe80::1%eth0` becomes
+/// `[fe80::1%eth0]:8080`)
+pub fn join_host_port(host: &str, port: u16) -> String {
+    if host.contains(':') && !host.starts_with('[') {
+        format!("[{}]:{}", host, port)
+    } else {
+        format!("{}:{}", host, port)
+    }
+}
+
+/// Parse a literal socket address, accepting IPv6 scope ids
+///
+/// Unlike `str::parse`, the scope id may name an interface, which is looked
+/// up with `if_nametoindex`. Hostnames are not resolved.
+pub fn parse_socket_addr(addr: &str) -> io::Result<SocketAddr> {
+    if let Ok(parsed) = addr.parse() {
+        return Ok(parsed);
+    }
+    let invalid = || {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Invalid socket address: {}", addr),
+        )
+    };
+
+    let (host, port) = addr
+        .strip_prefix('[')
+        .and_then(|rest| rest.split_once("]:"))
+        .ok_or_else(invalid)?;
+    let (ip, scope) = host.split_once('%').ok_or_else(invalid)?;
+    let ip: Ipv6Addr = ip.parse().map_err(|_| invalid())?;
+    let port: u16 = port.parse().map_err(|_| invalid())?;
+    let scope_id = match scope.parse::<u32>() {
+        Ok(index) => index,
+        Err(_) => interface_index(scope)?,
+    };
+    Ok(SocketAddrV6::new(ip, port, 0, scope_id).into())
+}
+
+#[cfg(unix)]
+fn interface_index(name: &str) -> io::Result<u32> {
+    let not_found = || {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("Unknown network interface: {}", name),
+        )
+    };
+    let c_name = std::ffi::CString::new(name).map_err(|_| not_found())?;
+    // SAFETY: `c_name` is a valid NUL-terminated string for the duration of the call
+    match unsafe { libc::if_nametoindex(c_name.as_ptr()) } {
+        0 => Err(not_found()),
+        index => Ok(index),
+    }
+}
+
+#[cfg(not(unix))]
+fn interface_index(name: &str) -> io::Result<u32> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!("Interface scope ids need a numeric index here: {}", name),
+    ))
+}
+
+async fn resolve(addr: &str) -> io::Result<SocketAddr> {
+    match parse_socket_addr(addr) {
+        Ok(parsed) => return Ok(parsed),
+        // Scoped addresses are never hostnames; report why parsing failed
+        Err(e) if addr.contains('%') => return Err(e),
+        Err(_) => {}
+    }
+    tokio::net::lookup_host(addr).await?.next().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Address resolved to nothing: {}", addr),
+        )
+    })
+}
+
+async fn with_retry<T>(
+    addr: &str,
+    config: &ListenerConfig,
+    mut bind: impl FnMut() -> io::Result<T>,
+) -> io::Result<T> {
+    let mut backoff = Duration::from_millis(config.bind_backoff_ms);
+    let mut attempt = 0;
+
+    loop {
+        match bind() {
+            Ok(socket) => return Ok(socket),
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse && attempt < config.bind_retries => {
+                attempt += 1;
+                warn!(
+                    "⚠️ {} in use, retrying bind in {:?} ({}/{})",
+                    addr, backoff, attempt, config.bind_retries
+                );
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(MAX_BIND_BACKOFF);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+fn new_socket(
+    addr: SocketAddr,
+    ty: Type,
+    protocol: Protocol,
+    config: &ListenerConfig,
+) -> io::Result<Socket> {
+    let socket = Socket::new(Domain::for_address(addr), ty, Some(protocol))?;
+    socket.set_reuse_address(config.reuse_addr)?;
+    #[cfg(unix)]
+    if config.reuse_port {
+        socket.set_reuse_port(true)?;
+    }
+    socket.set_nonblocking(true)?;
+    socket.bind(&addr.into())?;
+    Ok(socket)
+}
+
+fn tcp_socket(addr: SocketAddr, config: &ListenerConfig) -> io::Result<std::net::TcpListener> {
+    let socket = new_socket(addr, Type::STREAM, Protocol::TCP, config)?;
+    socket.listen(config.backlog)?;
+    Ok(socket.into())
+}
+
+fn udp_socket(addr: SocketAddr, config: &ListenerConfig) -> io::Result<std::net::UdpSocket> {
+    let socket = new_socket(addr, Type::DGRAM, Protocol::UDP, config)?;
+    Ok(socket.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn no_reuse() -> ListenerConfig {
+        ListenerConfig {
+            reuse_addr: false,
+            reuse_port: false,
+            bind_retries: 2,
+            bind_backoff_ms: 10,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bind_tcp_ephemeral_port() {
+        let listener = bind_tcp("127.0.0.1:0", &ListenerConfig::default())
+            .await
+            .unwrap();
+        assert!(listener.local_addr().unwrap().port() > 0);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_bind_tcp_second_bind_with_reuse_port_succeeds() {
+        let config = ListenerConfig {
+            reuse_port: true,
+            ..Default::default()
+        };
+        let first = bind_tcp("127.0.0.1:0", &config).await.unwrap();
+        let addr = first.local_addr().unwrap().to_string();
+
+        let second = bind_tcp(&addr, &config).await.unwrap();
+        assert_eq!(second.local_addr().unwrap(), first.local_addr().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_bind_tcp_retries_then_fails_without_reuse() {
+        let first = bind_tcp("127.0.0.1:0", &no_reuse()).await.unwrap();
+        let addr = first.local_addr().unwrap().to_string();
+
+        let started = std::time::Instant::now();
+        let err = bind_tcp(&addr, &no_reuse()).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AddrInUse);
+        // Two retries with 10ms + 20ms backoff
+        assert!(started.elapsed() >= Duration::from_millis(30));
+    }
+
+    #[tokio::test]
+    async fn test_bind_tcp_succeeds_once_port_is_released() {
+        let first = bind_tcp("127.0.0.1:0", &no_reuse()).await.unwrap();
+        let addr = first.local_addr().unwrap().to_string();
+
+        let config = ListenerConfig {
+            bind_retries: 10,
+            ..no_reuse()
+        };
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            drop(first);
+        });
+
+        assert!(bind_tcp(&addr, &config).await.is_ok());
+    }
+
+    #[test]
+    fn test_join_host_port() {
+        assert_eq!(join_host_port("0.0.0.0", 8080), "0.0.0.0:8080");
+        assert_eq!(join_host_port("localhost", 80), "localhost:80");
+        assert_eq!(join_host_port("::", 443), "[::]:443");
+        assert_eq!(join_host_port("fe80::1%eth0", 80), "[fe80::1%eth0]:80");
+        assert_eq!(join_host_port("[::1]", 80), "[::1]:80");
+    }
+
+    #[test]
+    fn test_parse_scoped_ipv6() {
+        let SocketAddr::V6(numeric) = parse_socket_addr("[fe80::1%7]:8080").unwrap() else {
+            panic!("expected IPv6");
+        };
+        assert_eq!(numeric.ip(), &"fe80::1".parse::<Ipv6Addr>().unwrap());
+        assert_eq!((numeric.port(), numeric.scope_id()), (8080, 7));
+
+        #[cfg(target_os = "linux")]
+        {
+            let SocketAddr::V6(named) = parse_socket_addr("[fe80::1%lo]:8080").unwrap() else {
+                panic!("expected IPv6");
+            };
+            assert_eq!(named.scope_id(), interface_index("lo").unwrap());
+            assert!(named.scope_id() > 0);
+        }
+
+        assert_eq!(
+            parse_socket_addr("127.0.0.1:80").unwrap(),
+            "127.0.0.1:80".parse::<SocketAddr>().unwrap()
+        );
+        for bad in [
+            "[fe80::1%no-such-if0]:80",
+            "[fe80::1%eth0]",
+            "fe80::1%eth0:80",
+            "[fe80::zz%1]:80",
+            "localhost:80",
+        ] {
+            assert!(parse_socket_addr(bad).is_err(), "{bad}");
+        }
+        assert_eq!(
+            parse_socket_addr("[fe80::1%no-such-if0]:80")
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::NotFound
+        );
+    }
+
+    /// First link-local IPv6 address and its interface, if the host has one
+    #[cfg(target_os = "linux")]
+    fn link_local_ipv6() -> Option<(String, String)> {
+        let table = std::fs::read_to_string("/proc/net/if_inet6").ok()?;
+        table.lines().find_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            // Scope 0x20 is link-local
+            let [hex, _, _, "20", _, name] = fields[..] else {
+                return None;
+            };
+            let groups: Vec<&str> = (0..8).map(|i| &hex[i * 4..i * 4 + 4]).collect();
+            Some((groups.join(":"), name.to_string()))
+        })
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_bind_tcp_scoped_link_local() {
+        let Some((ip, interface)) = link_local_ipv6() else {
+            eprintln!("No link-local IPv6 address available, skipping");
+            return;
+        };
+        let addr = join_host_port(&format!("{}%{}", ip, interface), 0);
+
+        let listener = bind_tcp(&addr, &ListenerConfig::default()).await.unwrap();
+        let SocketAddr::V6(local) = listener.local_addr().unwrap() else {
+            panic!("expected IPv6");
+        };
+        assert_eq!(local.scope_id(), interface_index(&interface).unwrap());
+        assert!(local.port() > 0);
+
+        // The bound address is directly usable as an endpoint
+        let client = TcpStream::connect(SocketAddr::V6(local)).await.unwrap();
+        let (_, peer) = listener.accept().await.unwrap();
+        assert_eq!(peer, client.local_addr().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_bind_udp_ephemeral_port() {
+        let socket = bind_udp("127.0.0.1:0", &ListenerConfig::default())
+            .await
+            .unwrap();
+        assert!(socket.local_addr().unwrap().port() > 0);
+    }
+
+    async fn accepted_pair(config: &ListenerConfig) -> (TcpStream, TcpStream) {
+        let listener = bind_tcp("127.0.0.1:0", config).await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let client = TcpStream::connect(addr).await.unwrap();
+        let (accepted, _) = listener.accept().await.unwrap();
+        (client, accepted)
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_configure_accepted_applies_options() {
+        let config = ListenerConfig {
+            keepalive: crate::config::TcpKeepaliveConfig {
+                enabled: true,
+                idle_secs: 42,
+                interval_secs: 7,
+                retries: 3,
+            },
+            ..Default::default()
+        };
+        let (_client, accepted) = accepted_pair(&config).await;
+        configure_accepted(&accepted, &config).unwrap();
+
+        let socket = SockRef::from(&accepted);
+        assert!(socket.tcp_nodelay().unwrap());
+        assert!(socket.keepalive().unwrap());
+        assert_eq!(
+            socket.tcp_keepalive_time().unwrap(),
+            Duration::from_secs(42)
+        );
+        assert_eq!(
+            socket.tcp_keepalive_interval().unwrap(),
+            Duration::from_secs(7)
+        );
+        assert_eq!(socket.tcp_keepalive_retries().unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_configure_accepted_disabled_options() {
+        let config = ListenerConfig {
+            tcp_nodelay: false,
+            keepalive: crate::config::TcpKeepaliveConfig {
+                enabled: false,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let (_client, accepted) = accepted_pair(&config).await;
+        configure_accepted(&accepted, &config).unwrap();
+
+        let socket = SockRef::from(&accepted);
+        assert!(!socket.tcp_nodelay().unwrap());
+        assert!(!socket.keepalive().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_bind_unresolvable_address() {
+        let result = bind_tcp("not an address", &ListenerConfig::default()).await;
+        assert!(result.is_err());
+    }
+}
@@ -4,6 +4,7 @@
 
 use metrics::{counter, describe_counter, describe_gauge, describe_histogram, gauge, histogram};
 use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+use std::collections::BTreeMap;
 use std::sync::OnceLock;
 use tracing::info;
 
@@ -17,20 +18,29 @@ pub mod names {
     pub const CONNECTIONS_ACTIVE: &str = "aegis_connections_active";
     pub const HANDSHAKES_TOTAL: &str = "aegis_pqc_handshakes_total";
     pub const HANDSHAKE_DURATION: &str = "aegis_pqc_handshake_duration_seconds";
+    pub const HANDSHAKE_TIMEOUTS: &str = "aegis_pqc_handshake_timeouts_total";
     pub const BYTES_SENT: &str = "aegis_bytes_sent_total";
     pub const BYTES_RECEIVED: &str = "aegis_bytes_received_total";
     pub const ENCRYPTION_OPERATIONS: &str = "aegis_encryption_operations_total";
     pub const ERRORS_TOTAL: &str = "aegis_errors_total";
     pub const CARBON_INTENSITY: &str = "aegis_carbon_intensity_g_kwh";
+    pub const CARBON_ROUTING_RECOMMENDATION: &str = "aegis_carbon_routing_recommendation";
     pub const ESTIMATED_ENERGY: &str = "aegis_estimated_energy_joules_total";
     pub const ESTIMATED_CARBON: &str = "aegis_estimated_carbon_grams_total";
     pub const DEFERRED_JOBS: &str = "aegis_deferred_jobs_current";
+    pub const ENERGY_BUDGET_REMAINING: &str = "aegis_energy_budget_remaining_joules";
     pub const CACHE_HITS: &str = "aegis_cache_hits_total";
     pub const CACHE_MISSES: &str = "aegis_cache_misses_total";
     pub const CACHE_BYTES_SAVED: &str = "aegis_cache_bytes_saved_total";
     pub const CACHE_MEMORY_BYTES: &str = "aegis_cache_memory_bytes";
     pub const WEBSOCKET_CONNECTIONS_ACTIVE: &str = "aegis_websocket_connections_active";
     pub const WEBSOCKET_MESSAGES_TOTAL: &str = "aegis_websocket_messages_total";
+    pub const UPSTREAM_CIRCUIT_STATE: &str = "aegis_upstream_circuit_state";
+    pub const UPSTREAM_DURATION: &str = "aegis_upstream_duration_seconds";
+    // Recorded by `aegis_crypto::stream::EncryptedStream`
+    pub const STREAM_FRAMES: &str = aegis_crypto::stream::metric_names::FRAMES;
+    pub const STREAM_BYTES: &str = aegis_crypto::stream::metric_names::BYTES;
+    pub const STREAM_DECRYPT_FAILURES: &str = aegis_crypto::stream::metric_names::DECRYPT_FAILURES;
 }
 
 /// Initialize the metrics system
@@ -63,7 +73,14 @@ pub fn init_metrics() -> PrometheusHandle {
                 0.001, 0.005, 0.010, 0.025, 0.050, 0.100, 0.250, 0.500, 1.0, 2.5, 5.0,
             ],
         )
-        .expect("Failed to set REQUEST_DURATION buckets");
+        .expect("Failed to set REQUEST_DURATION buckets")
+        .set_buckets_for_metric(
+            metrics_exporter_prometheus::Matcher::Full(names::UPSTREAM_DURATION.to_string()),
+            &[
+                0.001, 0.005, 0.010, 0.025, 0.050, 0.100, 0.250, 0.500, 1.0, 2.5, 5.0,
+            ],
+        )
+        .expect("Failed to set UPSTREAM_DURATION buckets");
 
     match builder.install_recorder() {
         Ok(handle) => {
@@ -88,6 +105,10 @@ pub fn init_metrics() -> PrometheusHandle {
                 names::CARBON_INTENSITY,
                 "Current carbon intensity for each region (gCO2/kWh)"
             );
+            describe_counter!(
+                names::CARBON_ROUTING_RECOMMENDATION,
+                "Dry-run carbon routing recommendations per region"
+            );
             describe_counter!(
                 names::ESTIMATED_ENERGY,
                 "Estimated energy consumed in Joules"
@@ -100,6 +121,10 @@ pub fn init_metrics() -> PrometheusHandle {
                 names::DEFERRED_JOBS,
                 "Number of jobs currently waiting in Green-Wait queue"
             );
+            describe_gauge!(
+                names::ENERGY_BUDGET_REMAINING,
+                "Energy left in the current budget window in Joules"
+            );
             describe_counter!(names::CACHE_HITS, "Total number of cache hits");
             describe_counter!(names::CACHE_MISSES, "Total number of cache misses");
             describe_counter!(
@@ -118,6 +143,26 @@ pub fn init_metrics() -> PrometheusHandle {
                 names::WEBSOCKET_MESSAGES_TOTAL,
                 "Total WebSocket messages forwarded"
             );
+            describe_gauge!(
+                names::UPSTREAM_CIRCUIT_STATE,
+                "Upstream circuit breaker state (0 = closed, 1 = half-open, 2 = open)"
+            );
+            describe_histogram!(
+                names::UPSTREAM_DURATION,
+                "Time from sending a request upstream to receiving its response headers, in seconds"
+            );
+            describe_counter!(
+                names::STREAM_FRAMES,
+                "Encrypted stream frames by direction (encrypt/decrypt)"
+            );
+            describe_counter!(
+                names::STREAM_BYTES,
+                "Encrypted stream bytes by direction, as plaintext and as framed ciphertext"
+            );
+            describe_counter!(
+                names::STREAM_DECRYPT_FAILURES,
+                "Encrypted stream frames that failed authentication"
+            );
 
             METRICS_HANDLE.set(handle.clone()).ok();
             handle
@@ -142,6 +187,88 @@ pub fn get_metrics_handle() -> Option<&'static PrometheusHandle> {
     METRICS_HANDLE.get()
 }
 
+/// Current values of the proxy's main metrics
+///
+/// Counters are summed across their labels.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MetricsSnapshot {
+    pub requests_total: u64,
+    pub errors_total: u64,
+    pub active_connections: f64,
+    pub handshakes_total: u64,
+    pub handshake_timeouts_total: u64,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    pub deferred_jobs: f64,
+    pub cache_hits: u64,
+    pub cache_misses: u64,
+    pub websocket_connections_active: f64,
+    pub estimated_energy_joules: u64,
+    pub estimated_carbon_grams: u64,
+    /// Last reported intensity per region, in gCO2/kWh
+    pub carbon_intensity: BTreeMap<String, f64>,
+}
+
+/// Read the current metric values from the installed recorder
+///
+/// Returns an all-zero snapshot if [`init_metrics`] has not been called.
+pub fn snapshot() -> MetricsSnapshot {
+    get_metrics_handle()
+        .map(|handle| MetricsSnapshot::from_rendered(&handle.render()))
+        .unwrap_or_default()
+}
+
+impl MetricsSnapshot {
+    fn from_rendered(rendered: &str) -> Self {
+        let mut snapshot = Self::default();
+        for line in rendered.lines().filter(|line| !line.starts_with('#')) {
+            let Some((series, value)) = line.rsplit_once(' ') else {
+                continue;
+            };
+            let Ok(value) = value.parse::<f64>() else {
+                continue;
+            };
+            let (name, labels) = series
+                .split_once('{')
+                .map_or((series, ""), |(name, labels)| (name, labels));
+            let count = value as u64;
+            match name {
+                names::REQUESTS_TOTAL => snapshot.requests_total += count,
+                names::ERRORS_TOTAL => snapshot.errors_total += count,
+                names::CONNECTIONS_ACTIVE => snapshot.active_connections = value,
+                names::HANDSHAKES_TOTAL => snapshot.handshakes_total += count,
+                names::HANDSHAKE_TIMEOUTS => snapshot.handshake_timeouts_total += count,
+                names::BYTES_SENT => snapshot.bytes_sent += count,
+                names::BYTES_RECEIVED => snapshot.bytes_received += count,
+                names::DEFERRED_JOBS => snapshot.deferred_jobs = value,
+                names::CACHE_HITS => snapshot.cache_hits += count,
+                names::CACHE_MISSES => snapshot.cache_misses += count,
+                names::WEBSOCKET_CONNECTIONS_ACTIVE => {
+                    snapshot.websocket_connections_active = value
+                }
+                names::ESTIMATED_ENERGY => snapshot.estimated_energy_joules += count,
+                names::ESTIMATED_CARBON => snapshot.estimated_carbon_grams += count,
+                names::CARBON_INTENSITY => {
+                    if let Some(region) = label(labels, "region") {
+                        snapshot.carbon_intensity.insert(region.to_string(), value);
+                    }
+                }
+                _ => {}
+            }
+        }
+        snapshot
+    }
+}
+
+/// Value of label `key` in a rendered `k="v",...}` label set
+fn label<'a>(labels: &'a str, key: &str) -> Option<&'a str> {
+    labels.trim_end_matches('}').split(',').find_map(|pair| {
+        pair.strip_prefix(key)?
+            .strip_prefix("=\"")?
+            .strip_suffix('"')
+    })
+}
+
 /// Record a request
 pub fn record_request(method: &str, path: &str, status: u16, duration_secs: f64) {
     counter!(names::REQUESTS_TOTAL, "method" => method.to_string(), "path" => path.to_string(), "status" => status.to_string()).increment(1);
@@ -157,6 +284,11 @@ pub fn record_handshake(algorithm: &str, duration_secs: f64, success: bool) {
     }
 }
 
+/// Record a PQC handshake abandoned after its timeout
+pub fn record_handshake_timeout() {
+    counter!(names::HANDSHAKE_TIMEOUTS).increment(1);
+}
+
 /// Update active connections gauge
 pub fn set_active_connections(count: f64) {
     gauge!(names::CONNECTIONS_ACTIVE).set(count);
@@ -193,6 +325,11 @@ pub fn update_carbon_intensity(region: &str, intensity: f64) {
     gauge!(names::CARBON_INTENSITY, "region" => region.to_string()).set(intensity);
 }
 
+/// Record a carbon routing recommendation that was not acted on
+pub fn record_carbon_routing_recommendation(region: &str) {
+    counter!(names::CARBON_ROUTING_RECOMMENDATION, "region" => region.to_string()).increment(1);
+}
+
 /// Record estimated energy and carbon
 pub fn record_energy_impact(joules: f64, carbon_grams: f64, region: &str) {
     counter!(names::ESTIMATED_ENERGY, "region" => region.to_string()).increment(joules as u64);
@@ -205,6 +342,22 @@ pub fn update_deferred_jobs(count: usize) {
     gauge!(names::DEFERRED_JOBS).set(count as f64);
 }
 
+/// Update the circuit breaker state of an upstream
+pub fn set_upstream_circuit_state(upstream: &str, state: &crate::circuit_breaker::CircuitState) {
+    gauge!(names::UPSTREAM_CIRCUIT_STATE, "upstream" => upstream.to_string()).set(state.as_gauge());
+}
+
+/// Record the round-trip time of a forwarded request, excluding time spent
+/// in the proxy itself
+pub fn record_upstream_duration(upstream: &str, duration_secs: f64) {
+    histogram!(names::UPSTREAM_DURATION, "upstream" => upstream.to_string()).record(duration_secs);
+}
+
+/// Update remaining energy budget
+pub fn update_energy_budget_remaining(joules: f64) {
+    gauge!(names::ENERGY_BUDGET_REMAINING).set(joules);
+}
+
 /// Record a cache hit
 pub fn record_cache_hit(bytes_saved: u64) {
     counter!(names::CACHE_HITS).increment(1);
@@ -257,6 +410,7 @@ mod tests {
         assert!(names::BYTES_RECEIVED.starts_with("aegis_"));
         assert!(names::ENCRYPTION_OPERATIONS.starts_with("aegis_"));
         assert!(names::ERRORS_TOTAL.starts_with("aegis_"));
+        assert!(names::UPSTREAM_DURATION.starts_with("aegis_"));
     }
 
     #[test]
@@ -496,4 +650,85 @@ mod tests {
             "Expected request metrics to be scraped"
         );
     }
+
+    /// Value of the sample `series` (name with labels) in `rendered`
+    fn sample(rendered: &str, series: &str) -> u64 {
+        rendered
+            .lines()
+            .find_map(|line| line.strip_prefix(series)?.trim().parse().ok())
+            .unwrap_or(0)
+    }
+
+    #[test]
+    fn test_snapshot_reflects_recorded_activity() {
+        init_metrics();
+        let before = snapshot();
+
+        record_request("GET", "/snapshot", 200, 0.01);
+        record_request("POST", "/snapshot", 500, 0.02);
+        record_handshake("ml-kem-768", 0.01, true);
+        record_error("snapshot_test");
+        record_bytes(1000, 500);
+        update_carbon_intensity("snapshot-region", 123.5);
+
+        // Other tests share the global recorder, so only a lower bound holds
+        let after = snapshot();
+        assert!(after.requests_total >= before.requests_total + 2);
+        assert!(after.handshakes_total > before.handshakes_total);
+        assert!(after.errors_total > before.errors_total);
+        assert!(after.bytes_sent >= before.bytes_sent + 1000);
+        assert!(after.bytes_received >= before.bytes_received + 500);
+        assert_eq!(after.carbon_intensity.get("snapshot-region"), Some(&123.5));
+    }
+
+    #[test]
+    fn test_snapshot_parses_rendered_series() {
+        let rendered = "\
+# TYPE aegis_requests_total counter
+aegis_requests_total{method=\"GET\",path=\"/\",status=\"200\"} 3
+aegis_requests_total{method=\"POST\",path=\"/\",status=\"201\"} 2
+aegis_connections_active 4
+aegis_deferred_jobs_current 7
+aegis_carbon_intensity_g_kwh{region=\"eu-west\"} 88.5
+aegis_request_duration_seconds_count{method=\"GET\"} 3
+";
+        let snapshot = MetricsSnapshot::from_rendered(rendered);
+        assert_eq!(snapshot.requests_total, 5);
+        assert_eq!(snapshot.active_connections, 4.0);
+        assert_eq!(snapshot.deferred_jobs, 7.0);
+        assert_eq!(snapshot.carbon_intensity.get("eu-west"), Some(&88.5));
+        assert_eq!(snapshot.handshakes_total, 0);
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_stream_frame_metrics() {
+        use aegis_crypto::stream::EncryptedStream;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let handle = init_metrics();
+        let encrypted = format!(r#"{}{{direction="encrypt"}}"#, names::STREAM_FRAMES);
+        let decrypted = format!(r#"{}{{direction="decrypt"}}"#, names::STREAM_FRAMES);
+        let plaintext_bytes = format!(
+            r#"{}{{direction="encrypt",form="plaintext"}}"#,
+            names::STREAM_BYTES
+        );
+        let before = handle.render();
+
+        let key = [0x24u8; 32];
+        let (client, server) = tokio::io::duplex(64 * 1024);
+        let mut writer = EncryptedStream::new(client, &key);
+        let mut reader = EncryptedStream::new(server, &key);
+        for _ in 0..3 {
+            writer.write_all(b"frame").await.unwrap();
+            writer.flush().await.unwrap();
+        }
+        let mut received = [0u8; 15];
+        reader.read_exact(&mut received).await.unwrap();
+
+        // Other tests share the global recorder, so only a lower bound holds
+        let after = handle.render();
+        assert!(sample(&after, &encrypted) >= sample(&before, &encrypted) + 3);
+        assert!(sample(&after, &decrypted) >= sample(&before, &decrypted) + 3);
+        assert!(sample(&after, &plaintext_bytes) >= sample(&before, &plaintext_bytes) + 15);
+    }
 }
@@ -103,6 +103,49 @@ impl PersistentQueue {
         Ok(Some((id, job)))
     }
 
+    /// Snapshot of the queued job IDs, front first
+    pub async fn ids(&self) -> Vec<u64> {
+        let mq = self.memory_queue.lock().await;
+        mq.iter().copied().collect()
+    }
+
+    /// Removes the job with `id` if `pred` accepts it
+    ///
+    /// Returns `None` when the job is no longer queued or `pred` rejects it;
+    /// rejected jobs keep their place in the queue.
+    pub async fn take_if<F>(&self, id: u64, pred: F) -> anyhow::Result<Option<DeferredJob>>
+    where
+        F: FnOnce(&DeferredJob) -> bool,
+    {
+        let mut mq = self.memory_queue.lock().await;
+        let Some(index) = mq.iter().position(|&queued| queued == id) else {
+            return Ok(None);
+        };
+
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(QUEUE_TABLE)?;
+        let Some(raw_data) = table.get(id)? else {
+            return Ok(None);
+        };
+        let job: DeferredJob = bincode::deserialize(raw_data.value())?;
+        if !pred(&job) {
+            return Ok(None);
+        }
+
+        drop(raw_data);
+        drop(table);
+        drop(read_txn);
+        let write_txn = self.db.begin_write()?;
+        {
+            let mut w_table = write_txn.open_table(QUEUE_TABLE)?;
+            w_table.remove(id)?;
+        }
+        write_txn.commit()?;
+        mq.remove(index);
+
+        Ok(Some(job))
+    }
+
     /// Removes all jobs from the queue
     pub async fn clear(&self) -> anyhow::Result<()> {
         let write_txn = self.db.begin_write()?;
@@ -133,7 +176,10 @@ impl PersistentQueue {
     }
 
     /// Returns queue statistics: (total, expired, critical, high, normal, low, background)
-    pub async fn get_stats(&self) -> (usize, usize, usize, usize, usize, usize, usize) {
+    ///
+    /// Jobs count as expired if they have waited longer than their priority's
+    /// entry in `wait_budgets` as of `now`.
+    pub async fn get_stats(&self, now: chrono::DateTime<chrono::Utc>, wait_budgets: &[std::time::Duration; 5]) -> (usize, usize, usize, usize, usize, usize, usize) {
         let (mut total, mut expired) = (0, 0);
         let mut by_priority = [0; 5];
         
@@ -146,7 +192,7 @@ impl PersistentQueue {
             let raw_data = val_guard.value();
             if let Ok(job) = bincode::deserialize::<DeferredJob>(raw_data) {
                 total += 1;
-                if job.is_expired() {
+                if job.is_expired_at(now, wait_budgets[job.priority as usize]) {
                     expired += 1;
                 }
                 by_priority[job.priority as usize] += 1;
@@ -222,4 +268,34 @@ mod tests {
         let (_, popped) = queue2.pop().await.unwrap().unwrap();
         assert_eq!(popped.id, job.id);
     }
+    #[tokio::test]
+    async fn test_take_if_keeps_rejected_jobs_in_place() {
+        let file = NamedTempFile::new().unwrap();
+        let queue = PersistentQueue::new(file.path()).unwrap();
+        for id in ["a", "b", "c"] {
+            let mut job = create_job();
+            job.id = id.to_string();
+            queue.push(&job).await.unwrap();
+        }
+
+        let ids = queue.ids().await;
+        assert_eq!(ids.len(), 3);
+        assert!(queue.take_if(ids[0], |_| false).await.unwrap().is_none());
+        let taken = queue.take_if(ids[1], |job| job.id == "b").await.unwrap().unwrap();
+        assert_eq!(taken.id, "b");
+        // Already taken
+        assert!(queue.take_if(ids[1], |_| true).await.unwrap().is_none());
+
+        assert_eq!(queue.ids().await, vec![ids[0], ids[2]]);
+        let reopened_order: Vec<_> = {
+            drop(queue);
+            let queue = PersistentQueue::new(file.path()).unwrap();
+            let mut order = Vec::new();
+            while let Some((_, job)) = queue.pop().await.unwrap() {
+                order.push(job.id);
+            }
+            order
+        };
+        assert_eq!(reopened_order, vec!["a", "c"]);
+    }
 }
@@ -1,12 +1,16 @@
 //! PQC-enabled proxy server implementation
 
+use crate::circuit_breaker::UpstreamBreakers;
 use crate::config::ProxyConfig;
+use crate::connection_info::ConnectionInfo;
+use crate::error::ProxyError;
+use crate::lifecycle::{ConnectionGuard, LifecycleManager};
+use aegis_crypto::audit::{AuditLog, ConnectionEstablished};
+use aegis_crypto::connection::PqcServerConnection;
 use aegis_crypto::signing::{MlDsa65Signer, SigningKeyPair};
-use aegis_crypto::stream::EncryptedStream;
 use aegis_crypto::tls::{PqcHandshake, PqcTlsConfig};
-use anyhow::Result;
 use std::sync::Arc;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use std::time::Duration;
 use tokio::net::TcpListener;
 use tracing::{debug, error, info, instrument, warn};
 
@@ -15,6 +19,10 @@ pub struct PqcProxyServer {
     config: ProxyConfig,
     handshake: Arc<PqcHandshake>,
     identity_key: Arc<MlDsa65Signer>,
+    handshake_timeout: Duration,
+    audit_log: Option<Arc<AuditLog>>,
+    breakers: Arc<UpstreamBreakers>,
+    lifecycle: Option<Arc<LifecycleManager>>,
 }
 
 impl PqcProxyServer {
@@ -26,18 +34,57 @@ impl PqcProxyServer {
         let identity_key =
             Arc::new(MlDsa65Signer::generate().expect("Failed to generate identity key"));
 
+        let handshake_timeout = Duration::from_millis(config.handshake_timeout_ms);
+        let breakers = Arc::new(UpstreamBreakers::default());
+        if let Some(breaker) = &config.circuit_breaker {
+            breakers.register(
+                &config.upstream_protocol.target(&config.upstream_addr),
+                breaker,
+            );
+        }
+        let audit_log =
+            config
+                .logging
+                .audit_log
+                .as_deref()
+                .and_then(|path| match AuditLog::open(path) {
+                    Ok(log) => Some(Arc::new(log)),
+                    Err(e) => {
+                        warn!("⚠️ Failed to open audit log {}: {}", path, e);
+                        None
+                    }
+                });
+
         Self {
             config,
             handshake,
             identity_key,
+            handshake_timeout,
+            audit_log,
+            breakers,
+            lifecycle: None,
         }
     }
 
+    /// Count each accepted connection as active until it closes, so a
+    /// graceful shutdown waits for it to drain
+    pub fn with_lifecycle(mut self, lifecycle: Arc<LifecycleManager>) -> Self {
+        self.lifecycle = Some(lifecycle);
+        self
+    }
+
+    /// ML-DSA-65 public key clients can pin to trust this server
+    pub fn identity_public_key(&self) -> &[u8] {
+        self.identity_key.public_key()
+    }
+
     /// Run the PQC proxy server
     #[instrument(skip(self))]
-    pub async fn run(&self) -> Result<()> {
-        let addr = format!("{}:{}", self.config.host, self.config.port);
-        let listener = TcpListener::bind(&addr).await?;
+    pub async fn run(&self) -> Result<(), ProxyError> {
+        let addr = crate::listener::join_host_port(&self.config.host, self.config.port);
+        let listener = crate::listener::bind_tcp(&addr, &self.config.listener)
+            .await
+            .map_err(|e| ProxyError::bind(&addr, e))?;
 
         info!("🎯 Aegis-Flow PQC proxy is ready to accept connections");
         info!("🔒 Using algorithm: X25519-MLKEM768-Hybrid");
@@ -51,7 +98,7 @@ impl PqcProxyServer {
         &self,
         listener: TcpListener,
         shutdown: impl std::future::Future<Output = ()>,
-    ) -> Result<()> {
+    ) -> Result<(), ProxyError> {
         // Create a pinned box for the shutdown future since we need to pin it for select!
         let mut shutdown = Box::pin(shutdown);
 
@@ -59,115 +106,69 @@ impl PqcProxyServer {
             tokio::select! {
                 accept_result = listener.accept() => {
                     match accept_result {
-                        Ok((mut socket, peer_addr)) => {
+                        Ok((socket, peer_addr)) => {
                             info!("📥 New connection from: {}", peer_addr);
+                            if let Err(e) =
+                                crate::listener::configure_accepted(&socket, &self.config.listener)
+                            {
+                                warn!("⚠️ Failed to set socket options for {}: {}", peer_addr, e);
+                            }
                             let handshake = Arc::clone(&self.handshake);
                             let identity_key = Arc::clone(&self.identity_key);
                             let config = self.config.clone();
+                            let handshake_timeout = self.handshake_timeout;
+                            let audit_log = self.audit_log.clone();
+                            let breakers = Arc::clone(&self.breakers);
+                            let guard = self.lifecycle.clone().map(ConnectionGuard::new);
 
                             tokio::spawn(async move {
-                                // PQC Handshake Phase
+                                let _guard = guard;
+                                // PQC Handshake Phase; the socket is dropped if the client stalls
                                 debug!("🤝 Initiating PQC handshake with {}", peer_addr);
-
-                                // Generate server keypair
-                                let (server_pk, _signature, server_state) = match handshake.server_init(identity_key.as_ref()) {
-                                    Ok(result) => result,
-                                    Err(e) => {
-                                        error!("❌ Failed to initialize handshake: {}", e);
+                                let accept = PqcServerConnection::accept(
+                                    handshake.as_ref(),
+                                    identity_key.as_ref(),
+                                    socket,
+                                );
+                                let connection = match tokio::time::timeout(handshake_timeout, accept).await {
+                                    Ok(Ok(connection)) => connection,
+                                    Ok(Err(e)) => {
+                                        error!("❌ PQC handshake with {} failed: {}", peer_addr, e);
                                         return;
                                     }
-                                };
-
-                                // Send public key and signature to client
-                                let pk_bytes = server_pk.to_bytes();
-                                let pk_len = pk_bytes.len() as u32;
-
-                                let sig_bytes = _signature.as_bytes();
-                                let sig_len = sig_bytes.len() as u32;
-
-                                // Send PK
-                                if let Err(e) = socket.write_all(&pk_len.to_be_bytes()).await {
-                                    error!("❌ Failed to send public key length: {}", e);
-                                    return;
-                                }
-                                if let Err(e) = socket.write_all(&pk_bytes).await {
-                                    error!("❌ Failed to send public key: {}", e);
-                                    return;
-                                }
-
-                                // Send Signature
-                                if let Err(e) = socket.write_all(&sig_len.to_be_bytes()).await {
-                                    error!("❌ Failed to send signature length: {}", e);
-                                    return;
-                                }
-                                if let Err(e) = socket.write_all(sig_bytes).await {
-                                    error!("❌ Failed to send signature: {}", e);
-                                    return;
-                                }
-
-                                // Send Server Identity PK (so client can verify)
-                                let id_pk = identity_key.public_key();
-                                let id_pk_len = id_pk.len() as u32;
-                                if let Err(e) = socket.write_all(&id_pk_len.to_be_bytes()).await {
-                                    error!("❌ Failed to send identity pk length: {}", e);
-                                    return;
-                                }
-                                if let Err(e) = socket.write_all(id_pk).await {
-                                    error!("❌ Failed to send identity pk: {}", e);
-                                    return;
-                                }
-
-                                // Receive ciphertext from client
-                                let mut ct_len_bytes = [0u8; 4];
-                                if let Err(e) = socket.read_exact(&mut ct_len_bytes).await {
-                                    error!("❌ Failed to read ciphertext length: {}", e);
-                                    return;
-                                }
-                                let ct_len = u32::from_be_bytes(ct_len_bytes) as usize;
-
-                                if ct_len > 10_000 {
-                                    error!("❌ Ciphertext too large: {} bytes", ct_len);
-                                    return;
-                                }
-
-                                let mut ct_bytes = vec![0u8; ct_len];
-                                if let Err(e) = socket.read_exact(&mut ct_bytes).await {
-                                    error!("❌ Failed to read ciphertext: {}", e);
-                                    return;
-                                }
-
-                                // Parse ciphertext and complete handshake
-                                let ciphertext = match aegis_crypto::HybridCiphertext::from_bytes(&ct_bytes)
-                                {
-                                    Ok(ct) => ct,
-                                    Err(e) => {
-                                        error!("❌ Failed to parse ciphertext: {}", e);
+                                    Err(_) => {
+                                        warn!(
+                                            "⏱️ PQC handshake with {} timed out after {:?}, closing connection",
+                                            peer_addr, handshake_timeout
+                                        );
+                                        crate::metrics::record_handshake_timeout();
                                         return;
                                     }
                                 };
 
-                                let secure_channel =
-                                    match handshake.server_complete(&ciphertext, server_state) {
-                                        Ok(channel) => channel,
-                                        Err(e) => {
-                                            error!("❌ Failed to complete handshake: {}", e);
-                                            return;
-                                        }
-                                    };
-
                                 info!(
                                     "✅ PQC handshake complete with {}, channel_id={}",
                                     peer_addr,
-                                    secure_channel.channel_id()
+                                    connection.channel_id()
                                 );
+                                ConnectionEstablished::new(
+                                    connection.channel_id(),
+                                    connection.algorithm(),
+                                    connection.cipher(),
+                                )
+                                .with_peer_addr(peer_addr)
+                                .emit(audit_log.as_deref());
 
                                 // Secure echo server (Encrypted Data Plane)
-                                let key = secure_channel.send_key().as_bytes();
-                                let encrypted_socket = EncryptedStream::new(socket, key);
+                                let algorithm = connection.algorithm();
+                                let encrypted_socket = connection.into_stream();
                                 let io = get_tokio_io(encrypted_socket);
-                                let upstream = config.upstream_addr.clone();
+                                let upstream = config.upstream_protocol.target(&config.upstream_addr);
+                                let connection_info = ConnectionInfo::encrypted(format!("{:?}", algorithm));
 
-                                let service = hyper::service::service_fn(move |req| {
+                                let service = hyper::service::service_fn(move |mut req: hyper::Request<hyper::body::Incoming>| {
+                                    req.extensions_mut().insert(connection_info.clone());
+                                    req.extensions_mut().insert(Arc::clone(&breakers));
                                     let upstream = upstream.clone();
                                     async move {
                                         crate::http_proxy::handle_request(
@@ -221,7 +222,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use aegis_crypto::tls::{PqcHandshake, PqcTlsConfig};
+    use aegis_crypto::connection::{PqcClient, ServerHello};
     use tokio::io::{AsyncReadExt, AsyncWriteExt};
     use tokio::net::TcpStream;
     use tokio::time::{Duration, timeout};
@@ -265,114 +266,21 @@ mod tests {
 
     #[tokio::test]
     async fn test_pqc_server_handshake() {
-        use crate::http_proxy::TokioExecutor;
-        use bytes::Bytes;
-        use http_body_util::Full;
-        use hyper::Request;
+        use crate::test_harness::PqcTestServer;
 
-        let config = ProxyConfig {
-            host: "127.0.0.1".to_string(),
-            port: 0,
+        let server = PqcTestServer::start_with(ProxyConfig {
             pqc_enabled: true,
             upstream_addr: "127.0.0.1:8080".to_string(),
             ..Default::default()
-        };
-
-        let listener = TcpListener::bind(format!("{}:{}", config.host, config.port))
-            .await
-            .unwrap();
-        let addr = listener.local_addr().unwrap();
-
-        let server = Arc::new(PqcProxyServer::new(config.clone()));
-        let (tx, rx) = tokio::sync::oneshot::channel();
-
-        let server_clone = Arc::clone(&server);
-        tokio::spawn(async move {
-            server_clone
-                .run_with_listener(listener, async {
-                    rx.await.ok();
-                })
-                .await
-        });
-
-        // Give server time to start accepting
-        tokio::time::sleep(Duration::from_millis(50)).await;
-
-        let mut client = TcpStream::connect(addr).await.unwrap();
-        let client_handshake = PqcHandshake::new(PqcTlsConfig::default());
-
-        // Receive server public key
-        let mut pk_len_bytes = [0u8; 4];
-        client.read_exact(&mut pk_len_bytes).await.unwrap();
-        let pk_len = u32::from_be_bytes(pk_len_bytes) as usize;
-
-        let mut pk_bytes = vec![0u8; pk_len];
-        client.read_exact(&mut pk_bytes).await.unwrap();
-
-        // Receive server signature
-        let mut sig_len_bytes = [0u8; 4];
-        client.read_exact(&mut sig_len_bytes).await.unwrap();
-        let sig_len = u32::from_be_bytes(sig_len_bytes) as usize;
-        let mut sig_bytes = vec![0u8; sig_len];
-        client.read_exact(&mut sig_bytes).await.unwrap();
-        let signature = aegis_crypto::signing::MlDsaSignature::new(
-            sig_bytes,
-            aegis_crypto::signing::MlDsaAlgorithm::MlDsa65,
-        );
-
-        // Receive server identity PK
-        let mut id_pk_len_bytes = [0u8; 4];
-        client.read_exact(&mut id_pk_len_bytes).await.unwrap();
-        let id_pk_len = u32::from_be_bytes(id_pk_len_bytes) as usize;
-        let mut id_pk_bytes = vec![0u8; id_pk_len];
-        client.read_exact(&mut id_pk_bytes).await.unwrap();
-
-        let server_pk = aegis_crypto::HybridPublicKey::from_bytes(&pk_bytes).unwrap();
-        let (ciphertext, client_channel) = client_handshake
-            .client_complete(&server_pk, &id_pk_bytes, &signature)
-            .unwrap();
-
-        let ct_bytes = ciphertext.to_bytes();
-        client
-            .write_all(&(ct_bytes.len() as u32).to_be_bytes())
-            .await
-            .unwrap();
-        client.write_all(&ct_bytes).await.unwrap();
-
-        // 🔒 Data Plane
-        let key = client_channel.send_key().as_bytes();
-        let encrypted_client = EncryptedStream::new(client, key);
-
-        // Wrap in TokioIo
-        let io = get_tokio_io(encrypted_client);
-
-        // Initiate HTTP/2 Client Handshake over Encrypted Stream
-        let (mut request_sender, connection) =
-            hyper::client::conn::http2::handshake(TokioExecutor, io)
-                .await
-                .unwrap();
-
-        // Spawn connection driver
-        tokio::spawn(async move {
-            if let Err(e) = connection.await {
-                // Connection closed or error
-                println!("Connection error: {:?}", e);
-            }
-        });
-
-        // Send HTTP/2 Request
-        let request = Request::builder()
-            .method("GET")
-            .uri("http://localhost/health")
-            .body(Full::new(Bytes::default()))
-            .unwrap();
-
-        let response = request_sender.send_request(request).await.unwrap();
+        })
+        .await
+        .unwrap();
 
+        // HTTP/2 over the encrypted stream
+        let response = server.get("/health").await.unwrap();
         assert!(response.status().is_success());
 
-        // Cleanup
-        tx.send(()).unwrap();
+        server.shutdown().await.unwrap();
     }
 
     #[tokio::test]
@@ -431,14 +339,8 @@ mod tests {
         // Client connects
         let mut stream = TcpStream::connect(addr).await.unwrap();
 
-        // Read Server PK Length (4 bytes)
-        let mut buf = [0u8; 4];
-        stream.read_exact(&mut buf).await.unwrap();
-        let pk_len = u32::from_be_bytes(buf);
-
-        // Read Server PK
-        let mut pk_buf = vec![0u8; pk_len as usize];
-        stream.read_exact(&mut pk_buf).await.unwrap();
+        // Read the server hello
+        ServerHello::read_from(&mut stream).await.unwrap();
 
         // Send Invalid Ciphertext Length (> 10240)
         let invalid_len = 10_241u32;
@@ -498,12 +400,8 @@ mod tests {
 
         let mut stream = TcpStream::connect(addr).await.unwrap();
 
-        // Read PK
-        let mut buf = [0u8; 4];
-        stream.read_exact(&mut buf).await.unwrap();
-        let pk_len = u32::from_be_bytes(buf);
-        let mut pk_buf = vec![0u8; pk_len as usize];
-        stream.read_exact(&mut pk_buf).await.unwrap();
+        // Read the server hello
+        ServerHello::read_from(&mut stream).await.unwrap();
 
         // Send Valid Length but MALFORMED Ciphertext
         let ct_len = 100u32;
@@ -671,12 +569,8 @@ mod tests {
 
         // Connect and send an oversized ciphertext length
         let mut stream = TcpStream::connect(addr).await.unwrap();
-        // Read the PK length and PK first
-        let mut pk_len_bytes = [0u8; 4];
-        let _ = stream.read_exact(&mut pk_len_bytes).await;
-        let pk_len = u32::from_be_bytes(pk_len_bytes) as usize;
-        let mut pk_bytes = vec![0u8; pk_len];
-        let _ = stream.read_exact(&mut pk_bytes).await;
+        // Read the server hello first
+        ServerHello::read_from(&mut stream).await.unwrap();
 
         // Send an oversized ciphertext length (> 10000 bytes)
         let fake_ct_len: u32 = 15000;
@@ -712,6 +606,23 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_pqc_server_port_in_use_yields_bind_error() {
+        let occupied = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let config = ProxyConfig {
+            host: "127.0.0.1".to_string(),
+            port: occupied.local_addr().unwrap().port(),
+            listener: crate::config::ListenerConfig {
+                bind_retries: 0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let server = PqcProxyServer::new(config);
+        let result = server.run().await;
+        assert!(matches!(result, Err(ProxyError::Bind { .. })));
+    }
+
     #[test]
     fn test_pqc_server_with_default_config() {
         let config = ProxyConfig::default();
@@ -799,12 +710,8 @@ mod tests {
 
         let mut stream = TcpStream::connect(addr).await.unwrap();
 
-        // Read PK length and PK
-        let mut pk_len_bytes = [0u8; 4];
-        stream.read_exact(&mut pk_len_bytes).await.unwrap();
-        let pk_len = u32::from_be_bytes(pk_len_bytes) as usize;
-        let mut pk_bytes = vec![0u8; pk_len];
-        stream.read_exact(&mut pk_bytes).await.unwrap();
+        // Read the server hello
+        ServerHello::read_from(&mut stream).await.unwrap();
 
         // Send valid ciphertext length (e.g., 500 bytes)
         let ct_len: u32 = 500;
@@ -843,12 +750,8 @@ mod tests {
 
         let mut stream = TcpStream::connect(addr).await.unwrap();
 
-        // Read PK length and PK
-        let mut pk_len_bytes = [0u8; 4];
-        stream.read_exact(&mut pk_len_bytes).await.unwrap();
-        let pk_len = u32::from_be_bytes(pk_len_bytes) as usize;
-        let mut pk_bytes = vec![0u8; pk_len];
-        stream.read_exact(&mut pk_bytes).await.unwrap();
+        // Read the server hello
+        ServerHello::read_from(&mut stream).await.unwrap();
 
         // Send only 2 bytes of ciphertext length instead of 4
         stream.write_all(&[0x00, 0x01]).await.unwrap();
@@ -893,49 +796,8 @@ mod tests {
 
         tokio::time::sleep(Duration::from_millis(50)).await;
 
-        let mut client = TcpStream::connect(addr).await.unwrap();
-        let client_handshake = PqcHandshake::new(PqcTlsConfig::default());
-
-        // Send handshake from server to client
-        let mut pk_len_bytes = [0u8; 4];
-        client.read_exact(&mut pk_len_bytes).await.unwrap();
-        let pk_len = u32::from_be_bytes(pk_len_bytes) as usize;
-        let mut pk_bytes = vec![0u8; pk_len];
-        client.read_exact(&mut pk_bytes).await.unwrap();
-
-        // Receive server signature
-        let mut sig_len_bytes = [0u8; 4];
-        client.read_exact(&mut sig_len_bytes).await.unwrap();
-        let sig_len = u32::from_be_bytes(sig_len_bytes) as usize;
-        let mut sig_bytes = vec![0u8; sig_len];
-        client.read_exact(&mut sig_bytes).await.unwrap();
-        let signature = aegis_crypto::signing::MlDsaSignature::new(
-            sig_bytes,
-            aegis_crypto::signing::MlDsaAlgorithm::MlDsa65,
-        );
-
-        // Receive server identity PK
-        let mut id_pk_len_bytes = [0u8; 4];
-        client.read_exact(&mut id_pk_len_bytes).await.unwrap();
-        let id_pk_len = u32::from_be_bytes(id_pk_len_bytes) as usize;
-        let mut id_pk_bytes = vec![0u8; id_pk_len];
-        client.read_exact(&mut id_pk_bytes).await.unwrap();
-
-        let server_pk = aegis_crypto::HybridPublicKey::from_bytes(&pk_bytes).unwrap();
-        let (ciphertext, client_channel) = client_handshake
-            .client_complete(&server_pk, &id_pk_bytes, &signature)
-            .unwrap();
-
-        let ct_bytes = ciphertext.to_bytes();
-        client
-            .write_all(&(ct_bytes.len() as u32).to_be_bytes())
-            .await
-            .unwrap();
-        client.write_all(&ct_bytes).await.unwrap();
-
-        // Setup encrypted stream
-        let key = client_channel.send_key().as_bytes();
-        let encrypted_client = EncryptedStream::new(client, key);
+        let client = TcpStream::connect(addr).await.unwrap();
+        let encrypted_client = PqcClient::default().connect(client).await.unwrap();
         let io = get_tokio_io(encrypted_client);
 
         // Initiate HTTP/2 connection
@@ -966,104 +828,18 @@ mod tests {
     #[tokio::test]
     async fn test_pqc_handshake_complete_success_path() {
         // Test complete successful handshake path (covers line 125 channel_id logging)
-        use crate::http_proxy::TokioExecutor;
-        use bytes::Bytes;
-        use http_body_util::Full;
-        use hyper::Request;
+        use crate::test_harness::PqcTestServer;
 
-        let config = ProxyConfig {
-            host: "127.0.0.1".to_string(),
-            port: 0,
-            pqc_enabled: true,
-            upstream_addr: "127.0.0.1:8080".to_string(),
-            ..Default::default()
-        };
-
-        let listener = TcpListener::bind(format!("{}:{}", config.host, config.port))
-            .await
-            .unwrap();
-        let addr = listener.local_addr().unwrap();
-
-        let server = Arc::new(PqcProxyServer::new(config.clone()));
-        let (tx, rx) = tokio::sync::oneshot::channel();
-
-        let server_clone = Arc::clone(&server);
-        tokio::spawn(async move {
-            server_clone
-                .run_with_listener(listener, async {
-                    rx.await.ok();
-                })
-                .await
-        });
-
-        tokio::time::sleep(Duration::from_millis(50)).await;
-
-        let mut client = TcpStream::connect(addr).await.unwrap();
-        let client_handshake = PqcHandshake::new(PqcTlsConfig::default());
-
-        // Send handshake from server to client
-        let mut pk_len_bytes = [0u8; 4];
-        client.read_exact(&mut pk_len_bytes).await.unwrap();
-        let pk_len = u32::from_be_bytes(pk_len_bytes) as usize;
-        let mut pk_bytes = vec![0u8; pk_len];
-        client.read_exact(&mut pk_bytes).await.unwrap();
-
-        // Receive server signature
-        let mut sig_len_bytes = [0u8; 4];
-        client.read_exact(&mut sig_len_bytes).await.unwrap();
-        let sig_len = u32::from_be_bytes(sig_len_bytes) as usize;
-        let mut sig_bytes = vec![0u8; sig_len];
-        client.read_exact(&mut sig_bytes).await.unwrap();
-        let signature = aegis_crypto::signing::MlDsaSignature::new(
-            sig_bytes,
-            aegis_crypto::signing::MlDsaAlgorithm::MlDsa65,
+        let server = PqcTestServer::start().await.unwrap();
+        assert_eq!(
+            server.identity_key().len(),
+            aegis_crypto::signing::MlDsaAlgorithm::MlDsa65.public_key_size()
         );
 
-        // Receive server identity PK
-        let mut id_pk_len_bytes = [0u8; 4];
-        client.read_exact(&mut id_pk_len_bytes).await.unwrap();
-        let id_pk_len = u32::from_be_bytes(id_pk_len_bytes) as usize;
-        let mut id_pk_bytes = vec![0u8; id_pk_len];
-        client.read_exact(&mut id_pk_bytes).await.unwrap();
-
-        let server_pk = aegis_crypto::HybridPublicKey::from_bytes(&pk_bytes).unwrap();
-        let (ciphertext, client_channel) = client_handshake
-            .client_complete(&server_pk, &id_pk_bytes, &signature)
-            .unwrap();
-
-        let ct_bytes = ciphertext.to_bytes();
-        client
-            .write_all(&(ct_bytes.len() as u32).to_be_bytes())
-            .await
-            .unwrap();
-        client.write_all(&ct_bytes).await.unwrap();
-
-        // Setup encrypted stream
-        let key = client_channel.send_key().as_bytes();
-        let encrypted_client = EncryptedStream::new(client, key);
-        let io = get_tokio_io(encrypted_client);
-
-        // Initiate HTTP/2 connection
-        let (mut request_sender, connection) =
-            hyper::client::conn::http2::handshake(TokioExecutor, io)
-                .await
-                .unwrap();
-
-        tokio::spawn(async move {
-            let _ = connection.await;
-        });
-
-        // Send health request
-        let request = Request::builder()
-            .method("GET")
-            .uri("http://localhost/health")
-            .body(Full::new(Bytes::default()))
-            .unwrap();
-
-        let response = request_sender.send_request(request).await.unwrap();
+        let response = server.get("/health").await.unwrap();
         assert!(response.status().is_success());
 
-        tx.send(()).unwrap();
+        server.shutdown().await.unwrap();
     }
 
     #[tokio::test]
@@ -1131,12 +907,8 @@ mod tests {
 
         let mut stream = TcpStream::connect(addr).await.unwrap();
 
-        // Read PK length and PK
-        let mut pk_len_bytes = [0u8; 4];
-        stream.read_exact(&mut pk_len_bytes).await.unwrap();
-        let pk_len = u32::from_be_bytes(pk_len_bytes) as usize;
-        let mut pk_bytes = vec![0u8; pk_len];
-        stream.read_exact(&mut pk_bytes).await.unwrap();
+        // Read the server hello
+        ServerHello::read_from(&mut stream).await.unwrap();
 
         // Send ciphertext length of 20 bytes (less than required 32 bytes for HybridCiphertext)
         let ct_len: u32 = 20;
@@ -1179,49 +951,8 @@ mod tests {
 
         tokio::time::sleep(Duration::from_millis(50)).await;
 
-        let mut client = TcpStream::connect(addr).await.unwrap();
-        let client_handshake = PqcHandshake::new(PqcTlsConfig::default());
-
-        // Send handshake from server to client
-        let mut pk_len_bytes = [0u8; 4];
-        client.read_exact(&mut pk_len_bytes).await.unwrap();
-        let pk_len = u32::from_be_bytes(pk_len_bytes) as usize;
-        let mut pk_bytes = vec![0u8; pk_len];
-        client.read_exact(&mut pk_bytes).await.unwrap();
-
-        // Receive server signature
-        let mut sig_len_bytes = [0u8; 4];
-        client.read_exact(&mut sig_len_bytes).await.unwrap();
-        let sig_len = u32::from_be_bytes(sig_len_bytes) as usize;
-        let mut sig_bytes = vec![0u8; sig_len];
-        client.read_exact(&mut sig_bytes).await.unwrap();
-        let signature = aegis_crypto::signing::MlDsaSignature::new(
-            sig_bytes,
-            aegis_crypto::signing::MlDsaAlgorithm::MlDsa65,
-        );
-
-        // Receive server identity PK
-        let mut id_pk_len_bytes = [0u8; 4];
-        client.read_exact(&mut id_pk_len_bytes).await.unwrap();
-        let id_pk_len = u32::from_be_bytes(id_pk_len_bytes) as usize;
-        let mut id_pk_bytes = vec![0u8; id_pk_len];
-        client.read_exact(&mut id_pk_bytes).await.unwrap();
-
-        let server_pk = aegis_crypto::HybridPublicKey::from_bytes(&pk_bytes).unwrap();
-        let (ciphertext, client_channel) = client_handshake
-            .client_complete(&server_pk, &id_pk_bytes, &signature)
-            .unwrap();
-
-        let ct_bytes = ciphertext.to_bytes();
-        client
-            .write_all(&(ct_bytes.len() as u32).to_be_bytes())
-            .await
-            .unwrap();
-        client.write_all(&ct_bytes).await.unwrap();
-
-        // Setup encrypted stream
-        let key = client_channel.send_key().as_bytes();
-        let mut encrypted_client = EncryptedStream::new(client, key);
+        let client = TcpStream::connect(addr).await.unwrap();
+        let mut encrypted_client = PqcClient::default().connect(client).await.unwrap();
 
         // Send INVALID HTTP/2 connection preface (random garbage)
         encrypted_client
@@ -1236,4 +967,153 @@ mod tests {
         // Cleanup
         tx.send(()).unwrap();
     }
+
+    #[tokio::test]
+    async fn test_pqc_handshake_timeout_closes_stalled_connection() {
+        let config = ProxyConfig {
+            host: "127.0.0.1".to_string(),
+            port: 0,
+            handshake_timeout_ms: 100,
+            ..Default::default()
+        };
+        let server = PqcProxyServer::new(config);
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            server
+                .run_with_listener(listener, std::future::pending())
+                .await
+                .ok();
+        });
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        ServerHello::read_from(&mut stream).await.unwrap();
+
+        // Announce a ciphertext but never send it
+        let started = tokio::time::Instant::now();
+        stream.write_all(&1120u32.to_be_bytes()).await.unwrap();
+
+        // The handshake task gives up and drops the socket
+        let mut buf = [0u8; 1];
+        let n = timeout(Duration::from_secs(5), stream.read(&mut buf))
+            .await
+            .expect("server kept the stalled connection open")
+            .unwrap();
+        assert_eq!(n, 0);
+        assert!(started.elapsed() >= Duration::from_millis(90));
+    }
+
+    #[derive(Clone, Default)]
+    struct CaptureWriter(Arc<parking_lot::Mutex<Vec<u8>>>);
+
+    impl std::io::Write for CaptureWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> tracing_subscriber::fmt::MakeWriter<'a> for CaptureWriter {
+        type Writer = Self;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_handshake_emits_connection_established() {
+        let capture = CaptureWriter::default();
+        let subscriber = tracing_subscriber::fmt()
+            .json()
+            .with_writer(capture.clone())
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let audit_path =
+            std::env::temp_dir().join(format!("aegis-pqc-audit-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&audit_path);
+        let mut config = ProxyConfig::default();
+        config.logging.audit_log = Some(audit_path.to_string_lossy().into_owned());
+
+        let server = PqcProxyServer::new(config);
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            server
+                .run_with_listener(listener, std::future::pending())
+                .await
+                .ok();
+        });
+
+        let socket = TcpStream::connect(addr).await.unwrap();
+        let peer = socket.local_addr().unwrap();
+        let _stream = PqcClient::default().connect(socket).await.unwrap();
+
+        let line = timeout(Duration::from_secs(5), async {
+            loop {
+                let output = String::from_utf8(capture.0.lock().clone()).unwrap();
+                if let Some(line) = output
+                    .lines()
+                    .find(|l| l.contains("\"event\":\"connection_established\""))
+                {
+                    return line.to_string();
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("connection_established event");
+
+        assert!(line.contains(&format!("\"peer_addr\":\"{}\"", peer)));
+        assert!(line.contains("\"algorithm\":\"HybridMlKem768\""));
+        assert!(line.contains("\"cipher\":\"Aes256Gcm\""));
+        assert!(line.contains("\"channel_id\":"));
+        assert!(line.contains("\"client_auth\":false"));
+
+        let audit = std::fs::read_to_string(&audit_path).unwrap();
+        assert!(audit.contains(&format!("\"peer_addr\":\"{}\"", peer)));
+        std::fs::remove_file(&audit_path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_pqc_connection_marked_encrypted() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // Upstream handing the forwarded request head back to the test
+        let upstream = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let upstream_addr = upstream.local_addr().unwrap().to_string();
+        let (head_tx, head_rx) = tokio::sync::oneshot::channel();
+        tokio::spawn(async move {
+            let (mut stream, _) = upstream.accept().await.unwrap();
+            let mut buf = [0u8; 4096];
+            let n = stream.read(&mut buf).await.unwrap();
+            head_tx
+                .send(String::from_utf8_lossy(&buf[..n]).to_lowercase())
+                .ok();
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")
+                .await
+                .unwrap();
+        });
+
+        let server = crate::test_harness::PqcTestServer::start_with(ProxyConfig {
+            pqc_enabled: true,
+            upstream_addr,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        let response = server.get("/api").await.unwrap();
+        assert_eq!(response.status(), 200);
+
+        let head = head_rx.await.unwrap();
+        assert!(head.contains("x-forwarded-proto: https"), "{head}");
+        server.shutdown().await.unwrap();
+    }
 }
@@ -12,6 +12,21 @@ use tokio::sync::RwLock;
 use tracing::{debug, error, info, instrument, warn};
 
 use crate::config::ProxyConfig;
+use crate::http3_handler::Http3Response;
+
+/// Largest request accepted on a raw QUIC stream, head and body together
+const MAX_REQUEST_BYTES: usize = 16 * 1024 * 1024;
+
+/// Allowance for the request line on top of the header size limit when
+/// looking for the end of a streamed request head
+const MAX_REQUEST_LINE_BYTES: usize = 8 * 1024;
+
+/// Size of each body read when streaming a request upstream
+const BODY_CHUNK_BYTES: usize = 16 * 1024;
+
+/// Body chunks buffered per stream before reading pauses, leaving QUIC flow
+/// control to slow the client down
+const BODY_CHANNEL_CHUNKS: usize = 8;
 
 /// QUIC server configuration
 #[derive(Debug, Clone)]
@@ -71,9 +86,20 @@ impl QuicServer {
     /// Create a new QUIC server
     pub fn new(config: QuicConfig, proxy_config: ProxyConfig) -> Self {
         let handler = crate::http3_handler::Http3Handler::new(
-            crate::http3_handler::Http3Config::default(),
+            crate::http3_handler::Http3Config {
+                max_header_bytes: proxy_config.max_header_bytes,
+                ..Default::default()
+            },
             proxy_config.upstream_addr.clone(),
-        );
+        )
+        .with_routes(
+            crate::route::RouteTable::new(proxy_config.routes.clone()).with_default_timeout(
+                proxy_config
+                    .request_timeout_ms
+                    .map(std::time::Duration::from_millis),
+            ),
+        )
+        .with_cors(proxy_config.cors.clone());
         Self {
             config,
             proxy_config,
@@ -147,10 +173,17 @@ impl QuicServer {
             info!("🛡️ PQC: Hybrid ML-KEM-768+X25519 configured in TLS layer");
         }
 
+        let socket =
+            crate::listener::bind_udp(&self.config.bind_address, &self.proxy_config.listener)
+                .await?;
+        let io = s2n_quic::provider::io::tokio::Builder::default()
+            .with_rx_socket(socket)?
+            .build()?;
+
         // Build the QUIC server
         let server = Server::builder()
             .with_tls(tls)?
-            .with_io(self.config.bind_address.as_str())?
+            .with_io(io)?
             .with_limits(limits)?
             .start()
             .map_err(|e| anyhow::anyhow!("Failed to start QUIC server: {}", e))?;
@@ -238,14 +271,18 @@ impl QuicServer {
         h3_handler: Arc<crate::http3_handler::Http3Handler>,
         stats: Arc<RwLock<QuicStats>>,
     ) -> Result<()> {
-        let mut h3_conn =
-            match h3::server::Connection::new(crate::h3_adapter::S2nConnection(connection)).await {
-                Ok(c) => c,
-                Err(e) => {
-                    warn!("HTTP/3 connection error: {}", e);
-                    return Err(anyhow::anyhow!("HTTP/3 connection error"));
-                }
-            };
+        // h3 answers header sections over the limit with 431 itself
+        let mut h3_conn = match h3::server::builder()
+            .max_field_section_size(h3_handler.max_header_bytes() as u64)
+            .build(crate::h3_adapter::S2nConnection(connection))
+            .await
+        {
+            Ok(c) => c,
+            Err(e) => {
+                warn!("HTTP/3 connection error: {}", e);
+                return Err(anyhow::anyhow!("HTTP/3 connection error"));
+            }
+        };
 
         // Accept HTTP/3 requests from the connection
         loop {
@@ -333,7 +370,13 @@ impl QuicServer {
         let response = handler.handle_request(request).await;
 
         let status = http::StatusCode::from_u16(response.status).unwrap_or(http::StatusCode::OK);
-        let h3_resp = http::Response::builder().status(status).body(()).unwrap();
+        let mut h3_resp = http::Response::builder().status(status);
+        for (name, value) in response.wire_headers() {
+            h3_resp = h3_resp.header(name, value);
+        }
+        let h3_resp = h3_resp
+            .body(())
+            .map_err(|e| anyhow::anyhow!("h3 resp err: {:?}", e))?;
 
         send_stream
             .send_response(h3_resp)
@@ -379,10 +422,11 @@ impl QuicServer {
     }
 
     #[allow(dead_code)]
-    /// Handle a single bidirectional stream with HTTP/3 handler
+    /// Handle a single bidirectional stream with HTTP/3 handler, streaming
+    /// the request body upstream
     async fn handle_stream(stream: BidirectionalStream, upstream: String) -> Result<()> {
         let (recv, send) = stream.split();
-        Self::process_stream(recv, send, upstream).await
+        Self::process_stream_streaming(recv, send, upstream).await
     }
 
     #[allow(dead_code)]
@@ -393,7 +437,7 @@ impl QuicServer {
         W: tokio::io::AsyncWrite + Unpin,
     {
         use crate::http3_handler::{Http3Config, Http3Handler, Http3Request};
-        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio::io::AsyncReadExt;
 
         // Create HTTP/3 handler
         let handler = Http3Handler::new(Http3Config::default(), upstream);
@@ -412,7 +456,7 @@ impl QuicServer {
             request_data.extend_from_slice(&buf[..n]);
 
             // Limit request size
-            if request_data.len() > 16 * 1024 * 1024 {
+            if request_data.len() > MAX_REQUEST_BYTES {
                 warn!("Request too large, dropping");
                 return Ok(());
             }
@@ -420,33 +464,165 @@ impl QuicServer {
 
         debug!("📨 Received {} bytes request", request_data.len());
 
-        // Parse simple HTTP-like request (method, path)
-        // For now, parse first line as "METHOD /path"
-        let request_str = String::from_utf8_lossy(&request_data);
-        let mut lines = request_str.lines();
-        let first_line = lines.next().unwrap_or("GET /");
-        let mut parts = first_line.split_whitespace();
-        let method = parts.next().unwrap_or("GET");
-        let path = parts.next().unwrap_or("/");
+        // Malformed or non-UTF-8 request heads get 400 rather than a guess
+        let response = match Http3Request::parse(&request_data) {
+            Ok(request) => handler.handle_request(request).await,
+            Err(e) => {
+                warn!("⚠️ Rejecting malformed HTTP/3 request: {}", e);
+                crate::metrics::record_error("bad_request");
+                Http3Response::new(400).with_body("Bad Request")
+            }
+        };
 
-        // Create HTTP/3 request
-        let request = Http3Request::new(method, path);
+        Self::write_response(&mut send, response).await
+    }
 
-        // Handle request
-        let response = handler.handle_request(request).await;
+    #[allow(dead_code)]
+    /// Process a stream, forwarding the request body as it arrives
+    ///
+    /// Only the request head is buffered. Body chunks pass through a bounded
+    /// channel to the upstream request, so when the upstream reads slower
+    /// than the client sends, the stream stops being read and QUIC flow
+    /// control pushes back on the client.
+    async fn process_stream_streaming<R, W>(
+        mut recv: R,
+        mut send: W,
+        upstream: String,
+    ) -> Result<()>
+    where
+        R: tokio::io::AsyncRead + Unpin,
+        W: tokio::io::AsyncWrite + Unpin,
+    {
+        use crate::http3_handler::{Http3Config, Http3Handler, Http3Request};
+        use tokio::io::AsyncReadExt;
 
-        // Send response status line
-        let status_line = format!("HTTP/3 {} OK\r\n\r\n", response.status);
-        send.write_all(status_line.as_bytes()).await?;
+        let handler = Http3Handler::new(Http3Config::default(), upstream);
+        let max_head_bytes = handler.max_header_bytes() + MAX_REQUEST_LINE_BYTES;
 
-        // Send response body
-        if !response.body.is_empty() {
-            send.write_all(response.body.as_bytes().unwrap_or(&[]))
-                .await?;
+        // Read until the blank line ending the head
+        let mut head = Vec::with_capacity(4096);
+        let mut buf = [0u8; 4096];
+        let head_end = loop {
+            if let Some(pos) = head.windows(4).position(|w| w == b"\r\n\r\n") {
+                break Some(pos + 4);
+            }
+            if head.len() > max_head_bytes {
+                warn!(
+                    "⚠️ Rejecting HTTP/3 request head over {} bytes",
+                    max_head_bytes
+                );
+                let response = Http3Response::new(431).with_body("Request Header Fields Too Large");
+                return Self::write_response(&mut send, response).await;
+            }
+            let n = recv.read(&mut buf).await?;
+            if n == 0 {
+                break None;
+            }
+            head.extend_from_slice(&buf[..n]);
+        };
+        let body_start = head_end.map(|end| head.split_off(end)).unwrap_or_default();
+
+        let request = match Http3Request::parse(&head) {
+            Ok(request) => request,
+            Err(e) => {
+                warn!("⚠️ Rejecting malformed HTTP/3 request: {}", e);
+                crate::metrics::record_error("bad_request");
+                let response = Http3Response::new(400).with_body("Bad Request");
+                return Self::write_response(&mut send, response).await;
+            }
+        };
+        if head_end.is_none() {
+            // The stream ended inside the head, so there is no body
+            let response = handler.handle_request(request).await;
+            return Self::write_response(&mut send, response).await;
         }
 
-        // Ensure flushed
-        send.flush().await?;
+        let (tx, rx) = tokio::sync::mpsc::channel(BODY_CHANNEL_CHUNKS);
+        let request = request.with_stream_body(rx);
+        let pump = async move {
+            let mut total = head.len() + body_start.len();
+            if !body_start.is_empty() && tx.send(Ok(bytes::Bytes::from(body_start))).await.is_err()
+            {
+                return Ok(());
+            }
+            let mut chunk = vec![0u8; BODY_CHUNK_BYTES];
+            loop {
+                let n = match recv.read(&mut chunk).await {
+                    Ok(0) => return Ok(()),
+                    Ok(n) => n,
+                    Err(e) => {
+                        let _ = tx.send(Err(e.to_string().into())).await;
+                        return Err(e);
+                    }
+                };
+                total += n;
+                if total > MAX_REQUEST_BYTES {
+                    warn!("Request too large, aborting upstream request");
+                    let _ = tx.send(Err("request body too large".into())).await;
+                    return Ok(());
+                }
+                // A closed channel means the handler is done with the body
+                let data = bytes::Bytes::copy_from_slice(&chunk[..n]);
+                if tx.send(Ok(data)).await.is_err() {
+                    return Ok(());
+                }
+            }
+        };
+
+        // The handler may answer before the whole body has arrived (or
+        // without reading it at all), so only a read error ends the wait
+        let handle = handler.handle_request(request);
+        tokio::pin!(handle, pump);
+        let mut pump_done = false;
+        let response = loop {
+            tokio::select! {
+                response = &mut handle => break response,
+                result = &mut pump, if !pump_done => {
+                    pump_done = true;
+                    result?;
+                }
+            }
+        };
+
+        Self::write_response(&mut send, response).await
+    }
+
+    /// Write a status line and body to a raw QUIC stream
+    async fn write_response<W>(send: &mut W, response: Http3Response) -> Result<()>
+    where
+        W: tokio::io::AsyncWrite + Unpin,
+    {
+        use crate::http3_handler::HttpBodyType;
+        use tokio::io::AsyncWriteExt;
+
+        let reason = hyper::StatusCode::from_u16(response.status)
+            .ok()
+            .and_then(|status| status.canonical_reason())
+            .unwrap_or("Unknown");
+        let mut head = format!("HTTP/3 {} {}\r\n", response.status, reason);
+        for (name, value) in response.wire_headers() {
+            head.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        head.push_str("\r\n");
+        send.write_all(head.as_bytes()).await?;
+
+        match response.body {
+            HttpBodyType::Bytes(b) => send.write_all(&b).await?,
+            HttpBodyType::Stream(mut rx) => {
+                while let Some(chunk) = rx.recv().await {
+                    match chunk {
+                        Ok(b) => send.write_all(&b).await?,
+                        Err(e) => {
+                            warn!("Upstream body error: {}", e);
+                            break;
+                        }
+                    }
+                }
+            }
+            HttpBodyType::Empty => {}
+        }
+        // Close our side so the client sees the end of the response
+        send.shutdown().await?;
 
         debug!("✅ Response sent with status {}", response.status);
         Ok(())
@@ -671,6 +847,27 @@ mod tests {
         // Status might be 200 or 502 depending on "backend" connectivity, but it should output a response
     }
 
+    #[tokio::test]
+    async fn test_process_stream_rejects_oversized_headers() {
+        let oversized = "a".repeat(crate::config::DEFAULT_MAX_HEADER_BYTES + 1);
+        let request = format!("GET /health HTTP/1.1\r\nX-Big: {}\r\n\r\n", oversized);
+        let mut recv = std::io::Cursor::new(request.into_bytes());
+        let mut send = Vec::new();
+
+        QuicServer::process_stream(&mut recv, &mut send, "backend".to_string())
+            .await
+            .unwrap();
+        assert!(String::from_utf8(send).unwrap().starts_with("HTTP/3 431"));
+
+        let request = b"GET /health HTTP/1.1\r\nX-Small: ok\r\n\r\n";
+        let mut recv = std::io::Cursor::new(request);
+        let mut send = Vec::new();
+        QuicServer::process_stream(&mut recv, &mut send, "backend".to_string())
+            .await
+            .unwrap();
+        assert!(String::from_utf8(send).unwrap().starts_with("HTTP/3 200"));
+    }
+
     #[tokio::test]
     async fn test_process_stream_large_request() {
         // Create a reader that yields 17MB of data
@@ -873,6 +1070,31 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_process_stream_rejects_malformed_requests() {
+        let cases: [&[u8]; 5] = [
+            b"GET /\xff\xfe HTTP/3\r\n\r\n",
+            b"GET /health HTTP/3\r\nX-Name: \xc3\x28\r\n\r\n",
+            b"GET\r\n\r\n",
+            b"GET health HTTP/3\r\n\r\n",
+            b"GET /health HTTP/3\r\nno colon here\r\n\r\n",
+        ];
+        for request in cases {
+            let mut recv = std::io::Cursor::new(request);
+            let mut send = Vec::new();
+            QuicServer::process_stream(&mut recv, &mut send, "backend".to_string())
+                .await
+                .unwrap();
+            let response = String::from_utf8(send).unwrap();
+            assert!(
+                response.starts_with("HTTP/3 400 Bad Request"),
+                "{:?} -> {}",
+                String::from_utf8_lossy(request),
+                response
+            );
+        }
+    }
+
     #[tokio::test]
     async fn test_process_stream_garbage_data() {
         // Send garbage data that doesn't look like HTTP/3
@@ -1166,4 +1388,152 @@ mod tests {
 
         std::fs::remove_dir_all(cert_dir).unwrap();
     }
+
+    #[tokio::test]
+    async fn test_process_stream_streaming_forwards_body_incrementally() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // Upstream reporting when the first body chunk shows up, then
+        // answering once the chunked body is complete
+        let upstream = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let upstream_addr = upstream.local_addr().unwrap();
+        let (first_tx, first_rx) = tokio::sync::oneshot::channel();
+        tokio::spawn(async move {
+            let (mut stream, _) = upstream.accept().await.unwrap();
+            let mut received = Vec::new();
+            let mut buf = [0u8; 4096];
+            let mut first_tx = Some(first_tx);
+            while !received.ends_with(b"0\r\n\r\n") {
+                let n = stream.read(&mut buf).await.unwrap();
+                assert!(n > 0, "upstream request ended early");
+                received.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&received);
+                if text.contains("first-part")
+                    && let Some(tx) = first_tx.take()
+                {
+                    tx.send(()).unwrap();
+                }
+            }
+            assert!(String::from_utf8_lossy(&received).contains("second-part"));
+            stream
+                .write_all(
+                    b"HTTP/1.1 200 OK\r\ncontent-length: 8\r\nconnection: close\r\n\r\nreceived",
+                )
+                .await
+                .unwrap();
+        });
+
+        let (mut client, server) = tokio::io::duplex(64 * 1024);
+        let (recv, send) = tokio::io::split(server);
+        let proxy = tokio::spawn(QuicServer::process_stream_streaming(
+            recv,
+            send,
+            upstream_addr.to_string(),
+        ));
+
+        client
+            .write_all(b"POST /upload HTTP/1.1\r\nHost: localhost\r\n\r\nfirst-part")
+            .await
+            .unwrap();
+
+        // The upstream sees the first chunk while the client is still sending
+        tokio::time::timeout(Duration::from_secs(5), first_rx)
+            .await
+            .expect("first chunk was not forwarded before the body ended")
+            .unwrap();
+
+        client.write_all(b"second-part").await.unwrap();
+        client.shutdown().await.unwrap();
+
+        let mut response = Vec::new();
+        client.read_to_end(&mut response).await.unwrap();
+        proxy.await.unwrap().unwrap();
+        let response = String::from_utf8(response).unwrap();
+        assert!(response.starts_with("HTTP/3 200 OK"), "{response}");
+        assert!(response.ends_with("received"), "{response}");
+    }
+
+    #[tokio::test]
+    async fn test_process_stream_streaming_without_reading_body() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // Built-in routes answer without waiting for the client to finish
+        let (mut client, server) = tokio::io::duplex(64 * 1024);
+        let (recv, send) = tokio::io::split(server);
+        let proxy = tokio::spawn(QuicServer::process_stream_streaming(
+            recv,
+            send,
+            "backend".to_string(),
+        ));
+        client
+            .write_all(b"GET /health HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+
+        let mut response = Vec::new();
+        tokio::time::timeout(Duration::from_secs(5), client.read_to_end(&mut response))
+            .await
+            .unwrap()
+            .unwrap();
+        proxy.await.unwrap().unwrap();
+        assert!(
+            String::from_utf8(response)
+                .unwrap()
+                .starts_with("HTTP/3 200")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_process_stream_streaming_rejects_bad_heads() {
+        let oversized =
+            "a".repeat(crate::config::DEFAULT_MAX_HEADER_BYTES + MAX_REQUEST_LINE_BYTES + 1);
+        let request = format!("GET /health HTTP/1.1\r\nX-Big: {}\r\n\r\n", oversized);
+        let mut send = Vec::new();
+        QuicServer::process_stream_streaming(
+            std::io::Cursor::new(request.into_bytes()),
+            &mut send,
+            "backend".to_string(),
+        )
+        .await
+        .unwrap();
+        assert!(String::from_utf8(send).unwrap().starts_with("HTTP/3 431"));
+
+        let mut send = Vec::new();
+        QuicServer::process_stream_streaming(
+            std::io::Cursor::new(b"NOT A REQUEST\r\n\r\nbody".to_vec()),
+            &mut send,
+            "backend".to_string(),
+        )
+        .await
+        .unwrap();
+        assert!(String::from_utf8(send).unwrap().starts_with("HTTP/3 400"));
+    }
+
+    #[tokio::test]
+    async fn test_empty_response_is_framed_and_closed() {
+        use tokio::io::AsyncReadExt;
+
+        for (response, expected) in [
+            (Http3Response::new(204), "HTTP/3 204 No Content\r\n\r\n"),
+            (
+                Http3Response::new(200).with_header("x-empty", "1"),
+                "HTTP/3 200 OK\r\nx-empty: 1\r\ncontent-length: 0\r\n\r\n",
+            ),
+        ] {
+            let (mut client, server) = tokio::io::duplex(1024);
+            let (_server_recv, mut server_send) = tokio::io::split(server);
+            QuicServer::write_response(&mut server_send, response)
+                .await
+                .unwrap();
+
+            // The server half is still alive, so EOF means the response
+            // closed the stream rather than leaving it open
+            let mut received = Vec::new();
+            tokio::time::timeout(Duration::from_secs(1), client.read_to_end(&mut received))
+                .await
+                .expect("response stream was left open")
+                .unwrap();
+            assert_eq!(String::from_utf8(received).unwrap(), expected);
+        }
+    }
 }
@@ -0,0 +1,302 @@
+//! Per-Route Policy
+//!
+//! Routes attach method allowlists, request timeouts and an encryption
+//! requirement to path prefixes. Disallowed methods are answered with `405`
+//! and an `Allow` header, plaintext requests to encryption-only routes with
+//! `403`; requests that outlive their route's timeout (or the global one
+//! when the route sets none) are answered with `504`.
+
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper::{Response, StatusCode, header::ALLOW};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::connection_info::ConnectionInfo;
+use crate::metrics;
+
+/// Policy for requests under a path prefix
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RouteConfig {
+    /// Path prefix the route applies to
+    pub path: String,
+    /// Methods accepted on this route; empty allows every method
+    #[serde(default)]
+    pub allowed_methods: Vec<String>,
+    /// Request timeout in milliseconds, overriding the global timeout
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+    /// Only serve the route over TLS or the PQC stream
+    #[serde(default)]
+    pub require_encryption: bool,
+}
+
+impl RouteConfig {
+    /// Create a route for a path prefix with no restrictions
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Restrict the route to the given methods
+    pub fn with_allowed_methods(mut self, methods: &[&str]) -> Self {
+        self.allowed_methods = methods.iter().map(|m| m.to_uppercase()).collect();
+        self
+    }
+
+    /// Set the route's request timeout
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout_ms = Some(timeout.as_millis() as u64);
+        self
+    }
+
+    /// Refuse plaintext connections on this route
+    pub fn with_require_encryption(mut self) -> Self {
+        self.require_encryption = true;
+        self
+    }
+
+    /// Whether `method` may be used on this route
+    pub fn allows(&self, method: &str) -> bool {
+        self.allowed_methods.is_empty()
+            || self
+                .allowed_methods
+                .iter()
+                .any(|m| m.eq_ignore_ascii_case(method))
+    }
+
+    /// Value for the `Allow` header of a `405` response
+    pub fn allow_header(&self) -> String {
+        self.allowed_methods
+            .iter()
+            .map(|m| m.to_uppercase())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Routes matched by longest path prefix
+#[derive(Debug, Clone, Default)]
+pub struct RouteTable {
+    routes: Vec<RouteConfig>,
+    default_timeout: Option<Duration>,
+}
+
+impl RouteTable {
+    /// Create a table from route configurations
+    pub fn new(routes: Vec<RouteConfig>) -> Self {
+        Self {
+            routes,
+            default_timeout: None,
+        }
+    }
+
+    /// Timeout for requests whose route sets none
+    pub fn with_default_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.default_timeout = timeout;
+        self
+    }
+
+    /// Route with the longest prefix matching `path`
+    pub fn match_route(&self, path: &str) -> Option<&RouteConfig> {
+        self.routes
+            .iter()
+            .filter(|route| path.starts_with(route.path.as_str()))
+            .max_by_key(|route| route.path.len())
+    }
+
+    /// Check the method against the matched route
+    ///
+    /// Returns the `Allow` header value when the method is rejected.
+    pub fn check_method(&self, method: &str, path: &str) -> Result<(), String> {
+        match self.match_route(path) {
+            Some(route) if !route.allows(method) => Err(route.allow_header()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Build the `405` response if the method is not allowed on `path`
+    pub fn check_request(&self, method: &str, path: &str) -> Option<Response<Full<Bytes>>> {
+        let allow = self.check_method(method, path).err()?;
+        metrics::record_error("method_not_allowed");
+        Some(method_not_allowed_response(&allow))
+    }
+
+    /// Build the `403` response if `path` requires encryption and the
+    /// connection is not encrypted
+    ///
+    /// Requests without connection info are treated as plaintext.
+    pub fn check_connection(
+        &self,
+        path: &str,
+        connection: Option<&ConnectionInfo>,
+    ) -> Option<Response<Full<Bytes>>> {
+        let route = self.match_route(path)?;
+        if !route.require_encryption || connection.is_some_and(|c| c.encrypted) {
+            return None;
+        }
+        metrics::record_error("encryption_required");
+        Some(encryption_required_response())
+    }
+
+    /// Timeout applying to `path`: the route's own, else the global one
+    pub fn timeout_for(&self, path: &str) -> Option<Duration> {
+        self.match_route(path)
+            .and_then(|route| route.timeout_ms)
+            .map(Duration::from_millis)
+            .or(self.default_timeout)
+    }
+}
+
+/// Build a `405` response listing the permitted methods
+pub fn method_not_allowed_response(allow: &str) -> Response<Full<Bytes>> {
+    Response::builder()
+        .status(StatusCode::METHOD_NOT_ALLOWED)
+        .header(ALLOW, allow)
+        .header("content-type", "application/json")
+        .body(Full::new(Bytes::from(
+            "{\"error\":\"method_not_allowed\",\"message\":\"Method not allowed on this route\"}",
+        )))
+        .unwrap()
+}
+
+/// Build a `403` response for a plaintext request to an encryption-only route
+pub fn encryption_required_response() -> Response<Full<Bytes>> {
+    Response::builder()
+        .status(StatusCode::FORBIDDEN)
+        .header("content-type", "application/json")
+        .body(Full::new(Bytes::from(
+            "{\"error\":\"encryption_required\",\"message\":\"This route requires an encrypted connection\"}",
+        )))
+        .unwrap()
+}
+
+/// Build a `504` response for a request that exceeded its timeout
+pub fn gateway_timeout_response() -> Response<Full<Bytes>> {
+    Response::builder()
+        .status(StatusCode::GATEWAY_TIMEOUT)
+        .header("content-type", "application/json")
+        .body(Full::new(Bytes::from(
+            "{\"error\":\"gateway_timeout\",\"message\":\"Request timed out\"}",
+        )))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> RouteTable {
+        RouteTable::new(vec![
+            RouteConfig::new("/api").with_allowed_methods(&["get", "post"]),
+            RouteConfig::new("/api/reports")
+                .with_allowed_methods(&["GET"])
+                .with_timeout(Duration::from_millis(250)),
+            RouteConfig::new("/uploads").with_timeout(Duration::from_secs(120)),
+        ])
+        .with_default_timeout(Some(Duration::from_secs(30)))
+    }
+
+    #[test]
+    fn test_longest_prefix_wins() {
+        let table = table();
+        assert_eq!(table.match_route("/api/users").unwrap().path, "/api");
+        assert_eq!(
+            table.match_route("/api/reports/daily").unwrap().path,
+            "/api/reports"
+        );
+        assert!(table.match_route("/health").is_none());
+    }
+
+    #[test]
+    fn test_method_allowlist() {
+        let table = table();
+        assert!(table.check_method("GET", "/api/users").is_ok());
+        assert!(table.check_method("post", "/api/users").is_ok());
+        assert_eq!(
+            table.check_method("DELETE", "/api/users"),
+            Err("GET, POST".to_string())
+        );
+        assert_eq!(
+            table.check_method("POST", "/api/reports"),
+            Err("GET".to_string())
+        );
+        // Routes without an allowlist and unmatched paths accept anything
+        assert!(table.check_method("DELETE", "/uploads/a").is_ok());
+        assert!(table.check_method("DELETE", "/other").is_ok());
+
+        let response = table.check_request("PUT", "/api/users").unwrap();
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(response.headers()[ALLOW], "GET, POST");
+        assert!(table.check_request("GET", "/api/users").is_none());
+    }
+
+    #[test]
+    fn test_timeout_resolution() {
+        let table = table();
+        assert_eq!(
+            table.timeout_for("/api/reports"),
+            Some(Duration::from_millis(250))
+        );
+        assert_eq!(
+            table.timeout_for("/uploads/big"),
+            Some(Duration::from_secs(120))
+        );
+        // Routes without a timeout fall back to the global one
+        assert_eq!(
+            table.timeout_for("/api/users"),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(table.timeout_for("/other"), Some(Duration::from_secs(30)));
+        assert_eq!(RouteTable::default().timeout_for("/other"), None);
+    }
+
+    #[test]
+    fn test_route_config_deserialize() {
+        let route: RouteConfig = toml::from_str(
+            r#"
+path = "/reports"
+allowed_methods = ["GET", "HEAD"]
+timeout_ms = 500
+"#,
+        )
+        .unwrap();
+        assert_eq!(route.allowed_methods, vec!["GET", "HEAD"]);
+        assert_eq!(route.timeout_ms, Some(500));
+
+        let route: RouteConfig = toml::from_str("path = \"/\"").unwrap();
+        assert!(route.allowed_methods.is_empty());
+        assert!(route.timeout_ms.is_none());
+    }
+
+    #[test]
+    fn test_encryption_required_routes() {
+        let table = RouteTable::new(vec![
+            RouteConfig::new("/admin").with_require_encryption(),
+            RouteConfig::new("/admin/public"),
+        ]);
+        let plaintext = ConnectionInfo::plaintext();
+        let encrypted = ConnectionInfo::encrypted("TLS13_AES_128_GCM_SHA256");
+
+        let response = table
+            .check_connection("/admin/users", Some(&plaintext))
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert!(table.check_connection("/admin/users", None).is_some());
+        assert!(
+            table
+                .check_connection("/admin/users", Some(&encrypted))
+                .is_none()
+        );
+
+        // Longer prefixes without the requirement and unmatched paths are open
+        assert!(
+            table
+                .check_connection("/admin/public/x", Some(&plaintext))
+                .is_none()
+        );
+        assert!(table.check_connection("/health", None).is_none());
+    }
+}
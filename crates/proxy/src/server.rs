@@ -1,7 +1,9 @@
 //! TCP/UDP server implementation
 
 use crate::ProxyConfig;
-use anyhow::Result;
+use crate::error::ProxyError;
+use crate::lifecycle::{ConnectionGuard, LifecycleManager};
+use std::sync::Arc;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::TcpListener;
 use tracing::{error, info, instrument, warn};
@@ -11,9 +13,11 @@ use tokio::io::{AsyncRead, AsyncWrite};
 
 /// Run the proxy server with the given configuration
 #[instrument(skip(config))]
-pub async fn run(config: ProxyConfig) -> Result<()> {
-    let addr = format!("{}:{}", config.host, config.port);
-    let listener = TcpListener::bind(&addr).await?;
+pub async fn run(config: ProxyConfig) -> Result<(), ProxyError> {
+    let addr = crate::listener::join_host_port(&config.host, config.port);
+    let listener = crate::listener::bind_tcp(&addr, &config.listener)
+        .await
+        .map_err(|e| ProxyError::bind(&addr, e))?;
 
     info!("🎯 Aegis-Flow proxy is ready to accept connections");
 
@@ -24,8 +28,18 @@ pub async fn run(config: ProxyConfig) -> Result<()> {
 pub async fn run_with_listener(
     listener: TcpListener,
     shutdown: impl std::future::Future<Output = ()>,
-) -> Result<()> {
-    run_accept_loop(listener, shutdown).await
+) -> Result<(), ProxyError> {
+    run_accept_loop(listener, shutdown, None).await
+}
+
+/// Run with provided listener and shutdown signal, counting connections in
+/// `lifecycle` so a graceful shutdown waits for them to drain
+pub async fn run_with_lifecycle(
+    listener: TcpListener,
+    shutdown: impl std::future::Future<Output = ()>,
+    lifecycle: Arc<LifecycleManager>,
+) -> Result<(), ProxyError> {
+    run_accept_loop(listener, shutdown, Some(lifecycle)).await
 }
 
 /// Trait to abstract connection accepting for testing
@@ -44,10 +58,13 @@ impl ConnectionAcceptor for TcpListener {
 }
 
 /// Generic accept loop that works with any ConnectionAcceptor
+///
+/// With a `lifecycle`, each connection counts as active until it closes.
 pub async fn run_accept_loop<A>(
     mut acceptor: A,
     shutdown: impl std::future::Future<Output = ()>,
-) -> Result<()>
+    lifecycle: Option<Arc<LifecycleManager>>,
+) -> Result<(), ProxyError>
 where
     A: ConnectionAcceptor + Send,
 {
@@ -59,7 +76,11 @@ where
                 match accept_result {
                     Ok((socket, peer_addr)) => {
                         info!("📥 New connection from: {}", peer_addr);
-                        tokio::spawn(handle_connection(socket, peer_addr));
+                        let guard = lifecycle.clone().map(ConnectionGuard::new);
+                        tokio::spawn(async move {
+                            let _guard = guard;
+                            handle_connection(socket, peer_addr).await;
+                        });
                     }
                     Err(e) => {
                         warn!("⚠️ Accept error: {}", e);
@@ -257,6 +278,23 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_run_port_in_use_yields_bind_error() {
+        let occupied = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = occupied.local_addr().unwrap().port();
+        let config = ProxyConfig {
+            host: "127.0.0.1".to_string(),
+            port,
+            listener: crate::config::ListenerConfig {
+                bind_retries: 0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let result = run(config).await;
+        assert!(matches!(result, Err(ProxyError::Bind { .. })));
+    }
+
     #[tokio::test]
     async fn test_handle_connection_zero_bytes() {
         let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
@@ -370,7 +408,7 @@ mod tests {
 
         // Run for a short time to process the first error
         let result = tokio::select! {
-             res = run_accept_loop(acceptor, std::future::pending()) => res,
+             res = run_accept_loop(acceptor, std::future::pending(), None) => res,
              _ = tokio::time::sleep(Duration::from_millis(50)) => Ok(()),
         };
 
@@ -464,7 +502,7 @@ mod tests {
 
         // Run loop for a short time
         let result = tokio::select! {
-             res = run_accept_loop(acceptor, std::future::pending()) => res,
+             res = run_accept_loop(acceptor, std::future::pending(), None) => res,
              // Give enough time for the mock steps to run
              _ = tokio::time::sleep(Duration::from_millis(100)) => Ok(()),
         };
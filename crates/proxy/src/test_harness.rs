@@ -0,0 +1,172 @@
+//! In-process PQC proxy for integration tests
+//!
+//! Enabled by the `test-harness` feature. [`PqcTestServer::start`] runs a
+//! [`PqcProxyServer`] on an ephemeral localhost port with a freshly
+//! generated identity key, and hands out clients that pin that key, so a
+//! test can go from nothing to an encrypted HTTP/2 request in a few lines:
+//!
+//! ```no_run
+//! # async fn example() -> anyhow::Result<()> {
+//! use aegis_proxy::test_harness::PqcTestServer;
+//!
+//! let server = PqcTestServer::start().await?;
+//! let response = server.get("/health").await?;
+//! assert_eq!(response.status(), 200);
+//! server.shutdown().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::config::ProxyConfig;
+use crate::error::ProxyError;
+use crate::http_proxy::TokioExecutor;
+use crate::pqc_server::PqcProxyServer;
+use aegis_crypto::connection::PqcClient;
+use aegis_crypto::stream::EncryptedStream;
+use anyhow::Result;
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper::client::conn::http2::SendRequest;
+use hyper::{Request, Response, body::Incoming};
+use std::net::SocketAddr;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+/// PQC proxy running on an ephemeral localhost port
+///
+/// Stops when [`shutdown`](Self::shutdown) is called or the value is
+/// dropped.
+pub struct PqcTestServer {
+    addr: SocketAddr,
+    identity_key: Vec<u8>,
+    shutdown: Option<oneshot::Sender<()>>,
+    task: Option<JoinHandle<Result<(), ProxyError>>>,
+}
+
+impl PqcTestServer {
+    /// Start a server with the default configuration
+    pub async fn start() -> Result<Self> {
+        Self::start_with(ProxyConfig {
+            pqc_enabled: true,
+            ..Default::default()
+        })
+        .await
+    }
+
+    /// Start a server with `config`; its host and port are ignored in
+    /// favour of an ephemeral localhost port
+    pub async fn start_with(config: ProxyConfig) -> Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let server = PqcProxyServer::new(ProxyConfig {
+            host: addr.ip().to_string(),
+            port: addr.port(),
+            ..config
+        });
+        let identity_key = server.identity_public_key().to_vec();
+
+        let (shutdown, shutdown_rx) = oneshot::channel();
+        let task = tokio::spawn(async move {
+            server
+                .run_with_listener(listener, async {
+                    shutdown_rx.await.ok();
+                })
+                .await
+        });
+
+        Ok(Self {
+            addr,
+            identity_key,
+            shutdown: Some(shutdown),
+            task: Some(task),
+        })
+    }
+
+    /// Address the server is listening on
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// ML-DSA-65 public key the server signs its handshake with
+    pub fn identity_key(&self) -> &[u8] {
+        &self.identity_key
+    }
+
+    /// Open a connection and complete the PQC handshake, trusting only this
+    /// server's identity key
+    pub async fn connect(&self) -> Result<EncryptedStream<TcpStream>> {
+        let socket = TcpStream::connect(self.addr).await?;
+        let stream = PqcClient::default()
+            .with_trusted_identity(self.identity_key.clone())
+            .connect(socket)
+            .await?;
+        Ok(stream)
+    }
+
+    /// Open an encrypted HTTP/2 connection, driven in the background
+    pub async fn http2_client(&self) -> Result<SendRequest<Full<Bytes>>> {
+        let io = hyper_util::rt::TokioIo::new(self.connect().await?);
+        let (sender, connection) = hyper::client::conn::http2::handshake(TokioExecutor, io).await?;
+        tokio::spawn(async move {
+            let _ = connection.await;
+        });
+        Ok(sender)
+    }
+
+    /// Send a `GET` for `path` over a new connection
+    pub async fn get(&self, path: &str) -> Result<Response<Incoming>> {
+        let mut sender = self.http2_client().await?;
+        let request = Request::builder()
+            .method("GET")
+            .uri(format!("http://localhost{path}"))
+            .body(Full::new(Bytes::new()))?;
+        Ok(sender.send_request(request).await?)
+    }
+
+    /// Stop accepting connections and wait for the server loop to exit
+    pub async fn shutdown(mut self) -> Result<()> {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        if let Some(task) = self.task.take() {
+            task.await??;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for PqcTestServer {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_harness_health_request() {
+        let server = PqcTestServer::start().await.unwrap();
+        let response = server.get("/health").await.unwrap();
+        assert_eq!(response.status(), 200);
+        server.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_harness_pins_server_identity() {
+        let server = PqcTestServer::start().await.unwrap();
+        let other = PqcTestServer::start().await.unwrap();
+        assert_ne!(server.identity_key(), other.identity_key());
+
+        let socket = TcpStream::connect(server.addr()).await.unwrap();
+        let result = PqcClient::default()
+            .with_trusted_identity(other.identity_key().to_vec())
+            .connect(socket)
+            .await;
+        assert!(result.is_err());
+    }
+}
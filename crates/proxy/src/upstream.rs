@@ -89,6 +89,16 @@ fn default_cb_open_time() -> u64 {
     5000
 }
 
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            error_threshold_percent: default_cb_error_thresh(),
+            window_size_ms: default_cb_window(),
+            open_time_ms: default_cb_open_time(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpstreamGroup {
     pub name: String,
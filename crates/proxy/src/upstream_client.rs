@@ -1,6 +1,138 @@
-use reqwest::Client;
+use reqwest::{Client, ClientBuilder};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::sync::OnceLock;
 use std::time::Duration;
 
+use crate::error::ProxyError;
+
+/// Protocol the forwarding client speaks to the upstream
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpstreamProtocol {
+    /// HTTP/1.1 over plaintext TCP
+    #[default]
+    H1,
+    /// HTTP/2 with prior knowledge over plaintext TCP
+    H2c,
+    /// HTTP/2 over TLS
+    #[serde(rename = "h2-tls")]
+    H2Tls,
+    /// HTTP/3 over QUIC (no client transport is available yet)
+    H3,
+}
+
+impl UpstreamProtocol {
+    /// Name used in configuration and upstream targets
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::H1 => "h1",
+            Self::H2c => "h2c",
+            Self::H2Tls => "h2-tls",
+            Self::H3 => "h3",
+        }
+    }
+
+    /// URL scheme used to reach the upstream
+    pub fn url_scheme(&self) -> &'static str {
+        match self {
+            Self::H1 | Self::H2c => "http://",
+            Self::H2Tls | Self::H3 => "https://",
+        }
+    }
+
+    /// Upstream target handed to the forwarding path
+    ///
+    /// `addr` is prefixed with `<protocol>://` unless it already names a
+    /// scheme. Plain `h1` leaves the address untouched.
+    pub fn target(&self, addr: &str) -> String {
+        if *self == Self::H1 || addr.contains("://") {
+            addr.to_string()
+        } else {
+            format!("{}://{}", self.as_str(), addr)
+        }
+    }
+
+    /// Split a target built by [`UpstreamProtocol::target`] into its protocol and address
+    pub fn parse_target(upstream: &str) -> Option<(Self, &str)> {
+        let (scheme, addr) = upstream.split_once("://")?;
+        let protocol = match scheme {
+            "h1" => Self::H1,
+            "h2c" => Self::H2c,
+            "h2-tls" => Self::H2Tls,
+            "h3" => Self::H3,
+            _ => return None,
+        };
+        Some((protocol, addr))
+    }
+}
+
+/// Forwarding client bound to an upstream protocol
+#[derive(Debug, Clone)]
+pub struct UpstreamClient {
+    protocol: UpstreamProtocol,
+    client: Client,
+}
+
+impl UpstreamClient {
+    /// Build a client speaking `protocol`
+    pub fn new(protocol: UpstreamProtocol) -> Result<Self, ProxyError> {
+        let builder = match protocol {
+            UpstreamProtocol::H1 => ClientBuilder::new().http1_only(),
+            UpstreamProtocol::H2c => ClientBuilder::new().http2_prior_knowledge(),
+            UpstreamProtocol::H2Tls => ClientBuilder::new()
+                .https_only(true)
+                .http2_prior_knowledge(),
+            UpstreamProtocol::H3 => {
+                return Err(ProxyError::ConfigInvalid(
+                    "HTTP/3 upstreams are not supported by the forwarding client".to_string(),
+                ));
+            }
+        };
+        let client = builder
+            .build()
+            .map_err(|e| ProxyError::TlsInit(e.to_string()))?;
+        Ok(Self { protocol, client })
+    }
+
+    /// Process-wide pooled client for `protocol`
+    ///
+    /// Connections are kept alive and reused across requests; the pool drops
+    /// a connection as soon as a request on it fails.
+    pub fn shared(protocol: UpstreamProtocol) -> Result<Self, ProxyError> {
+        static H1: OnceLock<UpstreamClient> = OnceLock::new();
+        static H2C: OnceLock<UpstreamClient> = OnceLock::new();
+        static H2_TLS: OnceLock<UpstreamClient> = OnceLock::new();
+
+        let cell = match protocol {
+            UpstreamProtocol::H1 => &H1,
+            UpstreamProtocol::H2c => &H2C,
+            UpstreamProtocol::H2Tls => &H2_TLS,
+            UpstreamProtocol::H3 => return Self::new(protocol),
+        };
+        if let Some(client) = cell.get() {
+            return Ok(client.clone());
+        }
+        let client = Self::new(protocol)?;
+        Ok(cell.get_or_init(|| client).clone())
+    }
+
+    /// Protocol this client speaks
+    pub fn protocol(&self) -> UpstreamProtocol {
+        self.protocol
+    }
+
+    /// Underlying HTTP client
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    /// Full URL for `path_and_query` on the upstream at `addr`
+    pub fn url(&self, addr: &str, path_and_query: &str) -> String {
+        format!("{}{}{}", self.protocol.url_scheme(), addr, path_and_query)
+    }
+}
+
 pub struct UpstreamClientOptions {
     pub connect_timeout_ms: u64,
     pub read_timeout_ms: u64,
@@ -19,6 +151,40 @@ impl Default for UpstreamClientOptions {
     }
 }
 
+/// Whether `err` means a reused upstream connection had already gone away
+///
+/// Covers connections closed mid-exchange, broken pipes / resets, and HTTP/2
+/// `GOAWAY` or refused streams. Requests failing this way never reached the
+/// upstream application, so idempotent ones are safe to retry.
+pub fn is_stale_connection_error(err: &(dyn Error + 'static)) -> bool {
+    let mut source = Some(err);
+    while let Some(err) = source {
+        if let Some(e) = err.downcast_ref::<hyper::Error>()
+            && (e.is_incomplete_message() || e.is_canceled() || e.is_closed())
+        {
+            return true;
+        }
+        if let Some(e) = err.downcast_ref::<h2::Error>()
+            && (e.is_go_away() || e.reason() == Some(h2::Reason::REFUSED_STREAM))
+        {
+            return true;
+        }
+        if let Some(e) = err.downcast_ref::<std::io::Error>()
+            && matches!(
+                e.kind(),
+                std::io::ErrorKind::BrokenPipe
+                    | std::io::ErrorKind::ConnectionReset
+                    | std::io::ErrorKind::ConnectionAborted
+                    | std::io::ErrorKind::UnexpectedEof
+            )
+        {
+            return true;
+        }
+        source = err.source();
+    }
+    false
+}
+
 pub fn create_upstream_client(options: &UpstreamClientOptions) -> Client {
     Client::builder()
         .connect_timeout(Duration::from_millis(options.connect_timeout_ms))
@@ -49,4 +215,110 @@ mod tests {
         // but if it built successfully without panicking, the options are valid.
         assert!(true);
     }
+
+    #[test]
+    fn test_client_per_protocol() {
+        for protocol in [
+            UpstreamProtocol::H1,
+            UpstreamProtocol::H2c,
+            UpstreamProtocol::H2Tls,
+        ] {
+            let client = UpstreamClient::new(protocol).unwrap();
+            assert_eq!(client.protocol(), protocol);
+        }
+        assert_eq!(
+            UpstreamClient::new(UpstreamProtocol::H2c)
+                .unwrap()
+                .url("backend:80", "/a?b=1"),
+            "http://backend:80/a?b=1"
+        );
+        assert_eq!(
+            UpstreamClient::new(UpstreamProtocol::H2Tls)
+                .unwrap()
+                .url("backend:443", "/"),
+            "https://backend:443/"
+        );
+        assert!(matches!(
+            UpstreamClient::new(UpstreamProtocol::H3),
+            Err(ProxyError::ConfigInvalid(_))
+        ));
+    }
+
+    #[test]
+    fn test_stale_connection_errors() {
+        use std::io::{Error as IoError, ErrorKind};
+
+        let reset = IoError::new(ErrorKind::ConnectionReset, "reset by peer");
+        assert!(is_stale_connection_error(&reset));
+
+        // Wrapped errors are found through the source chain
+        #[derive(Debug)]
+        struct Wrapped(IoError);
+        impl std::fmt::Display for Wrapped {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "request failed")
+            }
+        }
+        impl Error for Wrapped {
+            fn source(&self) -> Option<&(dyn Error + 'static)> {
+                Some(&self.0)
+            }
+        }
+        let wrapped = Wrapped(IoError::new(ErrorKind::BrokenPipe, "broken pipe"));
+        assert!(is_stale_connection_error(&wrapped));
+
+        assert!(is_stale_connection_error(&h2::Error::from(
+            h2::Reason::REFUSED_STREAM
+        )));
+
+        let refused = IoError::new(ErrorKind::ConnectionRefused, "refused");
+        assert!(!is_stale_connection_error(&refused));
+        assert!(!is_stale_connection_error(&h2::Error::from(
+            h2::Reason::PROTOCOL_ERROR
+        )));
+    }
+
+    #[test]
+    fn test_protocol_targets() {
+        assert_eq!(UpstreamProtocol::H1.target("backend:80"), "backend:80");
+        assert_eq!(
+            UpstreamProtocol::H2c.target("backend:80"),
+            "h2c://backend:80"
+        );
+        assert_eq!(
+            UpstreamProtocol::H2Tls.target("backend:443"),
+            "h2-tls://backend:443"
+        );
+        // Explicit schemes are left alone
+        assert_eq!(
+            UpstreamProtocol::H2c.target("grpc://backend:50051"),
+            "grpc://backend:50051"
+        );
+
+        assert_eq!(
+            UpstreamProtocol::parse_target("h2-tls://backend:443"),
+            Some((UpstreamProtocol::H2Tls, "backend:443"))
+        );
+        assert_eq!(UpstreamProtocol::parse_target("https://backend"), None);
+        assert_eq!(UpstreamProtocol::parse_target("backend:80"), None);
+    }
+
+    #[test]
+    fn test_protocol_deserialize() {
+        #[derive(Deserialize)]
+        struct Wrapper {
+            upstream_protocol: UpstreamProtocol,
+        }
+        for (name, protocol) in [
+            ("h1", UpstreamProtocol::H1),
+            ("h2c", UpstreamProtocol::H2c),
+            ("h2-tls", UpstreamProtocol::H2Tls),
+            ("h3", UpstreamProtocol::H3),
+        ] {
+            let parsed: Wrapper =
+                toml::from_str(&format!("upstream_protocol = \"{}\"", name)).unwrap();
+            assert_eq!(parsed.upstream_protocol, protocol);
+            assert_eq!(protocol.as_str(), name);
+        }
+    }
 }
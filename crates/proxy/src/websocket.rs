@@ -36,7 +36,11 @@ where
         .path_and_query()
         .map(|pq| pq.as_str())
         .unwrap_or(req.uri().path());
-    let upstream_url = format!("http://{}{}", upstream, path_and_query);
+    // Upgrades always use HTTP/1.1; only the configured transport security is kept
+    let upstream_url = match crate::upstream_client::UpstreamProtocol::parse_target(upstream) {
+        Some((protocol, addr)) => format!("{}{}{}", protocol.url_scheme(), addr, path_and_query),
+        None => format!("http://{}{}", upstream, path_and_query),
+    };
 
     debug!("🕸️ Forwarding WS upgrade to: {}", upstream_url);
 
@@ -32,6 +32,8 @@ impl EnergyApiClient for MockIntegrationClient {
             timestamp: chrono::Utc::now(),
             valid_for_seconds: 300,
             rating: None,
+            renewable_percentage: None,
+            power_breakdown: None,
         })
     }
 
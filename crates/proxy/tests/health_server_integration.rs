@@ -1,4 +1,4 @@
-use aegis_proxy::health_server::run_health_server_with_listener;
+use aegis_proxy::health_server::{run_health_server_with_admin, run_health_server_with_listener};
 use aegis_proxy::lifecycle::LifecycleManager;
 use std::sync::Arc;
 use std::time::Duration;
@@ -144,3 +144,47 @@ async fn test_health_server_delayed_shutdown() {
 
     assert!(result.is_ok());
 }
+
+#[tokio::test]
+async fn test_health_server_rejects_unauthenticated_maintenance_post() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let lifecycle = Arc::new(LifecycleManager::new());
+    lifecycle.mark_ready().await;
+
+    let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+    let server_lifecycle = lifecycle.clone();
+    let server_handle = tokio::spawn(async move {
+        run_health_server_with_admin(
+            listener,
+            server_lifecycle,
+            None,
+            Some("s3cret".to_string()),
+            async {
+                shutdown_rx.await.ok();
+            },
+        )
+        .await
+    });
+
+    let body = r#"{"enabled":true}"#;
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    stream
+        .write_all(
+            format!(
+                "POST /admin/maintenance HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .as_bytes(),
+        )
+        .await
+        .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    assert!(response.starts_with("HTTP/1.1 401"), "{response}");
+    assert!(!lifecycle.is_in_maintenance().await);
+
+    shutdown_tx.send(()).ok();
+    server_handle.await.unwrap().unwrap();
+}
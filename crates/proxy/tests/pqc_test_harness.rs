@@ -0,0 +1,16 @@
+//! Uses the `test-harness` feature the way an external integration test would
+
+use aegis_proxy::test_harness::PqcTestServer;
+use http_body_util::BodyExt;
+
+#[tokio::test]
+async fn test_harness_full_request_to_health() {
+    let server = PqcTestServer::start().await.unwrap();
+
+    let response = server.get("/health").await.unwrap();
+    assert_eq!(response.status(), 200);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    assert!(!body.is_empty());
+
+    server.shutdown().await.unwrap();
+}
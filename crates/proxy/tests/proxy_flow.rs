@@ -1,7 +1,8 @@
-use aegis_proxy::{HttpProxy, HttpProxyConfig};
+use aegis_proxy::{EnergyBudget, HttpProxy, HttpProxyConfig};
 use bytes::Bytes;
 use http_body_util::{BodyExt, Empty};
 use std::net::SocketAddr;
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::net::TcpListener;
 
@@ -177,6 +178,54 @@ async fn test_http_proxy_with_post_request() {
     let _ = proxy_handle.await;
 }
 
+#[tokio::test]
+async fn test_http_proxy_energy_budget_sheds_load() {
+    let proxy_port = get_free_port().await;
+    let proxy_addr: SocketAddr = format!("127.0.0.1:{}", proxy_port).parse().unwrap();
+
+    // Every request costs at least the 0.1mJ base overhead, so one fills the budget
+    let budget = Arc::new(EnergyBudget::new(1e-6, Duration::from_millis(300)));
+    let config = HttpProxyConfig {
+        listen_addr: proxy_addr,
+        upstream_addr: "127.0.0.1:9095".to_string(),
+        energy_budget: Some(budget.clone()),
+        ..Default::default()
+    };
+
+    let proxy = HttpProxy::new(config);
+
+    let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+    let proxy_handle = tokio::spawn(async move {
+        proxy
+            .run_with_shutdown(async {
+                shutdown_rx.await.ok();
+            })
+            .await
+    });
+
+    tokio::time::sleep(Duration::from_millis(150)).await;
+
+    let client = hyper_util::client::legacy::Client::builder(hyper_util::rt::TokioExecutor::new())
+        .build_http::<Empty<Bytes>>();
+    let uri: hyper::Uri = format!("http://{}/health", proxy_addr).parse().unwrap();
+
+    let resp = client.get(uri.clone()).await.expect("Request failed");
+    assert_eq!(resp.status(), 200);
+    assert_eq!(budget.remaining_joules(), 0.0);
+
+    let resp = client.get(uri.clone()).await.expect("Request failed");
+    assert_eq!(resp.status(), 503);
+    assert_eq!(resp.headers()["retry-after"], "1");
+
+    // Recovers once the recorded energy leaves the window
+    tokio::time::sleep(Duration::from_millis(350)).await;
+    let resp = client.get(uri).await.expect("Request failed");
+    assert_eq!(resp.status(), 200);
+
+    shutdown_tx.send(()).ok();
+    let _ = proxy_handle.await;
+}
+
 #[tokio::test]
 async fn test_http_proxy_delayed_shutdown() {
     let proxy_port = get_free_port().await;
@@ -144,12 +144,9 @@ impl EnergyMetrics {
         }
     }
 
-    /// Estimated carbon footprint in grams CO2
-    /// Using average grid intensity of 400 gCO2/kWh
+    /// Estimated carbon footprint in grams CO2 at the given grid intensity
     pub fn carbon_grams(&self, intensity_g_per_kwh: f64) -> f64 {
-        // Convert joules to kWh: 1 kWh = 3,600,000 J
-        let kwh = self.total_joules() / 3_600_000.0;
-        kwh * intensity_g_per_kwh
+        aegis_energy::grams_for_joules(self.total_joules(), intensity_g_per_kwh)
     }
 }
 
@@ -184,6 +181,11 @@ mod tests {
         let carbon = metrics.carbon_grams(400.0);
         assert!(carbon > 0.0);
         assert!(carbon < 1e-5);
+
+        // 7200 J = 0.002 kWh, at 500 gCO2/kWh = 1 gCO2
+        let metrics = EnergyMetrics::new("/batch", "POST")
+            .with_breakdown(EnergyBreakdown::new(7000.0, 200.0, 0.0, 0.0));
+        assert!((metrics.carbon_grams(500.0) - 1.0).abs() < 1e-12);
     }
 
     #[test]
@@ -269,4 +271,21 @@ mod tests {
         let metrics = EnergyMetrics::new("/api", "GET").with_request_id("req-1");
         assert_eq!(metrics.request_id, Some("req-1".to_string()));
     }
+
+    #[test]
+    fn test_energy_metrics_serialization() {
+        let metrics = EnergyMetrics::new("/api", "GET")
+            .with_breakdown(EnergyBreakdown::new(0.4, 0.3, 0.2, 0.1));
+
+        let json = serde_json::to_value(&metrics).unwrap();
+        let breakdown = &json["breakdown"];
+        assert_eq!(breakdown["cpu_joules"], 0.4);
+        assert_eq!(breakdown["memory_joules"], 0.3);
+        assert_eq!(breakdown["network_joules"], 0.2);
+        assert_eq!(breakdown["storage_joules"], 0.1);
+
+        let parsed: EnergyMetrics = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.endpoint, "/api");
+        assert!((parsed.total_joules() - 1.0).abs() < 1e-10);
+    }
 }
@@ -3,11 +3,22 @@
 //! Software-based energy estimation with optional eBPF support.
 
 use crate::energy::{EnergyBreakdown, EnergyMetrics, EnergySource};
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::future::Future;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
 use tracing::{debug, instrument};
 
+/// Default number of samples that may wait in the queue before being dropped
+pub const DEFAULT_SAMPLE_CAPACITY: usize = 4096;
+
+/// Default number of drained samples kept for percentile calculation
+pub const DEFAULT_WINDOW_SIZE: usize = 1024;
+
 /// Energy model coefficients for software estimation
 #[derive(Debug, Clone)]
 pub struct EnergyModel {
@@ -46,31 +57,75 @@ pub struct EnergyEstimator {
     request_count: AtomicU64,
     /// Total energy consumed (in micro-joules for precision)
     total_energy_uj: AtomicU64,
+    /// Per-component totals in micro-joules: cpu, memory, network, storage
+    component_energy_uj: [AtomicU64; 4],
     /// Source of measurements
     source: EnergySource,
+    /// Lock-free queue of per-request energy samples (micro-joules)
+    samples: mpsc::Sender<u64>,
+    /// Samples dropped because the queue was full
+    dropped_samples: AtomicU64,
+    /// Drain side of the sample queue and the percentile window
+    window: Mutex<SampleWindow>,
+}
+
+/// Recent samples moved off the request path by [`EnergyEstimator::drain_samples`]
+#[derive(Debug)]
+struct SampleWindow {
+    receiver: mpsc::Receiver<u64>,
+    samples: VecDeque<u64>,
+    size: usize,
+}
+
+impl SampleWindow {
+    fn push(&mut self, sample: u64) {
+        if self.samples.len() == self.size {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+}
+
+fn sample_queue(capacity: usize, window_size: usize) -> (mpsc::Sender<u64>, Mutex<SampleWindow>) {
+    let (sender, receiver) = mpsc::channel(capacity.max(1));
+    let window = SampleWindow {
+        receiver,
+        samples: VecDeque::with_capacity(window_size),
+        size: window_size.max(1),
+    };
+    (sender, Mutex::new(window))
 }
 
 impl EnergyEstimator {
     /// Create a new energy estimator with default model
     pub fn new() -> Self {
-        Self {
-            model: EnergyModel::default(),
-            request_count: AtomicU64::new(0),
-            total_energy_uj: AtomicU64::new(0),
-            source: EnergySource::Software,
-        }
+        Self::with_model(EnergyModel::default())
     }
 
     /// Create with custom energy model
     pub fn with_model(model: EnergyModel) -> Self {
+        let (samples, window) = sample_queue(DEFAULT_SAMPLE_CAPACITY, DEFAULT_WINDOW_SIZE);
         Self {
             model,
             request_count: AtomicU64::new(0),
             total_energy_uj: AtomicU64::new(0),
+            component_energy_uj: Default::default(),
             source: EnergySource::Software,
+            samples,
+            dropped_samples: AtomicU64::new(0),
+            window,
         }
     }
 
+    /// Set the sample queue capacity and percentile window size
+    ///
+    /// Samples that arrive while the queue is full are dropped and counted
+    /// in [`dropped_samples`](Self::dropped_samples); totals are unaffected.
+    pub fn with_sample_capacity(mut self, capacity: usize, window_size: usize) -> Self {
+        (self.samples, self.window) = sample_queue(capacity, window_size);
+        self
+    }
+
     /// Get the measurement source
     pub fn source(&self) -> EnergySource {
         self.source
@@ -86,6 +141,18 @@ impl EnergyEstimator {
         self.total_energy_uj.load(Ordering::Relaxed) as f64 / 1_000_000.0
     }
 
+    /// Get total energy consumed, split by component
+    pub fn total_breakdown(&self) -> EnergyBreakdown {
+        let [cpu, memory, network, storage] = &self.component_energy_uj;
+        let joules = |uj: &AtomicU64| uj.load(Ordering::Relaxed) as f64 / 1_000_000.0;
+        EnergyBreakdown::new(
+            joules(cpu),
+            joules(memory),
+            joules(network),
+            joules(storage),
+        )
+    }
+
     /// Measure energy for a synchronous operation
     #[instrument(skip(self, f))]
     pub fn measure<T, F: FnOnce() -> T>(
@@ -104,6 +171,23 @@ impl EnergyEstimator {
         (result, metrics)
     }
 
+    /// Measure energy for an asynchronous operation
+    pub async fn measure_async<T, F: Future<Output = T>>(
+        &self,
+        endpoint: &str,
+        method: &str,
+        fut: F,
+    ) -> (T, EnergyMetrics) {
+        let start = Instant::now();
+        let result = fut.await;
+        let duration = start.elapsed();
+
+        let metrics = self.estimate_from_duration(endpoint, method, duration, 0);
+        self.record_metrics(&metrics);
+
+        (result, metrics)
+    }
+
     /// Measure energy with known byte count
     #[instrument(skip(self, f))]
     pub fn measure_with_bytes<T, F: FnOnce() -> T>(
@@ -171,6 +255,80 @@ impl EnergyEstimator {
         // Convert to micro-joules for better precision
         let energy_uj = (metrics.total_joules() * 1_000_000.0) as u64;
         self.total_energy_uj.fetch_add(energy_uj, Ordering::Relaxed);
+
+        let breakdown = &metrics.breakdown;
+        let components = [
+            breakdown.cpu_joules,
+            breakdown.memory_joules,
+            breakdown.network_joules,
+            breakdown.storage_joules,
+        ];
+        for (total, joules) in self.component_energy_uj.iter().zip(components) {
+            total.fetch_add((joules * 1_000_000.0) as u64, Ordering::Relaxed);
+        }
+
+        // Never wait for the drain: a full queue sheds the sample instead
+        if self.samples.try_send(energy_uj).is_err() {
+            self.dropped_samples.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Number of samples dropped because the queue was full
+    pub fn dropped_samples(&self) -> u64 {
+        self.dropped_samples.load(Ordering::Relaxed)
+    }
+
+    /// Number of samples waiting to be drained
+    pub fn pending_samples(&self) -> usize {
+        self.samples.max_capacity() - self.samples.capacity()
+    }
+
+    /// Whether the sample queue is full and new samples are being dropped
+    pub fn is_saturated(&self) -> bool {
+        self.samples.capacity() == 0
+    }
+
+    /// Move queued samples into the percentile window, returning how many were drained
+    pub fn drain_samples(&self) -> usize {
+        let mut window = self.window.lock();
+        let mut drained = 0;
+        while let Ok(sample) = window.receiver.try_recv() {
+            window.push(sample);
+            drained += 1;
+        }
+        drained
+    }
+
+    /// Per-request energy percentile in joules over the drained window
+    ///
+    /// `p` is in `0.0..=1.0`. Pending samples are drained first.
+    pub fn percentile_joules(&self, p: f64) -> Option<f64> {
+        self.drain_samples();
+        let window = self.window.lock();
+        if window.samples.is_empty() {
+            return None;
+        }
+        let mut sorted: Vec<u64> = window.samples.iter().copied().collect();
+        drop(window);
+        sorted.sort_unstable();
+        let rank = (p.clamp(0.0, 1.0) * (sorted.len() - 1) as f64).round() as usize;
+        Some(sorted[rank] as f64 / 1_000_000.0)
+    }
+
+    /// Spawn a background task draining the sample queue every `interval`
+    pub fn spawn_drain(self: &Arc<Self>, interval: Duration) -> JoinHandle<()> {
+        let estimator = Arc::downgrade(self);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                let Some(estimator) = estimator.upgrade() else {
+                    break;
+                };
+                estimator.drain_samples();
+            }
+        })
     }
 
     /// Get average energy per request
@@ -187,6 +345,13 @@ impl EnergyEstimator {
     pub fn reset(&self) {
         self.request_count.store(0, Ordering::Relaxed);
         self.total_energy_uj.store(0, Ordering::Relaxed);
+        for total in &self.component_energy_uj {
+            total.store(0, Ordering::Relaxed);
+        }
+        self.dropped_samples.store(0, Ordering::Relaxed);
+        let mut window = self.window.lock();
+        while window.receiver.try_recv().is_ok() {}
+        window.samples.clear();
     }
 }
 
@@ -259,6 +424,29 @@ mod tests {
         estimator.reset();
         assert_eq!(estimator.request_count(), 0);
         assert_eq!(estimator.total_energy_joules(), 0.0);
+        assert_eq!(estimator.total_breakdown().total(), 0.0);
+    }
+
+    #[test]
+    fn test_total_breakdown_accumulates_components() {
+        let model = EnergyModel {
+            joules_per_cycle: 0.0,
+            joules_per_memory_byte: 1e-3,
+            joules_per_network_byte: 2e-3,
+            joules_per_storage_byte: 0.0,
+            base_overhead_joules: 0.5,
+        };
+        let estimator = EnergyEstimator::with_model(model);
+
+        estimator.measure_with_bytes("/upload", "POST", 1000, || ());
+        estimator.measure_with_bytes("/upload", "POST", 1000, || ());
+
+        let breakdown = estimator.total_breakdown();
+        assert!((breakdown.cpu_joules - 1.0).abs() < 1e-6);
+        assert!((breakdown.memory_joules - 2.0).abs() < 1e-6);
+        assert!((breakdown.network_joules - 4.0).abs() < 1e-6);
+        assert_eq!(breakdown.storage_joules, 0.0);
+        assert!((breakdown.total() - estimator.total_energy_joules()).abs() < 1e-5);
     }
 
     #[test]
@@ -461,6 +649,111 @@ mod tests {
         assert_eq!(metrics.endpoint, "/debug-path");
     }
 
+    #[test]
+    fn test_percentile_from_drained_window() {
+        let model = EnergyModel {
+            joules_per_cycle: 0.0,
+            joules_per_memory_byte: 0.0,
+            joules_per_network_byte: 1e-3,
+            joules_per_storage_byte: 0.0,
+            base_overhead_joules: 0.0,
+        };
+        let estimator = EnergyEstimator::with_model(model);
+        assert_eq!(estimator.percentile_joules(0.5), None);
+
+        for bytes in 1..=100 {
+            estimator.measure_with_bytes("/p", "GET", bytes * 1000, || ());
+        }
+        assert_eq!(estimator.pending_samples(), 100);
+
+        assert!((estimator.percentile_joules(0.0).unwrap() - 1.0).abs() < 1e-6);
+        assert!((estimator.percentile_joules(1.0).unwrap() - 100.0).abs() < 1e-6);
+        let p50 = estimator.percentile_joules(0.5).unwrap();
+        assert!((50.0..=51.0).contains(&p50));
+        assert_eq!(estimator.pending_samples(), 0);
+    }
+
+    #[test]
+    fn test_full_queue_drops_samples_but_keeps_counts() {
+        let estimator = EnergyEstimator::new().with_sample_capacity(4, 4);
+
+        for _ in 0..10 {
+            estimator.measure("/burst", "GET", || ());
+        }
+
+        assert!(estimator.is_saturated());
+        assert_eq!(estimator.request_count(), 10);
+        assert_eq!(estimator.dropped_samples(), 6);
+        assert_eq!(estimator.drain_samples(), 4);
+        assert!(!estimator.is_saturated());
+
+        estimator.reset();
+        assert_eq!(estimator.dropped_samples(), 0);
+        assert_eq!(estimator.percentile_joules(0.5), None);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+    async fn test_measure_under_contention() {
+        const TASKS: usize = 32;
+        const PER_TASK: usize = 2000;
+
+        let estimator = Arc::new(EnergyEstimator::new().with_sample_capacity(1024, 256));
+        let done = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let drain = {
+            let estimator = Arc::clone(&estimator);
+            let done = Arc::clone(&done);
+            tokio::spawn(async move {
+                let mut drained = 0;
+                while !done.load(Ordering::Relaxed) {
+                    drained += estimator.drain_samples();
+                    tokio::time::sleep(Duration::from_micros(200)).await;
+                }
+                drained + estimator.drain_samples()
+            })
+        };
+
+        let mut handles = Vec::new();
+        for _ in 0..TASKS {
+            let estimator = Arc::clone(&estimator);
+            handles.push(tokio::spawn(async move {
+                for i in 0..PER_TASK {
+                    if i % 2 == 0 {
+                        estimator.measure("/hot", "GET", || ());
+                    } else {
+                        estimator.measure_async("/hot", "GET", async {}).await;
+                    }
+                    if i % 64 == 0 {
+                        tokio::task::yield_now().await;
+                    }
+                }
+            }));
+        }
+
+        for handle in handles {
+            handle.await.unwrap();
+        }
+        done.store(true, Ordering::Relaxed);
+        let drained = drain.await.unwrap() as u64;
+
+        let total = (TASKS * PER_TASK) as u64;
+        assert_eq!(estimator.request_count(), total);
+        assert_eq!(drained + estimator.dropped_samples(), total);
+        assert!(estimator.percentile_joules(0.99).is_some());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_spawn_drain_moves_samples_off_queue() {
+        let estimator = Arc::new(EnergyEstimator::new());
+        let drain = estimator.spawn_drain(Duration::from_millis(10));
+
+        estimator.measure("/bg", "GET", || ());
+        assert_eq!(estimator.pending_samples(), 1);
+
+        tokio::time::sleep(Duration::from_millis(15)).await;
+        assert_eq!(estimator.pending_samples(), 0);
+        drain.abort();
+    }
+
     #[test]
     fn test_debug_logging() {
         let subscriber = tracing_subscriber::fmt()
@@ -64,8 +64,9 @@ fn main() {
     let high_carbon = 350.0; // If we used us-east-1
     let low_carbon = 45.0; // Using us-west-2
 
-    let kwh = (requests_per_hour as f64 * joules_per_request) / 3_600_000.0;
-    let saved_grams = kwh * (high_carbon - low_carbon);
+    let joules = requests_per_hour as f64 * joules_per_request;
+    let kwh = joules / aegis_energy::JOULES_PER_KWH;
+    let saved_grams = aegis_energy::grams_for_joules(joules, high_carbon - low_carbon);
 
     println!("   Requests/hour: {}", requests_per_hour);
     println!("   Energy/hour: {:.4} kWh", kwh);